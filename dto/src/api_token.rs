@@ -0,0 +1,95 @@
+use crate::user::Role;
+use chrono::NaiveDateTime;
+use derive_getters::Getters;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+
+/// The endpoint families an API token can be scoped to.
+pub const API_TOKEN_SCOPES: [Role; 2] = [Role::Check, Role::LookUp];
+
+/// A long-lived token, used by scripts to call the API with an `Authorization: Bearer` header.
+/// The token itself is only known when it is created.
+#[derive(Debug, Getters, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiToken {
+    id: i32,
+    name: String,
+    scopes: Vec<Role>,
+    created_by: String,
+    creation_date: NaiveDateTime,
+    revocation_date: Option<NaiveDateTime>,
+}
+
+impl ApiToken {
+    pub fn new(
+        id: i32,
+        name: String,
+        scopes: Vec<Role>,
+        created_by: String,
+        creation_date: NaiveDateTime,
+        revocation_date: Option<NaiveDateTime>,
+    ) -> Self {
+        Self {
+            id,
+            name,
+            scopes,
+            created_by,
+            creation_date,
+            revocation_date,
+        }
+    }
+
+    pub fn is_revoked(&self) -> bool {
+        self.revocation_date.is_some()
+    }
+
+    /// How the token appears in the audit log when it is used.
+    pub fn actor(&self) -> String {
+        format!("Jeton n°{} ({})", self.id, self.name)
+    }
+}
+
+/// An API token to create.
+#[derive(Debug, Getters, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewApiToken {
+    name: String,
+    scopes: Vec<Role>,
+}
+
+impl NewApiToken {
+    pub fn new(name: String, scopes: Vec<Role>) -> Self {
+        Self { name, scopes }
+    }
+}
+
+/// A freshly created API token, along with its value, which won't be shown again.
+#[derive(Getters, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CreatedApiToken {
+    id: i32,
+    token: String,
+}
+
+impl Debug for CreatedApiToken {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Created API Token {{id={}, token=MASKED}}", self.id)
+    }
+}
+
+impl CreatedApiToken {
+    pub fn new(id: i32, token: String) -> Self {
+        Self { id, token }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod debug {
+        use crate::api_token::CreatedApiToken;
+
+        #[test]
+        fn mask_token() {
+            let created = CreatedApiToken::new(1, "cnm_secret".to_owned());
+
+            assert!(!format!("{created:?}").contains("cnm_secret"));
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use derive_getters::Getters;
+use serde::{Deserialize, Serialize};
+
+/// A sensitive action, which is recorded into the audit log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditAction {
+    DownloadMemberships,
+    UploadMemberships,
+    LookMemberUp,
+    ConfirmUdaMembers,
+    NotifyMembers,
+    CreateUser,
+    UpdateUserRoles,
+    DeleteUser,
+    CreateApiToken,
+    RevokeApiToken,
+}
+
+impl AuditAction {
+    pub const ALL: [AuditAction; 10] = [
+        AuditAction::DownloadMemberships,
+        AuditAction::UploadMemberships,
+        AuditAction::LookMemberUp,
+        AuditAction::ConfirmUdaMembers,
+        AuditAction::NotifyMembers,
+        AuditAction::CreateUser,
+        AuditAction::UpdateUserRoles,
+        AuditAction::DeleteUser,
+        AuditAction::CreateApiToken,
+        AuditAction::RevokeApiToken,
+    ];
+
+    pub fn value(&self) -> &'static str {
+        match self {
+            AuditAction::DownloadMemberships => "DownloadMemberships",
+            AuditAction::UploadMemberships => "UploadMemberships",
+            AuditAction::LookMemberUp => "LookMemberUp",
+            AuditAction::ConfirmUdaMembers => "ConfirmUdaMembers",
+            AuditAction::NotifyMembers => "NotifyMembers",
+            AuditAction::CreateUser => "CreateUser",
+            AuditAction::UpdateUserRoles => "UpdateUserRoles",
+            AuditAction::DeleteUser => "DeleteUser",
+            AuditAction::CreateApiToken => "CreateApiToken",
+            AuditAction::RevokeApiToken => "RevokeApiToken",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AuditAction::DownloadMemberships => "Téléchargement de la liste des licences",
+            AuditAction::UploadMemberships => "Import d'un export Fileo",
+            AuditAction::LookMemberUp => "Recherche d'adhésion",
+            AuditAction::ConfirmUdaMembers => "Confirmation de membres UDA",
+            AuditAction::NotifyMembers => "Envoi d'email",
+            AuditAction::CreateUser => "Création d'un utilisateur",
+            AuditAction::UpdateUserRoles => "Modification des rôles d'un utilisateur",
+            AuditAction::DeleteUser => "Suppression d'un utilisateur",
+            AuditAction::CreateApiToken => "Création d'un jeton d'API",
+            AuditAction::RevokeApiToken => "Révocation d'un jeton d'API",
+        }
+    }
+
+    pub fn from_value(value: &str) -> Option<AuditAction> {
+        AuditAction::ALL
+            .into_iter()
+            .find(|action| action.value() == value)
+    }
+}
+
+/// Who has done what, and when.
+/// Targets are what the action has been applied to, e.g. membership numbers, UDA members IDs, email addresses, user logins or API token IDs.
+#[derive(Debug, Getters, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditLogEntry {
+    id: i32,
+    date: NaiveDateTime,
+    actor: String,
+    action: AuditAction,
+    targets: Vec<String>,
+}
+
+impl AuditLogEntry {
+    pub fn new(
+        id: i32,
+        date: NaiveDateTime,
+        actor: String,
+        action: AuditAction,
+        targets: Vec<String>,
+    ) -> Self {
+        Self {
+            id,
+            date,
+            actor,
+            action,
+            targets,
+        }
+    }
+}
+
+/// Criteria to browse the audit log with. Missing criteria don't filter anything out.
+#[derive(Debug, Getters, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditLogFilter {
+    /// Part of the login of the actor.
+    actor: Option<String>,
+    action: Option<AuditAction>,
+    /// First day of the period to look into, included.
+    from: Option<NaiveDate>,
+    /// Last day of the period to look into, included.
+    to: Option<NaiveDate>,
+}
+
+impl AuditLogFilter {
+    pub fn new(
+        actor: Option<String>,
+        action: Option<AuditAction>,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Self {
+        Self {
+            actor,
+            action,
+            from,
+            to,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod from_value {
+        use crate::audit_log::AuditAction;
+
+        #[test]
+        fn success() {
+            for action in AuditAction::ALL {
+                assert_eq!(Some(action), AuditAction::from_value(action.value()));
+            }
+        }
+
+        #[test]
+        fn unknown() {
+            assert_eq!(None, AuditAction::from_value("Unknown"));
+        }
+    }
+}
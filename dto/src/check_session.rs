@@ -0,0 +1,202 @@
+use crate::checked_member::CheckedMember;
+use crate::csv_member::CsvMember;
+use crate::event_period::EventPeriod;
+use crate::membership_status::MemberStatus;
+use crate::uda_member::UdaMember;
+use chrono::NaiveDateTime;
+use derive_getters::Getters;
+use serde::{Deserialize, Serialize};
+
+/// Where the members of a check session come from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CheckSource {
+    Csv,
+    /// Members have been imported from the UDA instance with this slug.
+    Uda(String),
+}
+
+/// What has been checked, when and by whom, without the results themselves.
+/// Valid members are those whose membership covers the event, even if it expires soon after.
+#[derive(Debug, Getters, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckSessionSummary {
+    id: i32,
+    date: NaiveDateTime,
+    user: String,
+    source: CheckSource,
+    event_period: EventPeriod,
+    checked_count: usize,
+    valid_count: usize,
+}
+
+impl CheckSessionSummary {
+    pub fn new(
+        id: i32,
+        date: NaiveDateTime,
+        user: String,
+        source: CheckSource,
+        event_period: EventPeriod,
+        checked_count: usize,
+        valid_count: usize,
+    ) -> Self {
+        Self {
+            id,
+            date,
+            user,
+            source,
+            event_period,
+            checked_count,
+            valid_count,
+        }
+    }
+}
+
+/// Results of a check session, whose members depend on its source.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub enum CheckSessionResults {
+    Csv(Vec<CheckedMember<CsvMember>>),
+    /// Members imported from the UDA instance with this slug.
+    Uda(String, Vec<CheckedMember<UdaMember>>),
+}
+
+/// A past check session, along with its results, so that it can be reopened.
+#[derive(Debug, Getters, PartialEq, Serialize, Deserialize)]
+pub struct CheckSession {
+    summary: CheckSessionSummary,
+    results: CheckSessionResults,
+}
+
+impl CheckSession {
+    pub fn new(summary: CheckSessionSummary, results: CheckSessionResults) -> Self {
+        Self { summary, results }
+    }
+}
+
+/// A member whose status differs between two check sessions.
+/// A missing status means the member hasn't been checked in that session.
+#[derive(Debug, Getters, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComparedMember {
+    member: String,
+    previous_status: Option<MemberStatus>,
+    current_status: Option<MemberStatus>,
+}
+
+impl ComparedMember {
+    pub fn new(
+        member: String,
+        previous_status: Option<MemberStatus>,
+        current_status: Option<MemberStatus>,
+    ) -> Self {
+        Self {
+            member,
+            previous_status,
+            current_status,
+        }
+    }
+}
+
+/// What has changed from a check session to another one.
+#[derive(Debug, Getters, PartialEq, Serialize, Deserialize)]
+pub struct CheckSessionComparison {
+    previous: CheckSessionSummary,
+    current: CheckSessionSummary,
+    changed_members: Vec<ComparedMember>,
+}
+
+impl CheckSessionComparison {
+    pub fn new(
+        previous: CheckSessionSummary,
+        current: CheckSessionSummary,
+        changed_members: Vec<ComparedMember>,
+    ) -> Self {
+        Self {
+            previous,
+            current,
+            changed_members,
+        }
+    }
+}
+
+/// What has changed since the previous check of the same event, for the same UDA instance.
+/// Without any previous check, every member is considered as newly registered.
+#[derive(Debug, Getters, PartialEq, Serialize, Deserialize)]
+pub struct CheckSessionDelta {
+    previous: Option<CheckSessionSummary>,
+    current: CheckSessionSummary,
+    new_members: Vec<ComparedMember>,
+    status_changes: Vec<ComparedMember>,
+    dropped_members: Vec<ComparedMember>,
+}
+
+impl CheckSessionDelta {
+    pub fn new(
+        previous: Option<CheckSessionSummary>,
+        current: CheckSessionSummary,
+        changed_members: Vec<ComparedMember>,
+    ) -> Self {
+        let mut new_members = vec![];
+        let mut status_changes = vec![];
+        let mut dropped_members = vec![];
+        for changed_member in changed_members {
+            match (
+                &changed_member.previous_status,
+                &changed_member.current_status,
+            ) {
+                (None, _) => new_members.push(changed_member),
+                (_, None) => dropped_members.push(changed_member),
+                _ => status_changes.push(changed_member),
+            }
+        }
+
+        Self {
+            previous,
+            current,
+            new_members,
+            status_changes,
+            dropped_members,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod check_session_delta {
+        use crate::check_session::{
+            CheckSessionDelta, CheckSessionSummary, CheckSource, ComparedMember,
+        };
+        use crate::event_period::EventPeriod;
+        use crate::membership_status::MemberStatus::{Unknown, UpToDate};
+        use chrono::{NaiveDate, NaiveDateTime};
+
+        #[test]
+        fn new() {
+            let summary = CheckSessionSummary::new(
+                1,
+                NaiveDateTime::default(),
+                "user".to_owned(),
+                CheckSource::Uda("test".to_owned()),
+                EventPeriod::single_day(NaiveDate::from_ymd_opt(2025, 7, 5).unwrap()),
+                2,
+                1,
+            );
+            let new_member = ComparedMember::new("Jon Doe".to_owned(), None, Some(UpToDate));
+            let status_change =
+                ComparedMember::new("Jonette Snow".to_owned(), Some(Unknown), Some(UpToDate));
+            let dropped_member = ComparedMember::new("Kris Holm".to_owned(), Some(Unknown), None);
+
+            let delta = CheckSessionDelta::new(
+                None,
+                summary.clone(),
+                vec![
+                    new_member.clone(),
+                    status_change.clone(),
+                    dropped_member.clone(),
+                ],
+            );
+            assert_eq!(&None, delta.previous());
+            assert_eq!(&summary, delta.current());
+            assert_eq!(&vec![new_member], delta.new_members());
+            assert_eq!(&vec![status_change], delta.status_changes());
+            assert_eq!(&vec![dropped_member], delta.dropped_members());
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::event_period::EventPeriod;
 use crate::member_to_check::MemberToCheck;
 use crate::membership::Membership;
 use crate::membership_status::MemberStatus::Unknown;
@@ -10,12 +11,20 @@ use std::cmp::Ordering;
 pub enum CheckResult {
     Match(Membership),
     PartialMatch(Membership),
+    /// Several distinct people match the member's names, without any way to tell which one is the right one.
+    /// The most recent membership of each of them is given, the most recent first.
+    AmbiguousMatch(Vec<Membership>),
+    /// No exact match has been found, but the membership has a name close to the member's one.
+    /// The second field is the similarity score, as a percentage.
+    FuzzyMatch(Membership, u8),
     NoMatch,
 }
 
 /// Ordering is based on whether there are match.
-/// Put simply, Match is greater than Partial Match, which in turn is greater than NoMatch.
+/// Put simply, Match is greater than Partial Match, which is greater than Ambiguous Match,
+/// which is greater than Fuzzy Match, which in turn is greater than NoMatch.
 /// If both self & other have the same level, then it is based on the memberships themselves.
+/// Fuzzy matches are first compared by their similarity score.
 impl PartialOrd for CheckResult {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -23,8 +32,10 @@ impl PartialOrd for CheckResult {
 }
 
 /// Ordering is based on whether there are match.
-/// Put simply, Match is greater than Partial Match, which in turn is greater than NoMatch.
+/// Put simply, Match is greater than Partial Match, which is greater than Ambiguous Match,
+/// which is greater than Fuzzy Match, which in turn is greater than NoMatch.
 /// If both self & other have the same level, then it is based on the memberships themselves.
+/// Fuzzy matches are first compared by their similarity score.
 impl Ord for CheckResult {
     fn cmp(&self, other: &Self) -> Ordering {
         match self {
@@ -37,6 +48,24 @@ impl Ord for CheckResult {
                 CheckResult::PartialMatch(other_membership) => {
                     self_membership.cmp(other_membership)
                 }
+                CheckResult::AmbiguousMatch(_)
+                | CheckResult::FuzzyMatch(_, _)
+                | CheckResult::NoMatch => Ordering::Greater,
+            },
+            CheckResult::AmbiguousMatch(self_memberships) => match other {
+                CheckResult::Match(_) | CheckResult::PartialMatch(_) => Ordering::Less,
+                CheckResult::AmbiguousMatch(other_memberships) => {
+                    self_memberships.cmp(other_memberships)
+                }
+                CheckResult::FuzzyMatch(_, _) | CheckResult::NoMatch => Ordering::Greater,
+            },
+            CheckResult::FuzzyMatch(self_membership, self_score) => match other {
+                CheckResult::Match(_)
+                | CheckResult::PartialMatch(_)
+                | CheckResult::AmbiguousMatch(_) => Ordering::Less,
+                CheckResult::FuzzyMatch(other_membership, other_score) => self_score
+                    .cmp(other_score)
+                    .then(self_membership.cmp(other_membership)),
                 CheckResult::NoMatch => Ordering::Greater,
             },
             CheckResult::NoMatch => match other {
@@ -49,25 +78,42 @@ impl Ord for CheckResult {
 
 /// A [CheckedMember] is a member whose membership has been checked.
 /// It may have a membership up-to-date, an expired membership or no membership at all.
+/// Its status is computed once, against the period of the event the member has been checked for.
 #[derive(Debug, Getters, Serialize, Deserialize, PartialEq)]
 pub struct CheckedMember<T: MemberToCheck> {
     member_to_check: T,
     membership: CheckResult,
+    status: MemberStatus,
 }
 
 impl<T: MemberToCheck> CheckedMember<T> {
-    pub fn new(member_to_check: T, membership: CheckResult) -> Self {
+    pub fn new(
+        member_to_check: T,
+        membership: CheckResult,
+        event_period: &EventPeriod,
+        expiring_soon_threshold: u64,
+    ) -> Self {
+        let status =
+            Self::compute_member_status(&membership, event_period, expiring_soon_threshold);
         Self {
             member_to_check,
             membership,
+            status,
         }
     }
 
-    pub fn compute_member_status(&self) -> MemberStatus {
-        match &self.membership {
-            CheckResult::NoMatch => Unknown,
-            CheckResult::Match(membership) | CheckResult::PartialMatch(membership) => {
-                compute_member_status(Some(membership))
+    fn compute_member_status(
+        membership: &CheckResult,
+        event_period: &EventPeriod,
+        expiring_soon_threshold: u64,
+    ) -> MemberStatus {
+        match membership {
+            // Nothing can be told about an ambiguous match, until the organizer picks the right candidate.
+            CheckResult::NoMatch | CheckResult::AmbiguousMatch(_) => Unknown,
+            CheckResult::Match(membership)
+            | CheckResult::PartialMatch(membership)
+            | CheckResult::FuzzyMatch(membership, _) => {
+                compute_member_status(Some(membership), event_period, expiring_soon_threshold)
             }
         }
     }
@@ -96,6 +142,9 @@ mod tests {
             None,
             Some("".to_owned()),
             Some("".to_owned()),
+            None,
+            None,
+            None,
         )
     }
 
@@ -105,6 +154,9 @@ mod tests {
             None,
             Some("".to_owned()),
             Some("".to_owned()),
+            None,
+            None,
+            None,
         )
     }
 
@@ -140,7 +192,9 @@ mod tests {
     // endregion
 
     mod check_result_cmp {
-        use crate::checked_member::CheckResult::{Match, NoMatch, PartialMatch};
+        use crate::checked_member::CheckResult::{
+            AmbiguousMatch, FuzzyMatch, Match, NoMatch, PartialMatch,
+        };
         use crate::checked_member::tests::{get_membership_1, get_membership_2};
         use std::cmp::Ordering;
 
@@ -211,6 +265,85 @@ mod tests {
             assert_eq!(Ordering::Less, NoMatch.cmp(&PartialMatch(membership)));
         }
 
+        #[test]
+        fn partial_match_fuzzy_match_is_greater() {
+            let membership_1 = get_membership_1();
+            let membership_2 = get_membership_2();
+            assert_eq!(
+                Ordering::Greater,
+                PartialMatch(membership_1).cmp(&FuzzyMatch(membership_2, 100))
+            );
+        }
+
+        #[test]
+        fn partial_match_ambiguous_match_is_greater() {
+            let membership_1 = get_membership_1();
+            let membership_2 = get_membership_2();
+            assert_eq!(
+                Ordering::Greater,
+                PartialMatch(membership_1.clone())
+                    .cmp(&AmbiguousMatch(vec![membership_1, membership_2]))
+            );
+        }
+
+        #[test]
+        fn ambiguous_match_fuzzy_match_is_greater() {
+            let membership_1 = get_membership_1();
+            let membership_2 = get_membership_2();
+            assert_eq!(
+                Ordering::Greater,
+                AmbiguousMatch(vec![membership_1.clone(), membership_2])
+                    .cmp(&FuzzyMatch(membership_1, 100))
+            );
+        }
+
+        #[test]
+        fn ambiguous_match_ambiguous_match_is_memberships_ord() {
+            let membership_1 = get_membership_1();
+            let membership_2 = get_membership_2();
+            assert_eq!(
+                Ordering::Less,
+                AmbiguousMatch(vec![membership_1.clone(), membership_2.clone()])
+                    .cmp(&AmbiguousMatch(vec![membership_2, membership_1]))
+            );
+        }
+
+        #[test]
+        fn fuzzy_match_no_match_is_greater() {
+            let membership = get_membership_1();
+            assert_eq!(Ordering::Greater, FuzzyMatch(membership, 0).cmp(&NoMatch));
+        }
+
+        #[test]
+        fn fuzzy_match_match_is_less() {
+            let membership_1 = get_membership_1();
+            let membership_2 = get_membership_2();
+            assert_eq!(
+                Ordering::Less,
+                FuzzyMatch(membership_1, 100).cmp(&Match(membership_2))
+            );
+        }
+
+        #[test]
+        fn fuzzy_match_fuzzy_match_is_score_ord() {
+            let membership_1 = get_membership_1();
+            let membership_2 = get_membership_2();
+            assert_eq!(
+                Ordering::Greater,
+                FuzzyMatch(membership_1, 90).cmp(&FuzzyMatch(membership_2, 80))
+            );
+        }
+
+        #[test]
+        fn fuzzy_match_fuzzy_match_same_score_is_membership_ord() {
+            let membership_1 = get_membership_1();
+            let membership_2 = get_membership_2();
+            assert_eq!(
+                membership_1.cmp(&membership_2),
+                FuzzyMatch(membership_1.clone(), 80).cmp(&FuzzyMatch(membership_2.clone(), 80))
+            );
+        }
+
         #[test]
         fn match_match_same_membership_is_membership_ord() {
             let membership_1 = get_membership_1();
@@ -235,8 +368,9 @@ mod tests {
     mod compute_member_status {
         use crate::checked_member::tests::get_member_to_check_1;
         use crate::checked_member::{CheckResult, CheckedMember};
+        use crate::event_period::EventPeriod;
         use crate::membership::Membership;
-        use crate::membership_status::MemberStatus::{Expired, Unknown, UpToDate};
+        use crate::membership_status::MemberStatus::{Expired, ExpiringSoon, Unknown, UpToDate};
         use chrono::{Days, Months, Utc};
 
         #[test]
@@ -260,9 +394,43 @@ mod tests {
                 "".to_owned(),
             );
 
-            let checked_member =
-                CheckedMember::new(get_member_to_check_1(), CheckResult::Match(membership));
-            assert_eq!(UpToDate, checked_member.compute_member_status());
+            let checked_member = CheckedMember::new(
+                get_member_to_check_1(),
+                CheckResult::Match(membership),
+                &EventPeriod::today(),
+                0,
+            );
+            assert_eq!(&UpToDate, checked_member.status());
+        }
+
+        #[test]
+        fn should_be_expiring_soon() {
+            let membership = Membership::new(
+                "1".to_owned(),
+                "".to_owned(),
+                None,
+                "".to_owned(),
+                None,
+                "".to_owned(),
+                Utc::now()
+                    .date_naive()
+                    .checked_sub_months(Months::new(12))
+                    .unwrap(),
+                Utc::now()
+                    .date_naive()
+                    .checked_add_days(Days::new(10))
+                    .unwrap(),
+                "".to_owned(),
+                "".to_owned(),
+            );
+
+            let checked_member = CheckedMember::new(
+                get_member_to_check_1(),
+                CheckResult::Match(membership),
+                &EventPeriod::today(),
+                30,
+            );
+            assert_eq!(&ExpiringSoon, checked_member.status());
         }
 
         #[test]
@@ -286,15 +454,72 @@ mod tests {
                 "".to_owned(),
             );
 
-            let checked_member =
-                CheckedMember::new(get_member_to_check_1(), CheckResult::Match(membership));
-            assert_eq!(Expired, checked_member.compute_member_status());
+            let checked_member = CheckedMember::new(
+                get_member_to_check_1(),
+                CheckResult::Match(membership),
+                &EventPeriod::today(),
+                0,
+            );
+            assert_eq!(&Expired, checked_member.status());
+        }
+
+        #[test]
+        fn should_be_unknown_when_ambiguous() {
+            let up_to_date_membership = Membership::new(
+                "1".to_owned(),
+                "".to_owned(),
+                None,
+                "1".to_owned(),
+                None,
+                "".to_owned(),
+                Utc::now()
+                    .date_naive()
+                    .checked_sub_months(Months::new(12))
+                    .unwrap(),
+                Utc::now()
+                    .date_naive()
+                    .checked_add_days(Days::new(10))
+                    .unwrap(),
+                "".to_owned(),
+                "".to_owned(),
+            );
+            let expired_membership = Membership::new(
+                "1".to_owned(),
+                "".to_owned(),
+                None,
+                "2".to_owned(),
+                None,
+                "".to_owned(),
+                Utc::now()
+                    .date_naive()
+                    .checked_sub_months(Months::new(12))
+                    .unwrap(),
+                Utc::now()
+                    .date_naive()
+                    .checked_sub_days(Days::new(10))
+                    .unwrap(),
+                "".to_owned(),
+                "".to_owned(),
+            );
+
+            let checked_member = CheckedMember::new(
+                get_member_to_check_1(),
+                CheckResult::AmbiguousMatch(vec![up_to_date_membership, expired_membership]),
+                &EventPeriod::today(),
+                0,
+            );
+            assert_eq!(&Unknown, checked_member.status());
         }
 
         #[test]
         fn should_be_unknown() {
-            let checked_member = CheckedMember::new(get_member_to_check_1(), CheckResult::NoMatch);
-            assert_eq!(Unknown, checked_member.compute_member_status());
+            let checked_member = CheckedMember::new(
+                get_member_to_check_1(),
+                CheckResult::NoMatch,
+                &EventPeriod::today(),
+                0,
+            );
+            assert_eq!(&Unknown, checked_member.status());
         }
     }
 
@@ -303,6 +528,7 @@ mod tests {
             get_member_to_check_1, get_member_to_check_2, get_membership_1, get_membership_2,
         };
         use crate::checked_member::{CheckResult, CheckedMember};
+        use crate::event_period::EventPeriod;
         use std::cmp::Ordering::{Greater, Less};
 
         #[test]
@@ -313,10 +539,18 @@ mod tests {
             let member_to_check_1 = get_member_to_check_1();
             let member_to_check_2 = get_member_to_check_2();
 
-            let checked_member_1 =
-                CheckedMember::new(member_to_check_1, CheckResult::Match(membership_1));
-            let checked_member_2 =
-                CheckedMember::new(member_to_check_2, CheckResult::Match(membership_2));
+            let checked_member_1 = CheckedMember::new(
+                member_to_check_1,
+                CheckResult::Match(membership_1),
+                &EventPeriod::today(),
+                0,
+            );
+            let checked_member_2 = CheckedMember::new(
+                member_to_check_2,
+                CheckResult::Match(membership_2),
+                &EventPeriod::today(),
+                0,
+            );
             assert_eq!(Some(Less), checked_member_1.partial_cmp(&checked_member_2))
         }
 
@@ -327,9 +561,18 @@ mod tests {
             let member_to_check_1 = get_member_to_check_1();
             let member_to_check_2 = get_member_to_check_2();
 
-            let checked_member_1 =
-                CheckedMember::new(member_to_check_1, CheckResult::Match(membership_1));
-            let checked_member_2 = CheckedMember::new(member_to_check_2, CheckResult::NoMatch);
+            let checked_member_1 = CheckedMember::new(
+                member_to_check_1,
+                CheckResult::Match(membership_1),
+                &EventPeriod::today(),
+                0,
+            );
+            let checked_member_2 = CheckedMember::new(
+                member_to_check_2,
+                CheckResult::NoMatch,
+                &EventPeriod::today(),
+                0,
+            );
             assert_eq!(
                 Some(Greater),
                 checked_member_1.partial_cmp(&checked_member_2)
@@ -343,9 +586,18 @@ mod tests {
             let member_to_check_1 = get_member_to_check_1();
             let member_to_check_2 = get_member_to_check_2();
 
-            let checked_member_1 = CheckedMember::new(member_to_check_1, CheckResult::NoMatch);
-            let checked_member_2 =
-                CheckedMember::new(member_to_check_2, CheckResult::Match(membership_2));
+            let checked_member_1 = CheckedMember::new(
+                member_to_check_1,
+                CheckResult::NoMatch,
+                &EventPeriod::today(),
+                0,
+            );
+            let checked_member_2 = CheckedMember::new(
+                member_to_check_2,
+                CheckResult::Match(membership_2),
+                &EventPeriod::today(),
+                0,
+            );
             assert_eq!(Some(Less), checked_member_1.partial_cmp(&checked_member_2))
         }
     }
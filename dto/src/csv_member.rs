@@ -1,4 +1,6 @@
 use crate::member_to_check::MemberToCheck;
+use crate::membership_import::RejectedRow;
+use chrono::NaiveDate;
 use derive_getters::Getters;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
@@ -12,6 +14,9 @@ pub struct CsvMember {
     identity: Option<String>,
     name: Option<String>,
     first_name: Option<String>,
+    birthdate: Option<NaiveDate>,
+    email_address: Option<String>,
+    club: Option<String>,
 }
 
 impl CsvMember {
@@ -20,12 +25,18 @@ impl CsvMember {
         identity: Option<String>,
         name: Option<String>,
         first_name: Option<String>,
+        birthdate: Option<NaiveDate>,
+        email_address: Option<String>,
+        club: Option<String>,
     ) -> Self {
         Self {
             membership_num,
             identity,
             name,
             first_name,
+            birthdate,
+            email_address,
+            club,
         }
     }
 }
@@ -51,12 +62,16 @@ impl MemberToCheck for CsvMember {
         self.name.clone()
     }
 
+    fn birthdate(&self) -> Option<NaiveDate> {
+        self.birthdate
+    }
+
     fn email(&self) -> Option<String> {
-        None
+        self.email_address.clone()
     }
 
     fn club(&self) -> Option<String> {
-        None
+        self.club.clone()
     }
 
     fn confirmed(&self) -> Option<bool> {
@@ -82,10 +97,27 @@ impl Ord for CsvMember {
     }
 }
 
+/// Members to check read from a spreadsheet, along with the rows that couldn't be read.
+#[derive(Debug, Getters, Serialize, Deserialize, PartialEq)]
+pub struct SpreadsheetMembers {
+    members: Vec<CsvMember>,
+    rejected_rows: Vec<RejectedRow>,
+}
+
+impl SpreadsheetMembers {
+    pub fn new(members: Vec<CsvMember>, rejected_rows: Vec<RejectedRow>) -> Self {
+        Self {
+            members,
+            rejected_rows,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::csv_member::CsvMember;
     use crate::member_to_check::MemberToCheck;
+    use chrono::NaiveDate;
 
     fn get_membership_number() -> String {
         "0123456789".to_owned()
@@ -99,6 +131,15 @@ mod tests {
     fn get_last_name() -> String {
         "Snow".to_owned()
     }
+    fn get_birthdate() -> NaiveDate {
+        NaiveDate::from_ymd_opt(1980, 2, 1).unwrap()
+    }
+    fn get_email_address() -> String {
+        "jon.snow@address.com".to_owned()
+    }
+    fn get_club() -> String {
+        "Winterfell Unicycle Club".to_owned()
+    }
 
     fn get_csv_member() -> CsvMember {
         CsvMember::new(
@@ -106,6 +147,9 @@ mod tests {
             Some(get_identity()),
             Some(get_last_name()),
             Some(get_first_name()),
+            Some(get_birthdate()),
+            Some(get_email_address()),
+            Some(get_club()),
         )
     }
 
@@ -142,16 +186,22 @@ mod tests {
         assert_eq!(Some(get_last_name()), MemberToCheck::last_name(&member));
     }
 
+    #[test]
+    fn should_get_birthdate() {
+        let member = get_csv_member();
+        assert_eq!(Some(get_birthdate()), MemberToCheck::birthdate(&member));
+    }
+
     #[test]
     fn should_get_email() {
         let member = get_csv_member();
-        assert_eq!(None, MemberToCheck::email(&member));
+        assert_eq!(Some(get_email_address()), MemberToCheck::email(&member));
     }
 
     #[test]
     fn should_get_club() {
         let member = get_csv_member();
-        assert_eq!(None, MemberToCheck::club(&member));
+        assert_eq!(Some(get_club()), MemberToCheck::club(&member));
     }
 
     #[test]
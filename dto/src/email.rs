@@ -1,19 +1,200 @@
+use crate::event_period::EventPeriod;
 use derive_getters::Getters;
 use serde::{Deserialize, Serialize};
 
+/// Someone to notify. Their membership, if known, is used to personalize the email they receive.
+#[derive(Debug, Getters, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct EmailRecipient {
+    email_address: String,
+    membership_number: Option<String>,
+}
+
+impl EmailRecipient {
+    pub fn new(email_address: String, membership_number: Option<String>) -> Self {
+        Self {
+            email_address,
+            membership_number,
+        }
+    }
+}
+
+/// An email to send to each recipient separately.
+/// Its subject and body are Tera templates, rendered for each recipient, see [PersonalizedEmail].
+/// The body is written in Markdown.
 #[derive(Getters, Serialize, Deserialize)]
 pub struct Email {
-    recipients: Vec<String>,
+    recipients: Vec<EmailRecipient>,
     subject: String,
     body: String,
+    event_name: Option<String>,
+    /// The period memberships are checked against, today if missing.
+    event_period: Option<EventPeriod>,
 }
 
 impl Email {
-    pub fn new(recipients: Vec<String>, subject: String, body: String) -> Self {
+    pub fn new(
+        recipients: Vec<EmailRecipient>,
+        subject: String,
+        body: String,
+        event_name: Option<String>,
+        event_period: Option<EventPeriod>,
+    ) -> Self {
         Self {
             recipients,
             subject,
             body,
+            event_name,
+            event_period,
+        }
+    }
+}
+
+/// The email a single recipient receives, once its templates have been rendered.
+#[derive(Debug, Getters, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersonalizedEmail {
+    recipient: String,
+    subject: String,
+    /// The plain-text alternative, for email clients which don't display HTML.
+    body: String,
+    html_body: String,
+}
+
+impl PersonalizedEmail {
+    pub fn new(recipient: String, subject: String, body: String, html_body: String) -> Self {
+        Self {
+            recipient,
+            subject,
+            body,
+            html_body,
+        }
+    }
+}
+
+/// Where an email stands in the queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EmailStatus {
+    /// Not sent yet, possibly after failed attempts which will be retried.
+    Pending,
+    Sent,
+    /// Rejected by the SMTP server, or still failing after the last retry.
+    Failed,
+}
+
+impl EmailStatus {
+    pub const ALL: [EmailStatus; 3] =
+        [EmailStatus::Pending, EmailStatus::Sent, EmailStatus::Failed];
+
+    pub fn value(&self) -> &'static str {
+        match self {
+            EmailStatus::Pending => "Pending",
+            EmailStatus::Sent => "Sent",
+            EmailStatus::Failed => "Failed",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EmailStatus::Pending => "En attente",
+            EmailStatus::Sent => "Envoyé",
+            EmailStatus::Failed => "Échec",
+        }
+    }
+
+    pub fn from_value(value: &str) -> Option<EmailStatus> {
+        EmailStatus::ALL
+            .into_iter()
+            .find(|status| status.value() == value)
+    }
+}
+
+/// Whether the email of a single recipient has been sent.
+#[derive(Debug, Getters, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecipientStatus {
+    recipient: String,
+    status: EmailStatus,
+    attempts: u32,
+    last_error: Option<String>,
+}
+
+impl RecipientStatus {
+    pub fn new(
+        recipient: String,
+        status: EmailStatus,
+        attempts: u32,
+        last_error: Option<String>,
+    ) -> Self {
+        Self {
+            recipient,
+            status,
+            attempts,
+            last_error,
+        }
+    }
+}
+
+/// Where each email of a notification stands, so that the sender can follow its progress.
+#[derive(Debug, Getters, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotificationStatus {
+    id: i32,
+    recipients: Vec<RecipientStatus>,
+}
+
+impl NotificationStatus {
+    pub fn new(id: i32, recipients: Vec<RecipientStatus>) -> Self {
+        Self { id, recipients }
+    }
+
+    /// Whether no email is left to send, be it sent or failed.
+    pub fn is_done(&self) -> bool {
+        self.recipients
+            .iter()
+            .all(|recipient| recipient.status != EmailStatus::Pending)
+    }
+
+    pub fn count(&self, status: EmailStatus) -> usize {
+        self.recipients
+            .iter()
+            .filter(|recipient| recipient.status == status)
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod from_value {
+        use crate::email::EmailStatus;
+
+        #[test]
+        fn success() {
+            for status in EmailStatus::ALL {
+                assert_eq!(Some(status), EmailStatus::from_value(status.value()));
+            }
+        }
+
+        #[test]
+        fn unknown() {
+            assert_eq!(None, EmailStatus::from_value("Unknown"));
+        }
+    }
+
+    mod is_done {
+        use crate::email::EmailStatus::{Failed, Pending, Sent};
+        use crate::email::{EmailStatus, NotificationStatus, RecipientStatus};
+
+        fn recipient(status: EmailStatus) -> RecipientStatus {
+            RecipientStatus::new("jon.doe@address.com".to_owned(), status, 1, None)
+        }
+
+        #[test]
+        fn done() {
+            let status = NotificationStatus::new(1, vec![recipient(Sent), recipient(Failed)]);
+            assert!(status.is_done());
+        }
+
+        #[test]
+        fn not_done() {
+            let status = NotificationStatus::new(1, vec![recipient(Sent), recipient(Pending)]);
+            assert!(!status.is_done());
         }
     }
 }
@@ -0,0 +1,179 @@
+use crate::membership_status::MemberStatus;
+use derive_getters::Getters;
+use serde::{Deserialize, Serialize};
+
+/// Which members a template is written for, so that it can be picked by default when notifying them.
+/// At most one template is the default for each use case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EmailTemplateUseCase {
+    Expired,
+    NoMembership,
+    ExpiringSoon,
+}
+
+impl EmailTemplateUseCase {
+    pub const ALL: [EmailTemplateUseCase; 3] = [
+        EmailTemplateUseCase::Expired,
+        EmailTemplateUseCase::NoMembership,
+        EmailTemplateUseCase::ExpiringSoon,
+    ];
+
+    pub fn value(&self) -> &'static str {
+        match self {
+            EmailTemplateUseCase::Expired => "Expired",
+            EmailTemplateUseCase::NoMembership => "NoMembership",
+            EmailTemplateUseCase::ExpiringSoon => "ExpiringSoon",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EmailTemplateUseCase::Expired => "Licence expirée",
+            EmailTemplateUseCase::NoMembership => "Aucune licence trouvée",
+            EmailTemplateUseCase::ExpiringSoon => "Licence bientôt expirée",
+        }
+    }
+
+    pub fn from_value(value: &str) -> Option<EmailTemplateUseCase> {
+        EmailTemplateUseCase::ALL
+            .into_iter()
+            .find(|use_case| use_case.value() == value)
+    }
+
+    /// Up-to-date members don't need to be notified, hence no use case.
+    pub fn from_member_status(status: &MemberStatus) -> Option<EmailTemplateUseCase> {
+        match status {
+            MemberStatus::Expired | MemberStatus::StartsAfterEvent => {
+                Some(EmailTemplateUseCase::Expired)
+            }
+            MemberStatus::ExpiringSoon => Some(EmailTemplateUseCase::ExpiringSoon),
+            MemberStatus::Unknown => Some(EmailTemplateUseCase::NoMembership),
+            MemberStatus::UpToDate => None,
+        }
+    }
+
+    /// The use case shared by most recipients, or the first one in [EmailTemplateUseCase::ALL] on a tie.
+    pub fn for_recipients(statuses: &[MemberStatus]) -> Option<EmailTemplateUseCase> {
+        let use_cases = statuses
+            .iter()
+            .filter_map(EmailTemplateUseCase::from_member_status)
+            .collect::<Vec<_>>();
+        EmailTemplateUseCase::ALL
+            .into_iter()
+            .map(|use_case| {
+                let count = use_cases.iter().filter(|other| **other == use_case).count();
+                (use_case, count)
+            })
+            .filter(|(_, count)| *count > 0)
+            .rev()
+            .max_by_key(|(_, count)| *count)
+            .map(|(use_case, _)| use_case)
+    }
+}
+
+/// A named subject and body, which may use the same variables as any notification email.
+#[derive(Debug, Getters, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmailTemplate {
+    id: i32,
+    name: String,
+    subject: String,
+    body: String,
+    use_case: Option<EmailTemplateUseCase>,
+}
+
+impl EmailTemplate {
+    pub fn new(
+        id: i32,
+        name: String,
+        subject: String,
+        body: String,
+        use_case: Option<EmailTemplateUseCase>,
+    ) -> Self {
+        Self {
+            id,
+            name,
+            subject,
+            body,
+            use_case,
+        }
+    }
+}
+
+/// A template to create, or the new content of an existing one.
+#[derive(Debug, Getters, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewEmailTemplate {
+    name: String,
+    subject: String,
+    body: String,
+    use_case: Option<EmailTemplateUseCase>,
+}
+
+impl NewEmailTemplate {
+    pub fn new(
+        name: String,
+        subject: String,
+        body: String,
+        use_case: Option<EmailTemplateUseCase>,
+    ) -> Self {
+        Self {
+            name,
+            subject,
+            body,
+            use_case,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod for_recipients {
+        use crate::email_template::EmailTemplateUseCase;
+        use crate::email_template::EmailTemplateUseCase::{Expired, ExpiringSoon, NoMembership};
+        use crate::membership_status::MemberStatus;
+
+        #[test]
+        fn most_common_use_case() {
+            let statuses = [
+                MemberStatus::ExpiringSoon,
+                MemberStatus::Expired,
+                MemberStatus::ExpiringSoon,
+                MemberStatus::UpToDate,
+            ];
+
+            assert_eq!(
+                Some(ExpiringSoon),
+                EmailTemplateUseCase::for_recipients(&statuses)
+            );
+        }
+
+        #[test]
+        fn first_use_case_on_tie() {
+            let statuses = [
+                MemberStatus::Unknown,
+                MemberStatus::ExpiringSoon,
+                MemberStatus::StartsAfterEvent,
+            ];
+
+            assert_eq!(
+                Some(Expired),
+                EmailTemplateUseCase::for_recipients(&statuses)
+            );
+            assert_eq!(
+                Some(NoMembership),
+                EmailTemplateUseCase::for_recipients(&[
+                    MemberStatus::ExpiringSoon,
+                    MemberStatus::Unknown
+                ])
+            );
+        }
+
+        #[test]
+        fn no_use_case_when_up_to_date() {
+            assert_eq!(
+                None,
+                EmailTemplateUseCase::for_recipients(&[MemberStatus::UpToDate])
+            );
+            assert_eq!(None, EmailTemplateUseCase::for_recipients(&[]));
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use chrono::{NaiveDate, Utc};
+use derive_getters::Getters;
+use serde::{Deserialize, Serialize};
+
+/// An [EventPeriod] is the period during which members are expected to have a valid membership.
+/// Both dates are included, so that a single-day event starts and ends on the same date.
+#[derive(Debug, Getters, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct EventPeriod {
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+}
+
+impl EventPeriod {
+    /// Create a new period, unless it ends before it starts.
+    pub fn new(start_date: NaiveDate, end_date: NaiveDate) -> Option<Self> {
+        if start_date <= end_date {
+            Some(Self {
+                start_date,
+                end_date,
+            })
+        } else {
+            None
+        }
+    }
+
+    pub fn single_day(date: NaiveDate) -> Self {
+        Self {
+            start_date: date,
+            end_date: date,
+        }
+    }
+
+    pub fn today() -> Self {
+        Self::single_day(Utc::now().date_naive())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::event_period::EventPeriod;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn should_create_period() {
+        let start_date = NaiveDate::from_ymd_opt(2025, 7, 1).unwrap();
+        let end_date = NaiveDate::from_ymd_opt(2025, 7, 3).unwrap();
+        let period = EventPeriod::new(start_date, end_date).unwrap();
+        assert_eq!(&start_date, period.start_date());
+        assert_eq!(&end_date, period.end_date());
+    }
+
+    #[test]
+    fn should_not_create_period_ending_before_starting() {
+        let start_date = NaiveDate::from_ymd_opt(2025, 7, 3).unwrap();
+        let end_date = NaiveDate::from_ymd_opt(2025, 7, 1).unwrap();
+        assert_eq!(None, EventPeriod::new(start_date, end_date));
+    }
+
+    #[test]
+    fn should_create_single_day_period() {
+        let date = NaiveDate::from_ymd_opt(2025, 7, 1).unwrap();
+        let period = EventPeriod::single_day(date);
+        assert_eq!(&date, period.start_date());
+        assert_eq!(&date, period.end_date());
+    }
+}
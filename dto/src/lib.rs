@@ -1,9 +1,17 @@
+pub mod api_token;
+pub mod audit_log;
+pub mod check_session;
 pub mod checked_member;
 pub mod csv_member;
 pub mod email;
+pub mod email_template;
+pub mod event_period;
+pub mod member_field;
 pub mod member_to_check;
 pub mod member_to_look_up;
 pub mod membership;
+pub mod membership_import;
 pub mod membership_status;
 pub mod uda_instance;
 pub mod uda_member;
+pub mod user;
@@ -0,0 +1,138 @@
+use diacritics::remove_diacritics;
+
+/// A field of a member to check, which a column of a CSV file or a spreadsheet can hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemberField {
+    MembershipNumber,
+    LastName,
+    FirstName,
+    Identity,
+    Birthdate,
+    EmailAddress,
+    Club,
+}
+
+impl MemberField {
+    pub const ALL: [MemberField; 7] = [
+        MemberField::MembershipNumber,
+        MemberField::LastName,
+        MemberField::FirstName,
+        MemberField::Identity,
+        MemberField::Birthdate,
+        MemberField::EmailAddress,
+        MemberField::Club,
+    ];
+
+    pub fn value(&self) -> &'static str {
+        match self {
+            MemberField::MembershipNumber => "MembershipNumber",
+            MemberField::LastName => "LastName",
+            MemberField::FirstName => "FirstName",
+            MemberField::Identity => "Identity",
+            MemberField::Birthdate => "Birthdate",
+            MemberField::EmailAddress => "EmailAddress",
+            MemberField::Club => "Club",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            MemberField::MembershipNumber => "Numéro d'adhésion",
+            MemberField::LastName => "Nom",
+            MemberField::FirstName => "Prénom",
+            MemberField::Identity => "Identité",
+            MemberField::Birthdate => "Date de naissance",
+            MemberField::EmailAddress => "Adresse email",
+            MemberField::Club => "Club",
+        }
+    }
+
+    pub fn from_value(value: &str) -> Option<MemberField> {
+        MemberField::ALL
+            .into_iter()
+            .find(|field| field.value() == value)
+    }
+
+    /// Guess which field a column holds from its header.
+    pub fn guess(header: &str) -> Option<MemberField> {
+        let header = remove_diacritics(&header.to_lowercase());
+        let contains_any = |words: &[&str]| words.iter().any(|word| header.contains(word));
+
+        if contains_any(&["telephone", "phone", "portable"]) {
+            None
+        } else if contains_any(&["mail", "courriel"]) {
+            Some(MemberField::EmailAddress)
+        } else if contains_any(&["club", "structure", "association"]) {
+            Some(MemberField::Club)
+        } else if contains_any(&["naissance", "birth"]) {
+            Some(MemberField::Birthdate)
+        } else if contains_any(&["prenom", "first"]) {
+            Some(MemberField::FirstName)
+        } else if contains_any(&["identite", "identity", "nom complet", "full name"]) {
+            Some(MemberField::Identity)
+        } else if contains_any(&["numero", "licence", "adhesion", "adherent", "membership"]) {
+            Some(MemberField::MembershipNumber)
+        } else if contains_any(&["nom", "name"]) {
+            Some(MemberField::LastName)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod guess {
+        use crate::member_field::MemberField;
+        use crate::member_field::MemberField::{
+            Birthdate, Club, EmailAddress, FirstName, Identity, LastName, MembershipNumber,
+        };
+
+        #[test]
+        fn membership_number() {
+            assert_eq!(
+                Some(MembershipNumber),
+                MemberField::guess("Numéro de licence")
+            );
+            assert_eq!(Some(MembershipNumber), MemberField::guess("N° d'adhérent"));
+        }
+
+        #[test]
+        fn last_name() {
+            assert_eq!(Some(LastName), MemberField::guess("Nom"));
+            assert_eq!(Some(LastName), MemberField::guess("Nom d'usage"));
+        }
+
+        #[test]
+        fn first_name() {
+            assert_eq!(Some(FirstName), MemberField::guess("Prénom"));
+        }
+
+        #[test]
+        fn identity() {
+            assert_eq!(Some(Identity), MemberField::guess("Identité"));
+        }
+
+        #[test]
+        fn birthdate() {
+            assert_eq!(Some(Birthdate), MemberField::guess("Date de naissance"));
+        }
+
+        #[test]
+        fn email_address() {
+            assert_eq!(Some(EmailAddress), MemberField::guess("E-mail"));
+            assert_eq!(Some(EmailAddress), MemberField::guess("Courriel"));
+        }
+
+        #[test]
+        fn club() {
+            assert_eq!(Some(Club), MemberField::guess("Nom du club"));
+        }
+
+        #[test]
+        fn unknown() {
+            assert_eq!(None, MemberField::guess("Téléphone portable"));
+            assert_eq!(None, MemberField::guess("Taille de t-shirt"));
+        }
+    }
+}
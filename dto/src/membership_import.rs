@@ -0,0 +1,92 @@
+use crate::membership::Membership;
+use chrono::NaiveDateTime;
+use derive_getters::Getters;
+use serde::{Deserialize, Serialize};
+
+/// What has changed in the known memberships during an import.
+#[derive(Debug, Getters, Default, PartialEq, Serialize, Deserialize)]
+pub struct MembershipImportDiff {
+    added: Vec<Membership>,
+    updated: Vec<Membership>,
+    removed: Vec<Membership>,
+}
+
+impl MembershipImportDiff {
+    pub fn new(added: Vec<Membership>, updated: Vec<Membership>, removed: Vec<Membership>) -> Self {
+        Self {
+            added,
+            updated,
+            removed,
+        }
+    }
+}
+
+/// A row of the imported file that couldn't be read as a membership.
+#[derive(Debug, Getters, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RejectedRow {
+    line: u64,
+    /// The header of the column at fault, when it is known.
+    column: Option<String>,
+    reason: String,
+}
+
+impl RejectedRow {
+    pub fn new(line: u64, column: Option<String>, reason: String) -> Self {
+        Self {
+            line,
+            column,
+            reason,
+        }
+    }
+}
+
+/// A past import of the memberships, as recorded in the history.
+#[derive(Debug, Getters, PartialEq, Serialize, Deserialize)]
+pub struct MembershipImport {
+    date: NaiveDateTime,
+    added_count: usize,
+    updated_count: usize,
+    removed_count: usize,
+    rejected_rows: Vec<RejectedRow>,
+}
+
+impl MembershipImport {
+    pub fn new(
+        date: NaiveDateTime,
+        added_count: usize,
+        updated_count: usize,
+        removed_count: usize,
+        rejected_rows: Vec<RejectedRow>,
+    ) -> Self {
+        Self {
+            date,
+            added_count,
+            updated_count,
+            removed_count,
+            rejected_rows,
+        }
+    }
+}
+
+/// What an import would do, computed before committing it.
+#[derive(Debug, Getters, Default, PartialEq, Serialize, Deserialize)]
+pub struct MembershipImportPreview {
+    memberships_count: usize,
+    rejected_rows: Vec<RejectedRow>,
+    /// Whether the import would be refused because too many rows have been rejected.
+    too_many_rejected_rows: bool,
+}
+
+impl MembershipImportPreview {
+    pub fn new(
+        memberships_count: usize,
+        rejected_rows: Vec<RejectedRow>,
+        too_many_rejected_rows: bool,
+    ) -> Self {
+        Self {
+            memberships_count,
+            rejected_rows,
+            too_many_rejected_rows,
+        }
+    }
+}
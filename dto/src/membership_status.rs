@@ -1,23 +1,163 @@
+use crate::event_period::EventPeriod;
 use crate::membership::Membership;
-use crate::membership_status::MemberStatus::{Expired, Unknown, UpToDate};
-use chrono::Utc;
+use crate::membership_status::MemberStatus::{
+    Expired, ExpiringSoon, StartsAfterEvent, Unknown, UpToDate,
+};
+use chrono::Days;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub enum MemberStatus {
     UpToDate,
+    /// The membership covers the event, but it ends shortly after.
+    ExpiringSoon,
+    /// The membership ends before the end of the event.
     Expired,
+    /// The membership starts after the beginning of the event.
+    StartsAfterEvent,
     Unknown,
 }
 
-pub fn compute_member_status(membership: Option<&Membership>) -> MemberStatus {
+/// Compute the status of a membership over the whole event period.
+/// The membership is up-to-date only if it covers the event, from its first day to its last one.
+/// If it ends within `expiring_soon_threshold` days after the event, it is expiring soon.
+/// A threshold of 0 means that no membership is ever expiring soon.
+pub fn compute_member_status(
+    membership: Option<&Membership>,
+    event_period: &EventPeriod,
+    expiring_soon_threshold: u64,
+) -> MemberStatus {
     match membership {
         None => Unknown,
         Some(membership) => {
-            if Utc::now().date_naive() <= *membership.end_date() {
-                UpToDate
-            } else {
+            if membership.end_date() < event_period.end_date() {
                 Expired
+            } else if membership.start_date() > event_period.start_date() {
+                StartsAfterEvent
+            } else if expiring_soon_threshold > 0
+                && event_period
+                    .end_date()
+                    .checked_add_days(Days::new(expiring_soon_threshold))
+                    .is_some_and(|threshold_date| *membership.end_date() <= threshold_date)
+            {
+                ExpiringSoon
+            } else {
+                UpToDate
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    mod compute_member_status {
+        use crate::event_period::EventPeriod;
+        use crate::membership::Membership;
+        use crate::membership_status::MemberStatus::{
+            Expired, ExpiringSoon, StartsAfterEvent, Unknown, UpToDate,
+        };
+        use crate::membership_status::compute_member_status;
+        use chrono::NaiveDate;
+
+        fn get_membership() -> Membership {
+            Membership::new(
+                "".to_owned(),
+                "".to_owned(),
+                None,
+                "".to_owned(),
+                None,
+                "".to_owned(),
+                NaiveDate::from_ymd_opt(2024, 9, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2025, 8, 31).unwrap(),
+                "".to_owned(),
+                "".to_owned(),
+            )
+        }
+
+        fn get_event_period(start_date: (i32, u32, u32), end_date: (i32, u32, u32)) -> EventPeriod {
+            EventPeriod::new(
+                NaiveDate::from_ymd_opt(start_date.0, start_date.1, start_date.2).unwrap(),
+                NaiveDate::from_ymd_opt(end_date.0, end_date.1, end_date.2).unwrap(),
+            )
+            .unwrap()
+        }
+
+        #[test]
+        fn should_be_up_to_date_when_covering_whole_event() {
+            let event_period = get_event_period((2025, 7, 1), (2025, 7, 5));
+            assert_eq!(
+                UpToDate,
+                compute_member_status(Some(&get_membership()), &event_period, 0)
+            );
+        }
+
+        #[test]
+        fn should_be_up_to_date_when_covering_event_bounds() {
+            let event_period = get_event_period((2024, 9, 1), (2025, 8, 31));
+            assert_eq!(
+                UpToDate,
+                compute_member_status(Some(&get_membership()), &event_period, 0)
+            );
+        }
+
+        #[test]
+        fn should_be_expired_when_ending_during_event() {
+            let event_period = get_event_period((2025, 8, 30), (2025, 9, 2));
+            assert_eq!(
+                Expired,
+                compute_member_status(Some(&get_membership()), &event_period, 0)
+            );
+        }
+
+        #[test]
+        fn should_be_expired_when_ending_before_event() {
+            let event_period = get_event_period((2025, 10, 1), (2025, 10, 1));
+            assert_eq!(
+                Expired,
+                compute_member_status(Some(&get_membership()), &event_period, 0)
+            );
+        }
+
+        #[test]
+        fn should_start_after_event_when_starting_during_event() {
+            let event_period = get_event_period((2024, 8, 30), (2024, 9, 2));
+            assert_eq!(
+                StartsAfterEvent,
+                compute_member_status(Some(&get_membership()), &event_period, 0)
+            );
+        }
+
+        #[test]
+        fn should_be_expiring_soon_when_ending_within_threshold() {
+            let event_period = get_event_period((2025, 8, 1), (2025, 8, 2));
+            assert_eq!(
+                ExpiringSoon,
+                compute_member_status(Some(&get_membership()), &event_period, 30)
+            );
+        }
+
+        #[test]
+        fn should_be_expiring_soon_when_ending_on_threshold() {
+            let event_period = get_event_period((2025, 8, 1), (2025, 8, 1));
+            assert_eq!(
+                ExpiringSoon,
+                compute_member_status(Some(&get_membership()), &event_period, 30)
+            );
+        }
+
+        #[test]
+        fn should_be_up_to_date_when_ending_after_threshold() {
+            let event_period = get_event_period((2025, 7, 1), (2025, 7, 5));
+            assert_eq!(
+                UpToDate,
+                compute_member_status(Some(&get_membership()), &event_period, 30)
+            );
+        }
+
+        #[test]
+        fn should_be_unknown_when_no_membership() {
+            let event_period = get_event_period((2025, 7, 1), (2025, 7, 5));
+            assert_eq!(Unknown, compute_member_status(None, &event_period, 0));
+        }
+    }
+}
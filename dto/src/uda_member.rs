@@ -1,4 +1,5 @@
 use crate::member_to_check::MemberToCheck;
+use chrono::NaiveDate;
 use derive_getters::Getters;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
@@ -11,17 +12,20 @@ pub struct UdaMember {
     membership_number: Option<String>,
     first_name: String,
     last_name: String,
+    birthdate: Option<NaiveDate>,
     email: String,
     club: Option<String>,
     confirmed: bool,
 }
 
 impl UdaMember {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         id: u16,
         membership_number: Option<String>,
         first_name: String,
         last_name: String,
+        birthdate: Option<NaiveDate>,
         email: String,
         club: Option<String>,
         confirmed: bool,
@@ -31,6 +35,7 @@ impl UdaMember {
             membership_number,
             first_name,
             last_name,
+            birthdate,
             email,
             club,
             confirmed,
@@ -77,6 +82,10 @@ impl MemberToCheck for UdaMember {
         Some(self.last_name.clone())
     }
 
+    fn birthdate(&self) -> Option<NaiveDate> {
+        self.birthdate
+    }
+
     fn email(&self) -> Option<String> {
         Some(self.email.clone())
     }
@@ -94,6 +103,7 @@ impl MemberToCheck for UdaMember {
 mod tests {
     use crate::member_to_check::MemberToCheck;
     use crate::uda_member::UdaMember;
+    use chrono::NaiveDate;
 
     fn get_id() -> u16 {
         42
@@ -107,6 +117,9 @@ mod tests {
     fn get_last_name() -> String {
         "Snow".to_owned()
     }
+    fn get_birthdate() -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(1980, 2, 1)
+    }
     fn get_email() -> String {
         "jon.snow@email.com".to_owned()
     }
@@ -123,6 +136,7 @@ mod tests {
             get_membership_number(),
             get_first_name(),
             get_last_name(),
+            get_birthdate(),
             get_email(),
             get_club(),
             get_confirmed(),
@@ -156,6 +170,12 @@ mod tests {
         assert_eq!(Some(get_last_name()), MemberToCheck::last_name(&member));
     }
 
+    #[test]
+    fn should_get_birthdate() {
+        let member = get_uda_member();
+        assert_eq!(get_birthdate(), MemberToCheck::birthdate(&member));
+    }
+
     #[test]
     fn should_get_email() {
         let member = get_uda_member();
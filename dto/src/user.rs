@@ -0,0 +1,167 @@
+use derive_getters::Getters;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+
+/// What a local user is allowed to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Role {
+    /// Manage users, browse the audit log, and do everything else.
+    Admin,
+    RefreshMemberships,
+    Check,
+    LookUp,
+    Email,
+}
+
+impl Role {
+    pub const ALL: [Role; 5] = [
+        Role::Admin,
+        Role::RefreshMemberships,
+        Role::Check,
+        Role::LookUp,
+        Role::Email,
+    ];
+
+    pub fn value(&self) -> &'static str {
+        match self {
+            Role::Admin => "Admin",
+            Role::RefreshMemberships => "RefreshMemberships",
+            Role::Check => "Check",
+            Role::LookUp => "LookUp",
+            Role::Email => "Email",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Role::Admin => "Administration",
+            Role::RefreshMemberships => "Mise à jour des licences",
+            Role::Check => "Vérification des licences",
+            Role::LookUp => "Recherche d'adhésion",
+            Role::Email => "Envoi d'email",
+        }
+    }
+
+    pub fn from_value(value: &str) -> Option<Role> {
+        Role::ALL.into_iter().find(|role| role.value() == value)
+    }
+}
+
+/// A local account, independent of Fileo.
+#[derive(Debug, Getters, Clone, PartialEq, Serialize, Deserialize)]
+pub struct User {
+    login: String,
+    roles: Vec<Role>,
+}
+
+impl User {
+    pub fn new(login: String, roles: Vec<Role>) -> Self {
+        Self { login, roles }
+    }
+
+    /// Admins have every role.
+    pub fn has_role(&self, role: Role) -> bool {
+        self.roles.contains(&Role::Admin) || self.roles.contains(&role)
+    }
+}
+
+/// Login and password of a local user.
+#[derive(Serialize, Deserialize, Getters, PartialEq, Clone, Default)]
+pub struct UserCredentials {
+    login: String,
+    password: String,
+}
+
+impl Debug for UserCredentials {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "User Credentials {{login={}, password=MASKED}}",
+            self.login
+        )
+    }
+}
+
+impl UserCredentials {
+    pub fn new(login: String, password: String) -> Self {
+        Self { login, password }
+    }
+}
+
+/// A local account to create, along with its initial password.
+#[derive(Serialize, Deserialize, Getters, PartialEq, Clone)]
+pub struct NewUser {
+    credentials: UserCredentials,
+    roles: Vec<Role>,
+}
+
+impl Debug for NewUser {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "New User {{credentials={:?}, roles={:?}}}",
+            self.credentials, self.roles
+        )
+    }
+}
+
+impl NewUser {
+    pub fn new(credentials: UserCredentials, roles: Vec<Role>) -> Self {
+        Self { credentials, roles }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod from_value {
+        use crate::user::Role;
+
+        #[test]
+        fn success() {
+            for role in Role::ALL {
+                assert_eq!(Some(role), Role::from_value(role.value()));
+            }
+        }
+
+        #[test]
+        fn unknown() {
+            assert_eq!(None, Role::from_value("Unknown"));
+        }
+    }
+
+    mod has_role {
+        use crate::user::{Role, User};
+
+        #[test]
+        fn only_granted_roles() {
+            let user = User::new("jon".to_owned(), vec![Role::Check]);
+
+            assert!(user.has_role(Role::Check));
+            assert!(!user.has_role(Role::LookUp));
+            assert!(!user.has_role(Role::Admin));
+        }
+
+        #[test]
+        fn admin_has_every_role() {
+            let user = User::new("jon".to_owned(), vec![Role::Admin]);
+
+            for role in Role::ALL {
+                assert!(user.has_role(role));
+            }
+        }
+    }
+
+    mod debug {
+        use crate::user::{NewUser, Role, UserCredentials};
+
+        #[test]
+        fn mask_password() {
+            let new_user = NewUser::new(
+                UserCredentials::new("jon".to_owned(), "secret".to_owned()),
+                vec![Role::Check],
+            );
+
+            assert!(!format!("{new_user:?}").contains("secret"));
+        }
+    }
+}
@@ -0,0 +1,2 @@
+DROP INDEX normalized_membership_number_start_date_index;
+DROP TABLE membership_import;
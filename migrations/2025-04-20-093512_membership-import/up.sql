@@ -0,0 +1,10 @@
+CREATE TABLE membership_import
+(
+    id            INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    date          VARCHAR NOT NULL,
+    added_count   INTEGER NOT NULL,
+    updated_count INTEGER NOT NULL,
+    removed_count INTEGER NOT NULL
+);
+
+CREATE INDEX normalized_membership_number_start_date_index ON membership (normalized_membership_number, start_date);
@@ -0,0 +1 @@
+DROP TABLE membership_import_rejected_row;
@@ -0,0 +1,8 @@
+CREATE TABLE membership_import_rejected_row
+(
+    id                   INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    membership_import_id INTEGER NOT NULL REFERENCES membership_import (id) ON DELETE CASCADE,
+    line                 BIGINT  NOT NULL,
+    column_name          VARCHAR,
+    reason               VARCHAR NOT NULL
+);
@@ -0,0 +1,15 @@
+-- Results are stored as JSON, along with the members they relate to,
+-- so that a session can be reopened as it was when it has been run.
+CREATE TABLE check_session
+(
+    id            INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    date          VARCHAR NOT NULL,
+    user_login    VARCHAR NOT NULL,
+    source        VARCHAR NOT NULL,
+    uda_instance  VARCHAR,
+    start_date    VARCHAR NOT NULL,
+    end_date      VARCHAR NOT NULL,
+    checked_count INTEGER NOT NULL,
+    valid_count   INTEGER NOT NULL,
+    results       VARCHAR NOT NULL
+);
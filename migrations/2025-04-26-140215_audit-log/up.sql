@@ -0,0 +1,26 @@
+-- Targets are stored as a JSON array.
+CREATE TABLE audit_log
+(
+    id          INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    date        VARCHAR NOT NULL,
+    actor_login VARCHAR NOT NULL,
+    action      VARCHAR NOT NULL,
+    targets     VARCHAR NOT NULL
+);
+
+CREATE INDEX audit_log_date_index ON audit_log (date);
+
+-- The audit log is append-only: entries can't be altered nor removed.
+CREATE TRIGGER audit_log_no_update
+    BEFORE UPDATE
+    ON audit_log
+BEGIN
+    SELECT RAISE(ABORT, 'audit_log is append-only');
+END;
+
+CREATE TRIGGER audit_log_no_delete
+    BEFORE DELETE
+    ON audit_log
+BEGIN
+    SELECT RAISE(ABORT, 'audit_log is append-only');
+END;
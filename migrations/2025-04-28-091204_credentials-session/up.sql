@@ -0,0 +1,8 @@
+-- Credentials are encrypted with the secret key of the server.
+CREATE TABLE credentials_session
+(
+    id              VARCHAR NOT NULL PRIMARY KEY,
+    kind            VARCHAR NOT NULL,
+    expiration_date VARCHAR NOT NULL,
+    credentials     VARCHAR NOT NULL
+);
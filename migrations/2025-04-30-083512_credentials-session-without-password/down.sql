@@ -0,0 +1 @@
+-- Deleted sessions can't be restored.
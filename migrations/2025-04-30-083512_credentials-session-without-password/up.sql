@@ -0,0 +1,3 @@
+-- Sessions used to hold the passwords of users. They now hold the cookies of upstream sessions instead.
+-- Users only have to log in again.
+DELETE FROM credentials_session;
@@ -0,0 +1,9 @@
+-- Local accounts, independent of Fileo.
+-- Roles are stored as a JSON array.
+CREATE TABLE user_account
+(
+    id            INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    login         VARCHAR NOT NULL UNIQUE,
+    password_hash VARCHAR NOT NULL,
+    roles         VARCHAR NOT NULL
+);
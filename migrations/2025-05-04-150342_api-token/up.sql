@@ -0,0 +1,13 @@
+-- Long-lived tokens for scripted access to the API.
+-- Only the SHA-256 hash of each token is stored, and scopes are stored as a JSON array of roles.
+-- Revoked tokens are kept, so that they can still be told apart in the audit log.
+CREATE TABLE api_token
+(
+    id              INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    name            VARCHAR NOT NULL,
+    token_hash      VARCHAR NOT NULL UNIQUE,
+    scopes          VARCHAR NOT NULL,
+    created_by      VARCHAR NOT NULL,
+    creation_date   VARCHAR NOT NULL,
+    revocation_date VARCHAR
+);
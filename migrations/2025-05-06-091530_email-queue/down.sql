@@ -0,0 +1,3 @@
+DROP TABLE queued_email;
+DROP TABLE notification_attachment;
+DROP TABLE notification;
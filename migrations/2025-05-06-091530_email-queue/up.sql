@@ -0,0 +1,36 @@
+-- Notifications are sent in the background: each recipient gets their own email, queued until it is sent.
+CREATE TABLE notification
+(
+    id            INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    created_by    VARCHAR NOT NULL,
+    creation_date VARCHAR NOT NULL
+);
+
+-- Files attached to every email of a notification.
+CREATE TABLE notification_attachment
+(
+    id              INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    notification_id INTEGER NOT NULL REFERENCES notification (id) ON DELETE CASCADE,
+    file_name       VARCHAR NOT NULL,
+    content_type    VARCHAR NOT NULL,
+    content         BLOB    NOT NULL
+);
+
+-- Emails are pending until they are sent, or until they fail for good.
+-- Failed attempts are retried from `next_attempt_date`.
+CREATE TABLE queued_email
+(
+    id                INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    notification_id   INTEGER NOT NULL REFERENCES notification (id) ON DELETE CASCADE,
+    recipient         VARCHAR NOT NULL,
+    subject           VARCHAR NOT NULL,
+    body              VARCHAR NOT NULL,
+    html_body         VARCHAR NOT NULL,
+    status            VARCHAR NOT NULL,
+    attempts          INTEGER NOT NULL DEFAULT 0,
+    next_attempt_date VARCHAR NOT NULL,
+    last_error        VARCHAR,
+    sent_date         VARCHAR
+);
+
+CREATE INDEX queued_email_status_index ON queued_email (status, next_attempt_date);
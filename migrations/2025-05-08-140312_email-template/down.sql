@@ -0,0 +1 @@
+DROP TABLE email_template;
\ No newline at end of file
@@ -0,0 +1,45 @@
+-- Named subjects and bodies, so that organizers don't have to retype them for every notification.
+-- At most one template is the default for each use case, e.g. expired memberships.
+CREATE TABLE email_template
+(
+    id       INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    name     VARCHAR NOT NULL UNIQUE,
+    subject  VARCHAR NOT NULL,
+    body     VARCHAR NOT NULL,
+    use_case VARCHAR UNIQUE
+);
+
+INSERT INTO email_template (name, subject, body, use_case)
+VALUES ('Licence expirée',
+        'Votre licence CNM/UNSLL a expiré',
+        'Bonjour{% if first_name %} {{ first_name }}{% endif %},
+
+Sauf erreur de notre part, vous souhaitez prochainement participer à un événement de monocycle officiel{% if event_name %} ({{ event_name }}){% endif %}. Pour ce faire, vous devez posséder une licence CNM/UNSLL à jour. Cependant, la vôtre{% if membership_number %} (n°{{ membership_number }}){% endif %} est {{ status }}{% if end_date %} : elle se termine le {{ end_date }}{% endif %}.
+Afin de pouvoir participer à cet événement, nous vous invitons à vous rapprocher de votre club ou à souscrire à une licence individuelle. Pour cette dernière option, la procédure se trouve ici : <https://monocycle.info/licence-sportive/>.
+
+Nous nous tenons à votre disposition pour toute question,
+Sportivement,
+La Commission Nationale Monocycle',
+        'Expired'),
+       ('Aucune licence trouvée',
+        'Nous n''avons pas trouvé votre licence CNM/UNSLL',
+        'Bonjour,
+
+Sauf erreur de notre part, vous souhaitez prochainement participer à un événement de monocycle officiel{% if event_name %} ({{ event_name }}){% endif %}. Pour ce faire, vous devez posséder une licence CNM/UNSLL à jour. Cependant, nous n''avons trouvé aucune licence à votre nom.
+Si vous en avez une, merci de nous indiquer son numéro en réponse à cet email. Sinon, nous vous invitons à vous rapprocher de votre club ou à souscrire à une licence individuelle. Pour cette dernière option, la procédure se trouve ici : <https://monocycle.info/licence-sportive/>.
+
+Nous nous tenons à votre disposition pour toute question,
+Sportivement,
+La Commission Nationale Monocycle',
+        'NoMembership'),
+       ('Licence bientôt expirée',
+        'Votre licence CNM/UNSLL expire bientôt',
+        'Bonjour{% if first_name %} {{ first_name }}{% endif %},
+
+Vous participez prochainement à un événement de monocycle officiel{% if event_name %} ({{ event_name }}){% endif %}, et votre licence CNM/UNSLL{% if membership_number %} (n°{{ membership_number }}){% endif %} est bien valide pour celui-ci. Elle se termine cependant peu après{% if end_date %}, le {{ end_date }}{% endif %}.
+Pensez à la renouveler auprès de votre club, ou en souscrivant à une licence individuelle, afin de pouvoir participer aux prochains événements. Pour cette dernière option, la procédure se trouve ici : <https://monocycle.info/licence-sportive/>.
+
+Nous nous tenons à votre disposition pour toute question,
+Sportivement,
+La Commission Nationale Monocycle',
+        'ExpiringSoon');
@@ -0,0 +1,124 @@
+use super::Result;
+use crate::database::model::api_token::{ApiToken, NewApiToken};
+use crate::database::schema::api_token::dsl::api_token;
+use crate::database::schema::api_token::{id, revocation_date, token_hash};
+use chrono::Utc;
+use diesel::prelude::*;
+use dto::user::Role;
+
+/// Store a new token, only knowing its hash, then return its ID.
+pub fn insert(
+    connection: &mut SqliteConnection,
+    name: &str,
+    hash: &str,
+    scopes: &[Role],
+    created_by: &str,
+) -> Result<i32> {
+    let new_token = NewApiToken::new(name, hash, scopes, created_by)?;
+    let token_id = diesel::insert_into(api_token)
+        .values(&new_token)
+        .returning(id)
+        .get_result::<i32>(connection)?;
+
+    debug!("Created API token [id: {token_id}, name: {name}, created_by: {created_by}]");
+
+    Ok(token_id)
+}
+
+/// Retrieve all tokens, revoked ones included, the most recent first.
+pub fn retrieve_all(connection: &mut SqliteConnection) -> Result<Vec<dto::api_token::ApiToken>> {
+    api_token
+        .order(id.desc())
+        .select(ApiToken::as_select())
+        .load(connection)?
+        .into_iter()
+        .map(dto::api_token::ApiToken::try_from)
+        .collect()
+}
+
+/// Retrieve the token matching the hash, unless it has been revoked.
+pub fn retrieve_active_by_hash(
+    connection: &mut SqliteConnection,
+    hash: &str,
+) -> Result<Option<dto::api_token::ApiToken>> {
+    api_token
+        .filter(token_hash.eq(hash))
+        .filter(revocation_date.is_null())
+        .select(ApiToken::as_select())
+        .first(connection)
+        .optional()?
+        .map(dto::api_token::ApiToken::try_from)
+        .transpose()
+}
+
+/// Return false if no such token exists, or if it has already been revoked.
+pub fn revoke(connection: &mut SqliteConnection, token_id: i32) -> Result<bool> {
+    let updated_count = diesel::update(
+        api_token
+            .filter(id.eq(token_id))
+            .filter(revocation_date.is_null()),
+    )
+    .set(revocation_date.eq(Utc::now().naive_local().to_string()))
+    .execute(connection)?;
+
+    Ok(updated_count > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::dao::api_token::{insert, retrieve_active_by_hash, retrieve_all, revoke};
+    use crate::database::with_temp_database;
+    use dto::user::Role::{Check, LookUp};
+
+    #[test]
+    fn insert_and_retrieve() {
+        with_temp_database(|pool| {
+            let mut connection = pool.get().unwrap();
+            let first_id =
+                insert(&mut connection, "inscriptions", "hash_1", &[Check], "jon").unwrap();
+            let second_id = insert(
+                &mut connection,
+                "recherche",
+                "hash_2",
+                &[Check, LookUp],
+                "jon",
+            )
+            .unwrap();
+
+            let tokens = retrieve_all(&mut connection).unwrap();
+            assert_eq!(2, tokens.len());
+            assert_eq!(&second_id, tokens[0].id());
+            assert_eq!("recherche", tokens[0].name());
+            assert_eq!(&vec![Check, LookUp], tokens[0].scopes());
+            assert_eq!("jon", tokens[0].created_by());
+            assert!(!tokens[0].is_revoked());
+            assert_eq!(&first_id, tokens[1].id());
+
+            let token = retrieve_active_by_hash(&mut connection, "hash_1").unwrap();
+            assert_eq!(Some(first_id), token.map(|token| *token.id()));
+            assert_eq!(
+                None,
+                retrieve_active_by_hash(&mut connection, "unknown").unwrap()
+            );
+        })
+    }
+
+    #[test]
+    fn revoke_token() {
+        with_temp_database(|pool| {
+            let mut connection = pool.get().unwrap();
+            let token_id =
+                insert(&mut connection, "inscriptions", "hash", &[Check], "jon").unwrap();
+
+            assert!(revoke(&mut connection, token_id).unwrap());
+            assert!(!revoke(&mut connection, token_id).unwrap());
+            assert!(!revoke(&mut connection, token_id + 1).unwrap());
+
+            assert_eq!(
+                None,
+                retrieve_active_by_hash(&mut connection, "hash").unwrap()
+            );
+            assert!(retrieve_all(&mut connection).unwrap()[0].is_revoked());
+        })
+    }
+}
@@ -0,0 +1,145 @@
+use super::Result;
+use crate::database::model::audit_log::{AuditLogEntry, NewAuditLogEntry};
+use crate::database::schema::audit_log::dsl::audit_log;
+use crate::database::schema::audit_log::{action, actor_login, date, id};
+use chrono::Days;
+use diesel::prelude::*;
+use dto::audit_log::{AuditAction, AuditLogFilter};
+
+/// Entries beyond this count are not retrieved, the filter should be narrowed down instead.
+const MAX_RETRIEVED_ENTRIES: i64 = 1000;
+
+/// Append an entry to the audit log.
+pub fn insert(
+    connection: &mut SqliteConnection,
+    actor: &str,
+    audited_action: AuditAction,
+    targets: &[String],
+) -> Result<()> {
+    let new_entry = NewAuditLogEntry::new(actor, audited_action, targets)?;
+    diesel::insert_into(audit_log)
+        .values(&new_entry)
+        .execute(connection)?;
+
+    debug!(
+        "Recorded audit log entry [actor: {actor}, action: {}, targets: {}]",
+        audited_action.value(),
+        targets.len()
+    );
+
+    Ok(())
+}
+
+/// Retrieve entries matching the filter, the most recent first.
+pub fn retrieve(
+    connection: &mut SqliteConnection,
+    filter: &AuditLogFilter,
+) -> Result<Vec<dto::audit_log::AuditLogEntry>> {
+    let mut query = audit_log.into_boxed();
+    if let Some(actor) = filter.actor() {
+        query = query.filter(actor_login.like(format!("%{actor}%")));
+    }
+    if let Some(audited_action) = filter.action() {
+        query = query.filter(action.eq(audited_action.value()));
+    }
+    if let Some(from) = filter.from() {
+        query = query.filter(date.ge(from.to_string()));
+    }
+    if let Some(to) = filter
+        .to()
+        .as_ref()
+        .and_then(|to| to.checked_add_days(Days::new(1)))
+    {
+        query = query.filter(date.lt(to.to_string()));
+    }
+
+    query
+        .order(id.desc())
+        .limit(MAX_RETRIEVED_ENTRIES)
+        .select(AuditLogEntry::as_select())
+        .load(connection)?
+        .into_iter()
+        .map(dto::audit_log::AuditLogEntry::try_from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    mod retrieve {
+        use crate::database::dao::audit_log::{insert, retrieve};
+        use crate::database::with_temp_database;
+        use chrono::Utc;
+        use diesel::RunQueryDsl;
+        use dto::audit_log::AuditAction::{ConfirmUdaMembers, LookMemberUp};
+        use dto::audit_log::AuditLogFilter;
+
+        #[test]
+        fn success() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                insert(&mut connection, "jon", LookMemberUp, &["123456".to_owned()]).unwrap();
+                insert(
+                    &mut connection,
+                    "jonette",
+                    ConfirmUdaMembers,
+                    &["1".to_owned(), "2".to_owned()],
+                )
+                .unwrap();
+
+                let entries = retrieve(&mut connection, &AuditLogFilter::default()).unwrap();
+                assert_eq!(2, entries.len());
+                assert_eq!("jonette", entries[0].actor());
+                assert_eq!(&ConfirmUdaMembers, entries[0].action());
+                assert_eq!(&vec!["1".to_owned(), "2".to_owned()], entries[0].targets());
+                assert_eq!("jon", entries[1].actor());
+            })
+        }
+
+        #[test]
+        fn filter() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                insert(&mut connection, "jon", LookMemberUp, &[]).unwrap();
+                insert(&mut connection, "jon", ConfirmUdaMembers, &[]).unwrap();
+                insert(&mut connection, "jonette", LookMemberUp, &[]).unwrap();
+                let today = Utc::now().date_naive();
+
+                let filter = AuditLogFilter::new(Some("jon".to_owned()), None, None, None);
+                assert_eq!(3, retrieve(&mut connection, &filter).unwrap().len());
+
+                let filter = AuditLogFilter::new(Some("ette".to_owned()), None, None, None);
+                assert_eq!(1, retrieve(&mut connection, &filter).unwrap().len());
+
+                let filter = AuditLogFilter::new(None, Some(LookMemberUp), None, None);
+                assert_eq!(2, retrieve(&mut connection, &filter).unwrap().len());
+
+                let filter = AuditLogFilter::new(None, None, Some(today), Some(today));
+                assert_eq!(3, retrieve(&mut connection, &filter).unwrap().len());
+
+                let yesterday = today.pred_opt().unwrap();
+                let filter = AuditLogFilter::new(None, None, None, Some(yesterday));
+                assert!(retrieve(&mut connection, &filter).unwrap().is_empty());
+            })
+        }
+
+        #[test]
+        fn append_only() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                insert(&mut connection, "jon", LookMemberUp, &[]).unwrap();
+
+                assert!(
+                    diesel::delete(crate::database::schema::audit_log::table)
+                        .execute(&mut connection)
+                        .is_err()
+                );
+                assert_eq!(
+                    1,
+                    retrieve(&mut connection, &AuditLogFilter::default())
+                        .unwrap()
+                        .len()
+                );
+            })
+        }
+    }
+}
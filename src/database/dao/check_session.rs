@@ -0,0 +1,289 @@
+use super::Result;
+use crate::database::model::check_session::{
+    CheckSession, CheckSessionSummary, NewCheckSession, UDA_SOURCE,
+};
+use crate::database::schema::check_session::dsl::check_session;
+use crate::database::schema::check_session::{end_date, id, source, start_date, uda_instance};
+use diesel::prelude::*;
+use dto::check_session::CheckSessionResults;
+use dto::event_period::EventPeriod;
+
+/// Record a check session, so that it can be reopened later on.
+/// Return the ID of the new session.
+pub fn insert(
+    connection: &mut SqliteConnection,
+    user_login: &str,
+    event_period: &EventPeriod,
+    results: &CheckSessionResults,
+) -> Result<i32> {
+    let new_check_session = NewCheckSession::new(user_login, event_period, results)?;
+    let session_id = diesel::insert_into(check_session)
+        .values(&new_check_session)
+        .returning(id)
+        .get_result::<i32>(connection)?;
+
+    debug!("Recorded check session [id: {session_id}, user: {user_login}]");
+
+    Ok(session_id)
+}
+
+/// Retrieve all check sessions, the most recent first.
+pub fn retrieve_all(
+    connection: &mut SqliteConnection,
+) -> Result<Vec<dto::check_session::CheckSessionSummary>> {
+    check_session
+        .order(id.desc())
+        .select(CheckSessionSummary::as_select())
+        .load(connection)?
+        .into_iter()
+        .map(dto::check_session::CheckSessionSummary::try_from)
+        .collect()
+}
+
+/// Retrieve a check session along with its results, if it exists.
+pub fn retrieve(
+    connection: &mut SqliteConnection,
+    session_id: i32,
+) -> Result<Option<dto::check_session::CheckSession>> {
+    check_session
+        .filter(id.eq(session_id))
+        .select(CheckSession::as_select())
+        .first(connection)
+        .optional()?
+        .map(dto::check_session::CheckSession::try_from)
+        .transpose()
+}
+
+/// Retrieve the last checks of an event for members imported from a UDA instance,
+/// the most recent first.
+pub fn retrieve_latest_for_uda_instance(
+    connection: &mut SqliteConnection,
+    instance: &str,
+    event_period: &EventPeriod,
+    count: i64,
+) -> Result<Vec<dto::check_session::CheckSession>> {
+    check_session
+        .filter(source.eq(UDA_SOURCE))
+        .filter(uda_instance.eq(instance))
+        .filter(start_date.eq(event_period.start_date().to_string()))
+        .filter(end_date.eq(event_period.end_date().to_string()))
+        .order(id.desc())
+        .limit(count)
+        .select(CheckSession::as_select())
+        .load(connection)?
+        .into_iter()
+        .map(dto::check_session::CheckSession::try_from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use dto::check_session::CheckSessionResults;
+    use dto::checked_member::{CheckResult, CheckedMember};
+    use dto::csv_member::CsvMember;
+    use dto::event_period::EventPeriod;
+    use dto::membership::tests::get_expected_membership;
+    use dto::uda_member::UdaMember;
+
+    fn event_period() -> EventPeriod {
+        EventPeriod::new(
+            NaiveDate::from_ymd_opt(2025, 7, 5).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 7, 6).unwrap(),
+        )
+        .unwrap()
+    }
+
+    fn csv_results() -> CheckSessionResults {
+        CheckSessionResults::Csv(vec![
+            CheckedMember::new(
+                CsvMember::new(
+                    Some("123456".to_owned()),
+                    None,
+                    Some("Doe".to_owned()),
+                    Some("Jon".to_owned()),
+                    None,
+                    None,
+                    None,
+                ),
+                CheckResult::Match(get_expected_membership()),
+                &event_period(),
+                30,
+            ),
+            CheckedMember::new(
+                CsvMember::new(
+                    None,
+                    Some("Jonette Snow".to_owned()),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                ),
+                CheckResult::NoMatch,
+                &event_period(),
+                30,
+            ),
+        ])
+    }
+
+    fn uda_results() -> CheckSessionResults {
+        CheckSessionResults::Uda(
+            "test".to_owned(),
+            vec![CheckedMember::new(
+                UdaMember::new(
+                    1,
+                    Some("123456".to_owned()),
+                    "Jon".to_owned(),
+                    "Doe".to_owned(),
+                    None,
+                    "jon.doe@email.com".to_owned(),
+                    None,
+                    true,
+                ),
+                CheckResult::Match(get_expected_membership()),
+                &event_period(),
+                30,
+            )],
+        )
+    }
+
+    mod retrieve_all {
+        use crate::database::dao::check_session::tests::{csv_results, event_period, uda_results};
+        use crate::database::dao::check_session::{insert, retrieve_all};
+        use crate::database::with_temp_database;
+        use dto::check_session::CheckSource;
+
+        #[test]
+        fn none() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+
+                assert!(retrieve_all(&mut connection).unwrap().is_empty());
+            })
+        }
+
+        #[test]
+        fn most_recent_first() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                let first_id =
+                    insert(&mut connection, "first", &event_period(), &csv_results()).unwrap();
+                let second_id =
+                    insert(&mut connection, "second", &event_period(), &uda_results()).unwrap();
+
+                let sessions = retrieve_all(&mut connection).unwrap();
+                assert_eq!(2, sessions.len());
+
+                let second_session = &sessions[0];
+                assert_eq!(&second_id, second_session.id());
+                assert_eq!("second", second_session.user());
+                assert_eq!(
+                    &CheckSource::Uda("test".to_owned()),
+                    second_session.source()
+                );
+                assert_eq!(&event_period(), second_session.event_period());
+
+                let first_session = &sessions[1];
+                assert_eq!(&first_id, first_session.id());
+                assert_eq!(&CheckSource::Csv, first_session.source());
+                assert_eq!(&2, first_session.checked_count());
+            })
+        }
+    }
+
+    mod retrieve {
+        use crate::database::dao::check_session::tests::{csv_results, event_period, uda_results};
+        use crate::database::dao::check_session::{insert, retrieve};
+        use crate::database::with_temp_database;
+
+        #[test]
+        fn csv() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                let session_id =
+                    insert(&mut connection, "user", &event_period(), &csv_results()).unwrap();
+
+                let session = retrieve(&mut connection, session_id).unwrap().unwrap();
+                assert_eq!(&csv_results(), session.results());
+                assert_eq!(&session_id, session.summary().id());
+            })
+        }
+
+        #[test]
+        fn uda() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                let session_id =
+                    insert(&mut connection, "user", &event_period(), &uda_results()).unwrap();
+
+                let session = retrieve(&mut connection, session_id).unwrap().unwrap();
+                assert_eq!(&uda_results(), session.results());
+            })
+        }
+
+        #[test]
+        fn none() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+
+                assert_eq!(None, retrieve(&mut connection, 42).unwrap());
+            })
+        }
+    }
+
+    mod retrieve_latest_for_uda_instance {
+        use crate::database::dao::check_session::tests::{csv_results, event_period, uda_results};
+        use crate::database::dao::check_session::{insert, retrieve_latest_for_uda_instance};
+        use crate::database::with_temp_database;
+        use chrono::NaiveDate;
+        use dto::check_session::CheckSessionResults;
+        use dto::event_period::EventPeriod;
+
+        #[test]
+        fn success() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                insert(&mut connection, "user", &event_period(), &uda_results()).unwrap();
+                let second_id =
+                    insert(&mut connection, "user", &event_period(), &uda_results()).unwrap();
+                let third_id =
+                    insert(&mut connection, "user", &event_period(), &uda_results()).unwrap();
+                insert(&mut connection, "user", &event_period(), &csv_results()).unwrap();
+                let other_instance_results = CheckSessionResults::Uda("other".to_owned(), vec![]);
+                insert(
+                    &mut connection,
+                    "user",
+                    &event_period(),
+                    &other_instance_results,
+                )
+                .unwrap();
+                let other_event_period =
+                    EventPeriod::single_day(NaiveDate::from_ymd_opt(2025, 7, 5).unwrap());
+                insert(&mut connection, "user", &other_event_period, &uda_results()).unwrap();
+
+                let sessions =
+                    retrieve_latest_for_uda_instance(&mut connection, "test", &event_period(), 2)
+                        .unwrap();
+                let ids: Vec<&i32> = sessions
+                    .iter()
+                    .map(|session| session.summary().id())
+                    .collect();
+                assert_eq!(vec![&third_id, &second_id], ids);
+            })
+        }
+
+        #[test]
+        fn none() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+
+                assert!(
+                    retrieve_latest_for_uda_instance(&mut connection, "test", &event_period(), 2)
+                        .unwrap()
+                        .is_empty()
+                );
+            })
+        }
+    }
+}
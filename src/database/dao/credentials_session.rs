@@ -0,0 +1,138 @@
+use super::Result;
+use crate::database::schema::credentials_session::dsl::credentials_session;
+use crate::database::schema::credentials_session::*;
+use chrono::{NaiveDateTime, Utc};
+use diesel::prelude::*;
+
+/// Store encrypted credentials, replacing those already stored with the same ID.
+pub fn upsert(
+    connection: &mut SqliteConnection,
+    session_id: &str,
+    session_kind: &str,
+    expiration: &NaiveDateTime,
+    encrypted_credentials: &str,
+) -> Result<()> {
+    diesel::replace_into(credentials_session)
+        .values((
+            id.eq(session_id),
+            kind.eq(session_kind),
+            expiration_date.eq(expiration.to_string()),
+            credentials.eq(encrypted_credentials),
+        ))
+        .execute(connection)?;
+
+    Ok(())
+}
+
+/// Retrieve encrypted credentials, unless they have expired.
+pub fn retrieve(
+    connection: &mut SqliteConnection,
+    session_id: &str,
+    session_kind: &str,
+) -> Result<Option<String>> {
+    let now = Utc::now().naive_local().to_string();
+    let result = credentials_session
+        .filter(id.eq(session_id))
+        .filter(kind.eq(session_kind))
+        .filter(expiration_date.gt(now))
+        .select(credentials)
+        .first::<String>(connection)
+        .optional()?;
+
+    Ok(result)
+}
+
+/// Revoke credentials, if they exist.
+pub fn delete(
+    connection: &mut SqliteConnection,
+    session_id: &str,
+    session_kind: &str,
+) -> Result<()> {
+    diesel::delete(
+        credentials_session
+            .filter(id.eq(session_id))
+            .filter(kind.eq(session_kind)),
+    )
+    .execute(connection)?;
+
+    Ok(())
+}
+
+/// Remove all expired credentials.
+/// Return how many of them have been removed.
+pub fn delete_expired(connection: &mut SqliteConnection) -> Result<usize> {
+    let now = Utc::now().naive_local().to_string();
+    let deleted_count =
+        diesel::delete(credentials_session.filter(expiration_date.le(now))).execute(connection)?;
+
+    Ok(deleted_count)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::dao::credentials_session::{delete, delete_expired, retrieve, upsert};
+    use crate::database::with_temp_database;
+    use chrono::{Duration, Utc};
+
+    #[test]
+    fn store_and_retrieve() {
+        with_temp_database(|pool| {
+            let mut connection = pool.get().unwrap();
+            let expiration = Utc::now().naive_local() + Duration::days(1);
+            upsert(&mut connection, "id", "Fileo", &expiration, "first").unwrap();
+            upsert(&mut connection, "id", "Fileo", &expiration, "second").unwrap();
+
+            assert_eq!(
+                Some("second".to_owned()),
+                retrieve(&mut connection, "id", "Fileo").unwrap()
+            );
+            assert_eq!(None, retrieve(&mut connection, "id", "UDA").unwrap());
+            assert_eq!(None, retrieve(&mut connection, "other", "Fileo").unwrap());
+        })
+    }
+
+    #[test]
+    fn revoke() {
+        with_temp_database(|pool| {
+            let mut connection = pool.get().unwrap();
+            let expiration = Utc::now().naive_local() + Duration::days(1);
+            upsert(&mut connection, "id", "Fileo", &expiration, "credentials").unwrap();
+
+            delete(&mut connection, "id", "Fileo").unwrap();
+            assert_eq!(None, retrieve(&mut connection, "id", "Fileo").unwrap());
+        })
+    }
+
+    #[test]
+    fn expire() {
+        with_temp_database(|pool| {
+            let mut connection = pool.get().unwrap();
+            let expiration = Utc::now().naive_local() - Duration::seconds(1);
+            upsert(
+                &mut connection,
+                "expired",
+                "Fileo",
+                &expiration,
+                "credentials",
+            )
+            .unwrap();
+            let expiration = Utc::now().naive_local() + Duration::days(1);
+            upsert(
+                &mut connection,
+                "valid",
+                "Fileo",
+                &expiration,
+                "credentials",
+            )
+            .unwrap();
+
+            assert_eq!(None, retrieve(&mut connection, "expired", "Fileo").unwrap());
+            assert_eq!(1, delete_expired(&mut connection).unwrap());
+            assert!(
+                retrieve(&mut connection, "valid", "Fileo")
+                    .unwrap()
+                    .is_some()
+            );
+        })
+    }
+}
@@ -0,0 +1,197 @@
+use super::Result;
+use crate::database::model::email_template::{EmailTemplate, NewEmailTemplate};
+use crate::database::schema::email_template::dsl::email_template;
+use crate::database::schema::email_template::{id, name, use_case};
+use diesel::prelude::*;
+use dto::email_template::EmailTemplateUseCase;
+
+/// Retrieve all templates, sorted by name.
+pub fn retrieve_all(
+    connection: &mut SqliteConnection,
+) -> Result<Vec<dto::email_template::EmailTemplate>> {
+    email_template
+        .order(name.asc())
+        .select(EmailTemplate::as_select())
+        .load(connection)?
+        .into_iter()
+        .map(dto::email_template::EmailTemplate::try_from)
+        .collect()
+}
+
+/// Whether another template than `excluded_id` already has this name.
+pub fn is_name_taken(
+    connection: &mut SqliteConnection,
+    template_name: &str,
+    excluded_id: Option<i32>,
+) -> Result<bool> {
+    let count = email_template
+        .filter(name.eq(template_name))
+        .filter(id.ne(excluded_id.unwrap_or_default()))
+        .count()
+        .get_result::<i64>(connection)?;
+
+    Ok(count > 0)
+}
+
+/// Store a new template, then return its ID.
+/// If it is the default for a use case, then the previous default for it no longer is.
+pub fn insert(
+    connection: &mut SqliteConnection,
+    template: &dto::email_template::NewEmailTemplate,
+) -> Result<i32> {
+    connection.transaction(|connection| {
+        if let Some(template_use_case) = template.use_case() {
+            clear_default(connection, template_use_case)?;
+        }
+        let template_id = diesel::insert_into(email_template)
+            .values(&NewEmailTemplate::new(template))
+            .returning(id)
+            .get_result::<i32>(connection)?;
+
+        debug!(
+            "Created email template [id: {template_id}, name: {}]",
+            template.name()
+        );
+        Ok(template_id)
+    })
+}
+
+/// Replace the content of a template. Return false if no such template exists.
+/// If it becomes the default for a use case, then the previous default for it no longer is.
+pub fn update(
+    connection: &mut SqliteConnection,
+    template_id: i32,
+    template: &dto::email_template::NewEmailTemplate,
+) -> Result<bool> {
+    connection.transaction(|connection| {
+        if let Some(template_use_case) = template.use_case() {
+            clear_default(connection, template_use_case)?;
+        }
+        let updated_count = diesel::update(email_template.filter(id.eq(template_id)))
+            .set(&NewEmailTemplate::new(template))
+            .execute(connection)?;
+
+        Ok(updated_count > 0)
+    })
+}
+
+/// Return false if no such template exists.
+pub fn delete(connection: &mut SqliteConnection, template_id: i32) -> Result<bool> {
+    let deleted_count =
+        diesel::delete(email_template.filter(id.eq(template_id))).execute(connection)?;
+
+    Ok(deleted_count > 0)
+}
+
+fn clear_default(
+    connection: &mut SqliteConnection,
+    template_use_case: &EmailTemplateUseCase,
+) -> Result<()> {
+    diesel::update(email_template.filter(use_case.eq(template_use_case.value())))
+        .set(use_case.eq(None::<String>))
+        .execute(connection)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::dao::email_template::{
+        delete, insert, is_name_taken, retrieve_all, update,
+    };
+    use crate::database::with_temp_database;
+    use dto::email_template::EmailTemplateUseCase::{Expired, ExpiringSoon, NoMembership};
+    use dto::email_template::{EmailTemplateUseCase, NewEmailTemplate};
+
+    fn new_template(name: &str, use_case: Option<EmailTemplateUseCase>) -> NewEmailTemplate {
+        NewEmailTemplate::new(
+            name.to_owned(),
+            "Votre licence".to_owned(),
+            "Bonjour {{ first_name }}".to_owned(),
+            use_case,
+        )
+    }
+
+    #[test]
+    fn default_templates() {
+        with_temp_database(|pool| {
+            let mut connection = pool.get().unwrap();
+
+            let templates = retrieve_all(&mut connection).unwrap();
+            let mut use_cases = templates
+                .iter()
+                .filter_map(|template| *template.use_case())
+                .map(|use_case| use_case.value())
+                .collect::<Vec<_>>();
+            use_cases.sort();
+            assert_eq!(
+                vec![Expired.value(), ExpiringSoon.value(), NoMembership.value()],
+                use_cases
+            );
+        })
+    }
+
+    #[test]
+    fn insert_and_retrieve() {
+        with_temp_database(|pool| {
+            let mut connection = pool.get().unwrap();
+            let template_id =
+                insert(&mut connection, &new_template("Relance", Some(Expired))).unwrap();
+
+            let templates = retrieve_all(&mut connection).unwrap();
+            let template = templates
+                .iter()
+                .find(|template| *template.id() == template_id)
+                .unwrap();
+            assert_eq!("Relance", template.name());
+            assert_eq!("Bonjour {{ first_name }}", template.body());
+            assert_eq!(&Some(Expired), template.use_case());
+            // The previous default for expired memberships no longer is
+            assert_eq!(
+                1,
+                templates
+                    .iter()
+                    .filter(|template| *template.use_case() == Some(Expired))
+                    .count()
+            );
+
+            assert!(is_name_taken(&mut connection, "Relance", None).unwrap());
+            assert!(!is_name_taken(&mut connection, "Relance", Some(template_id)).unwrap());
+            assert!(!is_name_taken(&mut connection, "Autre", None).unwrap());
+        })
+    }
+
+    #[test]
+    fn update_and_delete() {
+        with_temp_database(|pool| {
+            let mut connection = pool.get().unwrap();
+            let template_id =
+                insert(&mut connection, &new_template("Relance", Some(Expired))).unwrap();
+
+            assert!(update(&mut connection, template_id, &new_template("Rappel", None)).unwrap());
+            assert!(
+                !update(
+                    &mut connection,
+                    template_id + 1,
+                    &new_template("Rappel", None)
+                )
+                .unwrap()
+            );
+            let templates = retrieve_all(&mut connection).unwrap();
+            let template = templates
+                .iter()
+                .find(|template| *template.id() == template_id)
+                .unwrap();
+            assert_eq!("Rappel", template.name());
+            assert_eq!(&None, template.use_case());
+
+            assert!(delete(&mut connection, template_id).unwrap());
+            assert!(!delete(&mut connection, template_id).unwrap());
+            assert!(
+                !retrieve_all(&mut connection)
+                    .unwrap()
+                    .iter()
+                    .any(|template| *template.id() == template_id)
+            );
+        })
+    }
+}
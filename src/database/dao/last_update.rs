@@ -11,6 +11,8 @@ use std::fmt::{Display, Formatter};
 pub enum UpdatableElement {
     Memberships,
     UdaInstances,
+    MembershipsSyncSuccess,
+    MembershipsSyncFailure,
 }
 
 impl Display for UpdatableElement {
@@ -36,6 +38,19 @@ pub fn get_last_update(
     }
 }
 
+/// Record an automatic synchronization of the memberships, whether it has succeeded or not.
+pub fn record_memberships_sync(
+    connection: &mut SqliteConnection,
+    succeeded: bool,
+) -> Result<NaiveDateTime> {
+    let updatable_element = if succeeded {
+        UpdatableElement::MembershipsSyncSuccess
+    } else {
+        UpdatableElement::MembershipsSyncFailure
+    };
+    update(connection, &updatable_element)
+}
+
 pub(super) fn update(
     connection: &mut SqliteConnection,
     updatable_element: &UpdatableElement,
@@ -137,6 +152,51 @@ mod tests {
         }
     }
 
+    mod record_memberships_sync {
+        use crate::database::dao::last_update::{
+            UpdatableElement, get_last_update, record_memberships_sync,
+        };
+        use crate::database::with_temp_database;
+
+        #[test]
+        fn success() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+
+                let time = record_memberships_sync(&mut connection, true).unwrap();
+                assert_eq!(
+                    Some(time),
+                    get_last_update(&mut connection, &UpdatableElement::MembershipsSyncSuccess)
+                        .unwrap()
+                );
+                assert_eq!(
+                    None,
+                    get_last_update(&mut connection, &UpdatableElement::MembershipsSyncFailure)
+                        .unwrap()
+                );
+            })
+        }
+
+        #[test]
+        fn failure() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+
+                let time = record_memberships_sync(&mut connection, false).unwrap();
+                assert_eq!(
+                    Some(time),
+                    get_last_update(&mut connection, &UpdatableElement::MembershipsSyncFailure)
+                        .unwrap()
+                );
+                assert_eq!(
+                    None,
+                    get_last_update(&mut connection, &UpdatableElement::MembershipsSyncSuccess)
+                        .unwrap()
+                );
+            })
+        }
+    }
+
     mod update {
         use crate::database::dao::last_update::UpdatableElement;
         use crate::database::dao::last_update::tests::test_update_element;
@@ -1,9 +1,12 @@
 use super::Result;
 use crate::database::dao::last_update::UpdatableElement;
-use crate::database::model::membership::Membership;
+use crate::database::error::DatabaseError::EmptyMembershipImport;
+use crate::database::model::membership::{Membership, NewMembership};
 use crate::tools::normalize;
 use diesel::prelude::*;
 use diesel::{QueryDsl, RunQueryDsl, SelectableHelper};
+use dto::membership_import::{MembershipImportDiff, RejectedRow};
+use std::collections::HashMap;
 
 pub fn retrieve_memberships(
     connection: &mut SqliteConnection,
@@ -24,8 +27,18 @@ pub fn retrieve_memberships(
     Ok(memberships)
 }
 
-fn delete_all(connection: &mut SqliteConnection) -> Result<usize> {
-    let count = diesel::delete(crate::database::schema::membership::table).execute(connection)?;
+fn delete_all_by_id(connection: &mut SqliteConnection, ids: &[i32]) -> Result<usize> {
+    use crate::database::schema::membership::id;
+
+    // Limit of 32766 parameters in a query for SQLite > 3.32.0.
+    let ids = ids.chunks(32766);
+
+    let mut count = 0;
+    for chunk in ids {
+        count += diesel::delete(crate::database::schema::membership::table)
+            .filter(id.eq_any(chunk))
+            .execute(connection)?;
+    }
 
     Ok(count)
 }
@@ -34,37 +47,9 @@ fn insert_all(
     connection: &mut SqliteConnection,
     memberships: &[dto::membership::Membership],
 ) -> Result<usize> {
-    use crate::database::schema::membership::*;
-
     let memberships = memberships
         .iter()
-        .map(|membership| {
-            (
-                last_name.eq(membership.name().clone()),
-                first_name.eq(membership.first_name().clone()),
-                birthdate.eq(membership.birthdate().map(|b| b.to_string())),
-                membership_number.eq(membership.membership_number().clone()),
-                cell_number.eq(membership.cell_number().clone()),
-                email_address.eq(membership.email_address().clone()),
-                start_date.eq(membership.start_date().to_string()),
-                end_date.eq(membership.end_date().to_string()),
-                club.eq(membership.club().clone()),
-                structure_code.eq(membership.structure_code().clone()),
-                normalized_membership_number.eq(normalize(membership.membership_number())),
-                normalized_last_name.eq(normalize(membership.name())),
-                normalized_first_name.eq(normalize(membership.first_name())),
-                normalized_last_name_first_name.eq(format!(
-                    "{}{}",
-                    normalize(membership.name()),
-                    normalize(membership.first_name()),
-                )),
-                normalized_first_name_last_name.eq(format!(
-                    "{}{}",
-                    normalize(membership.first_name()),
-                    normalize(membership.name()),
-                )),
-            )
-        })
+        .map(NewMembership::from)
         .collect::<Vec<_>>();
     // Limit of 32766 parameters in a query for SQLite > 3.32.0.
     // As each line has 17 parameters, we have a theoretic maximum of 32 766 / 17 = 1927,4.
@@ -82,16 +67,112 @@ fn insert_all(
     Ok(count)
 }
 
-/// Delete all known memberships and replace them with new ones.
-/// Return the number of deleted memberships and the number of inserted memberships.
-pub fn replace_memberships(
+fn update(
+    connection: &mut SqliteConnection,
+    membership_id: i32,
+    membership: &dto::membership::Membership,
+) -> Result<usize> {
+    let count = diesel::update(crate::database::schema::membership::table.find(membership_id))
+        .set(NewMembership::from(membership))
+        .execute(connection)?;
+
+    Ok(count)
+}
+
+/// Same as [`import_memberships_with_rejected_rows`], for a file whose rows could all be read.
+#[cfg(test)]
+pub fn import_memberships(
+    connection: &mut SqliteConnection,
+    memberships: &[dto::membership::Membership],
+) -> Result<MembershipImportDiff> {
+    import_memberships_with_rejected_rows(connection, memberships, &[])
+}
+
+/// Import memberships, so that known memberships match the new ones.
+/// Memberships are identified by their membership number and their start date:
+/// new ones are inserted, known ones are updated if they have changed, and missing ones are removed.
+/// The import is recorded in the history, along with the rows of the imported file that couldn't be read,
+/// and what has changed is returned.
+///
+/// The whole import runs within a transaction:
+/// if anything fails, the previous memberships are kept untouched.
+/// An import without any membership is refused while memberships are known,
+/// as it most likely comes from a broken file rather than from every membership being gone.
+pub fn import_memberships_with_rejected_rows(
     connection: &mut SqliteConnection,
     memberships: &[dto::membership::Membership],
-) -> Result<(usize, usize)> {
-    let deleted_count = delete_all(connection)?;
-    let inserted_count = insert_all(connection, memberships)?;
+    rejected_rows: &[RejectedRow],
+) -> Result<MembershipImportDiff> {
+    connection.transaction(|connection| apply_import(connection, memberships, rejected_rows))
+}
+
+fn apply_import(
+    connection: &mut SqliteConnection,
+    memberships: &[dto::membership::Membership],
+    rejected_rows: &[RejectedRow],
+) -> Result<MembershipImportDiff> {
+    let mut known_memberships: HashMap<(String, String), Vec<Membership>> = HashMap::new();
+    for known_membership in crate::database::schema::membership::dsl::membership
+        .order(crate::database::schema::membership::id)
+        .select(Membership::as_select())
+        .load(connection)?
+    {
+        let key = (
+            known_membership.normalized_membership_number().to_owned(),
+            known_membership.start_date().to_owned(),
+        );
+        known_memberships
+            .entry(key)
+            .or_default()
+            .push(known_membership);
+    }
+    if memberships.is_empty() && !known_memberships.is_empty() {
+        return Err(EmptyMembershipImport);
+    }
+
+    let mut added = vec![];
+    let mut updated = vec![];
+    for membership in memberships {
+        let key = (
+            normalize(membership.membership_number()),
+            membership.start_date().to_string(),
+        );
+        let known_membership = known_memberships
+            .get_mut(&key)
+            .and_then(|known_memberships| known_memberships.pop());
+        match known_membership {
+            None => added.push(membership.clone()),
+            Some(known_membership) => {
+                let known_membership_id = known_membership.id();
+                if dto::membership::Membership::try_from(known_membership)? != *membership {
+                    update(connection, known_membership_id, membership)?;
+                    updated.push(membership.clone());
+                }
+            }
+        }
+    }
+
+    let mut removed_memberships = known_memberships
+        .into_values()
+        .flatten()
+        .collect::<Vec<_>>();
+    removed_memberships.sort_by_key(Membership::id);
+    let removed_ids = removed_memberships
+        .iter()
+        .map(Membership::id)
+        .collect::<Vec<_>>();
+    delete_all_by_id(connection, &removed_ids)?;
+    let removed = removed_memberships
+        .into_iter()
+        .map(dto::membership::Membership::try_from)
+        .collect::<Result<Vec<_>>>()?;
+
+    insert_all(connection, &added)?;
+
+    let diff = MembershipImportDiff::new(added, updated, removed);
+    super::membership_import::insert(connection, &diff, rejected_rows)?;
 
-    Ok((deleted_count, inserted_count))
+    Ok(diff)
 }
 
 pub(crate) mod find {
@@ -307,11 +388,29 @@ pub(crate) mod find {
             convert_to_dto(results)
         }
 
+        fn convert_to_dto(results: Vec<Membership>) -> Result<Option<dto::membership::Membership>> {
+            if let Some(membership) = results.first().cloned() {
+                Ok(Some(dto::membership::Membership::try_from(membership)?))
+            } else {
+                Ok(None)
+            }
+        }
+    }
+
+    /// Find memberships of every distinct person matching the criteria.
+    /// As a person may have several memberships, only their most recent one is kept.
+    /// People are told apart by their membership number.
+    pub(crate) mod per_person {
+        use super::super::Result;
+        use crate::database::model::membership::Membership;
+        use diesel::SqliteConnection;
+        use std::collections::BTreeSet;
+
         pub fn by_identity(
             connection: &mut SqliteConnection,
             identity: &str,
-        ) -> Result<Option<dto::membership::Membership>> {
-            let results = super::by_identity(connection, identity, Some(1))?;
+        ) -> Result<Vec<dto::membership::Membership>> {
+            let results = super::by_identity(connection, identity, None)?;
             convert_to_dto(results)
         }
 
@@ -319,18 +418,122 @@ pub(crate) mod find {
             connection: &mut SqliteConnection,
             last_name: &str,
             first_name: &str,
-        ) -> Result<Option<dto::membership::Membership>> {
-            let results =
-                super::by_last_name_first_name(connection, last_name, first_name, Some(1))?;
-
+        ) -> Result<Vec<dto::membership::Membership>> {
+            let results = super::by_last_name_first_name(connection, last_name, first_name, None)?;
             convert_to_dto(results)
         }
 
-        fn convert_to_dto(results: Vec<Membership>) -> Result<Option<dto::membership::Membership>> {
-            if let Some(membership) = results.first().cloned() {
-                Ok(Some(dto::membership::Membership::try_from(membership)?))
-            } else {
-                Ok(None)
+        /// Results are expected to be sorted, so that the first membership of each person is the one to keep.
+        fn convert_to_dto(results: Vec<Membership>) -> Result<Vec<dto::membership::Membership>> {
+            Ok({
+                let mut known_membership_numbers = BTreeSet::new();
+                let mut memberships = Vec::new();
+
+                for membership in results {
+                    if known_membership_numbers
+                        .insert(membership.normalized_membership_number().to_owned())
+                    {
+                        memberships.push(dto::membership::Membership::try_from(membership)?);
+                    }
+                }
+
+                memberships
+            })
+        }
+    }
+
+    /// Find memberships whose names are close to the searched ones, though not exactly the same.
+    /// As SQLite can't compute such a similarity, all memberships are loaded and compared in memory.
+    pub(crate) mod similar {
+        use super::super::Result;
+        use crate::database::dao::membership::find::get_order;
+        use crate::database::model::membership::Membership;
+        use crate::tools::{normalize, similarity};
+        use diesel::{QueryDsl, RunQueryDsl, SelectableHelper, SqliteConnection};
+
+        /// Every membership, sorted, to compare names with.
+        /// They are loaded on the first search only,
+        /// so that checking many members doesn't reload the whole table for each of them.
+        #[derive(Default)]
+        pub struct Candidates(Option<Vec<Membership>>);
+
+        impl Candidates {
+            fn get(&mut self, connection: &mut SqliteConnection) -> Result<&[Membership]> {
+                if self.0.is_none() {
+                    let memberships = crate::database::schema::membership::dsl::membership
+                        .order(get_order())
+                        .select(Membership::as_select())
+                        .load(connection)?;
+                    self.0 = Some(memberships);
+                }
+                Ok(self.0.as_deref().unwrap_or_default())
+            }
+        }
+
+        /// Find the membership whose names are the most similar to the given ones.
+        /// Return it alongside its similarity score if this score is at least `min_score`.
+        pub fn by_last_name_first_name(
+            connection: &mut SqliteConnection,
+            candidates: &mut Candidates,
+            last_name: &str,
+            first_name: &str,
+            min_score: u8,
+        ) -> Result<Option<(dto::membership::Membership, u8)>> {
+            let searched_name = format!("{}{}", normalize(last_name), normalize(first_name));
+            find_most_similar(candidates.get(connection)?, min_score, |membership| {
+                similarity(&searched_name, membership.normalized_last_name_first_name())
+            })
+        }
+
+        /// Find the membership whose identity is the most similar to the given one,
+        /// be it formatted as `last_name first_name` or `first_name last_name`.
+        /// Return it alongside its similarity score if this score is at least `min_score`.
+        pub fn by_identity(
+            connection: &mut SqliteConnection,
+            candidates: &mut Candidates,
+            identity: &str,
+            min_score: u8,
+        ) -> Result<Option<(dto::membership::Membership, u8)>> {
+            let searched_identity = normalize(identity);
+            find_most_similar(candidates.get(connection)?, min_score, |membership| {
+                similarity(
+                    &searched_identity,
+                    membership.normalized_last_name_first_name(),
+                )
+                .max(similarity(
+                    &searched_identity,
+                    membership.normalized_first_name_last_name(),
+                ))
+            })
+        }
+
+        fn find_most_similar<F>(
+            candidates: &[Membership],
+            min_score: u8,
+            compute_score: F,
+        ) -> Result<Option<(dto::membership::Membership, u8)>>
+        where
+            F: Fn(&Membership) -> u8,
+        {
+            // As candidates are already sorted, the first best-scoring membership is the one to keep.
+            let mut best_match: Option<(&Membership, u8)> = None;
+            for membership in candidates {
+                let score = compute_score(membership);
+                if score >= min_score
+                    && best_match
+                        .as_ref()
+                        .is_none_or(|(_, best_score)| score > *best_score)
+                {
+                    best_match = Some((membership, score));
+                }
+            }
+
+            match best_match {
+                Some((membership, score)) => Ok(Some((
+                    dto::membership::Membership::try_from(membership.clone())?,
+                    score,
+                ))),
+                None => Ok(None),
             }
         }
     }
@@ -402,29 +605,48 @@ mod tests {
         }
     }
 
-    mod delete_all {
-        use crate::database::dao::membership::delete_all;
+    mod delete_all_by_id {
+        use crate::database::dao::membership::delete_all_by_id;
+        use crate::database::dao::membership::retrieve_memberships;
         use crate::database::dao::membership::tests::populate_db;
+        use crate::database::model::membership::Membership;
         use crate::database::with_temp_database;
+        use diesel::{QueryDsl, RunQueryDsl, SelectableHelper};
 
         #[test]
         fn success() {
             with_temp_database(|pool| {
                 let mut connection = pool.get().unwrap();
                 let expected_memberships = populate_db(&mut connection);
+                let ids = crate::database::schema::membership::dsl::membership
+                    .select(Membership::as_select())
+                    .load(&mut connection)
+                    .unwrap()
+                    .iter()
+                    .map(Membership::id)
+                    .collect::<Vec<_>>();
 
-                let result = delete_all(&mut connection).unwrap();
-                assert_eq!(expected_memberships.len(), result);
+                let result = delete_all_by_id(&mut connection, &ids[..1]).unwrap();
+                assert_eq!(1, result);
+                assert_eq!(
+                    expected_memberships[1..].to_vec(),
+                    retrieve_memberships(&mut connection).unwrap()
+                );
             })
         }
 
         #[test]
-        fn success_when_already_empty() {
+        fn success_when_no_id() {
             with_temp_database(|pool| {
                 let mut connection = pool.get().unwrap();
+                let expected_memberships = populate_db(&mut connection);
 
-                let result = delete_all(&mut connection).unwrap();
+                let result = delete_all_by_id(&mut connection, &[]).unwrap();
                 assert_eq!(0, result);
+                assert_eq!(
+                    expected_memberships,
+                    retrieve_memberships(&mut connection).unwrap()
+                );
             })
         }
     }
@@ -498,48 +720,192 @@ mod tests {
         }
     }
 
-    mod replace_memberships {
+    mod import_memberships {
         use crate::database::dao::last_update::{UpdatableElement, get_last_update};
-        use crate::database::dao::membership::replace_memberships;
         use crate::database::dao::membership::tests::populate_db;
-        use crate::database::model::membership::Membership;
+        use crate::database::dao::membership::{import_memberships, retrieve_memberships};
+        use crate::database::dao::membership_import::retrieve_last;
+        use crate::database::error::DatabaseError::EmptyMembershipImport;
         use crate::database::with_temp_database;
-        use crate::membership::tests::{jon_doe_previous_membership, other_jon_doe};
-        use diesel::{QueryDsl, RunQueryDsl, SelectableHelper};
+        use crate::membership::tests::{
+            jon_doe, jon_doe_previous_membership, jonette_snow, other_jon_doe,
+        };
+        use chrono::{Months, Utc};
+        use diesel::RunQueryDsl;
+        use dto::membership::Membership;
+        use dto::membership_import::MembershipImportDiff;
+
+        fn jon_doe_with_new_email_address() -> Membership {
+            let jon_doe = jon_doe();
+            Membership::new(
+                jon_doe.name().to_owned(),
+                jon_doe.first_name().to_owned(),
+                *jon_doe.birthdate(),
+                jon_doe.membership_number().to_owned(),
+                jon_doe.cell_number().to_owned(),
+                "jon.doe@new-address.com".to_owned(),
+                *jon_doe.start_date(),
+                *jon_doe.end_date(),
+                jon_doe.club().to_owned(),
+                jon_doe.structure_code().to_owned(),
+            )
+        }
 
         #[test]
-        fn success() {
+        fn success_when_empty() {
             with_temp_database(|pool| {
                 let mut connection = pool.get().unwrap();
-                let initial_memberships = populate_db(&mut connection);
-                let expected_memberships = vec![jon_doe_previous_membership(), other_jon_doe()];
+                let memberships = vec![jon_doe(), jonette_snow()];
 
-                let result = replace_memberships(&mut connection, &expected_memberships).unwrap();
+                let result = import_memberships(&mut connection, &memberships).unwrap();
                 assert_eq!(
-                    (initial_memberships.len(), expected_memberships.len()),
+                    MembershipImportDiff::new(memberships.clone(), vec![], vec![]),
                     result
                 );
+                assert_eq!(memberships, retrieve_memberships(&mut connection).unwrap());
+            })
+        }
 
-                let results = crate::database::schema::membership::dsl::membership
-                    .select(Membership::as_select())
-                    .load(&mut connection)
-                    .unwrap();
-
-                let memberships = {
-                    let mut memberships = Vec::new();
-                    for result in results {
-                        memberships.push(dto::membership::Membership::try_from(result).unwrap());
-                    }
-
-                    memberships
-                };
+        #[test]
+        fn should_add_update_and_remove() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                populate_db(&mut connection);
+                let memberships = vec![jon_doe_with_new_email_address(), other_jon_doe()];
 
-                assert_eq!(expected_memberships, memberships);
+                let result = import_memberships(&mut connection, &memberships).unwrap();
+                assert_eq!(
+                    MembershipImportDiff::new(
+                        vec![other_jon_doe()],
+                        vec![jon_doe_with_new_email_address()],
+                        vec![jonette_snow()],
+                    ),
+                    result
+                );
+                assert_eq!(memberships, retrieve_memberships(&mut connection).unwrap());
                 get_last_update(&mut connection, &UpdatableElement::Memberships)
                     .unwrap()
                     .unwrap(); // The last_update table should have been updated
             })
         }
+
+        #[test]
+        fn should_not_change_anything_when_same_memberships() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                let memberships = populate_db(&mut connection);
+
+                let result = import_memberships(&mut connection, &memberships).unwrap();
+                assert_eq!(MembershipImportDiff::default(), result);
+                assert_eq!(memberships, retrieve_memberships(&mut connection).unwrap());
+            })
+        }
+
+        /// A membership is identified by its membership number and its start date,
+        /// so that a renewed membership doesn't replace the previous one.
+        #[test]
+        fn should_keep_previous_membership() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                populate_db(&mut connection);
+                let memberships = vec![jon_doe_previous_membership(), jon_doe(), jonette_snow()];
+
+                let result = import_memberships(&mut connection, &memberships).unwrap();
+                assert_eq!(
+                    MembershipImportDiff::new(vec![jon_doe_previous_membership()], vec![], vec![]),
+                    result
+                );
+                assert_eq!(
+                    vec![jon_doe(), jonette_snow(), jon_doe_previous_membership()],
+                    retrieve_memberships(&mut connection).unwrap()
+                );
+            })
+        }
+
+        /// If a chunk fails to be inserted, nothing should be changed.
+        #[test]
+        fn should_keep_previous_memberships_when_failing() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                let initial_memberships = populate_db(&mut connection);
+                diesel::sql_query(
+                    "CREATE TRIGGER fail_on_insert BEFORE INSERT ON membership \
+                    WHEN NEW.membership_number = 'failing' \
+                    BEGIN SELECT RAISE(ABORT, 'Simulated failure'); END;",
+                )
+                .execute(&mut connection)
+                .unwrap();
+                // The failing membership is in the second chunk, after the first one has been inserted.
+                let memberships = (0..3000)
+                    .map(|i| {
+                        let membership_number = if i == 2500 {
+                            "failing".to_owned()
+                        } else {
+                            i.to_string()
+                        };
+                        Membership::new(
+                            i.to_string(),
+                            i.to_string(),
+                            None,
+                            membership_number,
+                            None,
+                            i.to_string(),
+                            Utc::now()
+                                .date_naive()
+                                .checked_sub_months(Months::new(12))
+                                .unwrap(),
+                            Utc::now().date_naive(),
+                            i.to_string(),
+                            i.to_string(),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+
+                import_memberships(&mut connection, &memberships).unwrap_err();
+
+                assert_eq!(
+                    initial_memberships,
+                    retrieve_memberships(&mut connection).unwrap()
+                );
+                assert_eq!(
+                    None,
+                    get_last_update(&mut connection, &UpdatableElement::Memberships).unwrap()
+                );
+                assert_eq!(None, retrieve_last(&mut connection).unwrap());
+            })
+        }
+
+        #[test]
+        fn should_refuse_empty_import() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                let initial_memberships = populate_db(&mut connection);
+
+                let result = import_memberships(&mut connection, &[]);
+
+                assert_eq!(Err(EmptyMembershipImport), result);
+                assert_eq!(
+                    initial_memberships,
+                    retrieve_memberships(&mut connection).unwrap()
+                );
+                assert_eq!(None, retrieve_last(&mut connection).unwrap());
+            })
+        }
+
+        #[test]
+        fn should_record_import() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                populate_db(&mut connection);
+
+                import_memberships(&mut connection, &[jon_doe_with_new_email_address()]).unwrap();
+
+                let last_import = retrieve_last(&mut connection).unwrap().unwrap();
+                assert_eq!(0, *last_import.added_count());
+                assert_eq!(1, *last_import.updated_count());
+                assert_eq!(1, *last_import.removed_count());
+            })
+        }
     }
 
     mod find {
@@ -1149,11 +1515,16 @@ mod tests {
                     });
                 }
             }
+        }
 
+        mod per_person {
             mod by_identity {
-                use crate::database::dao::membership::find::first::by_identity;
+                use crate::database::dao::membership::find::per_person::by_identity;
                 use crate::database::dao::membership::insert_all;
                 use crate::database::with_temp_database;
+                use crate::membership::tests::{
+                    jon_doe, jon_doe_previous_membership, jonette_snow, other_jon_doe,
+                };
                 use chrono::{Months, Utc};
 
                 #[test]
@@ -1180,8 +1551,8 @@ mod tests {
 
                         insert_all(&mut connection, &[membership.clone()]).unwrap();
 
-                        let result = by_identity(&mut connection, &identity).unwrap().unwrap();
-                        assert_eq!(membership, result);
+                        let result = by_identity(&mut connection, &identity).unwrap();
+                        assert_eq!(vec![membership], result);
                     });
                 }
 
@@ -1209,8 +1580,8 @@ mod tests {
 
                         insert_all(&mut connection, &[membership.clone()]).unwrap();
 
-                        let result = by_identity(&mut connection, &identity).unwrap().unwrap();
-                        assert_eq!(membership, result);
+                        let result = by_identity(&mut connection, &identity).unwrap();
+                        assert_eq!(vec![membership], result);
                     });
                 }
 
@@ -1257,8 +1628,28 @@ mod tests {
 
                         insert_all(&mut connection, &[membership.clone(), old_membership]).unwrap();
 
-                        let result = by_identity(&mut connection, &identity).unwrap().unwrap();
-                        assert_eq!(membership, result);
+                        let result = by_identity(&mut connection, &identity).unwrap();
+                        assert_eq!(vec![membership], result);
+                    });
+                }
+
+                #[test]
+                fn find_every_homonym() {
+                    with_temp_database(|pool| {
+                        let mut connection = pool.get().unwrap();
+                        insert_all(
+                            &mut connection,
+                            &[
+                                jon_doe(),
+                                jon_doe_previous_membership(),
+                                other_jon_doe(),
+                                jonette_snow(),
+                            ],
+                        )
+                        .unwrap();
+
+                        let result = by_identity(&mut connection, "Jon Doe").unwrap();
+                        assert_eq!(vec![jon_doe(), other_jon_doe()], result);
                     });
                 }
 
@@ -1273,15 +1664,18 @@ mod tests {
                         insert_all(&mut connection, &[]).unwrap();
 
                         let result = by_identity(&mut connection, &identity).unwrap();
-                        assert_eq!(None, result);
+                        assert!(result.is_empty());
                     });
                 }
             }
 
             mod by_last_name_first_name {
-                use crate::database::dao::membership::find::first::by_last_name_first_name;
+                use crate::database::dao::membership::find::per_person::by_last_name_first_name;
                 use crate::database::dao::membership::insert_all;
                 use crate::database::with_temp_database;
+                use crate::membership::tests::{
+                    jon_doe, jon_doe_previous_membership, jonette_snow, other_jon_doe,
+                };
                 use chrono::{Months, Utc};
 
                 #[test]
@@ -1309,9 +1703,8 @@ mod tests {
 
                         let result =
                             by_last_name_first_name(&mut connection, &last_name, &first_name)
-                                .unwrap()
                                 .unwrap();
-                        assert_eq!(membership, result);
+                        assert_eq!(vec![membership], result);
                     });
                 }
 
@@ -1359,9 +1752,29 @@ mod tests {
 
                         let result =
                             by_last_name_first_name(&mut connection, &last_name, &first_name)
-                                .unwrap()
                                 .unwrap();
-                        assert_eq!(membership, result);
+                        assert_eq!(vec![membership], result);
+                    });
+                }
+
+                #[test]
+                fn find_every_homonym() {
+                    with_temp_database(|pool| {
+                        let mut connection = pool.get().unwrap();
+                        insert_all(
+                            &mut connection,
+                            &[
+                                jon_doe(),
+                                jon_doe_previous_membership(),
+                                other_jon_doe(),
+                                jonette_snow(),
+                            ],
+                        )
+                        .unwrap();
+
+                        let result =
+                            by_last_name_first_name(&mut connection, "Doe", "Jon").unwrap();
+                        assert_eq!(vec![jon_doe(), other_jon_doe()], result);
                     });
                 }
 
@@ -1377,6 +1790,117 @@ mod tests {
                         let result =
                             by_last_name_first_name(&mut connection, &last_name, &first_name)
                                 .unwrap();
+                        assert!(result.is_empty());
+                    });
+                }
+            }
+        }
+
+        mod similar {
+            mod by_last_name_first_name {
+                use crate::database::dao::membership::find::similar::{
+                    Candidates, by_last_name_first_name,
+                };
+                use crate::database::dao::membership::insert_all;
+                use crate::database::with_temp_database;
+                use crate::membership::tests::{
+                    jon_doe, jon_doe_previous_membership, jonette_snow,
+                };
+
+                #[test]
+                fn find_the_most_similar_one() {
+                    with_temp_database(|pool| {
+                        let mut connection = pool.get().unwrap();
+                        insert_all(
+                            &mut connection,
+                            &[jonette_snow(), jon_doe_previous_membership(), jon_doe()],
+                        )
+                        .unwrap();
+
+                        let result = by_last_name_first_name(
+                            &mut connection,
+                            &mut Candidates::default(),
+                            "Doe",
+                            "John",
+                            80,
+                        )
+                        .unwrap();
+                        assert_eq!(Some((jon_doe(), 85)), result);
+                    });
+                }
+
+                #[test]
+                fn none_similar_enough() {
+                    with_temp_database(|pool| {
+                        let mut connection = pool.get().unwrap();
+                        insert_all(&mut connection, &[jonette_snow(), jon_doe()]).unwrap();
+
+                        let result = by_last_name_first_name(
+                            &mut connection,
+                            &mut Candidates::default(),
+                            "Doe",
+                            "Johnny",
+                            80,
+                        )
+                        .unwrap();
+                        assert_eq!(None, result);
+                    });
+                }
+            }
+
+            mod by_identity {
+                use crate::database::dao::membership::find::similar::{Candidates, by_identity};
+                use crate::database::dao::membership::insert_all;
+                use crate::database::with_temp_database;
+                use crate::membership::tests::{jon_doe, jonette_snow};
+
+                #[test]
+                fn find_the_most_similar_one() {
+                    with_temp_database(|pool| {
+                        let mut connection = pool.get().unwrap();
+                        insert_all(&mut connection, &[jonette_snow(), jon_doe()]).unwrap();
+
+                        let result = by_identity(
+                            &mut connection,
+                            &mut Candidates::default(),
+                            "Doe John",
+                            80,
+                        )
+                        .unwrap();
+                        assert_eq!(Some((jon_doe(), 85)), result);
+                    });
+                }
+
+                #[test]
+                fn find_the_most_similar_one_by_reversed_identity() {
+                    with_temp_database(|pool| {
+                        let mut connection = pool.get().unwrap();
+                        insert_all(&mut connection, &[jonette_snow(), jon_doe()]).unwrap();
+
+                        let result = by_identity(
+                            &mut connection,
+                            &mut Candidates::default(),
+                            "John Doe",
+                            80,
+                        )
+                        .unwrap();
+                        assert_eq!(Some((jon_doe(), 85)), result);
+                    });
+                }
+
+                #[test]
+                fn none_similar_enough() {
+                    with_temp_database(|pool| {
+                        let mut connection = pool.get().unwrap();
+                        insert_all(&mut connection, &[jonette_snow(), jon_doe()]).unwrap();
+
+                        let result = by_identity(
+                            &mut connection,
+                            &mut Candidates::default(),
+                            "Johnny Doe",
+                            80,
+                        )
+                        .unwrap();
                         assert_eq!(None, result);
                     });
                 }
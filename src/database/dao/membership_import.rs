@@ -0,0 +1,119 @@
+use super::Result;
+use crate::database::model::membership_import::{
+    MembershipImport, MembershipImportRejectedRow, NewMembershipImportRejectedRow, to_dto,
+};
+use crate::database::schema::membership_import::dsl::membership_import;
+use crate::database::schema::membership_import::*;
+use crate::database::schema::membership_import_rejected_row;
+use chrono::Utc;
+use diesel::prelude::*;
+use dto::membership_import::{MembershipImportDiff, RejectedRow};
+
+/// Record an import into the history, along with how many memberships it has changed
+/// and which rows of the imported file have been rejected.
+pub(super) fn insert(
+    connection: &mut SqliteConnection,
+    diff: &MembershipImportDiff,
+    rejected_rows: &[RejectedRow],
+) -> Result<()> {
+    let import_date = Utc::now().naive_local();
+    let import_id = diesel::insert_into(membership_import)
+        .values((
+            date.eq(import_date.to_string()),
+            added_count.eq(diff.added().len() as i32),
+            updated_count.eq(diff.updated().len() as i32),
+            removed_count.eq(diff.removed().len() as i32),
+        ))
+        .returning(id)
+        .get_result::<i32>(connection)?;
+
+    let new_rejected_rows = rejected_rows
+        .iter()
+        .map(|rejected_row| NewMembershipImportRejectedRow::new(import_id, rejected_row))
+        .collect::<Result<Vec<_>>>()?;
+    diesel::insert_into(membership_import_rejected_row::table)
+        .values(&new_rejected_rows)
+        .execute(connection)?;
+
+    debug!(
+        "Recorded memberships import at {import_date} [added: {}, updated: {}, removed: {}, rejected: {}]",
+        diff.added().len(),
+        diff.updated().len(),
+        diff.removed().len(),
+        rejected_rows.len()
+    );
+
+    Ok(())
+}
+
+/// Retrieve the most recent import, if any.
+pub fn retrieve_last(
+    connection: &mut SqliteConnection,
+) -> Result<Option<dto::membership_import::MembershipImport>> {
+    let result = membership_import
+        .order(id.desc())
+        .limit(1)
+        .select(MembershipImport::as_select())
+        .first(connection)
+        .optional()?;
+
+    result
+        .map(|last_import| {
+            let rejected_rows = membership_import_rejected_row::table
+                .filter(membership_import_rejected_row::membership_import_id.eq(last_import.id()))
+                .order(membership_import_rejected_row::line)
+                .select(MembershipImportRejectedRow::as_select())
+                .load(connection)?;
+            to_dto(last_import, rejected_rows)
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    mod retrieve_last {
+        use crate::database::dao::membership_import::{insert, retrieve_last};
+        use crate::database::with_temp_database;
+        use crate::membership::tests::{jon_doe, jonette_snow, other_jon_doe};
+        use dto::membership_import::{MembershipImportDiff, RejectedRow};
+
+        #[test]
+        fn none() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+
+                assert_eq!(None, retrieve_last(&mut connection).unwrap());
+            })
+        }
+
+        #[test]
+        fn most_recent() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                let first_diff = MembershipImportDiff::new(vec![jon_doe()], vec![], vec![]);
+                let first_rejected_rows = vec![RejectedRow::new(3, None, "error".to_owned())];
+                insert(&mut connection, &first_diff, &first_rejected_rows).unwrap();
+                let second_diff = MembershipImportDiff::new(
+                    vec![jonette_snow(), other_jon_doe()],
+                    vec![jon_doe()],
+                    vec![],
+                );
+                let second_rejected_rows = vec![
+                    RejectedRow::new(
+                        2,
+                        Some("Date de naissance".to_owned()),
+                        "invalid date".to_owned(),
+                    ),
+                    RejectedRow::new(5, None, "missing field".to_owned()),
+                ];
+                insert(&mut connection, &second_diff, &second_rejected_rows).unwrap();
+
+                let result = retrieve_last(&mut connection).unwrap().unwrap();
+                assert_eq!(2, *result.added_count());
+                assert_eq!(1, *result.updated_count());
+                assert_eq!(0, *result.removed_count());
+                assert_eq!(&second_rejected_rows, result.rejected_rows());
+            })
+        }
+    }
+}
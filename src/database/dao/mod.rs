@@ -1,7 +1,15 @@
 use crate::database::error::DatabaseError;
 
+pub(crate) mod api_token;
+pub(crate) mod audit_log;
+pub(crate) mod check_session;
+pub(crate) mod credentials_session;
+pub(crate) mod email_template;
 pub(crate) mod last_update;
 pub(crate) mod membership;
+pub(crate) mod membership_import;
+pub(crate) mod notification;
 pub(crate) mod uda_instance;
+pub(crate) mod user_account;
 
 type Result<T, E = DatabaseError> = std::result::Result<T, E>;
@@ -0,0 +1,277 @@
+use super::Result;
+use crate::database::model::notification::{
+    NewNotificationAttachment, NewQueuedEmail, NotificationAttachment, QueuedEmailRow,
+};
+use crate::database::schema::{notification, notification_attachment, queued_email};
+use crate::membership::notification_queue::QueuedEmail;
+use crate::tools::email::EmailAttachment;
+use chrono::{NaiveDateTime, Utc};
+use diesel::prelude::*;
+use dto::email::{EmailStatus, NotificationStatus, PersonalizedEmail, RecipientStatus};
+
+/// Queue a notification, with an email per recipient, then return its ID.
+/// Emails are due right away.
+pub fn insert(
+    connection: &mut SqliteConnection,
+    created_by: &str,
+    emails: &[PersonalizedEmail],
+    attachments: &[EmailAttachment],
+) -> Result<i32> {
+    connection.transaction(|connection| {
+        let creation_date = Utc::now().naive_local();
+        let notification_id = diesel::insert_into(notification::table)
+            .values((
+                notification::created_by.eq(created_by),
+                notification::creation_date.eq(creation_date.to_string()),
+            ))
+            .returning(notification::id)
+            .get_result::<i32>(connection)?;
+
+        let new_attachments = attachments
+            .iter()
+            .map(|attachment| NewNotificationAttachment::new(notification_id, attachment))
+            .collect::<Vec<_>>();
+        diesel::insert_into(notification_attachment::table)
+            .values(&new_attachments)
+            .execute(connection)?;
+
+        let new_emails = emails
+            .iter()
+            .map(|email| NewQueuedEmail::new(notification_id, email, &creation_date))
+            .collect::<Vec<_>>();
+        diesel::insert_into(queued_email::table)
+            .values(&new_emails)
+            .execute(connection)?;
+
+        debug!(
+            "Queued notification [id: {notification_id}, created_by: {created_by}, emails: {}, attachments: {}]",
+            emails.len(),
+            attachments.len()
+        );
+        Ok(notification_id)
+    })
+}
+
+/// Retrieve where each email of the notification stands, if the notification exists.
+pub fn retrieve_status(
+    connection: &mut SqliteConnection,
+    notification_id: i32,
+) -> Result<Option<NotificationStatus>> {
+    let exists = notification::table
+        .filter(notification::id.eq(notification_id))
+        .count()
+        .get_result::<i64>(connection)?
+        > 0;
+    if !exists {
+        return Ok(None);
+    }
+
+    let recipients = queued_email::table
+        .filter(queued_email::notification_id.eq(notification_id))
+        .order(queued_email::id)
+        .select(QueuedEmailRow::as_select())
+        .load(connection)?
+        .into_iter()
+        .map(RecipientStatus::try_from)
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Some(NotificationStatus::new(notification_id, recipients)))
+}
+
+/// Retrieve the pending emails whose next attempt is due, the oldest first.
+pub fn retrieve_due_emails(
+    connection: &mut SqliteConnection,
+    now: &NaiveDateTime,
+    limit: usize,
+) -> Result<Vec<QueuedEmail>> {
+    queued_email::table
+        .filter(queued_email::status.eq(EmailStatus::Pending.value()))
+        .filter(queued_email::next_attempt_date.le(now.to_string()))
+        .order(queued_email::id)
+        .limit(i64::try_from(limit).unwrap_or(i64::MAX))
+        .select(QueuedEmailRow::as_select())
+        .load(connection)?
+        .into_iter()
+        .map(QueuedEmail::try_from)
+        .collect()
+}
+
+pub fn retrieve_attachments(
+    connection: &mut SqliteConnection,
+    notification_id: i32,
+) -> Result<Vec<EmailAttachment>> {
+    Ok(notification_attachment::table
+        .filter(notification_attachment::notification_id.eq(notification_id))
+        .order(notification_attachment::id)
+        .select(NotificationAttachment::as_select())
+        .load(connection)?
+        .into_iter()
+        .map(EmailAttachment::from)
+        .collect())
+}
+
+pub fn mark_sent(connection: &mut SqliteConnection, email_id: i32) -> Result<()> {
+    diesel::update(queued_email::table.filter(queued_email::id.eq(email_id)))
+        .set((
+            queued_email::status.eq(EmailStatus::Sent.value()),
+            queued_email::attempts.eq(queued_email::attempts + 1),
+            queued_email::last_error.eq(None::<String>),
+            queued_email::sent_date.eq(Utc::now().naive_local().to_string()),
+        ))
+        .execute(connection)?;
+    Ok(())
+}
+
+/// Record a failed attempt. The email is retried from `next_attempt_date` if any, or has failed for good otherwise.
+pub fn mark_failed(
+    connection: &mut SqliteConnection,
+    email_id: i32,
+    error: &str,
+    next_attempt_date: Option<&NaiveDateTime>,
+) -> Result<()> {
+    let status = match next_attempt_date {
+        Some(_) => EmailStatus::Pending,
+        None => EmailStatus::Failed,
+    };
+    diesel::update(queued_email::table.filter(queued_email::id.eq(email_id)))
+        .set((
+            queued_email::status.eq(status.value()),
+            queued_email::attempts.eq(queued_email::attempts + 1),
+            queued_email::last_error.eq(error),
+        ))
+        .execute(connection)?;
+    if let Some(next_attempt_date) = next_attempt_date {
+        diesel::update(queued_email::table.filter(queued_email::id.eq(email_id)))
+            .set(queued_email::next_attempt_date.eq(next_attempt_date.to_string()))
+            .execute(connection)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::dao::notification::{
+        insert, mark_failed, mark_sent, retrieve_attachments, retrieve_due_emails, retrieve_status,
+    };
+    use crate::database::with_temp_database;
+    use crate::tools::email::EmailAttachment;
+    use chrono::{TimeDelta, Utc};
+    use dto::email::EmailStatus::{Failed, Pending, Sent};
+    use dto::email::{NotificationStatus, PersonalizedEmail, RecipientStatus};
+
+    fn email(recipient: &str) -> PersonalizedEmail {
+        PersonalizedEmail::new(
+            recipient.to_owned(),
+            "Licence".to_owned(),
+            "Bonjour".to_owned(),
+            "<p>Bonjour</p>".to_owned(),
+        )
+    }
+
+    #[test]
+    fn insert_and_retrieve() {
+        with_temp_database(|pool| {
+            let mut connection = pool.get().unwrap();
+            let attachment = EmailAttachment::new(
+                "rules.pdf".to_owned(),
+                "application/pdf".to_owned(),
+                b"%PDF-1.4".to_vec(),
+            );
+            let notification_id = insert(
+                &mut connection,
+                "jon",
+                &[email("jon@address.com"), email("jonette@address.com")],
+                std::slice::from_ref(&attachment),
+            )
+            .unwrap();
+
+            let now = Utc::now().naive_local();
+            let due_emails = retrieve_due_emails(&mut connection, &now, 10).unwrap();
+            assert_eq!(2, due_emails.len());
+            assert_eq!(&email("jon@address.com"), due_emails[0].email());
+            assert_eq!(&notification_id, due_emails[0].notification_id());
+            assert_eq!(
+                1,
+                retrieve_due_emails(&mut connection, &now, 1).unwrap().len()
+            );
+            assert_eq!(
+                vec![attachment],
+                retrieve_attachments(&mut connection, notification_id).unwrap()
+            );
+        })
+    }
+
+    #[test]
+    fn record_attempts() {
+        with_temp_database(|pool| {
+            let mut connection = pool.get().unwrap();
+            let notification_id = insert(
+                &mut connection,
+                "jon",
+                &[
+                    email("jon@address.com"),
+                    email("jonette@address.com"),
+                    email("other@address.com"),
+                ],
+                &[],
+            )
+            .unwrap();
+            let now = Utc::now().naive_local();
+            let due_emails = retrieve_due_emails(&mut connection, &now, 10).unwrap();
+
+            mark_sent(&mut connection, *due_emails[0].id()).unwrap();
+            let next_attempt_date = now + TimeDelta::minutes(1);
+            mark_failed(
+                &mut connection,
+                *due_emails[1].id(),
+                "CantSendMessage",
+                Some(&next_attempt_date),
+            )
+            .unwrap();
+            mark_failed(
+                &mut connection,
+                *due_emails[2].id(),
+                "MessageRejected",
+                None,
+            )
+            .unwrap();
+
+            assert_eq!(
+                Some(NotificationStatus::new(
+                    notification_id,
+                    vec![
+                        RecipientStatus::new("jon@address.com".to_owned(), Sent, 1, None),
+                        RecipientStatus::new(
+                            "jonette@address.com".to_owned(),
+                            Pending,
+                            1,
+                            Some("CantSendMessage".to_owned())
+                        ),
+                        RecipientStatus::new(
+                            "other@address.com".to_owned(),
+                            Failed,
+                            1,
+                            Some("MessageRejected".to_owned())
+                        ),
+                    ]
+                )),
+                retrieve_status(&mut connection, notification_id).unwrap()
+            );
+            assert!(
+                retrieve_due_emails(&mut connection, &now, 10)
+                    .unwrap()
+                    .is_empty()
+            );
+            let due_emails = retrieve_due_emails(&mut connection, &next_attempt_date, 10).unwrap();
+            assert_eq!(1, due_emails.len());
+            assert_eq!(&1, due_emails[0].attempts());
+        })
+    }
+
+    #[test]
+    fn unknown_notification() {
+        with_temp_database(|pool| {
+            let mut connection = pool.get().unwrap();
+            assert_eq!(None, retrieve_status(&mut connection, 1).unwrap());
+        })
+    }
+}
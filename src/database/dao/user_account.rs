@@ -0,0 +1,158 @@
+use super::Result;
+use crate::database::model::user_account::{NewUserAccount, UserAccount, serialize_roles};
+use crate::database::schema::user_account::dsl::user_account;
+use crate::database::schema::user_account::{login, password_hash, roles};
+use diesel::prelude::*;
+use dto::user::{Role, User};
+
+/// Create a local account.
+/// Return false if another account already has the same login.
+pub fn insert(
+    connection: &mut SqliteConnection,
+    user_login: &str,
+    user_password_hash: &str,
+    user_roles: &[Role],
+) -> Result<bool> {
+    let new_account = NewUserAccount::new(user_login, user_password_hash, user_roles)?;
+    let inserted_count = diesel::insert_or_ignore_into(user_account)
+        .values(&new_account)
+        .execute(connection)?;
+
+    Ok(inserted_count > 0)
+}
+
+/// Retrieve all local accounts, sorted by login.
+pub fn retrieve_all(connection: &mut SqliteConnection) -> Result<Vec<User>> {
+    user_account
+        .order(login.asc())
+        .select(UserAccount::as_select())
+        .load(connection)?
+        .into_iter()
+        .map(User::try_from)
+        .collect()
+}
+
+pub fn retrieve(connection: &mut SqliteConnection, user_login: &str) -> Result<Option<User>> {
+    user_account
+        .filter(login.eq(user_login))
+        .select(UserAccount::as_select())
+        .first(connection)
+        .optional()?
+        .map(User::try_from)
+        .transpose()
+}
+
+/// Retrieve the hash of the password of a user, so that it can be verified on login.
+pub fn retrieve_password_hash(
+    connection: &mut SqliteConnection,
+    user_login: &str,
+) -> Result<Option<String>> {
+    let result = user_account
+        .filter(login.eq(user_login))
+        .select(password_hash)
+        .first::<String>(connection)
+        .optional()?;
+
+    Ok(result)
+}
+
+/// Return false if no such user exists.
+pub fn update_roles(
+    connection: &mut SqliteConnection,
+    user_login: &str,
+    user_roles: &[Role],
+) -> Result<bool> {
+    let updated_count = diesel::update(user_account.filter(login.eq(user_login)))
+        .set(roles.eq(serialize_roles(user_roles)?))
+        .execute(connection)?;
+
+    Ok(updated_count > 0)
+}
+
+/// Return false if no such user exists.
+pub fn delete(connection: &mut SqliteConnection, user_login: &str) -> Result<bool> {
+    let deleted_count =
+        diesel::delete(user_account.filter(login.eq(user_login))).execute(connection)?;
+
+    Ok(deleted_count > 0)
+}
+
+pub fn count(connection: &mut SqliteConnection) -> Result<i64> {
+    let result = user_account.count().get_result(connection)?;
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::dao::user_account::{
+        count, delete, insert, retrieve, retrieve_all, retrieve_password_hash, update_roles,
+    };
+    use crate::database::with_temp_database;
+    use dto::user::Role::{Admin, Check, Email, LookUp};
+    use dto::user::User;
+
+    #[test]
+    fn insert_and_retrieve() {
+        with_temp_database(|pool| {
+            let mut connection = pool.get().unwrap();
+            assert!(insert(&mut connection, "jonette", "hash_1", &[Check, LookUp]).unwrap());
+            assert!(insert(&mut connection, "jon", "hash_2", &[Admin]).unwrap());
+
+            assert_eq!(2, count(&mut connection).unwrap());
+            assert_eq!(
+                vec![
+                    User::new("jon".to_owned(), vec![Admin]),
+                    User::new("jonette".to_owned(), vec![Check, LookUp]),
+                ],
+                retrieve_all(&mut connection).unwrap()
+            );
+            assert_eq!(
+                Some(User::new("jonette".to_owned(), vec![Check, LookUp])),
+                retrieve(&mut connection, "jonette").unwrap()
+            );
+            assert_eq!(
+                Some("hash_1".to_owned()),
+                retrieve_password_hash(&mut connection, "jonette").unwrap()
+            );
+            assert_eq!(None, retrieve(&mut connection, "unknown").unwrap());
+            assert_eq!(
+                None,
+                retrieve_password_hash(&mut connection, "unknown").unwrap()
+            );
+        })
+    }
+
+    #[test]
+    fn insert_duplicate_login() {
+        with_temp_database(|pool| {
+            let mut connection = pool.get().unwrap();
+            assert!(insert(&mut connection, "jon", "hash_1", &[Check]).unwrap());
+            assert!(!insert(&mut connection, "jon", "hash_2", &[Admin]).unwrap());
+
+            assert_eq!(
+                Some("hash_1".to_owned()),
+                retrieve_password_hash(&mut connection, "jon").unwrap()
+            );
+        })
+    }
+
+    #[test]
+    fn update_and_delete() {
+        with_temp_database(|pool| {
+            let mut connection = pool.get().unwrap();
+            insert(&mut connection, "jon", "hash", &[Check]).unwrap();
+
+            assert!(update_roles(&mut connection, "jon", &[Check, Email]).unwrap());
+            assert!(!update_roles(&mut connection, "unknown", &[Admin]).unwrap());
+            assert_eq!(
+                Some(User::new("jon".to_owned(), vec![Check, Email])),
+                retrieve(&mut connection, "jon").unwrap()
+            );
+
+            assert!(delete(&mut connection, "jon").unwrap());
+            assert!(!delete(&mut connection, "jon").unwrap());
+            assert_eq!(0, count(&mut connection).unwrap());
+        })
+    }
+}
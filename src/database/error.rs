@@ -21,6 +21,8 @@ pub enum DatabaseError {
     CantUpdateLastUpdated(String),
     #[error("Last update should be known at this point.")]
     UnknownLastUpdate,
+    #[error("An import without any membership can't replace the known memberships.")]
+    EmptyMembershipImport,
 }
 
 impl From<Box<dyn Error + Send + Sync + 'static>> for DatabaseError {
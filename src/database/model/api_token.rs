@@ -0,0 +1,66 @@
+use crate::database::error::DatabaseError;
+use crate::database::model::user_account::{deserialize_roles, serialize_roles};
+use chrono::{NaiveDateTime, Utc};
+use diesel::{Insertable, Queryable, Selectable};
+use dto::user::Role;
+
+#[derive(Queryable, Selectable, Debug)]
+#[diesel(table_name = crate::database::schema::api_token)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct ApiToken {
+    id: i32,
+    name: String,
+    scopes: String,
+    created_by: String,
+    creation_date: String,
+    revocation_date: Option<String>,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = crate::database::schema::api_token)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct NewApiToken {
+    name: String,
+    token_hash: String,
+    scopes: String,
+    created_by: String,
+    creation_date: String,
+}
+
+impl NewApiToken {
+    pub fn new(
+        name: &str,
+        token_hash: &str,
+        scopes: &[Role],
+        created_by: &str,
+    ) -> Result<Self, DatabaseError> {
+        Ok(Self {
+            name: name.to_owned(),
+            token_hash: token_hash.to_owned(),
+            scopes: serialize_roles(scopes)?,
+            created_by: created_by.to_owned(),
+            creation_date: Utc::now().naive_local().to_string(),
+        })
+    }
+}
+
+impl TryFrom<ApiToken> for dto::api_token::ApiToken {
+    type Error = DatabaseError;
+
+    fn try_from(value: ApiToken) -> Result<Self, Self::Error> {
+        let parse_date = |date: &str| NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S%.f");
+
+        Ok(dto::api_token::ApiToken::new(
+            value.id,
+            value.name,
+            deserialize_roles(&value.scopes)?,
+            value.created_by,
+            parse_date(&value.creation_date)?,
+            value
+                .revocation_date
+                .as_deref()
+                .map(parse_date)
+                .transpose()?,
+        ))
+    }
+}
@@ -0,0 +1,63 @@
+use crate::database::error::DatabaseError;
+use crate::database::error::DatabaseError::ConversionError;
+use chrono::{NaiveDateTime, Utc};
+use diesel::{Insertable, Queryable, Selectable};
+use dto::audit_log::AuditAction;
+use rocket::serde::json;
+
+#[derive(Queryable, Selectable, Debug)]
+#[diesel(table_name = crate::database::schema::audit_log)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct AuditLogEntry {
+    id: i32,
+    date: String,
+    actor_login: String,
+    action: String,
+    targets: String,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = crate::database::schema::audit_log)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct NewAuditLogEntry {
+    date: String,
+    actor_login: String,
+    action: String,
+    targets: String,
+}
+
+impl NewAuditLogEntry {
+    pub fn new(
+        actor_login: &str,
+        action: AuditAction,
+        targets: &[String],
+    ) -> Result<Self, DatabaseError> {
+        Ok(Self {
+            date: Utc::now().naive_local().to_string(),
+            actor_login: actor_login.to_owned(),
+            action: action.value().to_owned(),
+            targets: json::to_string(&targets)
+                .map_err(|error| ConversionError(error.to_string()))?,
+        })
+    }
+}
+
+impl TryFrom<AuditLogEntry> for dto::audit_log::AuditLogEntry {
+    type Error = DatabaseError;
+
+    fn try_from(value: AuditLogEntry) -> Result<Self, Self::Error> {
+        let action = AuditAction::from_value(&value.action).ok_or_else(|| {
+            ConversionError(format!("Unknown audited action [action: {}]", value.action))
+        })?;
+        let targets = json::from_str(&value.targets)
+            .map_err(|error: json::serde_json::Error| ConversionError(error.to_string()))?;
+
+        Ok(dto::audit_log::AuditLogEntry::new(
+            value.id,
+            NaiveDateTime::parse_from_str(&value.date, "%Y-%m-%d %H:%M:%S%.f")?,
+            value.actor_login,
+            action,
+            targets,
+        ))
+    }
+}
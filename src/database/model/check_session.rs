@@ -0,0 +1,154 @@
+use crate::database::error::DatabaseError;
+use crate::database::error::DatabaseError::ConversionError;
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use diesel::{Insertable, Queryable, Selectable};
+use dto::check_session::{CheckSessionResults, CheckSource};
+use dto::checked_member::CheckedMember;
+use dto::event_period::EventPeriod;
+use dto::member_to_check::MemberToCheck;
+use dto::membership_status::MemberStatus::{ExpiringSoon, UpToDate};
+use rocket::serde::json;
+
+const CSV_SOURCE: &str = "CSV";
+pub(crate) const UDA_SOURCE: &str = "UDA";
+
+/// A check session, without its results, which are only loaded when the session is reopened.
+#[derive(Queryable, Selectable, Debug)]
+#[diesel(table_name = crate::database::schema::check_session)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct CheckSessionSummary {
+    id: i32,
+    date: String,
+    user_login: String,
+    source: String,
+    uda_instance: Option<String>,
+    start_date: String,
+    end_date: String,
+    checked_count: i32,
+    valid_count: i32,
+}
+
+#[derive(Queryable, Selectable, Debug)]
+#[diesel(table_name = crate::database::schema::check_session)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct CheckSession {
+    #[diesel(embed)]
+    summary: CheckSessionSummary,
+    results: String,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = crate::database::schema::check_session)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct NewCheckSession {
+    date: String,
+    user_login: String,
+    source: String,
+    uda_instance: Option<String>,
+    start_date: String,
+    end_date: String,
+    checked_count: i32,
+    valid_count: i32,
+    results: String,
+}
+
+impl NewCheckSession {
+    pub fn new(
+        user_login: &str,
+        event_period: &EventPeriod,
+        results: &CheckSessionResults,
+    ) -> Result<Self, DatabaseError> {
+        let (source, uda_instance, (checked_count, valid_count), results) = match results {
+            CheckSessionResults::Csv(checked_members) => (
+                CSV_SOURCE,
+                None,
+                count(checked_members)?,
+                json::to_string(checked_members),
+            ),
+            CheckSessionResults::Uda(uda_instance, checked_members) => (
+                UDA_SOURCE,
+                Some(uda_instance.clone()),
+                count(checked_members)?,
+                json::to_string(checked_members),
+            ),
+        };
+
+        Ok(Self {
+            date: Utc::now().naive_local().to_string(),
+            user_login: user_login.to_owned(),
+            source: source.to_owned(),
+            uda_instance,
+            start_date: event_period.start_date().to_string(),
+            end_date: event_period.end_date().to_string(),
+            checked_count,
+            valid_count,
+            results: results.map_err(|error| ConversionError(error.to_string()))?,
+        })
+    }
+}
+
+/// Count checked members, and those whose membership covers the event.
+fn count<T: MemberToCheck>(
+    checked_members: &[CheckedMember<T>],
+) -> Result<(i32, i32), DatabaseError> {
+    let to_count =
+        |count: usize| i32::try_from(count).map_err(|error| ConversionError(error.to_string()));
+    let valid_count = checked_members
+        .iter()
+        .filter(|checked_member| matches!(checked_member.status(), UpToDate | ExpiringSoon))
+        .count();
+    Ok((to_count(checked_members.len())?, to_count(valid_count)?))
+}
+
+impl TryFrom<CheckSessionSummary> for dto::check_session::CheckSessionSummary {
+    type Error = DatabaseError;
+
+    fn try_from(value: CheckSessionSummary) -> Result<Self, Self::Error> {
+        let to_count =
+            |count: i32| usize::try_from(count).map_err(|error| ConversionError(error.to_string()));
+        let source = match (value.source.as_str(), value.uda_instance) {
+            (CSV_SOURCE, _) => CheckSource::Csv,
+            (UDA_SOURCE, Some(uda_instance)) => CheckSource::Uda(uda_instance),
+            (source, _) => {
+                return Err(ConversionError(format!(
+                    "Unknown check session source [source: {source}]"
+                )));
+            }
+        };
+        let event_period = EventPeriod::new(
+            value.start_date.parse::<NaiveDate>()?,
+            value.end_date.parse::<NaiveDate>()?,
+        )
+        .ok_or_else(|| ConversionError("Event period ends before it starts".to_owned()))?;
+
+        Ok(dto::check_session::CheckSessionSummary::new(
+            value.id,
+            NaiveDateTime::parse_from_str(&value.date, "%Y-%m-%d %H:%M:%S%.f")?,
+            value.user_login,
+            source,
+            event_period,
+            to_count(value.checked_count)?,
+            to_count(value.valid_count)?,
+        ))
+    }
+}
+
+impl TryFrom<CheckSession> for dto::check_session::CheckSession {
+    type Error = DatabaseError;
+
+    fn try_from(value: CheckSession) -> Result<Self, Self::Error> {
+        let summary = dto::check_session::CheckSessionSummary::try_from(value.summary)?;
+        let to_error = |error: json::serde_json::Error| ConversionError(error.to_string());
+        let results = match summary.source() {
+            CheckSource::Csv => {
+                CheckSessionResults::Csv(json::from_str(&value.results).map_err(to_error)?)
+            }
+            CheckSource::Uda(uda_instance) => CheckSessionResults::Uda(
+                uda_instance.clone(),
+                json::from_str(&value.results).map_err(to_error)?,
+            ),
+        };
+
+        Ok(dto::check_session::CheckSession::new(summary, results))
+    }
+}
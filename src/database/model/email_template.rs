@@ -0,0 +1,65 @@
+use crate::database::error::DatabaseError;
+use crate::database::error::DatabaseError::ConversionError;
+use diesel::{AsChangeset, Insertable, Queryable, Selectable};
+use dto::email_template::EmailTemplateUseCase;
+
+#[derive(Queryable, Selectable, Debug)]
+#[diesel(table_name = crate::database::schema::email_template)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct EmailTemplate {
+    id: i32,
+    name: String,
+    subject: String,
+    body: String,
+    use_case: Option<String>,
+}
+
+/// Also used to update a template, in which case a missing use case is cleared rather than left untouched.
+#[derive(Insertable, AsChangeset, Debug)]
+#[diesel(table_name = crate::database::schema::email_template)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[diesel(treat_none_as_null = true)]
+pub(crate) struct NewEmailTemplate<'a> {
+    name: &'a str,
+    subject: &'a str,
+    body: &'a str,
+    use_case: Option<&'static str>,
+}
+
+impl<'a> NewEmailTemplate<'a> {
+    pub fn new(template: &'a dto::email_template::NewEmailTemplate) -> Self {
+        Self {
+            name: template.name(),
+            subject: template.subject(),
+            body: template.body(),
+            use_case: template
+                .use_case()
+                .as_ref()
+                .map(EmailTemplateUseCase::value),
+        }
+    }
+}
+
+impl TryFrom<EmailTemplate> for dto::email_template::EmailTemplate {
+    type Error = DatabaseError;
+
+    fn try_from(value: EmailTemplate) -> Result<Self, Self::Error> {
+        let use_case = value
+            .use_case
+            .map(|use_case| {
+                EmailTemplateUseCase::from_value(&use_case).ok_or_else(|| {
+                    ConversionError(format!(
+                        "Unknown email template use case [use_case: {use_case}]"
+                    ))
+                })
+            })
+            .transpose()?;
+        Ok(dto::email_template::EmailTemplate::new(
+            value.id,
+            value.name,
+            value.subject,
+            value.body,
+            use_case,
+        ))
+    }
+}
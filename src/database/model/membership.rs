@@ -1,4 +1,5 @@
 use crate::database::error::DatabaseError;
+use crate::tools::normalize;
 use chrono::NaiveDate;
 use diesel::prelude::*;
 use std::str::FromStr;
@@ -25,6 +26,28 @@ pub(crate) struct Membership {
     normalized_first_name_last_name: String,
 }
 
+impl Membership {
+    pub(crate) fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub(crate) fn start_date(&self) -> &str {
+        &self.start_date
+    }
+
+    pub(crate) fn normalized_membership_number(&self) -> &str {
+        &self.normalized_membership_number
+    }
+
+    pub(crate) fn normalized_last_name_first_name(&self) -> &str {
+        &self.normalized_last_name_first_name
+    }
+
+    pub(crate) fn normalized_first_name_last_name(&self) -> &str {
+        &self.normalized_first_name_last_name
+    }
+}
+
 impl TryFrom<Membership> for dto::membership::Membership {
     type Error = DatabaseError;
 
@@ -49,3 +72,56 @@ impl TryFrom<Membership> for dto::membership::Membership {
         ))
     }
 }
+
+/// A membership as it is written into the database, along with its pre-computed fields.
+#[derive(Insertable, AsChangeset, Debug)]
+#[diesel(table_name = crate::database::schema::membership)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[diesel(treat_none_as_null = true)]
+pub(crate) struct NewMembership {
+    last_name: String,
+    first_name: String,
+    birthdate: Option<String>,
+    membership_number: String,
+    cell_number: Option<String>,
+    email_address: String,
+    start_date: String,
+    end_date: String,
+    club: String,
+    structure_code: String,
+    normalized_membership_number: String,
+    normalized_last_name: String,
+    normalized_first_name: String,
+    normalized_last_name_first_name: String,
+    normalized_first_name_last_name: String,
+}
+
+impl From<&dto::membership::Membership> for NewMembership {
+    fn from(value: &dto::membership::Membership) -> Self {
+        Self {
+            last_name: value.name().clone(),
+            first_name: value.first_name().clone(),
+            birthdate: value.birthdate().map(|birthdate| birthdate.to_string()),
+            membership_number: value.membership_number().clone(),
+            cell_number: value.cell_number().clone(),
+            email_address: value.email_address().clone(),
+            start_date: value.start_date().to_string(),
+            end_date: value.end_date().to_string(),
+            club: value.club().clone(),
+            structure_code: value.structure_code().clone(),
+            normalized_membership_number: normalize(value.membership_number()),
+            normalized_last_name: normalize(value.name()),
+            normalized_first_name: normalize(value.first_name()),
+            normalized_last_name_first_name: format!(
+                "{}{}",
+                normalize(value.name()),
+                normalize(value.first_name()),
+            ),
+            normalized_first_name_last_name: format!(
+                "{}{}",
+                normalize(value.first_name()),
+                normalize(value.name()),
+            ),
+        }
+    }
+}
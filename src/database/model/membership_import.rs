@@ -0,0 +1,94 @@
+use crate::database::error::DatabaseError;
+use crate::database::error::DatabaseError::ConversionError;
+use chrono::NaiveDateTime;
+use diesel::{Insertable, Queryable, Selectable};
+use dto::membership_import::RejectedRow;
+
+#[derive(Queryable, Selectable, Debug)]
+#[diesel(table_name = crate::database::schema::membership_import)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct MembershipImport {
+    id: i32,
+    date: String,
+    added_count: i32,
+    updated_count: i32,
+    removed_count: i32,
+}
+
+impl MembershipImport {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+}
+
+/// A row rejected during an import, as it is stored in the database.
+#[derive(Queryable, Selectable, Debug)]
+#[diesel(table_name = crate::database::schema::membership_import_rejected_row)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct MembershipImportRejectedRow {
+    #[allow(dead_code)]
+    id: i32,
+    #[allow(dead_code)]
+    membership_import_id: i32,
+    line: i64,
+    column_name: Option<String>,
+    reason: String,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = crate::database::schema::membership_import_rejected_row)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct NewMembershipImportRejectedRow {
+    membership_import_id: i32,
+    line: i64,
+    column_name: Option<String>,
+    reason: String,
+}
+
+impl NewMembershipImportRejectedRow {
+    pub fn new(
+        membership_import_id: i32,
+        rejected_row: &RejectedRow,
+    ) -> Result<Self, DatabaseError> {
+        Ok(Self {
+            membership_import_id,
+            line: i64::try_from(*rejected_row.line())
+                .map_err(|error| ConversionError(error.to_string()))?,
+            column_name: rejected_row.column().clone(),
+            reason: rejected_row.reason().clone(),
+        })
+    }
+}
+
+impl TryFrom<MembershipImportRejectedRow> for RejectedRow {
+    type Error = DatabaseError;
+
+    fn try_from(value: MembershipImportRejectedRow) -> Result<Self, Self::Error> {
+        Ok(RejectedRow::new(
+            u64::try_from(value.line).map_err(|error| ConversionError(error.to_string()))?,
+            value.column_name,
+            value.reason,
+        ))
+    }
+}
+
+/// Build the summary of an import, along with the rows it has rejected.
+pub(crate) fn to_dto(
+    value: MembershipImport,
+    rejected_rows: Vec<MembershipImportRejectedRow>,
+) -> Result<dto::membership_import::MembershipImport, DatabaseError> {
+    let to_count =
+        |count: i32| usize::try_from(count).map_err(|error| ConversionError(error.to_string()));
+    let date = NaiveDateTime::parse_from_str(&value.date, "%Y-%m-%d %H:%M:%S%.f")?;
+    let rejected_rows = rejected_rows
+        .into_iter()
+        .map(RejectedRow::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(dto::membership_import::MembershipImport::new(
+        date,
+        to_count(value.added_count)?,
+        to_count(value.updated_count)?,
+        to_count(value.removed_count)?,
+        rejected_rows,
+    ))
+}
@@ -1,3 +1,10 @@
+pub(super) mod api_token;
+pub(super) mod audit_log;
+pub(super) mod check_session;
+pub(super) mod email_template;
 pub(super) mod last_update;
 pub(super) mod membership;
+pub(super) mod membership_import;
+pub(super) mod notification;
 pub(super) mod uda_instance;
+pub(super) mod user_account;
@@ -0,0 +1,123 @@
+use crate::database::error::DatabaseError;
+use crate::database::error::DatabaseError::ConversionError;
+use crate::membership::notification_queue::QueuedEmail;
+use crate::tools::email::EmailAttachment;
+use chrono::NaiveDateTime;
+use diesel::{Insertable, Queryable, Selectable};
+use dto::email::{EmailStatus, PersonalizedEmail, RecipientStatus};
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = crate::database::schema::notification_attachment)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct NewNotificationAttachment<'a> {
+    notification_id: i32,
+    file_name: &'a str,
+    content_type: &'a str,
+    content: &'a [u8],
+}
+
+impl<'a> NewNotificationAttachment<'a> {
+    pub fn new(notification_id: i32, attachment: &'a EmailAttachment) -> Self {
+        Self {
+            notification_id,
+            file_name: attachment.file_name(),
+            content_type: attachment.content_type(),
+            content: attachment.content(),
+        }
+    }
+}
+
+#[derive(Queryable, Selectable, Debug)]
+#[diesel(table_name = crate::database::schema::notification_attachment)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct NotificationAttachment {
+    file_name: String,
+    content_type: String,
+    content: Vec<u8>,
+}
+
+impl From<NotificationAttachment> for EmailAttachment {
+    fn from(value: NotificationAttachment) -> Self {
+        EmailAttachment::new(value.file_name, value.content_type, value.content)
+    }
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = crate::database::schema::queued_email)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct NewQueuedEmail<'a> {
+    notification_id: i32,
+    recipient: &'a str,
+    subject: &'a str,
+    body: &'a str,
+    html_body: &'a str,
+    status: &'static str,
+    next_attempt_date: String,
+}
+
+impl<'a> NewQueuedEmail<'a> {
+    pub fn new(
+        notification_id: i32,
+        email: &'a PersonalizedEmail,
+        next_attempt_date: &NaiveDateTime,
+    ) -> Self {
+        Self {
+            notification_id,
+            recipient: email.recipient(),
+            subject: email.subject(),
+            body: email.body(),
+            html_body: email.html_body(),
+            status: EmailStatus::Pending.value(),
+            next_attempt_date: next_attempt_date.to_string(),
+        }
+    }
+}
+
+/// An email of a notification, as it is stored in the queue.
+#[derive(Queryable, Selectable, Debug)]
+#[diesel(table_name = crate::database::schema::queued_email)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct QueuedEmailRow {
+    id: i32,
+    notification_id: i32,
+    recipient: String,
+    subject: String,
+    body: String,
+    html_body: String,
+    status: String,
+    attempts: i32,
+    last_error: Option<String>,
+}
+
+fn to_attempts(attempts: i32) -> Result<u32, DatabaseError> {
+    u32::try_from(attempts).map_err(|error| ConversionError(error.to_string()))
+}
+
+impl TryFrom<QueuedEmailRow> for QueuedEmail {
+    type Error = DatabaseError;
+
+    fn try_from(value: QueuedEmailRow) -> Result<Self, Self::Error> {
+        Ok(QueuedEmail::new(
+            value.id,
+            value.notification_id,
+            PersonalizedEmail::new(value.recipient, value.subject, value.body, value.html_body),
+            to_attempts(value.attempts)?,
+        ))
+    }
+}
+
+impl TryFrom<QueuedEmailRow> for RecipientStatus {
+    type Error = DatabaseError;
+
+    fn try_from(value: QueuedEmailRow) -> Result<Self, Self::Error> {
+        let status = EmailStatus::from_value(&value.status).ok_or_else(|| {
+            ConversionError(format!("Unknown email status [status: {}]", value.status))
+        })?;
+        Ok(RecipientStatus::new(
+            value.recipient,
+            status,
+            to_attempts(value.attempts)?,
+            value.last_error,
+        ))
+    }
+}
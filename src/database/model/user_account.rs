@@ -0,0 +1,59 @@
+use crate::database::error::DatabaseError;
+use crate::database::error::DatabaseError::ConversionError;
+use diesel::{Insertable, Queryable, Selectable};
+use dto::user::{Role, User};
+use rocket::serde::json;
+
+#[derive(Queryable, Selectable, Debug)]
+#[diesel(table_name = crate::database::schema::user_account)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct UserAccount {
+    login: String,
+    roles: String,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = crate::database::schema::user_account)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub(crate) struct NewUserAccount {
+    login: String,
+    password_hash: String,
+    roles: String,
+}
+
+impl NewUserAccount {
+    pub fn new(login: &str, password_hash: &str, roles: &[Role]) -> Result<Self, DatabaseError> {
+        Ok(Self {
+            login: login.to_owned(),
+            password_hash: password_hash.to_owned(),
+            roles: serialize_roles(roles)?,
+        })
+    }
+}
+
+pub(crate) fn serialize_roles(roles: &[Role]) -> Result<String, DatabaseError> {
+    let values: Vec<&str> = roles.iter().map(Role::value).collect();
+    json::to_string(&values).map_err(|error| ConversionError(error.to_string()))
+}
+
+pub(crate) fn deserialize_roles(serialized_roles: &str) -> Result<Vec<Role>, DatabaseError> {
+    let values: Vec<String> = json::from_str(serialized_roles)
+        .map_err(|error: json::serde_json::Error| ConversionError(error.to_string()))?;
+    values
+        .iter()
+        .map(|role| {
+            Role::from_value(role)
+                .ok_or_else(|| ConversionError(format!("Unknown role [role: {role}]")))
+        })
+        .collect()
+}
+
+impl TryFrom<UserAccount> for User {
+    type Error = DatabaseError;
+
+    fn try_from(value: UserAccount) -> Result<Self, Self::Error> {
+        let roles = deserialize_roles(&value.roles)?;
+
+        Ok(User::new(value.login, roles))
+    }
+}
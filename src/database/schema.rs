@@ -1,5 +1,61 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    api_token (id) {
+        id -> Integer,
+        name -> Text,
+        token_hash -> Text,
+        scopes -> Text,
+        created_by -> Text,
+        creation_date -> Text,
+        revocation_date -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    audit_log (id) {
+        id -> Integer,
+        date -> Text,
+        actor_login -> Text,
+        action -> Text,
+        targets -> Text,
+    }
+}
+
+diesel::table! {
+    check_session (id) {
+        id -> Integer,
+        date -> Text,
+        user_login -> Text,
+        source -> Text,
+        uda_instance -> Nullable<Text>,
+        start_date -> Text,
+        end_date -> Text,
+        checked_count -> Integer,
+        valid_count -> Integer,
+        results -> Text,
+    }
+}
+
+diesel::table! {
+    credentials_session (id) {
+        id -> Text,
+        kind -> Text,
+        expiration_date -> Text,
+        credentials -> Text,
+    }
+}
+
+diesel::table! {
+    email_template (id) {
+        id -> Integer,
+        name -> Text,
+        subject -> Text,
+        body -> Text,
+        use_case -> Nullable<Text>,
+    }
+}
+
 diesel::table! {
     last_update (element) {
         element -> Text,
@@ -28,6 +84,60 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    membership_import (id) {
+        id -> Integer,
+        date -> Text,
+        added_count -> Integer,
+        updated_count -> Integer,
+        removed_count -> Integer,
+    }
+}
+
+diesel::table! {
+    membership_import_rejected_row (id) {
+        id -> Integer,
+        membership_import_id -> Integer,
+        line -> BigInt,
+        column_name -> Nullable<Text>,
+        reason -> Text,
+    }
+}
+
+diesel::table! {
+    notification (id) {
+        id -> Integer,
+        created_by -> Text,
+        creation_date -> Text,
+    }
+}
+
+diesel::table! {
+    notification_attachment (id) {
+        id -> Integer,
+        notification_id -> Integer,
+        file_name -> Text,
+        content_type -> Text,
+        content -> Binary,
+    }
+}
+
+diesel::table! {
+    queued_email (id) {
+        id -> Integer,
+        notification_id -> Integer,
+        recipient -> Text,
+        subject -> Text,
+        body -> Text,
+        html_body -> Text,
+        status -> Text,
+        attempts -> Integer,
+        next_attempt_date -> Text,
+        last_error -> Nullable<Text>,
+        sent_date -> Nullable<Text>,
+    }
+}
+
 diesel::table! {
     uda_instance (id) {
         id -> Integer,
@@ -37,4 +147,32 @@ diesel::table! {
     }
 }
 
-diesel::allow_tables_to_appear_in_same_query!(last_update, membership, uda_instance,);
+diesel::table! {
+    user_account (id) {
+        id -> Integer,
+        login -> Text,
+        password_hash -> Text,
+        roles -> Text,
+    }
+}
+
+diesel::joinable!(membership_import_rejected_row -> membership_import (membership_import_id));
+diesel::joinable!(notification_attachment -> notification (notification_id));
+diesel::joinable!(queued_email -> notification (notification_id));
+
+diesel::allow_tables_to_appear_in_same_query!(
+    api_token,
+    audit_log,
+    check_session,
+    credentials_session,
+    email_template,
+    last_update,
+    membership,
+    membership_import,
+    membership_import_rejected_row,
+    notification,
+    notification_attachment,
+    queued_email,
+    uda_instance,
+    user_account,
+);
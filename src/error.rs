@@ -1,6 +1,8 @@
 use crate::database::error::DatabaseError;
 use crate::fileo::error::FileoError;
+use crate::membership::error::MembershipError;
 use crate::uda::error::UdaError;
+use crate::user::error::UserError;
 use crate::web::error::WebError;
 use thiserror::Error;
 
@@ -14,6 +16,10 @@ pub enum ApplicationError {
     Web(#[from] WebError),
     #[error("Error while working with Fileo.")]
     Fileo(#[from] FileoError),
+    #[error("Error while working with members to check.")]
+    Membership(#[from] MembershipError),
     #[error("Error while working with UDA.")]
     Uda(#[from] UdaError),
+    #[error("Error while working with local users.")]
+    User(#[from] UserError),
 }
@@ -1,18 +1,18 @@
-use crate::fileo::credentials::FileoCredentials;
+use crate::fileo::session::FileoSession;
 use crate::web::authentication;
 use rocket::request::FromRequest;
 use rocket::{Request, request};
 
 pub const AUTHENTICATION_COOKIE: &str = "Fileo-Authentication";
 
-/// If an endpoint requires Fileo credentials to be called,
-/// then its implementation should require a [FileoCredentials] parameter.
-/// Rocket will summon this guard to ensure such credentials exists.
+/// If an endpoint requires the user to be logged in Fileo to be called,
+/// then its implementation should require a [FileoSession] parameter.
+/// Rocket will summon this guard to ensure such a session exists.
 /// If it doesn't, then the caller receives an Unauthorized status.
 ///
 /// Currently, such authentication is passed from the caller to the server using a `Fileo-Authentication` private cookie.
 #[rocket::async_trait]
-impl<'r> FromRequest<'r> for FileoCredentials {
+impl<'r> FromRequest<'r> for FileoSession {
     type Error = ();
 
     async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
@@ -22,6 +22,7 @@ impl<'r> FromRequest<'r> for FileoCredentials {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tools::web::SessionCookies;
     use crate::web::credentials_storage::CredentialsStorage;
     use rocket::http::{Cookie, Status};
     use rocket::local::asynchronous::Client;
@@ -29,11 +30,12 @@ mod tests {
 
     #[async_test]
     async fn should_fileo_request_succeed() {
-        let credentials =
-            FileoCredentials::new("test_login".to_owned(), "test_password".to_owned());
+        let credentials = FileoSession::new("test_login".to_owned(), SessionCookies::default());
         let mut credentials_storage = CredentialsStorage::default();
         let uuid = "0ea9a5fb-0f46-4057-902a-2552ed956bde".to_owned();
-        credentials_storage.store(uuid.clone(), credentials.clone());
+        credentials_storage
+            .store(uuid.clone(), credentials.clone())
+            .unwrap();
         let credentials_storage_mutex = Mutex::new(credentials_storage);
 
         let rocket = rocket::build().manage(credentials_storage_mutex);
@@ -41,14 +43,14 @@ mod tests {
         let cookie = Cookie::new(AUTHENTICATION_COOKIE, uuid);
         let request = client.get("http://localhost").cookie(cookie.clone());
 
-        let outcome = FileoCredentials::from_request(&request).await;
+        let outcome = FileoSession::from_request(&request).await;
         assert!(outcome.is_success());
         assert_eq!(credentials, outcome.succeeded().unwrap());
     }
 
     #[async_test]
     async fn should_fileo_request_fail_when_no_matching_credentials() {
-        let credentials_storage = CredentialsStorage::<FileoCredentials>::default();
+        let credentials_storage = CredentialsStorage::<FileoSession>::default();
         let credentials_uuid = "0ea9a5fb-0f46-4057-902a-2552ed956bde".to_owned();
         let credentials_storage_mutex = Mutex::new(credentials_storage);
 
@@ -57,21 +59,21 @@ mod tests {
         let cookie = Cookie::new(AUTHENTICATION_COOKIE, credentials_uuid);
         let request = client.get("http://localhost").cookie(cookie);
 
-        let outcome = FileoCredentials::from_request(&request).await;
+        let outcome = FileoSession::from_request(&request).await;
         assert!(outcome.is_forward());
         assert_eq!(Status::Unauthorized, outcome.forwarded().unwrap());
     }
 
     #[async_test]
     async fn should_fileo_request_fail_when_no_header() {
-        let credentials_storage = CredentialsStorage::<FileoCredentials>::default();
+        let credentials_storage = CredentialsStorage::<FileoSession>::default();
         let credentials_storage_mutex = Mutex::new(credentials_storage);
 
         let rocket = rocket::build().manage(credentials_storage_mutex);
         let client = Client::tracked(rocket).await.unwrap();
         let request = client.get("http://localhost");
 
-        let outcome = FileoCredentials::from_request(&request).await;
+        let outcome = FileoSession::from_request(&request).await;
         assert!(outcome.is_forward());
         assert_eq!(Status::Unauthorized, outcome.forwarded().unwrap());
     }
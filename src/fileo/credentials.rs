@@ -2,6 +2,8 @@ use derive_getters::Getters;
 use rocket::serde::{Deserialize, Serialize};
 use std::fmt::{Debug, Formatter};
 
+/// Login and password of a Fileo user, only used to log in.
+/// Once logged in, a [FileoSession](crate::fileo::session::FileoSession) is kept instead.
 #[derive(Serialize, Deserialize, Getters, PartialEq, Clone, Default)]
 pub struct FileoCredentials {
     login: String,
@@ -18,7 +20,6 @@ impl Debug for FileoCredentials {
     }
 }
 
-#[cfg(test)]
 impl FileoCredentials {
     pub fn new(login: String, password: String) -> Self {
         Self { login, password }
@@ -8,14 +8,18 @@ use crate::fileo::error::FileoError::{
     NoDownloadLink,
 };
 use crate::fileo::imported_membership::ImportedMembership;
+use crate::fileo::session::FileoSession;
 use crate::membership::config::MembershipsProviderConfig;
-use crate::tools::web::build_client;
+use crate::tools::web::{build_client, build_client_resuming_session};
 use crate::tools::{log_error_and_return, log_message, log_message_and_return};
 use crate::web::error::WebError::{
-    CantReadPageContent, ConnectionFailed, LackOfPermissions, NotFound, WrongCredentials,
+    CantReadPageContent, ConnectionFailed, LackOfPermissions, NotFound, SessionExpired,
+    WrongCredentials,
 };
-use csv::Reader;
+use csv::{Reader, StringRecord};
+use derive_getters::Getters;
 use dto::membership::Membership;
+use dto::membership_import::RejectedRow;
 use encoding::all::ISO_8859_1;
 use encoding::{DecoderTrap, Encoding};
 use log::{debug, error};
@@ -29,15 +33,34 @@ use rocket::http::ContentType;
 pub async fn download_memberships_list(
     memberships_provider_config: &MembershipsProviderConfig,
     credentials: &FileoCredentials,
-) -> Result<Vec<Membership>> {
+) -> Result<ParsedMemberships> {
+    let client = build_client()?;
+    login_to_fileo(&client, memberships_provider_config.host(), credentials).await?;
+    download_memberships_list_with_client(&client, memberships_provider_config).await
+}
+
+/// Download the memberships list from Fileo,
+/// reusing the session opened when the user has logged in.
+/// Fail with [SessionExpired] if Fileo has closed it since.
+pub async fn download_memberships_list_resuming_session(
+    memberships_provider_config: &MembershipsProviderConfig,
+    session: &FileoSession,
+) -> Result<ParsedMemberships> {
+    let client =
+        build_client_resuming_session(memberships_provider_config.host(), session.cookies())?;
+    download_memberships_list_with_client(&client, memberships_provider_config).await
+}
+
+async fn download_memberships_list_with_client(
+    client: &Client,
+    memberships_provider_config: &MembershipsProviderConfig,
+) -> Result<ParsedMemberships> {
     let host = memberships_provider_config.host();
     let download_link_regex = memberships_provider_config.download_link_regex();
 
-    let client = build_client()?;
-    login_to_fileo(&client, host, credentials).await?;
-    load_list_into_server_session(&client, host).await?;
-    let download_url = retrieve_download_link(&client, host, download_link_regex).await?;
-    let file_content = download_list(&client, &download_url).await?;
+    load_list_into_server_session(client, host).await?;
+    let download_url = retrieve_download_link(client, host, download_link_regex).await?;
+    let file_content = download_list(client, &download_url).await?;
     Ok(parse_file(&file_content))
 }
 
@@ -113,6 +136,11 @@ async fn retrieve_download_link(
         .text()
         .await
         .map_err(log_error_and_return(CantReadPageContent))?;
+    // Once the session has expired, Fileo displays its login form instead.
+    if page_content.contains("formConnecter") {
+        debug!("Fileo session has expired.");
+        return Err(ApplicationError::from(SessionExpired));
+    }
     let regex = download_link_regex;
     let file_url = regex.find(&page_content).ok_or(NoDownloadLink)?.as_str();
     Ok(file_url.to_owned())
@@ -134,9 +162,7 @@ async fn download_list(client: &Client, file_url: &str) -> Result<String> {
         .bytes()
         .await
         .map_err(log_error_and_return(MalformedMembershipsDownloadResponse))?;
-    Ok(ISO_8859_1
-        .decode(file_content_as_bytes.as_ref(), DecoderTrap::Strict)
-        .map_err(FileoError::from)?)
+    decode_file(file_content_as_bytes.as_ref())
 }
 // endregion
 
@@ -238,7 +264,31 @@ fn format_arguments_into_body(args: &[(&str, &str)]) -> String {
         .join("&")
 }
 
-fn parse_file(file_content: &str) -> Vec<Membership> {
+/// Memberships parsed from a Fileo export, along with the rows that couldn't be parsed.
+#[derive(Debug, Getters, Default, PartialEq)]
+pub struct ParsedMemberships {
+    memberships: Vec<Membership>,
+    rejected_rows: Vec<RejectedRow>,
+}
+
+impl ParsedMemberships {
+    /// Whether the share of rejected rows, as a percentage, is above `max_rejected_ratio`.
+    /// When it is, the file is likely not to have the expected format anymore.
+    pub fn has_too_many_rejected_rows(&self, max_rejected_ratio: u64) -> bool {
+        let rejected_count = self.rejected_rows.len() as u64;
+        let total_count = self.memberships.len() as u64 + rejected_count;
+        rejected_count * 100 > max_rejected_ratio * total_count
+    }
+}
+
+/// Fileo exports are encoded in ISO-8859-1.
+pub(crate) fn decode_file(file_content: &[u8]) -> Result<String> {
+    Ok(ISO_8859_1
+        .decode(file_content, DecoderTrap::Strict)
+        .map_err(FileoError::from)?)
+}
+
+pub(crate) fn parse_file(file_content: &str) -> ParsedMemberships {
     let reader = BufReader::new(file_content.as_bytes());
     let mut reader = csv::ReaderBuilder::new()
         .delimiter(b';')
@@ -246,20 +296,43 @@ fn parse_file(file_content: &str) -> Vec<Membership> {
     load_memberships(&mut reader)
 }
 
-fn load_memberships<T>(reader: &mut Reader<T>) -> Vec<Membership>
+fn load_memberships<T>(reader: &mut Reader<T>) -> ParsedMemberships
 where
     T: std::io::Read,
 {
-    reader
-        .deserialize()
-        .filter_map(|result: Result<ImportedMembership, _>| match result {
-            Ok(membership) => Some(membership.into()),
-            Err(e) => {
-                log_message("Error while reading membership")(e);
-                None
+    let headers = reader.headers().cloned().unwrap_or_default();
+    let mut parsed_memberships = ParsedMemberships::default();
+    for result in reader.deserialize::<ImportedMembership>() {
+        match result {
+            Ok(membership) => parsed_memberships.memberships.push(membership.into()),
+            Err(error) => {
+                parsed_memberships
+                    .rejected_rows
+                    .push(to_rejected_row(&error, &headers));
+                log_message("Error while reading membership")(error);
             }
-        })
-        .collect::<Vec<_>>()
+        }
+    }
+    parsed_memberships
+}
+
+/// Describe where a row has failed to be read, and why.
+/// The column is only known when a field can't be deserialized.
+fn to_rejected_row(error: &csv::Error, headers: &StringRecord) -> RejectedRow {
+    let line = error
+        .position()
+        .map(|position| position.line())
+        .unwrap_or_default();
+    match error.kind() {
+        csv::ErrorKind::Deserialize { err, .. } => {
+            let column = err
+                .field()
+                .and_then(|field| headers.get(field as usize))
+                .map(str::to_owned);
+            RejectedRow::new(line, column, err.kind().to_string())
+        }
+        _ => RejectedRow::new(line, None, error.to_string()),
+    }
 }
 
 #[cfg(test)]
@@ -268,35 +341,23 @@ mod tests {
     use crate::error::ApplicationError;
     use crate::error::ApplicationError::{Fileo, Web};
     use crate::membership::config::MembershipsProviderConfig;
+    use crate::tools::web::SessionCookies;
     use crate::web::error::WebError;
     use dto::membership::tests::{get_expected_membership, get_membership_as_csv};
     use encoding::EncoderTrap;
     use regex::Regex;
     use rocket::http::ContentType;
-    use wiremock::matchers::{body_string_contains, method, path, query_param_contains};
+    use wiremock::matchers::{body_string_contains, header, method, path, query_param_contains};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
-    #[async_test]
-    async fn should_download_members_list() {
-        let mock_server = MockServer::start().await;
-
-        let config = MembershipsProviderConfig::new(
-            mock_server.uri(),
-            Regex::new(&format!("{}/download\\.csv", mock_server.uri())).unwrap(),
-        );
-        let credentials =
-            FileoCredentials::new("test_login".to_owned(), "test_password".to_owned());
+    /// Mock the pages to go through once logged in, for the session whose cookies are given.
+    async fn setup_memberships_list_download(
+        mock_server: &MockServer,
+        session_cookies: &str,
+    ) -> MembershipsProviderConfig {
         let download_filename = "download.csv";
         let download_link = format!("{}/{download_filename}", mock_server.uri());
 
-        Mock::given(method("POST"))
-            .and(path("/page.php"))
-            .and(body_string_contains("Action=connect_user"))
-            .respond_with(ResponseTemplate::new(200).set_body_string(
-                "Profil Commission thématique - UNSLL - Commission Nationale Monocycle",
-            ))
-            .mount(&mock_server)
-            .await;
         Mock::given(method("POST"))
             .and(path("/page.php"))
             .and(query_param_contains(
@@ -304,8 +365,9 @@ mod tests {
                 "bo/extranet/adhesion/annuaire/index",
             ))
             .and(body_string_contains("Action=adherent_filtrer"))
+            .and(header("Cookie", session_cookies))
             .respond_with(ResponseTemplate::new(200))
-            .mount(&mock_server)
+            .mount(mock_server)
             .await;
         Mock::given(method("POST"))
             .and(path("/includer.php"))
@@ -314,7 +376,7 @@ mod tests {
                 format!("<p>Here is the download link: {download_link}</p>"),
                 "text/html",
             ))
-            .mount(&mock_server)
+            .mount(mock_server)
             .await;
         let member_as_csv = get_membership_as_csv();
         let member_as_csv = ISO_8859_1
@@ -324,13 +386,54 @@ mod tests {
         Mock::given(method("GET"))
             .and(path(format!("/{download_filename}").to_owned()))
             .respond_with(ResponseTemplate::new(200).set_body_raw(message_in_latin1, "text/csv"))
+            .mount(mock_server)
+            .await;
+
+        MembershipsProviderConfig::new(
+            mock_server.uri(),
+            Regex::new(&format!("{}/download\\.csv", mock_server.uri())).unwrap(),
+        )
+    }
+
+    #[async_test]
+    async fn should_download_members_list() {
+        let mock_server = MockServer::start().await;
+        let credentials =
+            FileoCredentials::new("test_login".to_owned(), "test_password".to_owned());
+
+        Mock::given(method("POST"))
+            .and(path("/page.php"))
+            .and(body_string_contains("Action=connect_user"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("Set-Cookie", "PHPSESSID=1234; Path=/")
+                    .set_body_string(
+                        "Profil Commission thématique - UNSLL - Commission Nationale Monocycle",
+                    ),
+            )
             .mount(&mock_server)
             .await;
+        let config = setup_memberships_list_download(&mock_server, "PHPSESSID=1234").await;
 
         let result = download_memberships_list(&config, &credentials)
             .await
             .unwrap();
-        assert_eq!(vec![get_expected_membership()], result);
+        assert_eq!(&vec![get_expected_membership()], result.memberships());
+    }
+
+    #[async_test]
+    async fn should_download_members_list_resuming_session() {
+        let mock_server = MockServer::start().await;
+        let session = FileoSession::new(
+            "test_login".to_owned(),
+            SessionCookies::new("PHPSESSID=1234"),
+        );
+        let config = setup_memberships_list_download(&mock_server, "PHPSESSID=1234").await;
+
+        let result = download_memberships_list_resuming_session(&config, &session)
+            .await
+            .unwrap();
+        assert_eq!(&vec![get_expected_membership()], result.memberships());
     }
 
     #[test]
@@ -571,6 +674,29 @@ mod tests {
         assert!(result.is_err_and(|e| matches!(e, Fileo(NoDownloadLink))));
     }
 
+    #[async_test]
+    async fn should_not_retrieve_download_link_when_session_has_expired() {
+        let mock_server = MockServer::start().await;
+        let download_link_regex =
+            Regex::new(&format!("{}/download\\.csv", mock_server.uri())).unwrap();
+
+        Mock::given(method("POST"))
+            .and(path("/includer.php"))
+            .and(query_param_contains("inc", "ajax/adherent/adherent_export"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                r#"<form name="formConnecter" method="post"></form>"#.to_string(),
+                "text/html",
+            ))
+            .mount(&mock_server)
+            .await;
+
+        let client = build_client().unwrap();
+
+        let result =
+            retrieve_download_link(&client, &mock_server.uri(), &download_link_regex).await;
+        assert!(result.is_err_and(|e| matches!(e, Web(SessionExpired))));
+    }
+
     #[async_test]
     async fn should_download_list() {
         let message_in_latin1: &[u8] = &[239]; // Represents the character `ï` in LATIN1/ISO_8859_1
@@ -724,8 +850,9 @@ mod tests {
             let mut reader = csv::ReaderBuilder::new()
                 .delimiter(b';')
                 .from_reader(BufReader::new(entry.as_bytes()));
-            let members = load_memberships(&mut reader);
-            assert_eq!(vec![expected_member], members);
+            let parsed_memberships = load_memberships(&mut reader);
+            assert_eq!(&vec![expected_member], parsed_memberships.memberships());
+            assert!(parsed_memberships.rejected_rows().is_empty());
         }
 
         #[test]
@@ -734,8 +861,70 @@ mod tests {
             let mut reader = csv::ReaderBuilder::new()
                 .delimiter(b';')
                 .from_reader(BufReader::new(entry.as_bytes()));
-            let members = load_memberships(&mut reader);
-            assert!(members.is_empty(), "`members` is not empty.");
+            let parsed_memberships = load_memberships(&mut reader);
+            assert!(
+                parsed_memberships.memberships().is_empty(),
+                "`memberships` is not empty."
+            );
+            let rejected_rows = parsed_memberships.rejected_rows();
+            assert_eq!(1, rejected_rows.len());
+            let rejected_row = rejected_rows.first().unwrap();
+            assert_eq!(&2, rejected_row.line());
+            assert_eq!(&None, rejected_row.column());
+            assert!(!rejected_row.reason().is_empty());
+        }
+
+        #[test]
+        fn should_report_rejected_rows_and_keep_the_others() {
+            let valid_entry = get_membership_as_csv();
+            let invalid_row = valid_entry
+                .lines()
+                .last()
+                .unwrap()
+                .replace("30-09-2024", "Oops");
+            let entry = format!("{valid_entry}\n{invalid_row}");
+            let mut reader = csv::ReaderBuilder::new()
+                .delimiter(b';')
+                .from_reader(BufReader::new(entry.as_bytes()));
+
+            let parsed_memberships = load_memberships(&mut reader);
+            assert_eq!(
+                &vec![get_expected_membership()],
+                parsed_memberships.memberships()
+            );
+            let rejected_rows = parsed_memberships.rejected_rows();
+            assert_eq!(1, rejected_rows.len());
+            assert_eq!(&3, rejected_rows.first().unwrap().line());
+        }
+    }
+
+    mod has_too_many_rejected_rows {
+        use crate::fileo::download::ParsedMemberships;
+        use dto::membership::tests::get_expected_membership;
+        use dto::membership_import::RejectedRow;
+        use parameterized::{ide, parameterized};
+
+        ide!();
+
+        #[parameterized(
+            memberships_count = {9, 8, 0, 0},
+            rejected_count = {1, 2, 0, 1},
+            expected_result = {false, true, false, true}
+        )]
+        fn should_compare_rejected_ratio(
+            memberships_count: usize,
+            rejected_count: usize,
+            expected_result: bool,
+        ) {
+            let parsed_memberships = ParsedMemberships {
+                memberships: vec![get_expected_membership(); memberships_count],
+                rejected_rows: vec![RejectedRow::new(2, None, "error".to_owned()); rejected_count],
+            };
+
+            assert_eq!(
+                expected_result,
+                parsed_memberships.has_too_many_rejected_rows(10)
+            );
         }
     }
 }
@@ -14,6 +14,12 @@ pub enum FileoError {
     NoDownloadLink,
     #[error("The memberships file can't be read as bytes.")]
     MalformedMembershipsDownloadResponse,
+    #[error("The --file argument is missing.")]
+    MissingImportFile,
+    #[error("Can't read the memberships file to import [error: {0}]")]
+    CantReadImportFile(String),
+    #[error("Too many rows of the memberships file have been rejected [rejected: {0}, total: {1}]")]
+    TooManyRejectedRows(usize, usize),
 }
 
 impl From<Cow<'static, str>> for FileoError {
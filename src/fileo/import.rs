@@ -0,0 +1,258 @@
+use crate::database::dao::membership::import_memberships_with_rejected_rows;
+use crate::database::error::DatabaseError;
+use crate::error::Result;
+use crate::fileo::download::{ParsedMemberships, decode_file, parse_file};
+use crate::fileo::error::FileoError::{CantReadImportFile, MissingImportFile, TooManyRejectedRows};
+use crate::tools::env_args::{has_arg, retrieve_arg_value, retrieve_expected_arg_value};
+use diesel::SqliteConnection;
+use diesel::r2d2::{ConnectionManager, Pool};
+use dto::membership_import::{MembershipImportDiff, MembershipImportPreview};
+use std::fs;
+
+/// Subcommand importing a Fileo CSV export from disk instead of starting the servers.
+pub const IMPORT_FILEO_CSV_COMMAND: &str = "import-fileo-csv";
+const FILE_ARG: &str = "--file";
+const DRY_RUN_ARG: &str = "--dry-run";
+const MAX_REJECTED_RATIO_ARG: &str = "--max-rejected-ratio";
+/// Above 10% of rejected rows, Fileo has most likely changed the export format.
+const DEFAULT_MAX_REJECTED_RATIO: u64 = 10;
+
+/// Parse a Fileo CSV export without importing it,
+/// so that the user can check what would be imported.
+pub fn preview_file(file_content: &[u8]) -> Result<MembershipImportPreview> {
+    let parsed_memberships = parse_file(&decode_file(file_content)?);
+    Ok(MembershipImportPreview::new(
+        parsed_memberships.memberships().len(),
+        parsed_memberships.rejected_rows().clone(),
+        parsed_memberships.has_too_many_rejected_rows(retrieve_max_rejected_ratio()),
+    ))
+}
+
+/// Parse a Fileo CSV export and import its memberships.
+/// Rows that can't be parsed are left aside and recorded with the import.
+pub fn import_file(
+    connection: &mut SqliteConnection,
+    file_content: &[u8],
+) -> Result<MembershipImportDiff> {
+    let parsed_memberships = parse_file(&decode_file(file_content)?);
+    import_parsed_memberships(connection, &parsed_memberships)
+}
+
+/// Import memberships parsed from a Fileo export, along with the rows that have been rejected.
+/// The import is refused if too many rows have been rejected,
+/// so that a change in the export format doesn't silently drop memberships.
+pub fn import_parsed_memberships(
+    connection: &mut SqliteConnection,
+    parsed_memberships: &ParsedMemberships,
+) -> Result<MembershipImportDiff> {
+    if parsed_memberships.has_too_many_rejected_rows(retrieve_max_rejected_ratio()) {
+        let rejected_count = parsed_memberships.rejected_rows().len();
+        let total_count = parsed_memberships.memberships().len() + rejected_count;
+        return Err(TooManyRejectedRows(rejected_count, total_count).into());
+    }
+
+    Ok(import_memberships_with_rejected_rows(
+        connection,
+        parsed_memberships.memberships(),
+        parsed_memberships.rejected_rows(),
+    )?)
+}
+
+/// Retrieve which percentage of rejected rows makes an import refused.
+fn retrieve_max_rejected_ratio() -> u64 {
+    retrieve_arg_value(MAX_REJECTED_RATIO_ARG)
+        .and_then(|ratio| ratio.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_MAX_REJECTED_RATIO)
+}
+
+/// Import the Fileo CSV export located at `--file`.
+/// With `--dry-run`, only print what would be imported.
+pub fn import_file_from_command_line(
+    pool: &Pool<ConnectionManager<SqliteConnection>>,
+) -> Result<()> {
+    let path = retrieve_expected_arg_value(FILE_ARG, MissingImportFile)?;
+    let file_content = fs::read(&path).map_err(|error| CantReadImportFile(error.to_string()))?;
+
+    let preview = preview_file(&file_content)?;
+    println!(
+        "{} memberships read, {} rows rejected",
+        preview.memberships_count(),
+        preview.rejected_rows().len()
+    );
+    for rejected_row in preview.rejected_rows() {
+        println!(
+            "  - line {}{}: {}",
+            rejected_row.line(),
+            rejected_row
+                .column()
+                .as_ref()
+                .map(|column| format!(", column {column}"))
+                .unwrap_or_default(),
+            rejected_row.reason()
+        );
+    }
+    if *preview.too_many_rejected_rows() {
+        println!("Too many rows have been rejected, memberships won't be imported");
+    }
+    if has_arg(DRY_RUN_ARG) {
+        return Ok(());
+    }
+
+    let mut connection = pool.get().map_err(DatabaseError::from)?;
+    let diff = import_file(&mut connection, &file_content)?;
+    println!(
+        "Memberships imported [added: {}, updated: {}, removed: {}]",
+        diff.added().len(),
+        diff.updated().len(),
+        diff.removed().len()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    mod preview_file {
+        use crate::fileo::import::preview_file;
+        use dto::membership::tests::{get_malformed_membership_as_csv, get_membership_as_csv};
+        use encoding::all::ISO_8859_1;
+        use encoding::{EncoderTrap, Encoding};
+
+        #[test]
+        fn success() {
+            let file_content = ISO_8859_1
+                .encode(&get_membership_as_csv(), EncoderTrap::Strict)
+                .unwrap();
+
+            let preview = preview_file(&file_content).unwrap();
+            assert_eq!(&1, preview.memberships_count());
+            assert!(preview.rejected_rows().is_empty());
+        }
+
+        #[test]
+        fn with_rejected_rows() {
+            let file_content = ISO_8859_1
+                .encode(&get_malformed_membership_as_csv(), EncoderTrap::Strict)
+                .unwrap();
+
+            let preview = preview_file(&file_content).unwrap();
+            assert_eq!(&0, preview.memberships_count());
+            assert_eq!(1, preview.rejected_rows().len());
+            assert!(preview.too_many_rejected_rows());
+        }
+    }
+
+    mod import_file {
+        use crate::database::dao::membership_import::retrieve_last;
+        use crate::database::{dao, with_temp_database};
+        use crate::fileo::import::{MAX_REJECTED_RATIO_ARG, import_file};
+        use crate::tools::env_args::with_env_args;
+        use dto::membership::tests::{get_malformed_membership_as_csv, get_membership_as_csv};
+        use encoding::all::ISO_8859_1;
+        use encoding::{EncoderTrap, Encoding};
+
+        fn build_file_with_rejected_row() -> Vec<u8> {
+            let file_content = format!(
+                "{}\n{}",
+                get_membership_as_csv(),
+                get_malformed_membership_as_csv().lines().last().unwrap()
+            );
+            ISO_8859_1
+                .encode(&file_content, EncoderTrap::Strict)
+                .unwrap()
+        }
+
+        #[test]
+        fn should_record_rejected_rows() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                let args = vec![format!("{MAX_REJECTED_RATIO_ARG}=50")];
+
+                with_env_args(args, || {
+                    import_file(&mut connection, &build_file_with_rejected_row())
+                })
+                .unwrap();
+
+                let last_import = retrieve_last(&mut connection).unwrap().unwrap();
+                assert_eq!(&1, last_import.added_count());
+                assert_eq!(1, last_import.rejected_rows().len());
+                assert_eq!(&3, last_import.rejected_rows().first().unwrap().line());
+            })
+        }
+
+        #[test]
+        fn should_refuse_when_too_many_rejected_rows() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                let args = vec![format!("{MAX_REJECTED_RATIO_ARG}=49")];
+
+                with_env_args(args, || {
+                    import_file(&mut connection, &build_file_with_rejected_row())
+                })
+                .unwrap_err();
+
+                let memberships = dao::membership::retrieve_memberships(&mut connection).unwrap();
+                assert!(memberships.is_empty());
+                assert_eq!(None, retrieve_last(&mut connection).unwrap());
+            })
+        }
+    }
+
+    mod import_file_from_command_line {
+        use crate::database::{dao, with_temp_database};
+        use crate::fileo::import::{DRY_RUN_ARG, FILE_ARG, import_file_from_command_line};
+        use crate::tools::env_args::with_env_args;
+        use crate::tools::test::tests::temp_dir;
+        use dto::membership::tests::{get_expected_membership, get_membership_as_csv};
+        use encoding::all::ISO_8859_1;
+        use encoding::{EncoderTrap, Encoding};
+        use std::fs;
+        use std::path::PathBuf;
+
+        fn write_file() -> PathBuf {
+            let path = temp_dir().join("memberships.csv");
+            let file_content = ISO_8859_1
+                .encode(&get_membership_as_csv(), EncoderTrap::Strict)
+                .unwrap();
+            fs::write(&path, file_content).unwrap();
+            path
+        }
+
+        #[test]
+        fn success() {
+            with_temp_database(|pool| {
+                let path = write_file();
+                let args = vec![format!("{FILE_ARG}={}", path.to_str().unwrap())];
+
+                with_env_args(args, || import_file_from_command_line(&pool)).unwrap();
+
+                let mut connection = pool.get().unwrap();
+                let memberships = dao::membership::retrieve_memberships(&mut connection).unwrap();
+                assert_eq!(vec![get_expected_membership()], memberships);
+            })
+        }
+
+        #[test]
+        fn should_not_import_when_dry_run() {
+            with_temp_database(|pool| {
+                let path = write_file();
+                let args = vec![
+                    format!("{FILE_ARG}={}", path.to_str().unwrap()),
+                    DRY_RUN_ARG.to_owned(),
+                ];
+
+                with_env_args(args, || import_file_from_command_line(&pool)).unwrap();
+
+                let mut connection = pool.get().unwrap();
+                let memberships = dao::membership::retrieve_memberships(&mut connection).unwrap();
+                assert!(memberships.is_empty());
+            })
+        }
+
+        #[test]
+        fn fail_when_file_is_missing() {
+            with_temp_database(|pool| {
+                assert!(with_env_args(vec![], || import_file_from_command_line(&pool)).is_err());
+            })
+        }
+    }
+}
@@ -2,4 +2,7 @@ pub(crate) mod authentication;
 pub(crate) mod credentials;
 pub(crate) mod download;
 pub mod error;
+pub(crate) mod import;
 pub(crate) mod imported_membership;
+pub(crate) mod session;
+pub(crate) mod sync;
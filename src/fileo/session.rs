@@ -0,0 +1,22 @@
+use crate::tools::web::SessionCookies;
+use crate::web::credentials_storage::sqlite::StoredCredentials;
+use derive_getters::Getters;
+use rocket::serde::{Deserialize, Serialize};
+
+/// What is kept of a user once logged in Fileo:
+/// the session Fileo has opened for them, rather than their password.
+#[derive(Serialize, Deserialize, Getters, PartialEq, Clone, Debug)]
+pub struct FileoSession {
+    login: String,
+    cookies: SessionCookies,
+}
+
+impl FileoSession {
+    pub fn new(login: String, cookies: SessionCookies) -> Self {
+        Self { login, cookies }
+    }
+}
+
+impl StoredCredentials for FileoSession {
+    const KIND: &'static str = "Fileo";
+}
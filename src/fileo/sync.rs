@@ -0,0 +1,360 @@
+use crate::database::dao::last_update::UpdatableElement::{
+    MembershipsSyncFailure, MembershipsSyncSuccess,
+};
+use crate::database::dao::last_update::{get_last_update, record_memberships_sync};
+use crate::database::error::DatabaseError;
+use crate::error::ApplicationError;
+use crate::fileo::credentials::FileoCredentials;
+use crate::fileo::download::{ParsedMemberships, download_memberships_list};
+use crate::fileo::import::import_parsed_memberships;
+use crate::membership::config::MembershipsProviderConfig;
+use crate::tools::env_args::retrieve_arg_value;
+use chrono::NaiveDateTime;
+use derive_getters::Getters;
+use diesel::SqliteConnection;
+use diesel::r2d2::{ConnectionManager, Pool};
+use rocket::fairing::AdHoc;
+use rocket::serde::json::from_str;
+use serde::Serialize;
+use std::fs;
+use std::time::Duration;
+
+const SYNC_LOGIN_ARG: &str = "--fileo-sync-login";
+const SYNC_PASSWORD_ARG: &str = "--fileo-sync-password";
+const SYNC_CREDENTIALS_FILE_ARG: &str = "--fileo-sync-credentials-file";
+const SYNC_INTERVAL_ARG: &str = "--fileo-sync-interval";
+/// Memberships are synchronized once a day by default.
+const DEFAULT_SYNC_INTERVAL_IN_MINUTES: u64 = 24 * 60;
+
+/// When the last automatic synchronization has happened, and whether it has succeeded.
+#[derive(Debug, Getters, PartialEq, Serialize)]
+pub struct LastSync {
+    date: NaiveDateTime,
+    succeeded: bool,
+}
+
+/// Synchronize memberships with Fileo in the background, once the app has lifted off.
+/// The synchronization requires a service account: without it, no synchronization is made.
+pub fn fileo_sync_fairing() -> AdHoc {
+    AdHoc::on_liftoff("Fileo synchronization", |rocket| {
+        Box::pin(async move {
+            let credentials = match retrieve_service_account() {
+                Some(credentials) => credentials,
+                None => {
+                    debug!("No Fileo service account, memberships won't be synchronized");
+                    return;
+                }
+            };
+            let pool = rocket
+                .state::<Pool<ConnectionManager<SqliteConnection>>>()
+                .expect("The database pool should be managed at this point")
+                .clone();
+            let config = rocket
+                .state::<MembershipsProviderConfig>()
+                .expect("The memberships provider config should be managed at this point")
+                .clone();
+            let sync_interval = retrieve_sync_interval();
+
+            rocket::tokio::spawn(async move {
+                // The first synchronization waits for a whole interval, so that startup isn't slowed down.
+                let mut interval = rocket::tokio::time::interval_at(
+                    rocket::tokio::time::Instant::now() + sync_interval,
+                    sync_interval,
+                );
+                loop {
+                    interval.tick().await;
+                    synchronize_memberships(&config, &credentials, &pool).await;
+                }
+            });
+        })
+    })
+}
+
+/// Download memberships from Fileo and import them,
+/// then record whether the synchronization has succeeded.
+async fn synchronize_memberships(
+    config: &MembershipsProviderConfig,
+    credentials: &FileoCredentials,
+    pool: &Pool<ConnectionManager<SqliteConnection>>,
+) {
+    let parsed_memberships = download_memberships_list(config, credentials)
+        .await
+        .map_err(|error| error!("Can't download memberships to synchronize with Fileo: {error:?}"))
+        .ok();
+
+    // Database calls are blocking, so they are kept off the async runtime.
+    let pool = pool.clone();
+    let result = rocket::tokio::task::spawn_blocking(move || {
+        import_and_record_memberships(&pool, parsed_memberships.as_ref())
+    })
+    .await;
+    if let Err(error) = result {
+        error!("Can't synchronize memberships with Fileo: {error:?}");
+    }
+}
+
+/// Import the downloaded memberships, if any, then record whether the synchronization has succeeded.
+fn import_and_record_memberships(
+    pool: &Pool<ConnectionManager<SqliteConnection>>,
+    parsed_memberships: Option<&ParsedMemberships>,
+) {
+    let succeeded = match parsed_memberships {
+        Some(parsed_memberships) => {
+            let result = pool
+                .get()
+                .map_err(DatabaseError::from)
+                .map_err(ApplicationError::from)
+                .and_then(|mut connection| {
+                    import_parsed_memberships(&mut connection, parsed_memberships)
+                });
+            match result {
+                Ok(diff) => {
+                    debug!(
+                        "Memberships have been synchronized with Fileo [added: {}, updated: {}, removed: {}]",
+                        diff.added().len(),
+                        diff.updated().len(),
+                        diff.removed().len()
+                    );
+                    true
+                }
+                Err(error) => {
+                    error!("Can't import memberships synchronized with Fileo: {error:?}");
+                    false
+                }
+            }
+        }
+        None => false,
+    };
+
+    let recorded = pool
+        .get()
+        .map_err(DatabaseError::from)
+        .and_then(|mut connection| record_memberships_sync(&mut connection, succeeded));
+    if let Err(error) = recorded {
+        error!("Can't record memberships synchronization: {error:?}");
+    }
+}
+
+/// Retrieve the last automatic synchronization, whether it has succeeded or not.
+pub fn retrieve_last_sync(
+    connection: &mut SqliteConnection,
+) -> crate::database::Result<Option<LastSync>> {
+    let last_success = get_last_update(connection, &MembershipsSyncSuccess)?;
+    let last_failure = get_last_update(connection, &MembershipsSyncFailure)?;
+
+    Ok([(last_success, true), (last_failure, false)]
+        .into_iter()
+        .filter_map(|(date, succeeded)| date.map(|date| LastSync { date, succeeded }))
+        .max_by_key(|last_sync| last_sync.date))
+}
+
+// region Retrieve args
+/// The service account is retrieved from args if both login and password are provided,
+/// or from a JSON file otherwise.
+fn retrieve_service_account() -> Option<FileoCredentials> {
+    let login = retrieve_arg_value(SYNC_LOGIN_ARG);
+    let password = retrieve_arg_value(SYNC_PASSWORD_ARG);
+    match (login, password) {
+        (Some(login), Some(password)) => Some(FileoCredentials::new(login, password)),
+        _ => retrieve_arg_value(SYNC_CREDENTIALS_FILE_ARG)
+            .and_then(|path| read_service_account_file(&path)),
+    }
+}
+
+fn read_service_account_file(path: &str) -> Option<FileoCredentials> {
+    let content = fs::read_to_string(path)
+        .map_err(|error| {
+            error!("Can't read Fileo service account file [path: {path}, error: {error:?}]")
+        })
+        .ok()?;
+    from_str::<FileoCredentials>(&content)
+        .map_err(|error| {
+            error!("Can't parse Fileo service account file [path: {path}, error: {error:?}]")
+        })
+        .ok()
+}
+
+fn retrieve_sync_interval() -> Duration {
+    let minutes = retrieve_arg_value(SYNC_INTERVAL_ARG)
+        .and_then(|interval| interval.parse::<u64>().ok())
+        .filter(|interval| *interval > 0)
+        .unwrap_or(DEFAULT_SYNC_INTERVAL_IN_MINUTES);
+    Duration::from_secs(minutes * 60)
+}
+// endregion
+
+#[cfg(test)]
+mod tests {
+    mod retrieve_service_account {
+        use crate::fileo::credentials::FileoCredentials;
+        use crate::fileo::sync::{
+            SYNC_CREDENTIALS_FILE_ARG, SYNC_LOGIN_ARG, SYNC_PASSWORD_ARG, retrieve_service_account,
+        };
+        use crate::tools::env_args::with_env_args;
+        use crate::tools::test::tests::temp_dir;
+        use std::fs;
+
+        #[test]
+        fn from_args() {
+            let args = vec![
+                format!("{SYNC_LOGIN_ARG}=login"),
+                format!("{SYNC_PASSWORD_ARG}=password"),
+            ];
+
+            assert_eq!(
+                Some(FileoCredentials::new(
+                    "login".to_owned(),
+                    "password".to_owned()
+                )),
+                with_env_args(args, retrieve_service_account)
+            );
+        }
+
+        #[test]
+        fn from_file() {
+            let path = temp_dir().join("service-account.json");
+            fs::write(&path, r#"{"login": "login", "password": "password"}"#).unwrap();
+            let args = vec![format!(
+                "{SYNC_CREDENTIALS_FILE_ARG}={}",
+                path.to_str().unwrap()
+            )];
+
+            assert_eq!(
+                Some(FileoCredentials::new(
+                    "login".to_owned(),
+                    "password".to_owned()
+                )),
+                with_env_args(args, retrieve_service_account)
+            );
+        }
+
+        #[test]
+        fn none_when_file_is_invalid() {
+            let path = temp_dir().join("service-account.json");
+            fs::write(&path, "login:password").unwrap();
+            let args = vec![format!(
+                "{SYNC_CREDENTIALS_FILE_ARG}={}",
+                path.to_str().unwrap()
+            )];
+
+            assert_eq!(None, with_env_args(args, retrieve_service_account));
+        }
+
+        #[test]
+        fn none_when_password_is_missing() {
+            let args = vec![format!("{SYNC_LOGIN_ARG}=login")];
+
+            assert_eq!(None, with_env_args(args, retrieve_service_account));
+        }
+    }
+
+    mod retrieve_sync_interval {
+        use crate::fileo::sync::{
+            DEFAULT_SYNC_INTERVAL_IN_MINUTES, SYNC_INTERVAL_ARG, retrieve_sync_interval,
+        };
+        use crate::tools::env_args::with_env_args;
+        use std::time::Duration;
+
+        #[test]
+        fn custom_interval() {
+            let args = vec![format!("{SYNC_INTERVAL_ARG}=30")];
+
+            assert_eq!(
+                Duration::from_secs(30 * 60),
+                with_env_args(args, retrieve_sync_interval)
+            );
+        }
+
+        #[test]
+        fn default_interval_when_missing() {
+            assert_eq!(
+                Duration::from_secs(DEFAULT_SYNC_INTERVAL_IN_MINUTES * 60),
+                with_env_args(vec![], retrieve_sync_interval)
+            );
+        }
+
+        #[test]
+        fn default_interval_when_zero() {
+            let args = vec![format!("{SYNC_INTERVAL_ARG}=0")];
+
+            assert_eq!(
+                Duration::from_secs(DEFAULT_SYNC_INTERVAL_IN_MINUTES * 60),
+                with_env_args(args, retrieve_sync_interval)
+            );
+        }
+    }
+
+    mod synchronize_memberships {
+        use crate::database::with_temp_database;
+        use crate::fileo::credentials::FileoCredentials;
+        use crate::fileo::sync::{retrieve_last_sync, synchronize_memberships};
+        use crate::membership::config::MembershipsProviderConfig;
+        use diesel::SqliteConnection;
+        use diesel::r2d2::{ConnectionManager, Pool};
+        use regex::Regex;
+        use rocket::tokio::runtime::Runtime;
+        use wiremock::MockServer;
+
+        #[test]
+        fn should_record_failure() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let mock_server = MockServer::start().await;
+                let config = MembershipsProviderConfig::new(
+                    mock_server.uri(),
+                    Regex::new(&format!("{}/download\\.csv", mock_server.uri())).unwrap(),
+                );
+                let credentials =
+                    FileoCredentials::new("test_login".to_owned(), "test_password".to_owned());
+
+                synchronize_memberships(&config, &credentials, &pool).await;
+
+                let mut connection = pool.get().unwrap();
+                let last_sync = retrieve_last_sync(&mut connection).unwrap().unwrap();
+                assert!(!last_sync.succeeded());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+    }
+
+    mod retrieve_last_sync {
+        use crate::database::dao::last_update::record_memberships_sync;
+        use crate::database::with_temp_database;
+        use crate::fileo::sync::retrieve_last_sync;
+
+        #[test]
+        fn none() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+
+                assert_eq!(None, retrieve_last_sync(&mut connection).unwrap());
+            })
+        }
+
+        #[test]
+        fn most_recent_success() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                record_memberships_sync(&mut connection, false).unwrap();
+                let date = record_memberships_sync(&mut connection, true).unwrap();
+
+                let last_sync = retrieve_last_sync(&mut connection).unwrap().unwrap();
+                assert_eq!(&date, last_sync.date());
+                assert!(last_sync.succeeded());
+            })
+        }
+
+        #[test]
+        fn most_recent_failure() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                record_memberships_sync(&mut connection, true).unwrap();
+                let date = record_memberships_sync(&mut connection, false).unwrap();
+
+                let last_sync = retrieve_last_sync(&mut connection).unwrap().unwrap();
+                assert_eq!(&date, last_sync.date());
+                assert!(!last_sync.succeeded());
+            })
+        }
+    }
+}
@@ -8,17 +8,34 @@ mod fileo;
 mod membership;
 mod tools;
 mod uda;
+mod user;
 mod web;
 
 use crate::database::init_connection_pool;
 #[cfg(feature = "demo")]
 use crate::demo_mock_server::init_demo;
+use crate::fileo::import::{IMPORT_FILEO_CSV_COMMAND, import_file_from_command_line};
+use crate::tools::env_args::has_arg;
+use crate::user::bootstrap::create_first_admin;
 use crate::web::start_servers;
 
 #[launch]
 async fn rocket() -> _ {
     env_logger::init();
     let pool = init_connection_pool().expect("Failed to initialize database connection pool");
+    if has_arg(IMPORT_FILEO_CSV_COMMAND) {
+        let exit_code = match import_file_from_command_line(&pool) {
+            Ok(()) => 0,
+            Err(error) => {
+                eprintln!("Can't import memberships: {error:?}");
+                1
+            }
+        };
+        std::process::exit(exit_code);
+    }
+    if let Err(error) = create_first_admin(&pool) {
+        error!("Can't create first admin: {error:?}");
+    }
     #[cfg(feature = "demo")]
     init_demo().await;
     start_servers(pool)
@@ -1,35 +1,57 @@
 use crate::database::dao;
+use crate::database::dao::membership::find::similar::Candidates;
 use crate::database::error::DatabaseError::R2d2;
 use crate::error::{ApplicationError, Result};
+use chrono::NaiveDate;
 use diesel::SqliteConnection;
 use diesel::r2d2::{ConnectionManager, Pool};
-use dto::checked_member::CheckResult::{Match, NoMatch, PartialMatch};
+use dto::checked_member::CheckResult::{AmbiguousMatch, FuzzyMatch, Match, NoMatch, PartialMatch};
 use dto::checked_member::{CheckResult, CheckedMember};
+use dto::event_period::EventPeriod;
 use dto::member_to_check::MemberToCheck;
+use dto::membership::Membership;
 
-/// For each member, look into the database to check whether there is a match.
+/// The minimal similarity score, as a percentage, for names to be considered a fuzzy match.
+const FUZZY_MATCH_MIN_SCORE: u8 = 80;
+
+/// For each member, look into the database to check whether there is a match,
+/// then compute their status against the event period.
+/// Memberships ending within `expiring_soon_threshold` days after the event are expiring soon.
 /// Matches are made in the following order:
 /// 1. If membership number and names match, that's a perfect match ✔
 /// 2. If membership number and identity match, that's also a perfect match ✔
 /// 3. If membership number matches, that's a partial match ✔
 /// 4. If the member to check has a membership number, but this number doesn't match anything, that's a no match ✖
-/// 5. If the names match, that's a partial match ✔
-/// 6. If the identity matches, that's a partial match ✔
-/// 7. If there has been no match so far, then that's a no match ✖
+/// 5. If the names and the birthdate match, that's a perfect match ✔
+/// 6. If the names match, that's a partial match ✔
+/// 7. If the identity and the birthdate match, that's a perfect match ✔
+/// 8. If the identity matches, that's a partial match ✔
+/// 9. If the names are similar enough, that's a fuzzy match ✔
+/// 10. If the identity is similar enough, that's a fuzzy match ✔
+/// 11. If there has been no match so far, then that's a no match ✖
+///
+/// If several people match the names or the identity, and the birthdate can't tell them apart,
+/// that's an ambiguous match ✔
+/// If the names or the identity match, but both birthdates are known and differ, that's a no match ✖
 pub fn check_members<T: MemberToCheck>(
     pool: &Pool<ConnectionManager<SqliteConnection>>,
     members_to_check: Vec<T>,
+    event_period: &EventPeriod,
+    expiring_soon_threshold: u64,
 ) -> Result<Vec<CheckedMember<T>>> {
     Ok({
         let mut result = vec![];
 
+        let mut candidates = Candidates::default();
         for member_to_check in members_to_check.into_iter() {
             let mut connection = pool
                 .get()
                 .map_err(|error| ApplicationError::Database(R2d2(error.to_string())))?;
             let checked_member = CheckedMember::new(
                 member_to_check.clone(),
-                check_member(&mut connection, &member_to_check)?,
+                check_member(&mut connection, &mut candidates, &member_to_check)?,
+                event_period,
+                expiring_soon_threshold,
             );
             result.push(checked_member);
         }
@@ -44,17 +66,27 @@ pub fn check_members<T: MemberToCheck>(
 /// 2. If membership number and identity match, that's also a perfect match ✔
 /// 3. If membership number matches, that's a partial match ✔
 /// 4. If the member to check has a membership number, but this number doesn't match anything, that's a no match ✖
-/// 5. If the names match, that's a partial match ✔
-/// 6. If the identity matches, that's a partial match ✔
-/// 7. If there has been no match so far, then that's a no match ✖
+/// 5. If the names and the birthdate match, that's a perfect match ✔
+/// 6. If the names match, that's a partial match ✔
+/// 7. If the identity and the birthdate match, that's a perfect match ✔
+/// 8. If the identity matches, that's a partial match ✔
+/// 9. If the names are similar enough, that's a fuzzy match ✔
+/// 10. If the identity is similar enough, that's a fuzzy match ✔
+/// 11. If there has been no match so far, then that's a no match ✖
+///
+/// If several people match the names or the identity, and the birthdate can't tell them apart,
+/// that's an ambiguous match ✔
+/// If the names or the identity match, but both birthdates are known and differ, that's a no match ✖
 fn check_member<T: MemberToCheck>(
     connection: &mut SqliteConnection,
+    candidates: &mut Candidates,
     member_to_check: &T,
 ) -> Result<CheckResult> {
     let membership_number = member_to_check.membership_num();
     let first_name = member_to_check.first_name();
     let last_name = member_to_check.last_name();
     let identity = member_to_check.identity();
+    let birthdate = member_to_check.birthdate();
 
     if membership_number.is_some() {
         if first_name.is_some() && last_name.is_some() {
@@ -114,12 +146,13 @@ fn check_member<T: MemberToCheck>(
         let first_name = first_name
             .clone()
             .expect("There should be a value at this point");
-        if let Some(membership) = dao::membership::find::first::by_last_name_first_name(
+        let memberships = dao::membership::find::per_person::by_last_name_first_name(
             connection,
             &last_name,
             &first_name,
-        )? {
-            return Ok(PartialMatch(membership));
+        )?;
+        if let Some(check_result) = to_name_check_result(memberships, birthdate) {
+            return Ok(check_result);
         }
     }
 
@@ -127,24 +160,81 @@ fn check_member<T: MemberToCheck>(
         let identity = identity
             .clone()
             .expect("There should be a value at this point");
-        if let Some(membership) = dao::membership::find::first::by_identity(connection, &identity)?
-        {
-            return Ok(PartialMatch(membership));
+        let memberships = dao::membership::find::per_person::by_identity(connection, &identity)?;
+        if let Some(check_result) = to_name_check_result(memberships, birthdate) {
+            return Ok(check_result);
         }
     }
 
+    if let (Some(last_name), Some(first_name)) = (last_name, first_name)
+        && let Some((membership, score)) = dao::membership::find::similar::by_last_name_first_name(
+            connection,
+            candidates,
+            &last_name,
+            &first_name,
+            FUZZY_MATCH_MIN_SCORE,
+        )?
+    {
+        return Ok(FuzzyMatch(membership, score));
+    }
+
+    if let Some(identity) = identity
+        && let Some((membership, score)) = dao::membership::find::similar::by_identity(
+            connection,
+            candidates,
+            &identity,
+            FUZZY_MATCH_MIN_SCORE,
+        )?
+    {
+        return Ok(FuzzyMatch(membership, score));
+    }
+
     Ok(NoMatch)
 }
 
+/// Build the result of a check made on names only, based on the memberships of each matching person.
+/// If the birthdate is known, it is used to confirm the match, or to tell homonyms apart:
+/// people born on another day are ruled out, and if nobody is left, there is no match.
+/// When several people still match, there is no way to tell which one is the right one,
+/// so all of them are returned as candidates.
+fn to_name_check_result(
+    memberships: Vec<Membership>,
+    birthdate: Option<NaiveDate>,
+) -> Option<CheckResult> {
+    if memberships.is_empty() {
+        return None;
+    }
+    let (mut same_birthdate_memberships, other_memberships): (Vec<_>, Vec<_>) = memberships
+        .into_iter()
+        .partition(|membership| birthdate.is_some() && *membership.birthdate() == birthdate);
+
+    match same_birthdate_memberships.len() {
+        0 => {}
+        1 => return same_birthdate_memberships.pop().map(Match),
+        _ => return Some(AmbiguousMatch(same_birthdate_memberships)),
+    }
+
+    let mut unknown_birthdate_memberships = other_memberships
+        .into_iter()
+        .filter(|membership| birthdate.is_none() || membership.birthdate().is_none())
+        .collect::<Vec<_>>();
+    match unknown_birthdate_memberships.len() {
+        0 => Some(NoMatch),
+        1 => unknown_birthdate_memberships.pop().map(PartialMatch),
+        _ => Some(AmbiguousMatch(unknown_birthdate_memberships)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     mod check_members {
-        use crate::database::dao::membership::replace_memberships;
+        use crate::database::dao::membership::import_memberships;
         use crate::database::with_temp_database;
         use crate::membership::check::check_members;
         use dto::checked_member::CheckResult::{Match, NoMatch};
         use dto::checked_member::CheckedMember;
         use dto::csv_member::CsvMember;
+        use dto::event_period::EventPeriod;
         use dto::membership::tests::{
             MEMBER_FIRST_NAME, MEMBER_NAME, MEMBERSHIP_NUMBER, get_expected_membership,
         };
@@ -154,20 +244,25 @@ mod tests {
             with_temp_database(|pool| {
                 let membership = get_expected_membership();
                 let mut connection = pool.get().unwrap();
-                replace_memberships(&mut connection, &[membership.clone()]).unwrap();
+                import_memberships(&mut connection, std::slice::from_ref(&membership)).unwrap();
                 let member_to_check = CsvMember::new(
                     Some(MEMBERSHIP_NUMBER.to_owned()),
                     None,
                     Some(MEMBER_NAME.to_owned()),
                     Some(MEMBER_FIRST_NAME.to_owned()),
+                    None,
+                    None,
+                    None,
                 );
 
                 assert_eq!(
                     vec![CheckedMember::new(
                         member_to_check.clone(),
-                        Match(membership)
+                        Match(membership),
+                        &EventPeriod::today(),
+                        0,
                     )],
-                    check_members(&pool, vec![member_to_check]).unwrap()
+                    check_members(&pool, vec![member_to_check], &EventPeriod::today(), 0).unwrap()
                 );
             });
         }
@@ -177,29 +272,94 @@ mod tests {
             with_temp_database(|pool| {
                 let membership = get_expected_membership();
                 let mut connection = pool.get().unwrap();
-                replace_memberships(&mut connection, &[membership.clone()]).unwrap();
+                import_memberships(&mut connection, std::slice::from_ref(&membership)).unwrap();
                 let invalid_membership_number = format!("{MEMBERSHIP_NUMBER} oops");
                 let member_to_check = CsvMember::new(
                     Some(invalid_membership_number),
                     None,
                     Some(MEMBER_NAME.to_owned()),
                     Some(MEMBER_FIRST_NAME.to_owned()),
+                    None,
+                    None,
+                    None,
                 );
 
                 assert_eq!(
-                    vec![CheckedMember::new(member_to_check.clone(), NoMatch)],
-                    check_members(&pool, vec![member_to_check]).unwrap()
+                    vec![CheckedMember::new(
+                        member_to_check.clone(),
+                        NoMatch,
+                        &EventPeriod::today(),
+                        0,
+                    )],
+                    check_members(&pool, vec![member_to_check], &EventPeriod::today(), 0).unwrap()
                 );
             });
         }
     }
 
+    mod check_members_status {
+        use crate::database::dao::membership::import_memberships;
+        use crate::database::with_temp_database;
+        use crate::membership::check::check_members;
+        use chrono::NaiveDate;
+        use dto::csv_member::CsvMember;
+        use dto::event_period::EventPeriod;
+        use dto::membership::tests::{
+            MEMBER_FIRST_NAME, MEMBER_NAME, MEMBERSHIP_NUMBER, get_expected_membership,
+        };
+        use dto::membership_status::MemberStatus;
+        use dto::membership_status::MemberStatus::{
+            Expired, ExpiringSoon, StartsAfterEvent, UpToDate,
+        };
+        use parameterized::{ide, parameterized};
+
+        ide!();
+
+        #[parameterized(
+            event_period = {
+                EventPeriod::new(NaiveDate::from_ymd_opt(2025, 7, 1).unwrap(), NaiveDate::from_ymd_opt(2025, 7, 5).unwrap()).unwrap(),
+                EventPeriod::new(NaiveDate::from_ymd_opt(2025, 9, 28).unwrap(), NaiveDate::from_ymd_opt(2025, 10, 2).unwrap()).unwrap(),
+                EventPeriod::single_day(NaiveDate::from_ymd_opt(2024, 9, 1).unwrap()),
+                EventPeriod::new(NaiveDate::from_ymd_opt(2025, 9, 10).unwrap(), NaiveDate::from_ymd_opt(2025, 9, 12).unwrap()).unwrap(),
+            },
+            expected_status = {
+                UpToDate,
+                Expired,
+                StartsAfterEvent,
+                ExpiringSoon,
+            }
+        )]
+        fn status_against_event_period(event_period: EventPeriod, expected_status: MemberStatus) {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                import_memberships(&mut connection, &[get_expected_membership()]).unwrap();
+                let member_to_check = CsvMember::new(
+                    Some(MEMBERSHIP_NUMBER.to_owned()),
+                    None,
+                    Some(MEMBER_NAME.to_owned()),
+                    Some(MEMBER_FIRST_NAME.to_owned()),
+                    None,
+                    None,
+                    None,
+                );
+
+                let checked_members =
+                    check_members(&pool, vec![member_to_check], &event_period, 30).unwrap();
+                assert_eq!(&expected_status, checked_members[0].status());
+            });
+        }
+    }
+
     mod check_member {
-        use crate::database::dao::membership::replace_memberships;
+        use crate::database::dao::membership::find::similar::Candidates;
+        use crate::database::dao::membership::import_memberships;
         use crate::database::with_temp_database;
         use crate::membership::check::check_member;
-        use chrono::Months;
-        use dto::checked_member::CheckResult::{Match, NoMatch, PartialMatch};
+        use crate::membership::tests::{jon_doe, jon_doe_previous_membership, other_jon_doe};
+        use chrono::{Months, NaiveDate};
+        use dto::checked_member::CheckResult::{
+            AmbiguousMatch, FuzzyMatch, Match, NoMatch, PartialMatch,
+        };
         use dto::csv_member::CsvMember;
         use dto::membership::Membership;
         use dto::membership::tests::{
@@ -211,17 +371,25 @@ mod tests {
             with_temp_database(|pool| {
                 let membership = get_expected_membership();
                 let mut connection = pool.get().unwrap();
-                replace_memberships(&mut connection, &[membership.clone()]).unwrap();
+                import_memberships(&mut connection, std::slice::from_ref(&membership)).unwrap();
                 let member_to_check = CsvMember::new(
                     Some(MEMBERSHIP_NUMBER.to_owned()),
                     None,
                     Some(MEMBER_NAME.to_owned()),
                     Some(MEMBER_FIRST_NAME.to_owned()),
+                    None,
+                    None,
+                    None,
                 );
 
                 assert_eq!(
                     Match(membership),
-                    check_member(&mut connection, &member_to_check).unwrap()
+                    check_member(
+                        &mut connection,
+                        &mut Candidates::default(),
+                        &member_to_check
+                    )
+                    .unwrap()
                 );
             });
         }
@@ -231,17 +399,25 @@ mod tests {
             with_temp_database(|pool| {
                 let membership = get_expected_membership();
                 let mut connection = pool.get().unwrap();
-                replace_memberships(&mut connection, &[membership.clone()]).unwrap();
+                import_memberships(&mut connection, std::slice::from_ref(&membership)).unwrap();
                 let member_to_check = CsvMember::new(
                     Some(format!("0{MEMBERSHIP_NUMBER}")), // Prepending with a 0 should not change anything
                     None,
                     Some(MEMBER_NAME.to_owned()),
                     Some(MEMBER_FIRST_NAME.to_owned()),
+                    None,
+                    None,
+                    None,
                 );
 
                 assert_eq!(
                     Match(membership),
-                    check_member(&mut connection, &member_to_check).unwrap()
+                    check_member(
+                        &mut connection,
+                        &mut Candidates::default(),
+                        &member_to_check
+                    )
+                    .unwrap()
                 );
             });
         }
@@ -251,17 +427,25 @@ mod tests {
             with_temp_database(|pool| {
                 let membership = get_expected_membership();
                 let mut connection = pool.get().unwrap();
-                replace_memberships(&mut connection, &[membership.clone()]).unwrap();
+                import_memberships(&mut connection, std::slice::from_ref(&membership)).unwrap();
                 let member_to_check = CsvMember::new(
                     Some(format!("  {MEMBERSHIP_NUMBER} ")),
                     None,
                     Some(format!(" {MEMBER_NAME}  ")),
                     Some(format!("{MEMBER_FIRST_NAME}  ")),
+                    None,
+                    None,
+                    None,
                 );
 
                 assert_eq!(
                     Match(membership),
-                    check_member(&mut connection, &member_to_check).unwrap()
+                    check_member(
+                        &mut connection,
+                        &mut Candidates::default(),
+                        &member_to_check
+                    )
+                    .unwrap()
                 );
             });
         }
@@ -271,17 +455,25 @@ mod tests {
             with_temp_database(|pool| {
                 let membership = get_expected_membership();
                 let mut connection = pool.get().unwrap();
-                replace_memberships(&mut connection, &[membership.clone()]).unwrap();
+                import_memberships(&mut connection, std::slice::from_ref(&membership)).unwrap();
                 let member_to_check = CsvMember::new(
                     Some(MEMBERSHIP_NUMBER.to_owned()), // Prepending with a 0 should not change anything
                     Some(format!("{} {}", MEMBER_NAME, MEMBER_FIRST_NAME)),
                     None,
                     None,
+                    None,
+                    None,
+                    None,
                 );
 
                 assert_eq!(
                     Match(membership),
-                    check_member(&mut connection, &member_to_check).unwrap()
+                    check_member(
+                        &mut connection,
+                        &mut Candidates::default(),
+                        &member_to_check
+                    )
+                    .unwrap()
                 );
             });
         }
@@ -291,17 +483,25 @@ mod tests {
             with_temp_database(|pool| {
                 let membership = get_expected_membership();
                 let mut connection = pool.get().unwrap();
-                replace_memberships(&mut connection, &[membership.clone()]).unwrap();
+                import_memberships(&mut connection, std::slice::from_ref(&membership)).unwrap();
                 let member_to_check = CsvMember::new(
                     None,
                     Some(format!("{MEMBER_NAME} {MEMBER_FIRST_NAME}")),
                     None,
                     None,
+                    None,
+                    None,
+                    None,
                 );
 
                 assert_eq!(
                     PartialMatch(membership),
-                    check_member(&mut connection, &member_to_check).unwrap()
+                    check_member(
+                        &mut connection,
+                        &mut Candidates::default(),
+                        &member_to_check
+                    )
+                    .unwrap()
                 );
             });
         }
@@ -311,18 +511,347 @@ mod tests {
             with_temp_database(|pool| {
                 let membership = get_expected_membership();
                 let mut connection = pool.get().unwrap();
-                replace_memberships(&mut connection, &[membership.clone()]).unwrap();
+                import_memberships(&mut connection, std::slice::from_ref(&membership)).unwrap();
                 let invalid_membership_number = format!("{MEMBERSHIP_NUMBER} oops");
                 let member_to_check = CsvMember::new(
                     Some(invalid_membership_number),
                     None,
                     Some(MEMBER_NAME.to_owned()),
                     Some(MEMBER_FIRST_NAME.to_owned()),
+                    None,
+                    None,
+                    None,
                 );
 
                 assert_eq!(
                     NoMatch,
-                    check_member(&mut connection, &member_to_check).unwrap()
+                    check_member(
+                        &mut connection,
+                        &mut Candidates::default(),
+                        &member_to_check
+                    )
+                    .unwrap()
+                );
+            });
+        }
+
+        #[test]
+        fn fuzzy_match_when_names_with_typo() {
+            with_temp_database(|pool| {
+                let membership = get_expected_membership();
+                let mut connection = pool.get().unwrap();
+                import_memberships(&mut connection, std::slice::from_ref(&membership)).unwrap();
+                let member_to_check = CsvMember::new(
+                    None,
+                    None,
+                    Some(MEMBER_NAME.to_owned()),
+                    Some(format!("{MEMBER_FIRST_NAME}h")),
+                    None,
+                    None,
+                    None,
+                );
+
+                assert_eq!(
+                    FuzzyMatch(membership, 85),
+                    check_member(
+                        &mut connection,
+                        &mut Candidates::default(),
+                        &member_to_check
+                    )
+                    .unwrap()
+                );
+            });
+        }
+
+        #[test]
+        fn fuzzy_match_when_identity_with_typo() {
+            with_temp_database(|pool| {
+                let membership = get_expected_membership();
+                let mut connection = pool.get().unwrap();
+                import_memberships(&mut connection, std::slice::from_ref(&membership)).unwrap();
+                let member_to_check = CsvMember::new(
+                    None,
+                    Some(format!("{MEMBER_FIRST_NAME}h {MEMBER_NAME}")),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                );
+
+                assert_eq!(
+                    FuzzyMatch(membership, 85),
+                    check_member(
+                        &mut connection,
+                        &mut Candidates::default(),
+                        &member_to_check
+                    )
+                    .unwrap()
+                );
+            });
+        }
+
+        #[test]
+        fn no_match_when_names_too_different() {
+            with_temp_database(|pool| {
+                let membership = get_expected_membership();
+                let mut connection = pool.get().unwrap();
+                import_memberships(&mut connection, std::slice::from_ref(&membership)).unwrap();
+                let member_to_check = CsvMember::new(
+                    None,
+                    None,
+                    Some("Snow".to_owned()),
+                    Some("Jonette".to_owned()),
+                    None,
+                    None,
+                    None,
+                );
+
+                assert_eq!(
+                    NoMatch,
+                    check_member(
+                        &mut connection,
+                        &mut Candidates::default(),
+                        &member_to_check
+                    )
+                    .unwrap()
+                );
+            });
+        }
+
+        #[test]
+        fn ambiguous_match_when_homonyms() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                import_memberships(
+                    &mut connection,
+                    &[jon_doe(), jon_doe_previous_membership(), other_jon_doe()],
+                )
+                .unwrap();
+                let member_to_check = CsvMember::new(
+                    None,
+                    None,
+                    Some(jon_doe().name().to_owned()),
+                    Some(jon_doe().first_name().to_owned()),
+                    None,
+                    None,
+                    None,
+                );
+
+                assert_eq!(
+                    AmbiguousMatch(vec![jon_doe(), other_jon_doe()]),
+                    check_member(
+                        &mut connection,
+                        &mut Candidates::default(),
+                        &member_to_check
+                    )
+                    .unwrap()
+                );
+            });
+        }
+
+        #[test]
+        fn ambiguous_match_when_homonyms_by_identity() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                import_memberships(&mut connection, &[jon_doe(), other_jon_doe()]).unwrap();
+                let member_to_check = CsvMember::new(
+                    None,
+                    Some(format!("{} {}", jon_doe().first_name(), jon_doe().name())),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                );
+
+                assert_eq!(
+                    AmbiguousMatch(vec![jon_doe(), other_jon_doe()]),
+                    check_member(
+                        &mut connection,
+                        &mut Candidates::default(),
+                        &member_to_check
+                    )
+                    .unwrap()
+                );
+            });
+        }
+
+        #[test]
+        fn match_when_names_and_birthdate() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                import_memberships(
+                    &mut connection,
+                    &[jon_doe(), jon_doe_previous_membership(), other_jon_doe()],
+                )
+                .unwrap();
+                let member_to_check = CsvMember::new(
+                    None,
+                    None,
+                    Some(other_jon_doe().name().to_owned()),
+                    Some(other_jon_doe().first_name().to_owned()),
+                    other_jon_doe().birthdate().to_owned(),
+                    None,
+                    None,
+                );
+
+                assert_eq!(
+                    Match(other_jon_doe()),
+                    check_member(
+                        &mut connection,
+                        &mut Candidates::default(),
+                        &member_to_check
+                    )
+                    .unwrap()
+                );
+            });
+        }
+
+        #[test]
+        fn match_when_identity_and_birthdate() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                import_memberships(&mut connection, &[jon_doe(), other_jon_doe()]).unwrap();
+                let member_to_check = CsvMember::new(
+                    None,
+                    Some(format!("{} {}", jon_doe().name(), jon_doe().first_name())),
+                    None,
+                    None,
+                    jon_doe().birthdate().to_owned(),
+                    None,
+                    None,
+                );
+
+                assert_eq!(
+                    Match(jon_doe()),
+                    check_member(
+                        &mut connection,
+                        &mut Candidates::default(),
+                        &member_to_check
+                    )
+                    .unwrap()
+                );
+            });
+        }
+
+        #[test]
+        fn partial_match_when_names_but_unknown_birthdate() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                let membership = Membership::new(
+                    jon_doe().name().to_owned(),
+                    jon_doe().first_name().to_owned(),
+                    None,
+                    jon_doe().membership_number().to_owned(),
+                    None,
+                    jon_doe().email_address().to_owned(),
+                    *jon_doe().start_date(),
+                    *jon_doe().end_date(),
+                    jon_doe().club().to_owned(),
+                    jon_doe().structure_code().to_owned(),
+                );
+                import_memberships(&mut connection, std::slice::from_ref(&membership)).unwrap();
+                let member_to_check = CsvMember::new(
+                    None,
+                    None,
+                    Some(jon_doe().name().to_owned()),
+                    Some(jon_doe().first_name().to_owned()),
+                    NaiveDate::from_ymd_opt(2000, 1, 1),
+                    None,
+                    None,
+                );
+
+                assert_eq!(
+                    PartialMatch(membership),
+                    check_member(
+                        &mut connection,
+                        &mut Candidates::default(),
+                        &member_to_check
+                    )
+                    .unwrap()
+                );
+            });
+        }
+
+        #[test]
+        fn no_match_when_names_but_conflicting_birthdate() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                import_memberships(&mut connection, &[jon_doe()]).unwrap();
+                let member_to_check = CsvMember::new(
+                    None,
+                    None,
+                    Some(jon_doe().name().to_owned()),
+                    Some(jon_doe().first_name().to_owned()),
+                    NaiveDate::from_ymd_opt(2000, 1, 1),
+                    None,
+                    None,
+                );
+
+                assert_eq!(
+                    NoMatch,
+                    check_member(
+                        &mut connection,
+                        &mut Candidates::default(),
+                        &member_to_check
+                    )
+                    .unwrap()
+                );
+            });
+        }
+
+        #[test]
+        fn no_match_when_homonyms_with_conflicting_birthdates() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                import_memberships(&mut connection, &[jon_doe(), other_jon_doe()]).unwrap();
+                let member_to_check = CsvMember::new(
+                    None,
+                    None,
+                    Some(jon_doe().name().to_owned()),
+                    Some(jon_doe().first_name().to_owned()),
+                    NaiveDate::from_ymd_opt(2000, 1, 1),
+                    None,
+                    None,
+                );
+
+                assert_eq!(
+                    NoMatch,
+                    check_member(
+                        &mut connection,
+                        &mut Candidates::default(),
+                        &member_to_check
+                    )
+                    .unwrap()
+                );
+            });
+        }
+
+        #[test]
+        fn partial_match_when_several_memberships_of_same_person() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                import_memberships(&mut connection, &[jon_doe_previous_membership(), jon_doe()])
+                    .unwrap();
+                let member_to_check = CsvMember::new(
+                    None,
+                    None,
+                    Some(jon_doe().name().to_owned()),
+                    Some(jon_doe().first_name().to_owned()),
+                    None,
+                    None,
+                    None,
+                );
+
+                assert_eq!(
+                    PartialMatch(jon_doe()),
+                    check_member(
+                        &mut connection,
+                        &mut Candidates::default(),
+                        &member_to_check
+                    )
+                    .unwrap()
                 );
             });
         }
@@ -356,7 +885,7 @@ mod tests {
                     matching_membership.club().to_owned(),
                     matching_membership.structure_code().to_owned(),
                 );
-                replace_memberships(
+                import_memberships(
                     &mut connection,
                     &[
                         matching_membership.clone(),
@@ -371,11 +900,19 @@ mod tests {
                     None,
                     Some(MEMBER_NAME.to_owned()),
                     Some(MEMBER_FIRST_NAME.to_owned()),
+                    None,
+                    None,
+                    None,
                 );
 
                 assert_eq!(
                     Match(matching_membership),
-                    check_member(&mut connection, &member_to_check).unwrap()
+                    check_member(
+                        &mut connection,
+                        &mut Candidates::default(),
+                        &member_to_check
+                    )
+                    .unwrap()
                 );
             });
         }
@@ -405,7 +942,7 @@ mod tests {
                     newest_membership.club().to_owned(),
                     newest_membership.structure_code().to_owned(),
                 );
-                replace_memberships(
+                import_memberships(
                     &mut connection,
                     &[newest_membership.clone(), oldest_membership],
                 )
@@ -415,11 +952,19 @@ mod tests {
                     None,
                     Some(MEMBER_NAME.to_owned()),
                     Some(MEMBER_FIRST_NAME.to_owned()),
+                    None,
+                    None,
+                    None,
                 );
 
                 assert_eq!(
                     Match(newest_membership),
-                    check_member(&mut connection, &member_to_check).unwrap()
+                    check_member(
+                        &mut connection,
+                        &mut Candidates::default(),
+                        &member_to_check
+                    )
+                    .unwrap()
                 );
             });
         }
@@ -0,0 +1,397 @@
+use crate::tools::normalize;
+use dto::check_session::{
+    CheckSession, CheckSessionComparison, CheckSessionDelta, CheckSessionResults, ComparedMember,
+};
+use dto::checked_member::CheckedMember;
+use dto::member_to_check::MemberToCheck;
+use dto::membership_status::MemberStatus;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Compare two check sessions, and list members whose status has changed in between,
+/// including those who have only been checked in one of the sessions.
+/// Members are told apart by their names, or by their membership number if they have no name,
+/// so that sessions from different sources can be compared.
+/// Sessions of the same UDA instance tell participants apart by their UDA id instead.
+pub fn compare_check_sessions(
+    previous: &CheckSession,
+    current: &CheckSession,
+) -> CheckSessionComparison {
+    CheckSessionComparison::new(
+        previous.summary().clone(),
+        current.summary().clone(),
+        list_changed_members(Some(previous.results()), current.results()),
+    )
+}
+
+/// Compare a check session with the previous check of the same event, if any,
+/// to tell newly registered members, status changes and members who dropped out apart.
+pub fn compute_check_session_delta(
+    previous: Option<&CheckSession>,
+    current: &CheckSession,
+) -> CheckSessionDelta {
+    CheckSessionDelta::new(
+        previous.map(|previous| previous.summary().clone()),
+        current.summary().clone(),
+        list_changed_members(previous.map(CheckSession::results), current.results()),
+    )
+}
+
+fn list_changed_members(
+    previous: Option<&CheckSessionResults>,
+    current: &CheckSessionResults,
+) -> Vec<ComparedMember> {
+    let by_uda_id = can_compare_by_uda_id(previous, current);
+    let previous_statuses = previous
+        .map(|previous| to_statuses(previous, by_uda_id))
+        .unwrap_or_default();
+    let current_statuses = to_statuses(current, by_uda_id);
+
+    let keys: BTreeSet<&String> = previous_statuses
+        .keys()
+        .chain(current_statuses.keys())
+        .collect();
+    let mut changed_members = vec![];
+    for key in keys {
+        let previous = previous_statuses.get(key);
+        let current = current_statuses.get(key);
+        let previous_status = previous.map(|(_, status)| status.clone());
+        let current_status = current.map(|(_, status)| status.clone());
+        if previous_status != current_status {
+            let (member, _) = current.or(previous).expect("Key comes from either session");
+            changed_members.push(ComparedMember::new(
+                member.clone(),
+                previous_status,
+                current_status,
+            ));
+        }
+    }
+    changed_members.sort_by(|first, second| first.member().cmp(second.member()));
+    changed_members
+}
+
+/// UDA ids are kept when participants are renamed, and tell homonyms apart,
+/// but they only make sense within the same UDA instance.
+fn can_compare_by_uda_id(
+    previous: Option<&CheckSessionResults>,
+    current: &CheckSessionResults,
+) -> bool {
+    match (previous, current) {
+        (None, CheckSessionResults::Uda(_, _)) => true,
+        (
+            Some(CheckSessionResults::Uda(previous_instance, _)),
+            CheckSessionResults::Uda(current_instance, _),
+        ) => previous_instance == current_instance,
+        _ => false,
+    }
+}
+
+/// Index the status of each member by a key identifying them, along with a label describing them.
+fn to_statuses(
+    results: &CheckSessionResults,
+    by_uda_id: bool,
+) -> BTreeMap<String, (String, MemberStatus)> {
+    match results {
+        CheckSessionResults::Csv(checked_members) => {
+            index_statuses(checked_members, |_, label| normalize(label))
+        }
+        CheckSessionResults::Uda(_, checked_members) if by_uda_id => {
+            index_statuses(checked_members, |member, _| member.id().to_string())
+        }
+        CheckSessionResults::Uda(_, checked_members) => {
+            index_statuses(checked_members, |_, label| normalize(label))
+        }
+    }
+}
+
+fn index_statuses<T: MemberToCheck>(
+    checked_members: &[CheckedMember<T>],
+    key: impl Fn(&T, &str) -> String,
+) -> BTreeMap<String, (String, MemberStatus)> {
+    checked_members
+        .iter()
+        .filter_map(|checked_member| {
+            let member = checked_member.member_to_check();
+            let label = match (member.first_name(), member.last_name()) {
+                (Some(first_name), Some(last_name)) => Some(format!("{first_name} {last_name}")),
+                _ => member.identity(),
+            }
+            .filter(|label| !label.trim().is_empty())
+            .or_else(|| member.membership_num())?;
+            Some((
+                key(member, &label),
+                (label, checked_member.status().clone()),
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    mod compare_check_sessions {
+        use crate::membership::check_session::compare_check_sessions;
+        use chrono::{NaiveDate, NaiveDateTime};
+        use dto::check_session::{
+            CheckSession, CheckSessionResults, CheckSessionSummary, CheckSource, ComparedMember,
+        };
+        use dto::checked_member::{CheckResult, CheckedMember};
+        use dto::csv_member::CsvMember;
+        use dto::event_period::EventPeriod;
+        use dto::membership::tests::get_expected_membership;
+        use dto::membership_status::MemberStatus::{Unknown, UpToDate};
+        use dto::uda_member::UdaMember;
+
+        fn event_period() -> EventPeriod {
+            EventPeriod::single_day(NaiveDate::from_ymd_opt(2025, 7, 5).unwrap())
+        }
+
+        fn summary(id: i32, source: CheckSource) -> CheckSessionSummary {
+            CheckSessionSummary::new(
+                id,
+                NaiveDateTime::default(),
+                "user".to_owned(),
+                source,
+                event_period(),
+                0,
+                0,
+            )
+        }
+
+        fn csv_member(first_name: &str, last_name: &str) -> CsvMember {
+            CsvMember::new(
+                None,
+                None,
+                Some(last_name.to_owned()),
+                Some(first_name.to_owned()),
+                None,
+                None,
+                None,
+            )
+        }
+
+        #[test]
+        fn success() {
+            let previous = CheckSession::new(
+                summary(1, CheckSource::Csv),
+                CheckSessionResults::Csv(vec![
+                    CheckedMember::new(
+                        csv_member("Jon", "Doe"),
+                        CheckResult::NoMatch,
+                        &event_period(),
+                        30,
+                    ),
+                    CheckedMember::new(
+                        csv_member("Jonette", "Snow"),
+                        CheckResult::NoMatch,
+                        &event_period(),
+                        30,
+                    ),
+                    CheckedMember::new(
+                        csv_member("Kris", "Holm"),
+                        CheckResult::NoMatch,
+                        &event_period(),
+                        30,
+                    ),
+                ]),
+            );
+            let current = CheckSession::new(
+                summary(2, CheckSource::Uda("test".to_owned())),
+                CheckSessionResults::Uda(
+                    "test".to_owned(),
+                    vec![
+                        CheckedMember::new(
+                            UdaMember::new(
+                                1,
+                                Some("123456".to_owned()),
+                                "Jon".to_owned(),
+                                "Doe".to_owned(),
+                                None,
+                                "jon.doe@email.com".to_owned(),
+                                None,
+                                true,
+                            ),
+                            CheckResult::Match(get_expected_membership()),
+                            &event_period(),
+                            30,
+                        ),
+                        CheckedMember::new(
+                            UdaMember::new(
+                                2,
+                                None,
+                                "Jonette".to_owned(),
+                                "Snow".to_owned(),
+                                None,
+                                "jonette.snow@email.com".to_owned(),
+                                None,
+                                false,
+                            ),
+                            CheckResult::NoMatch,
+                            &event_period(),
+                            30,
+                        ),
+                    ],
+                ),
+            );
+
+            let comparison = compare_check_sessions(&previous, &current);
+            assert_eq!(&1, comparison.previous().id());
+            assert_eq!(&2, comparison.current().id());
+            assert_eq!(
+                &vec![
+                    ComparedMember::new("Jon Doe".to_owned(), Some(Unknown), Some(UpToDate)),
+                    ComparedMember::new("Kris Holm".to_owned(), Some(Unknown), None),
+                ],
+                comparison.changed_members()
+            );
+        }
+    }
+
+    mod compute_check_session_delta {
+        use crate::membership::check_session::compute_check_session_delta;
+        use chrono::{NaiveDate, NaiveDateTime};
+        use dto::check_session::{
+            CheckSession, CheckSessionResults, CheckSessionSummary, CheckSource, ComparedMember,
+        };
+        use dto::checked_member::{CheckResult, CheckedMember};
+        use dto::event_period::EventPeriod;
+        use dto::membership::tests::get_expected_membership;
+        use dto::membership_status::MemberStatus::{Unknown, UpToDate};
+        use dto::uda_member::UdaMember;
+
+        fn check_session(id: i32, members: Vec<(UdaMember, CheckResult)>) -> CheckSession {
+            let event_period =
+                EventPeriod::single_day(NaiveDate::from_ymd_opt(2025, 7, 5).unwrap());
+            let summary = CheckSessionSummary::new(
+                id,
+                NaiveDateTime::default(),
+                "user".to_owned(),
+                CheckSource::Uda("test".to_owned()),
+                event_period.clone(),
+                members.len(),
+                0,
+            );
+            let checked_members = members
+                .into_iter()
+                .map(|(member, result)| CheckedMember::new(member, result, &event_period, 30))
+                .collect();
+            CheckSession::new(
+                summary,
+                CheckSessionResults::Uda("test".to_owned(), checked_members),
+            )
+        }
+
+        fn uda_member(id: u16, first_name: &str, last_name: &str) -> UdaMember {
+            UdaMember::new(
+                id,
+                None,
+                first_name.to_owned(),
+                last_name.to_owned(),
+                None,
+                format!("{first_name}.{last_name}@email.com"),
+                None,
+                false,
+            )
+        }
+
+        #[test]
+        fn success() {
+            let previous = check_session(
+                1,
+                vec![
+                    (uda_member(1, "Jon", "Doe"), CheckResult::NoMatch),
+                    (uda_member(2, "Kris", "Holm"), CheckResult::NoMatch),
+                ],
+            );
+            let current = check_session(
+                2,
+                vec![
+                    (
+                        uda_member(1, "Jon", "Doe"),
+                        CheckResult::Match(get_expected_membership()),
+                    ),
+                    (uda_member(3, "Jonette", "Snow"), CheckResult::NoMatch),
+                ],
+            );
+
+            let delta = compute_check_session_delta(Some(&previous), &current);
+            assert_eq!(&Some(previous.summary().clone()), delta.previous());
+            assert_eq!(
+                &vec![ComparedMember::new(
+                    "Jonette Snow".to_owned(),
+                    None,
+                    Some(Unknown)
+                )],
+                delta.new_members()
+            );
+            assert_eq!(
+                &vec![ComparedMember::new(
+                    "Jon Doe".to_owned(),
+                    Some(Unknown),
+                    Some(UpToDate)
+                )],
+                delta.status_changes()
+            );
+            assert_eq!(
+                &vec![ComparedMember::new(
+                    "Kris Holm".to_owned(),
+                    Some(Unknown),
+                    None
+                )],
+                delta.dropped_members()
+            );
+        }
+
+        #[test]
+        fn by_uda_id() {
+            let previous = check_session(
+                1,
+                vec![
+                    (uda_member(1, "Jon", "Doe"), CheckResult::NoMatch),
+                    (uda_member(2, "Jon", "Doe"), CheckResult::NoMatch),
+                    (uda_member(3, "Kris", "Holm"), CheckResult::NoMatch),
+                ],
+            );
+            let current = check_session(
+                2,
+                vec![
+                    (uda_member(1, "Jon", "Doe"), CheckResult::NoMatch),
+                    (
+                        uda_member(2, "Jon", "Doe"),
+                        CheckResult::Match(get_expected_membership()),
+                    ),
+                    (uda_member(3, "Kristopher", "Holm"), CheckResult::NoMatch),
+                ],
+            );
+
+            let delta = compute_check_session_delta(Some(&previous), &current);
+            assert!(delta.new_members().is_empty());
+            assert!(delta.dropped_members().is_empty());
+            assert_eq!(
+                &vec![ComparedMember::new(
+                    "Jon Doe".to_owned(),
+                    Some(Unknown),
+                    Some(UpToDate)
+                )],
+                delta.status_changes()
+            );
+        }
+
+        #[test]
+        fn first_check() {
+            let current =
+                check_session(2, vec![(uda_member(1, "Jon", "Doe"), CheckResult::NoMatch)]);
+
+            let delta = compute_check_session_delta(None, &current);
+            assert_eq!(&None, delta.previous());
+            assert_eq!(
+                &vec![ComparedMember::new(
+                    "Jon Doe".to_owned(),
+                    None,
+                    Some(Unknown)
+                )],
+                delta.new_members()
+            );
+            assert!(delta.status_changes().is_empty());
+            assert!(delta.dropped_members().is_empty());
+        }
+    }
+}
@@ -1,7 +1,11 @@
+use crate::tools::env_args::retrieve_arg_value;
 use derive_getters::Getters;
 use regex::Regex;
 
-#[derive(Getters)]
+const EXPIRING_SOON_THRESHOLD_ARG: &str = "--expiring-soon-threshold";
+const DEFAULT_EXPIRING_SOON_THRESHOLD: u64 = 30;
+
+#[derive(Getters, Clone)]
 pub struct MembershipsProviderConfig {
     host: String,
     download_link_regex: Regex,
@@ -15,3 +19,46 @@ impl MembershipsProviderConfig {
         }
     }
 }
+
+/// Retrieve how many days after the event a membership has to end to be considered expiring soon.
+pub fn retrieve_expiring_soon_threshold() -> u64 {
+    retrieve_arg_value(EXPIRING_SOON_THRESHOLD_ARG)
+        .and_then(|threshold| threshold.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_EXPIRING_SOON_THRESHOLD)
+}
+
+#[cfg(test)]
+mod tests {
+    mod retrieve_expiring_soon_threshold {
+        use crate::membership::config::{
+            DEFAULT_EXPIRING_SOON_THRESHOLD, EXPIRING_SOON_THRESHOLD_ARG,
+            retrieve_expiring_soon_threshold,
+        };
+        use crate::tools::env_args::with_env_args;
+
+        #[test]
+        fn should_retrieve_threshold() {
+            let args = vec![format!("{EXPIRING_SOON_THRESHOLD_ARG}=15")];
+
+            assert_eq!(15, with_env_args(args, retrieve_expiring_soon_threshold));
+        }
+
+        #[test]
+        fn should_retrieve_default_threshold_when_missing() {
+            assert_eq!(
+                DEFAULT_EXPIRING_SOON_THRESHOLD,
+                with_env_args(vec![], retrieve_expiring_soon_threshold)
+            );
+        }
+
+        #[test]
+        fn should_retrieve_default_threshold_when_invalid() {
+            let args = vec![format!("{EXPIRING_SOON_THRESHOLD_ARG}=soon")];
+
+            assert_eq!(
+                DEFAULT_EXPIRING_SOON_THRESHOLD,
+                with_env_args(args, retrieve_expiring_soon_threshold)
+            );
+        }
+    }
+}
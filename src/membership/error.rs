@@ -0,0 +1,19 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum MembershipError {
+    #[error("The spreadsheet of members to check can't be read.")]
+    MalformedSpreadsheet,
+    #[error("No column of the spreadsheet of members to check has been recognized.")]
+    NoKnownColumn,
+    #[error("Can't export check results [error: {0}]")]
+    CantExportCheckResults(String),
+    #[error("The email template can't be rendered [error: {0}]")]
+    InvalidEmailTemplate(String),
+    #[error(
+        "The email transport didn't tell how every email went [expected: {expected}, actual: {actual}]"
+    )]
+    MissingSendingResults { expected: usize, actual: usize },
+    #[error("The email queue can't be processed [error: {0}]")]
+    CantProcessEmailQueue(String),
+}
@@ -0,0 +1,443 @@
+use crate::error::Result;
+use crate::membership::error::MembershipError::CantExportCheckResults;
+use crate::tools::pdf::PdfWriter;
+use chrono::{Local, NaiveDate};
+use dto::checked_member::{CheckResult, CheckedMember};
+use dto::event_period::EventPeriod;
+use dto::member_to_check::MemberToCheck;
+use dto::membership::Membership;
+use dto::membership_status::MemberStatus;
+use rust_xlsxwriter::{Format, Workbook};
+
+const DATE_FORMAT: &str = "%d/%m/%Y";
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const HEADERS: [&str; 11] = [
+    "Numéro d'adhésion",
+    "Nom",
+    "Prénom",
+    "Identité",
+    "Date de naissance",
+    "Adresse email",
+    "Résultat",
+    "Numéro d'adhésion trouvé",
+    "Club",
+    "Fin d'adhésion",
+    "Statut",
+];
+
+/// A format check results can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Xlsx,
+    Pdf,
+}
+
+impl TryFrom<&str> for ExportFormat {
+    type Error = String;
+
+    fn try_from(value: &str) -> std::result::Result<Self, Self::Error> {
+        match value {
+            "csv" => Ok(ExportFormat::Csv),
+            "xlsx" => Ok(ExportFormat::Xlsx),
+            "pdf" => Ok(ExportFormat::Pdf),
+            _ => Err(format!("Unknown export format [format: {value}]")),
+        }
+    }
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Xlsx => "xlsx",
+            ExportFormat::Pdf => "pdf",
+        }
+    }
+}
+
+/// Export check results, so that they can be filed along with the event paperwork.
+pub fn export_checked_members<T: MemberToCheck>(
+    checked_members: &[CheckedMember<T>],
+    event_period: &EventPeriod,
+    format: ExportFormat,
+) -> Result<Vec<u8>> {
+    let rows: Vec<[String; 11]> = checked_members.iter().map(to_row).collect();
+    match format {
+        ExportFormat::Csv => export_to_csv(&rows),
+        ExportFormat::Xlsx => export_to_xlsx(&rows),
+        ExportFormat::Pdf => Ok(export_to_pdf(&rows, event_period)),
+    }
+}
+
+fn to_row<T: MemberToCheck>(checked_member: &CheckedMember<T>) -> [String; 11] {
+    let member = checked_member.member_to_check();
+    let membership = found_membership(checked_member.membership());
+    [
+        member.membership_num().unwrap_or_default(),
+        member.last_name().unwrap_or_default(),
+        member.first_name().unwrap_or_default(),
+        member.identity().unwrap_or_default(),
+        format_date(member.birthdate()),
+        member.email().unwrap_or_default(),
+        describe_check_result(checked_member.membership()),
+        membership
+            .map(|membership| membership.membership_number().clone())
+            .unwrap_or_default(),
+        membership
+            .map(|membership| membership.club().clone())
+            .or_else(|| member.club())
+            .unwrap_or_default(),
+        format_date(membership.map(|membership| *membership.end_date())),
+        describe_status(checked_member.status()).to_owned(),
+    ]
+}
+
+/// The membership the member has been matched with, if any.
+/// When several people match, none is given, as the organizer has yet to pick the right one.
+fn found_membership(check_result: &CheckResult) -> Option<&Membership> {
+    match check_result {
+        CheckResult::Match(membership)
+        | CheckResult::PartialMatch(membership)
+        | CheckResult::FuzzyMatch(membership, _) => Some(membership),
+        CheckResult::AmbiguousMatch(_) | CheckResult::NoMatch => None,
+    }
+}
+
+fn describe_check_result(check_result: &CheckResult) -> String {
+    match check_result {
+        CheckResult::Match(_) => "Correspondance".to_owned(),
+        CheckResult::PartialMatch(_) => "Correspondance partielle".to_owned(),
+        CheckResult::AmbiguousMatch(_) => "Plusieurs correspondances".to_owned(),
+        CheckResult::FuzzyMatch(_, score) => format!("Correspondance approchante ({score} %)"),
+        CheckResult::NoMatch => "Aucune correspondance".to_owned(),
+    }
+}
+
+fn describe_status(status: &MemberStatus) -> &'static str {
+    match status {
+        MemberStatus::UpToDate => "À jour",
+        MemberStatus::ExpiringSoon => "Expire bientôt",
+        MemberStatus::Expired => "Expirée",
+        MemberStatus::StartsAfterEvent => "Débute après l'événement",
+        MemberStatus::Unknown => "Inconnue",
+    }
+}
+
+fn format_date(date: Option<NaiveDate>) -> String {
+    date.map(|date| date.format(DATE_FORMAT).to_string())
+        .unwrap_or_default()
+}
+
+/// CSV exports are `;`-separated and start with a BOM, so that spreadsheets open them as UTF-8.
+fn export_to_csv(rows: &[[String; 11]]) -> Result<Vec<u8>> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(b';')
+        .from_writer(UTF8_BOM.to_vec());
+    writer
+        .write_record(HEADERS)
+        .map_err(|error| CantExportCheckResults(error.to_string()))?;
+    for row in rows {
+        writer
+            .write_record(row.iter().map(|cell| escape_formula(cell)))
+            .map_err(|error| CantExportCheckResults(error.to_string()))?;
+    }
+    Ok(writer
+        .into_inner()
+        .map_err(|error| CantExportCheckResults(error.to_string()))?)
+}
+
+/// Spreadsheets evaluate cells starting with `=`, `+`, `-` or `@` as formulas,
+/// so such values, which come from imported files, are prefixed with `'` to be shown as text.
+fn escape_formula(cell: &str) -> String {
+    if cell.starts_with(['=', '+', '-', '@']) {
+        format!("'{cell}")
+    } else {
+        cell.to_owned()
+    }
+}
+
+fn export_to_xlsx(rows: &[[String; 11]]) -> Result<Vec<u8>> {
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    let header_format = Format::new().set_bold();
+    let to_error = |error: rust_xlsxwriter::XlsxError| CantExportCheckResults(error.to_string());
+
+    for (column, header) in HEADERS.iter().enumerate() {
+        worksheet
+            .write_string_with_format(0, column as u16, *header, &header_format)
+            .map_err(to_error)?;
+    }
+    for (index, row) in rows.iter().enumerate() {
+        for (column, value) in row.iter().enumerate() {
+            worksheet
+                .write_string(index as u32 + 1, column as u16, value)
+                .map_err(to_error)?;
+        }
+    }
+    worksheet.autofit();
+
+    Ok(workbook.save_to_buffer().map_err(to_error)?)
+}
+
+/// Build a printable attestation listing every checked member.
+fn export_to_pdf(rows: &[[String; 11]], event_period: &EventPeriod) -> Vec<u8> {
+    // Horizontal offset and maximum length of each column of the table
+    const COLUMNS: [(f32, usize); 7] = [
+        (0.0, 32),
+        (170.0, 12),
+        (240.0, 10),
+        (300.0, 28),
+        (450.0, 12),
+        (520.0, 28),
+        (670.0, 24),
+    ];
+    let mut writer = PdfWriter::default();
+
+    writer.write_line(
+        &[(0.0, "Attestation de vérification des licences")],
+        16.0,
+        true,
+    );
+    let period = if event_period.start_date() == event_period.end_date() {
+        format!(
+            "Événement le {}",
+            event_period.start_date().format(DATE_FORMAT)
+        )
+    } else {
+        format!(
+            "Événement du {} au {}",
+            event_period.start_date().format(DATE_FORMAT),
+            event_period.end_date().format(DATE_FORMAT)
+        )
+    };
+    writer.write_line(&[(0.0, period.as_str())], 10.0, false);
+    let generation_date = format!(
+        "Vérification effectuée le {}",
+        Local::now().format("%d/%m/%Y à %H:%M")
+    );
+    writer.write_line(&[(0.0, generation_date.as_str())], 10.0, false);
+    let up_to_date_count = rows
+        .iter()
+        .filter(|row| row[10] == describe_status(&MemberStatus::UpToDate))
+        .count();
+    let summary = format!(
+        "{} participants vérifiés, dont {up_to_date_count} avec une licence à jour",
+        rows.len()
+    );
+    writer.write_line(&[(0.0, summary.as_str())], 10.0, false);
+    writer.skip_line(10.0);
+
+    let headers = [
+        "Participant",
+        "N° déclaré",
+        "Naissance",
+        "Résultat",
+        "N° trouvé",
+        "Club",
+        "Fin d'adhésion / statut",
+    ];
+    let header_cells: Vec<(f32, &str)> = COLUMNS
+        .iter()
+        .zip(headers)
+        .map(|((x, _), header)| (*x, header))
+        .collect();
+    writer.write_line(&header_cells, 8.0, true);
+
+    for row in rows {
+        let participant = if row[1].is_empty() && row[2].is_empty() {
+            row[3].clone()
+        } else {
+            format!("{} {}", row[2], row[1])
+        };
+        let end_date_and_status = if row[9].is_empty() {
+            row[10].clone()
+        } else {
+            format!("{} - {}", row[9], row[10])
+        };
+        let values = [
+            participant,
+            row[0].clone(),
+            row[4].clone(),
+            row[6].clone(),
+            row[7].clone(),
+            row[8].clone(),
+            end_date_and_status,
+        ];
+        let values: Vec<String> = COLUMNS
+            .iter()
+            .zip(values)
+            .map(|((_, max_length), value)| truncate(value.trim(), *max_length))
+            .collect();
+        let cells: Vec<(f32, &str)> = COLUMNS
+            .iter()
+            .zip(values.iter())
+            .map(|((x, _), value)| (*x, value.as_str()))
+            .collect();
+        writer.write_line(&cells, 8.0, false);
+    }
+
+    writer.build()
+}
+
+/// Cut the value so that it fits within its column.
+fn truncate(value: &str, max_length: usize) -> String {
+    if value.chars().count() > max_length {
+        let truncated: String = value.chars().take(max_length - 1).collect();
+        format!("{truncated}…")
+    } else {
+        value.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use dto::checked_member::{CheckResult, CheckedMember};
+    use dto::csv_member::CsvMember;
+    use dto::event_period::EventPeriod;
+    use dto::membership::tests::get_expected_membership;
+
+    fn event_period() -> EventPeriod {
+        EventPeriod::single_day(NaiveDate::from_ymd_opt(2025, 7, 5).unwrap())
+    }
+
+    fn get_checked_members() -> Vec<CheckedMember<CsvMember>> {
+        vec![
+            CheckedMember::new(
+                CsvMember::new(
+                    Some("123456".to_owned()),
+                    None,
+                    Some("Doe".to_owned()),
+                    Some("Jon".to_owned()),
+                    None,
+                    None,
+                    None,
+                ),
+                CheckResult::Match(get_expected_membership()),
+                &event_period(),
+                30,
+            ),
+            CheckedMember::new(
+                CsvMember::new(
+                    None,
+                    None,
+                    Some("Snow".to_owned()),
+                    Some("Jonette".to_owned()),
+                    None,
+                    None,
+                    Some("Other club".to_owned()),
+                ),
+                CheckResult::NoMatch,
+                &event_period(),
+                30,
+            ),
+        ]
+    }
+
+    mod export_format {
+        use crate::membership::export::ExportFormat;
+
+        #[test]
+        fn success() {
+            assert_eq!(Ok(ExportFormat::Csv), ExportFormat::try_from("csv"));
+            assert_eq!(Ok(ExportFormat::Xlsx), ExportFormat::try_from("xlsx"));
+            assert_eq!(Ok(ExportFormat::Pdf), ExportFormat::try_from("pdf"));
+        }
+
+        #[test]
+        fn fail_when_unknown() {
+            assert!(ExportFormat::try_from("docx").is_err());
+        }
+    }
+
+    mod export_checked_members {
+        use crate::membership::export::ExportFormat::{Csv, Pdf, Xlsx};
+        use crate::membership::export::tests::{event_period, get_checked_members};
+        use crate::membership::export::{HEADERS, export_checked_members};
+        use calamine::{Data, Reader, open_workbook_auto_from_rs};
+        use dto::membership::tests::get_expected_membership;
+        use std::io::Cursor;
+
+        #[test]
+        fn csv() {
+            let content =
+                export_checked_members(&get_checked_members(), &event_period(), Csv).unwrap();
+
+            let text = String::from_utf8(content).unwrap();
+            let lines: Vec<&str> = text.lines().collect();
+            assert_eq!(3, lines.len());
+            assert_eq!(format!("\u{feff}{}", HEADERS.join(";")), lines[0]);
+            assert!(lines[1].starts_with("123456;Doe;Jon;;;;Correspondance;123456;"));
+            assert!(lines[1].contains(get_expected_membership().club()));
+            assert_eq!(
+                ";Snow;Jonette;;;;Aucune correspondance;;Other club;;Inconnue",
+                lines[2]
+            );
+        }
+
+        #[test]
+        fn xlsx() {
+            let content =
+                export_checked_members(&get_checked_members(), &event_period(), Xlsx).unwrap();
+
+            let mut workbook = open_workbook_auto_from_rs(Cursor::new(content)).unwrap();
+            let range = workbook.worksheet_range_at(0).unwrap().unwrap();
+            assert_eq!((3, 11), range.get_size());
+            assert_eq!(
+                Some(&Data::String("Numéro d'adhésion".to_owned())),
+                range.get((0, 0))
+            );
+            assert_eq!(
+                Some(&Data::String("Aucune correspondance".to_owned())),
+                range.get((2, 6))
+            );
+        }
+
+        #[test]
+        fn pdf() {
+            let content =
+                export_checked_members(&get_checked_members(), &event_period(), Pdf).unwrap();
+
+            assert!(content.starts_with(b"%PDF-"));
+            let expected = b"(Jonette Snow) Tj";
+            assert!(
+                content
+                    .windows(expected.len())
+                    .any(|window| window == expected)
+            );
+        }
+    }
+
+    mod escape_formula {
+        use crate::membership::export::escape_formula;
+
+        #[test]
+        fn formula() {
+            assert_eq!("'=1+1", escape_formula("=1+1"));
+            assert_eq!("'+33 6", escape_formula("+33 6"));
+            assert_eq!("'-2", escape_formula("-2"));
+            assert_eq!("'@SUM(A1)", escape_formula("@SUM(A1)"));
+        }
+
+        #[test]
+        fn plain_value() {
+            assert_eq!("Jon-Doe", escape_formula("Jon-Doe"));
+            assert_eq!("", escape_formula(""));
+        }
+    }
+
+    mod truncate {
+        use crate::membership::export::truncate;
+
+        #[test]
+        fn short_value() {
+            assert_eq!("Doe", truncate("Doe", 5));
+        }
+
+        #[test]
+        fn long_value() {
+            assert_eq!("Jonet…", truncate("Jonette", 6));
+        }
+    }
+}
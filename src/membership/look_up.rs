@@ -28,7 +28,7 @@ pub fn look_member_up(
 #[cfg(test)]
 mod tests {
     mod look_member_up {
-        use crate::database::dao::membership::replace_memberships;
+        use crate::database::dao::membership::import_memberships;
         use crate::database::with_temp_database;
         use crate::membership::look_up::look_member_up;
         use crate::membership::tests::{
@@ -42,7 +42,7 @@ mod tests {
         fn by_membership_num() {
             with_temp_database(|pool| {
                 let mut connection = pool.get().unwrap();
-                replace_memberships(
+                import_memberships(
                     &mut connection,
                     &[
                         jonette_snow(),
@@ -68,7 +68,7 @@ mod tests {
         fn by_last_name() {
             with_temp_database(|pool| {
                 let mut connection = pool.get().unwrap();
-                replace_memberships(
+                import_memberships(
                     &mut connection,
                     &[
                         jonette_snow(),
@@ -94,7 +94,7 @@ mod tests {
         fn by_first_name() {
             with_temp_database(|pool| {
                 let mut connection = pool.get().unwrap();
-                replace_memberships(
+                import_memberships(
                     &mut connection,
                     &[
                         jonette_snow(),
@@ -120,7 +120,7 @@ mod tests {
         fn no_criteria() {
             with_temp_database(|pool| {
                 let mut connection = pool.get().unwrap();
-                replace_memberships(
+                import_memberships(
                     &mut connection,
                     &[
                         jonette_snow(),
@@ -1,7 +1,13 @@
 pub(crate) mod check;
+pub(crate) mod check_session;
 pub(crate) mod config;
+pub(crate) mod error;
+pub(crate) mod export;
 pub(crate) mod look_up;
 pub(crate) mod memberships;
+pub(crate) mod notification;
+pub(crate) mod notification_queue;
+pub(crate) mod spreadsheet;
 
 #[cfg(test)]
 pub(crate) mod tests {
@@ -0,0 +1,389 @@
+use crate::error::{ApplicationError, Result};
+use crate::membership::error::MembershipError::InvalidEmailTemplate;
+use crate::membership::look_up::look_member_up;
+use crate::tools::markdown::{markdown_to_html, markdown_to_text};
+use diesel::SqliteConnection;
+use dto::email::{Email, EmailRecipient, PersonalizedEmail};
+use dto::event_period::EventPeriod;
+use dto::member_to_look_up::MemberToLookUp;
+use dto::membership::Membership;
+use dto::membership_status::{MemberStatus, compute_member_status};
+use tera::ast::{Expr, ExprVal, Node};
+use tera::{Context, Template, Tera};
+
+const TEMPLATE_NAME: &str = "email";
+/// Filters which only transform the value they are given.
+const ALLOWED_FILTERS: [&str; 6] = ["upper", "lower", "capitalize", "title", "trim", "default"];
+
+/// Render the email each recipient receives.
+pub fn personalize_emails(
+    connection: &mut SqliteConnection,
+    email: &Email,
+    expiring_soon_threshold: u64,
+) -> Result<Vec<PersonalizedEmail>> {
+    email
+        .recipients()
+        .iter()
+        .map(|recipient| personalize_email(connection, email, recipient, expiring_soon_threshold))
+        .collect()
+}
+
+/// Render the subject and the body of the email for a single recipient.
+/// They may use the following variables, which are empty if the membership of the recipient is unknown:
+/// `first_name`, `last_name`, `membership_number`, `end_date`, `club`, `status` and `event_name`.
+pub fn personalize_email(
+    connection: &mut SqliteConnection,
+    email: &Email,
+    recipient: &EmailRecipient,
+    expiring_soon_threshold: u64,
+) -> Result<PersonalizedEmail> {
+    let membership = match recipient.membership_number() {
+        None => None,
+        Some(membership_number) => {
+            let member_to_look_up =
+                MemberToLookUp::new(Some(membership_number.to_owned()), None, None);
+            // Memberships are sorted by end date, so the last one is the most recent one.
+            look_member_up(connection, &member_to_look_up)?.pop_last()
+        }
+    };
+    let event_period = email
+        .event_period()
+        .clone()
+        .unwrap_or_else(EventPeriod::today);
+    let status = compute_member_status(membership.as_ref(), &event_period, expiring_soon_threshold);
+    let context = build_context(membership.as_ref(), &status, email.event_name().as_deref());
+
+    let body = render(email.body(), &context)?;
+    Ok(PersonalizedEmail::new(
+        recipient.email_address().to_owned(),
+        render(email.subject(), &context)?,
+        markdown_to_text(&body),
+        markdown_to_html(&body),
+    ))
+}
+
+/// Check that a subject and a body can be rendered, e.g. before saving them as a template,
+/// by rendering them for a recipient whose membership is unknown.
+pub fn check_email_template(subject: &str, body: &str) -> Result<()> {
+    let context = build_context(None, &MemberStatus::Unknown, None);
+    render(subject, &context)?;
+    render(body, &context)?;
+    Ok(())
+}
+
+fn build_context(
+    membership: Option<&Membership>,
+    status: &MemberStatus,
+    event_name: Option<&str>,
+) -> Context {
+    let mut context = Context::new();
+    context.insert(
+        "first_name",
+        membership.map_or("", |membership| membership.first_name().as_str()),
+    );
+    context.insert(
+        "last_name",
+        membership.map_or("", |membership| membership.name().as_str()),
+    );
+    context.insert(
+        "membership_number",
+        membership.map_or("", |membership| membership.membership_number().as_str()),
+    );
+    context.insert(
+        "end_date",
+        &membership.map_or(String::new(), |membership| {
+            membership.end_date().format("%d/%m/%Y").to_string()
+        }),
+    );
+    context.insert(
+        "club",
+        membership.map_or("", |membership| membership.club().as_str()),
+    );
+    context.insert("status", status_label(status));
+    context.insert("event_name", event_name.unwrap_or_default());
+    context
+}
+
+fn status_label(status: &MemberStatus) -> &'static str {
+    match status {
+        MemberStatus::UpToDate => "à jour",
+        MemberStatus::ExpiringSoon => "bientôt expirée",
+        MemberStatus::Expired => "expirée",
+        MemberStatus::StartsAfterEvent => "pas encore commencée",
+        MemberStatus::Unknown => "inconnue",
+    }
+}
+
+/// Templates are written by organizers, so they may only print and test variables:
+/// Tera functions, such as `get_env`, would let them read the server's secrets.
+/// Nothing is escaped here: the body is only escaped once converted from Markdown to HTML.
+fn render(template: &str, context: &Context) -> Result<String> {
+    let parsed = Template::new(TEMPLATE_NAME, None, template).map_err(to_invalid_template)?;
+    check_nodes(&parsed.ast)?;
+
+    let mut tera = Tera::default();
+    tera.add_raw_template(TEMPLATE_NAME, template)
+        .map_err(to_invalid_template)?;
+    tera.render(TEMPLATE_NAME, context)
+        .map_err(to_invalid_template)
+}
+
+fn to_invalid_template(error: tera::Error) -> ApplicationError {
+    InvalidEmailTemplate(format!("{error:?}")).into()
+}
+
+/// Only text, variables and conditions are allowed.
+fn check_nodes(nodes: &[Node]) -> Result<()> {
+    for node in nodes {
+        match node {
+            Node::Text(_) | Node::Raw(_, _, _) | Node::Comment(_, _) => {}
+            Node::VariableBlock(_, expression) => check_expression(expression)?,
+            Node::If(condition, _) => {
+                for (_, expression, body) in &condition.conditions {
+                    check_expression(expression)?;
+                    check_nodes(body)?;
+                }
+                if let Some((_, body)) = &condition.otherwise {
+                    check_nodes(body)?;
+                }
+            }
+            _ => {
+                return Err(InvalidEmailTemplate(format!(
+                    "Only variables and conditions are allowed [node: {node:?}]"
+                ))
+                .into());
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check_expression(expression: &Expr) -> Result<()> {
+    for filter in &expression.filters {
+        if !ALLOWED_FILTERS.contains(&filter.name.as_str()) {
+            return Err(InvalidEmailTemplate(format!(
+                "Filter not allowed [filter: {}]",
+                filter.name
+            ))
+            .into());
+        }
+        filter.args.values().try_for_each(check_expression)?;
+    }
+    check_value(&expression.val)
+}
+
+fn check_value(value: &ExprVal) -> Result<()> {
+    match value {
+        ExprVal::String(_)
+        | ExprVal::Int(_)
+        | ExprVal::Float(_)
+        | ExprVal::Bool(_)
+        | ExprVal::Ident(_) => Ok(()),
+        ExprVal::Math(math) => {
+            check_expression(&math.lhs)?;
+            check_expression(&math.rhs)
+        }
+        ExprVal::Logic(logic) => {
+            check_expression(&logic.lhs)?;
+            check_expression(&logic.rhs)
+        }
+        ExprVal::In(inclusion) => {
+            check_expression(&inclusion.lhs)?;
+            check_expression(&inclusion.rhs)
+        }
+        ExprVal::Test(test) => test.args.iter().try_for_each(check_expression),
+        ExprVal::Array(values) => values.iter().try_for_each(check_expression),
+        ExprVal::StringConcat(concat) => concat.values.iter().try_for_each(check_value),
+        ExprVal::FunctionCall(function) => Err(InvalidEmailTemplate(format!(
+            "Function not allowed [function: {}]",
+            function.name
+        ))
+        .into()),
+        ExprVal::MacroCall(call) => {
+            Err(InvalidEmailTemplate(format!("Macro not allowed [macro: {}]", call.name)).into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod check_email_template {
+        use crate::database::{dao, with_temp_database};
+        use crate::membership::notification::check_email_template;
+
+        #[test]
+        fn success() {
+            assert!(
+                check_email_template(
+                    "Votre licence {{ membership_number }}",
+                    "Bonjour{% if first_name %} {{ first_name }}{% endif %}"
+                )
+                .is_ok()
+            );
+        }
+
+        #[test]
+        fn fail_when_unknown_variable() {
+            assert!(check_email_template("Votre licence", "Bonjour {{ nickname }}").is_err());
+            assert!(check_email_template("Votre licence {% if", "Bonjour").is_err());
+        }
+
+        #[test]
+        fn success_with_default_templates() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                for template in dao::email_template::retrieve_all(&mut connection).unwrap() {
+                    assert!(check_email_template(template.subject(), template.body()).is_ok());
+                }
+            })
+        }
+
+        #[test]
+        fn success_with_allowed_filter() {
+            assert!(
+                check_email_template("Votre licence", "Bonjour {{ first_name | upper }}").is_ok()
+            );
+        }
+
+        #[test]
+        fn fail_when_reading_environment() {
+            assert!(
+                check_email_template(
+                    "Votre licence",
+                    r#"{{ get_env(name="ROCKET_SECRET_KEY") }}"#
+                )
+                .is_err()
+            );
+            assert!(
+                check_email_template(
+                    r#"{% if get_env(name="HOME") %}Votre licence{% endif %}"#,
+                    "Bonjour"
+                )
+                .is_err()
+            );
+        }
+
+        #[test]
+        fn fail_when_filter_is_not_allowed() {
+            assert!(
+                check_email_template("Votre licence", "{{ first_name | json_encode }}").is_err()
+            );
+        }
+
+        #[test]
+        fn fail_when_not_a_variable_or_condition() {
+            assert!(check_email_template("Votre licence", r#"{% set x = "y" %}{{ x }}"#).is_err());
+            assert!(
+                check_email_template("Votre licence", "{% for c in club %}{{ c }}{% endfor %}")
+                    .is_err()
+            );
+        }
+    }
+
+    mod personalize_email {
+        use crate::database::dao::membership::import_memberships;
+        use crate::database::with_temp_database;
+        use crate::error::ApplicationError;
+        use crate::membership::error::MembershipError::InvalidEmailTemplate;
+        use crate::membership::notification::personalize_email;
+        use crate::membership::tests::{jon_doe, jon_doe_previous_membership};
+        use chrono::NaiveDate;
+        use dto::email::{Email, EmailRecipient, PersonalizedEmail};
+        use dto::event_period::EventPeriod;
+
+        fn event_period() -> EventPeriod {
+            EventPeriod::single_day(NaiveDate::from_ymd_opt(2025, 10, 4).unwrap())
+        }
+
+        #[test]
+        fn success() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                import_memberships(&mut connection, &[jon_doe(), jon_doe_previous_membership()])
+                    .unwrap();
+                let recipient = EmailRecipient::new(
+                    "jon.doe@address.com".to_owned(),
+                    Some("123456".to_owned()),
+                );
+                let email = Email::new(
+                    vec![recipient.clone()],
+                    "{{ event_name }} : votre licence".to_owned(),
+                    "Bonjour {{ first_name }} {{ last_name }}, votre licence n°{{ membership_number }} ({{ club }}) est {{ status }} depuis le {{ end_date }}.".to_owned(),
+                    Some("Coupe de France".to_owned()),
+                    Some(event_period()),
+                );
+
+                let result = personalize_email(&mut connection, &email, &recipient, 30).unwrap();
+                assert_eq!(
+                    PersonalizedEmail::new(
+                        "jon.doe@address.com".to_owned(),
+                        "Coupe de France : votre licence".to_owned(),
+                        "Bonjour Jon Doe, votre licence n°123456 (My club) est expirée depuis le 30/09/2025.".to_owned(),
+                        "<p>Bonjour Jon Doe, votre licence n°123456 (My club) est expirée depuis le 30/09/2025.</p>\n".to_owned(),
+                    ),
+                    result
+                );
+            })
+        }
+
+        #[test]
+        fn success_when_membership_is_unknown() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                let recipient = EmailRecipient::new("jon.doe@address.com".to_owned(), None);
+                let email = Email::new(
+                    vec![recipient.clone()],
+                    "Votre licence".to_owned(),
+                    "Bonjour {{ first_name }}, votre licence est {{ status }}.".to_owned(),
+                    None,
+                    Some(event_period()),
+                );
+
+                let result = personalize_email(&mut connection, &email, &recipient, 30).unwrap();
+                assert_eq!("Bonjour , votre licence est inconnue.", result.body());
+            })
+        }
+
+        #[test]
+        fn fail_when_template_is_invalid() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                let recipient = EmailRecipient::new("jon.doe@address.com".to_owned(), None);
+                let email = Email::new(
+                    vec![recipient.clone()],
+                    "Votre licence".to_owned(),
+                    "Bonjour {{ unknown_variable }}".to_owned(),
+                    None,
+                    None,
+                );
+
+                let result = personalize_email(&mut connection, &email, &recipient, 30);
+                assert!(matches!(
+                    result,
+                    Err(ApplicationError::Membership(InvalidEmailTemplate(_)))
+                ));
+            })
+        }
+
+        #[test]
+        fn fail_when_reading_environment() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                let recipient = EmailRecipient::new("jon.doe@address.com".to_owned(), None);
+                let email = Email::new(
+                    vec![recipient.clone()],
+                    "Votre licence".to_owned(),
+                    r#"{{ get_env(name="ROCKET_SECRET_KEY", default="") }}"#.to_owned(),
+                    None,
+                    None,
+                );
+
+                let result = personalize_email(&mut connection, &email, &recipient, 30);
+                assert!(matches!(
+                    result,
+                    Err(ApplicationError::Membership(InvalidEmailTemplate(_)))
+                ));
+            })
+        }
+    }
+}
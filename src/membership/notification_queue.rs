@@ -0,0 +1,561 @@
+use crate::database::dao;
+use crate::database::error::DatabaseError;
+use crate::error::Result;
+use crate::membership::error::MembershipError::{CantProcessEmailQueue, MissingSendingResults};
+use crate::tools::email;
+use crate::tools::email::{EmailAttachment, EmailTransport, build_email_transport, create_message};
+use crate::tools::env_args::retrieve_arg_value;
+use chrono::{NaiveDateTime, TimeDelta, Utc};
+use derive_getters::Getters;
+use diesel::SqliteConnection;
+use diesel::r2d2::{ConnectionManager, Pool};
+use dto::email::PersonalizedEmail;
+use rocket::fairing::AdHoc;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+const EMAILS_PER_MINUTE_ARG: &str = "--emails-per-minute";
+/// Stays well below the limits of most SMTP providers, e.g. Gmail.
+const DEFAULT_EMAILS_PER_MINUTE: usize = 20;
+/// How often the queue is checked for due emails.
+const QUEUE_POLL_INTERVAL: Duration = Duration::from_secs(10);
+/// An email still failing after this many attempts is given up on.
+const MAX_ATTEMPTS: u32 = 5;
+/// Retries are delayed by 1, 2, 4, then 8 minutes.
+const FIRST_RETRY_DELAY_IN_MINUTES: i64 = 1;
+
+/// The attachments of each notification, by its ID.
+type NotificationAttachments = HashMap<i32, Vec<EmailAttachment>>;
+
+/// An email waiting in the queue, along with the notification it belongs to.
+#[derive(Debug, Getters, Clone, PartialEq)]
+pub struct QueuedEmail {
+    id: i32,
+    notification_id: i32,
+    email: PersonalizedEmail,
+    /// How many times sending it has already failed.
+    attempts: u32,
+}
+
+impl QueuedEmail {
+    pub fn new(id: i32, notification_id: i32, email: PersonalizedEmail, attempts: u32) -> Self {
+        Self {
+            id,
+            notification_id,
+            email,
+            attempts,
+        }
+    }
+}
+
+/// Queue the emails of a notification, so that they are sent in the background, then return its ID.
+pub fn enqueue_notification(
+    connection: &mut SqliteConnection,
+    created_by: &str,
+    emails: &[PersonalizedEmail],
+    attachments: &[EmailAttachment],
+) -> Result<i32> {
+    Ok(dao::notification::insert(
+        connection,
+        created_by,
+        emails,
+        attachments,
+    )?)
+}
+
+/// Send queued emails in the background, once the app has lifted off.
+pub fn email_queue_fairing() -> AdHoc {
+    AdHoc::on_liftoff("Email queue", |rocket| {
+        Box::pin(async move {
+            let pool = rocket
+                .state::<Pool<ConnectionManager<SqliteConnection>>>()
+                .expect("The database pool should be managed at this point")
+                .clone();
+            let mut rate_limiter = RateLimiter::new(retrieve_emails_per_minute());
+            let transport = build_email_transport();
+
+            rocket::tokio::spawn(async move {
+                let mut interval = rocket::tokio::time::interval(QUEUE_POLL_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    let limit = rate_limiter.available(Instant::now());
+                    if limit == 0 {
+                        continue;
+                    }
+                    match send_due_emails(&pool, transport.as_ref(), limit).await {
+                        Ok(attempts) => rate_limiter.record(Instant::now(), attempts),
+                        Err(error) => error!("Can't send queued emails: {error:?}"),
+                    }
+                }
+            });
+        })
+    })
+}
+
+/// Send up to `limit` due emails in a single batch, and record how it went for each of them.
+/// Return how many emails have been attempted.
+async fn send_due_emails(
+    pool: &Pool<ConnectionManager<SqliteConnection>>,
+    transport: &dyn EmailTransport,
+    limit: usize,
+) -> Result<usize> {
+    let now = Utc::now().naive_local();
+    let (due_emails, attachments) = with_blocking_connection(pool, move |connection| {
+        retrieve_due_emails_with_attachments(connection, &now, limit)
+    })
+    .await?;
+    if due_emails.is_empty() {
+        return Ok(0);
+    }
+
+    // An email which can't be written fails on its own, without preventing the others from being sent.
+    let mut messages = vec![];
+    let mut creation_results = vec![];
+    for due_email in &due_emails {
+        match create_message(due_email.email(), &attachments[due_email.notification_id()]) {
+            Ok(message) => {
+                messages.push(message);
+                creation_results.push(Ok(()));
+            }
+            Err(error) => creation_results.push(Err(error)),
+        }
+    }
+    let expected_results_count = messages.len();
+    let sending_results = if messages.is_empty() {
+        Ok(vec![])
+    } else {
+        transport.send(messages).await
+    };
+    // When nothing can be sent, e.g. because the SMTP server is down, every email has failed the same way.
+    let sending_results =
+        sending_results.unwrap_or_else(|error| vec![Err(error); expected_results_count]);
+
+    // Emails the transport gives no result for can't be told sent, so they are retried like failed ones.
+    let results_count = sending_results.len();
+    let mut sending_results = sending_results
+        .into_iter()
+        .chain(std::iter::repeat(Err(email::Error::CantSendMessage)));
+    let results = creation_results
+        .into_iter()
+        .map(|creation_result| creation_result.and_then(|()| sending_results.next().unwrap()))
+        .collect::<Vec<_>>();
+
+    let attempts = due_emails.len();
+    with_blocking_connection(pool, move |connection| {
+        for (due_email, result) in due_emails.iter().zip(results) {
+            record_attempt(connection, due_email, result, &now)?;
+        }
+        Ok(())
+    })
+    .await?;
+    if results_count != expected_results_count {
+        return Err(MissingSendingResults {
+            expected: expected_results_count,
+            actual: results_count,
+        }
+        .into());
+    }
+    debug!("Attempted to send queued emails [count: {attempts}]");
+    Ok(attempts)
+}
+
+/// Database calls are blocking, so they are kept off the async runtime.
+async fn with_blocking_connection<T, F>(
+    pool: &Pool<ConnectionManager<SqliteConnection>>,
+    function: F,
+) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&mut SqliteConnection) -> Result<T> + Send + 'static,
+{
+    let pool = pool.clone();
+    rocket::tokio::task::spawn_blocking(move || {
+        let mut connection = pool.get().map_err(DatabaseError::from)?;
+        function(&mut connection)
+    })
+    .await
+    .map_err(|error| CantProcessEmailQueue(error.to_string()))?
+}
+
+/// Retrieve the due emails, along with the attachments of their notifications.
+fn retrieve_due_emails_with_attachments(
+    connection: &mut SqliteConnection,
+    now: &NaiveDateTime,
+    limit: usize,
+) -> Result<(Vec<QueuedEmail>, NotificationAttachments)> {
+    let due_emails = dao::notification::retrieve_due_emails(connection, now, limit)?;
+    let mut attachments = NotificationAttachments::new();
+    for due_email in &due_emails {
+        if !attachments.contains_key(due_email.notification_id()) {
+            let notification_attachments =
+                dao::notification::retrieve_attachments(connection, *due_email.notification_id())?;
+            attachments.insert(*due_email.notification_id(), notification_attachments);
+        }
+    }
+    Ok((due_emails, attachments))
+}
+
+fn record_attempt(
+    connection: &mut SqliteConnection,
+    due_email: &QueuedEmail,
+    result: Result<(), email::Error>,
+    now: &NaiveDateTime,
+) -> Result<()> {
+    match result {
+        Ok(()) => dao::notification::mark_sent(connection, *due_email.id())?,
+        Err(error) => {
+            let attempts = due_email.attempts() + 1;
+            let next_attempt_date = (!error.is_permanent() && attempts < MAX_ATTEMPTS)
+                .then(|| compute_next_attempt_date(now, attempts));
+            dao::notification::mark_failed(
+                connection,
+                *due_email.id(),
+                &format!("{error:?}"),
+                next_attempt_date.as_ref(),
+            )?
+        }
+    }
+    Ok(())
+}
+
+/// Each retry waits twice as long as the previous one.
+fn compute_next_attempt_date(now: &NaiveDateTime, attempts: u32) -> NaiveDateTime {
+    *now + TimeDelta::minutes(FIRST_RETRY_DELAY_IN_MINUTES << (attempts - 1))
+}
+
+/// Keep track of the emails sent within the last minute, so that SMTP providers don't reject the next ones.
+struct RateLimiter {
+    max_per_minute: usize,
+    attempts: VecDeque<Instant>,
+}
+
+impl RateLimiter {
+    fn new(max_per_minute: usize) -> Self {
+        Self {
+            max_per_minute,
+            attempts: VecDeque::new(),
+        }
+    }
+
+    /// How many emails can be sent right now.
+    fn available(&mut self, now: Instant) -> usize {
+        while self
+            .attempts
+            .front()
+            .is_some_and(|attempt| now.duration_since(*attempt) >= Duration::from_secs(60))
+        {
+            self.attempts.pop_front();
+        }
+        self.max_per_minute.saturating_sub(self.attempts.len())
+    }
+
+    fn record(&mut self, now: Instant, count: usize) {
+        self.attempts.extend(std::iter::repeat_n(now, count));
+    }
+}
+
+fn retrieve_emails_per_minute() -> usize {
+    retrieve_arg_value(EMAILS_PER_MINUTE_ARG)
+        .and_then(|count| count.parse::<usize>().ok())
+        .filter(|count| *count > 0)
+        .unwrap_or(DEFAULT_EMAILS_PER_MINUTE)
+}
+
+#[cfg(test)]
+mod tests {
+    mod send_due_emails {
+        use crate::database::dao;
+        use crate::database::with_temp_database;
+        use crate::error::ApplicationError;
+        use crate::membership::error::MembershipError::MissingSendingResults;
+        use crate::membership::notification_queue::send_due_emails;
+        use crate::tools::email;
+        use crate::tools::email::in_memory::InMemoryTransport;
+        use crate::tools::email::{EmailAttachment, EmailTransport};
+        use crate::tools::env_args::with_env_args;
+        use dto::email::EmailStatus::{Failed, Pending, Sent};
+        use dto::email::PersonalizedEmail;
+        use mail_send::mail_builder::MessageBuilder;
+        use rocket::tokio::runtime::Runtime;
+
+        fn email(recipient: &str) -> PersonalizedEmail {
+            PersonalizedEmail::new(
+                recipient.to_owned(),
+                "Licence expirée".to_owned(),
+                "Bonjour".to_owned(),
+                "<p>Bonjour</p>".to_owned(),
+            )
+        }
+
+        #[test]
+        fn success() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                let attachment = EmailAttachment::new(
+                    "rules.pdf".to_owned(),
+                    "application/pdf".to_owned(),
+                    b"%PDF-1.4".to_vec(),
+                );
+                let notification_id = dao::notification::insert(
+                    &mut connection,
+                    "jon",
+                    &[email("jon@address.com"), email("jonette@address.com")],
+                    &[attachment],
+                )
+                .unwrap();
+                let transport = InMemoryTransport::default();
+                let args = vec![
+                    "--email-sender-name=Club".to_owned(),
+                    "--email-sender-address=club@address.com".to_owned(),
+                ];
+
+                let attempts = with_env_args(args, || {
+                    Runtime::new()
+                        .unwrap()
+                        .block_on(send_due_emails(&pool, &transport, 1))
+                        .unwrap()
+                });
+
+                assert_eq!(1, attempts);
+                let messages = transport.sent_messages();
+                assert_eq!(1, messages.len());
+                assert!(messages[0].contains("jon@address.com"));
+                assert!(messages[0].contains("club@address.com"));
+                assert!(messages[0].contains("filename=\"rules.pdf\""));
+                let status = dao::notification::retrieve_status(&mut connection, notification_id)
+                    .unwrap()
+                    .unwrap();
+                assert_eq!(&Sent, status.recipients()[0].status());
+                assert_eq!(&Pending, status.recipients()[1].status());
+            })
+        }
+
+        /// Loses track of every message but the first one.
+        struct LossyTransport;
+
+        #[rocket::async_trait]
+        impl EmailTransport for LossyTransport {
+            async fn send(
+                &self,
+                _messages: Vec<MessageBuilder<'_>>,
+            ) -> Result<Vec<Result<(), email::Error>>, email::Error> {
+                Ok(vec![Ok(())])
+            }
+        }
+
+        #[test]
+        fn fail_when_results_are_missing() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                let notification_id = dao::notification::insert(
+                    &mut connection,
+                    "jon",
+                    &[email("jon@address.com"), email("jonette@address.com")],
+                    &[],
+                )
+                .unwrap();
+                let args = vec![
+                    "--email-sender-name=Club".to_owned(),
+                    "--email-sender-address=club@address.com".to_owned(),
+                ];
+
+                let result = with_env_args(args, || {
+                    Runtime::new()
+                        .unwrap()
+                        .block_on(send_due_emails(&pool, &LossyTransport, 10))
+                });
+
+                assert!(matches!(
+                    result,
+                    Err(ApplicationError::Membership(MissingSendingResults {
+                        expected: 2,
+                        actual: 1
+                    }))
+                ));
+                let status = dao::notification::retrieve_status(&mut connection, notification_id)
+                    .unwrap()
+                    .unwrap();
+                assert_eq!(&Sent, status.recipients()[0].status());
+                assert_eq!(&Pending, status.recipients()[1].status());
+                assert_eq!(
+                    &Some("CantSendMessage".to_owned()),
+                    status.recipients()[1].last_error()
+                );
+            })
+        }
+
+        #[test]
+        fn fail_without_sender() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                let notification_id = dao::notification::insert(
+                    &mut connection,
+                    "jon",
+                    &[email("jon@address.com")],
+                    &[],
+                )
+                .unwrap();
+                let transport = InMemoryTransport::default();
+
+                let attempts = with_env_args(vec![], || {
+                    Runtime::new()
+                        .unwrap()
+                        .block_on(send_due_emails(&pool, &transport, 10))
+                        .unwrap()
+                });
+
+                assert_eq!(1, attempts);
+                assert!(transport.sent_messages().is_empty());
+                let status = dao::notification::retrieve_status(&mut connection, notification_id)
+                    .unwrap()
+                    .unwrap();
+                assert_eq!(&Failed, status.recipients()[0].status());
+                assert_eq!(
+                    &Some("MissingEmailSenderName".to_owned()),
+                    status.recipients()[0].last_error()
+                );
+            })
+        }
+    }
+
+    mod record_attempt {
+        use crate::database::dao;
+        use crate::database::with_temp_database;
+        use crate::membership::notification_queue::{MAX_ATTEMPTS, QueuedEmail, record_attempt};
+        use crate::tools::email::Error;
+        use crate::tools::email::Error::{CantSendMessage, MessageRejected, MissingSmtpLogin};
+        use chrono::{TimeDelta, Utc};
+        use dto::email::EmailStatus::{Failed, Pending, Sent};
+        use dto::email::{EmailStatus, PersonalizedEmail};
+
+        fn email() -> PersonalizedEmail {
+            PersonalizedEmail::new(
+                "jon@address.com".to_owned(),
+                "Licence".to_owned(),
+                "Bonjour".to_owned(),
+                "<p>Bonjour</p>".to_owned(),
+            )
+        }
+
+        /// Record an attempt of an email which has already failed `attempts` times, then return its status.
+        fn record(result: Result<(), Error>, attempts: u32) -> EmailStatus {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                let notification_id =
+                    dao::notification::insert(&mut connection, "jon", &[email()], &[]).unwrap();
+                let now = Utc::now().naive_local();
+                let due_email = dao::notification::retrieve_due_emails(&mut connection, &now, 1)
+                    .unwrap()
+                    .pop()
+                    .unwrap();
+                let due_email =
+                    QueuedEmail::new(*due_email.id(), notification_id, email(), attempts);
+
+                record_attempt(&mut connection, &due_email, result, &now).unwrap();
+
+                let status = dao::notification::retrieve_status(&mut connection, notification_id)
+                    .unwrap()
+                    .unwrap();
+                let later = now + TimeDelta::hours(1);
+                let retried = !dao::notification::retrieve_due_emails(&mut connection, &later, 1)
+                    .unwrap()
+                    .is_empty();
+                assert_eq!(
+                    retried,
+                    *status.recipients()[0].status() == Pending,
+                    "Pending emails should be retried"
+                );
+                *status.recipients()[0].status()
+            })
+        }
+
+        #[test]
+        fn sent() {
+            assert_eq!(Sent, record(Ok(()), 0));
+        }
+
+        #[test]
+        fn retry_when_transient_error() {
+            assert_eq!(Pending, record(Err(CantSendMessage), 0));
+        }
+
+        #[test]
+        fn fail_when_permanent_error() {
+            assert_eq!(Failed, record(Err(MessageRejected), 0));
+        }
+
+        #[test]
+        fn fail_when_missing_configuration() {
+            assert_eq!(Failed, record(Err(MissingSmtpLogin), 0));
+        }
+
+        #[test]
+        fn fail_after_last_attempt() {
+            assert_eq!(Failed, record(Err(CantSendMessage), MAX_ATTEMPTS - 1));
+        }
+    }
+
+    mod compute_next_attempt_date {
+        use crate::membership::notification_queue::compute_next_attempt_date;
+        use chrono::{NaiveDate, TimeDelta};
+
+        #[test]
+        fn exponential_backoff() {
+            let now = NaiveDate::from_ymd_opt(2025, 5, 6)
+                .unwrap()
+                .and_hms_opt(10, 0, 0)
+                .unwrap();
+
+            assert_eq!(
+                now + TimeDelta::minutes(1),
+                compute_next_attempt_date(&now, 1)
+            );
+            assert_eq!(
+                now + TimeDelta::minutes(8),
+                compute_next_attempt_date(&now, 4)
+            );
+        }
+    }
+
+    mod rate_limiter {
+        use crate::membership::notification_queue::RateLimiter;
+        use std::time::{Duration, Instant};
+
+        #[test]
+        fn limit_per_minute() {
+            let mut rate_limiter = RateLimiter::new(5);
+            let start = Instant::now();
+            assert_eq!(5, rate_limiter.available(start));
+
+            rate_limiter.record(start, 3);
+            assert_eq!(2, rate_limiter.available(start + Duration::from_secs(30)));
+
+            rate_limiter.record(start + Duration::from_secs(30), 2);
+            assert_eq!(0, rate_limiter.available(start + Duration::from_secs(59)));
+            assert_eq!(3, rate_limiter.available(start + Duration::from_secs(60)));
+            assert_eq!(5, rate_limiter.available(start + Duration::from_secs(90)));
+        }
+    }
+
+    mod retrieve_emails_per_minute {
+        use crate::membership::notification_queue::{
+            DEFAULT_EMAILS_PER_MINUTE, EMAILS_PER_MINUTE_ARG, retrieve_emails_per_minute,
+        };
+        use crate::tools::env_args::with_env_args;
+
+        #[test]
+        fn custom_limit() {
+            let args = vec![format!("{EMAILS_PER_MINUTE_ARG}=50")];
+            assert_eq!(50, with_env_args(args, retrieve_emails_per_minute));
+        }
+
+        #[test]
+        fn default_limit_when_zero() {
+            let args = vec![format!("{EMAILS_PER_MINUTE_ARG}=0")];
+            assert_eq!(
+                DEFAULT_EMAILS_PER_MINUTE,
+                with_env_args(args, retrieve_emails_per_minute)
+            );
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use crate::error::Result;
+use crate::membership::error::MembershipError::{MalformedSpreadsheet, NoKnownColumn};
+use crate::tools::log_message_and_return;
+use calamine::{
+    Data, RangeDeserializer, RangeDeserializerBuilder, Reader, open_workbook_auto_from_rs,
+};
+use chrono::{Days, NaiveDate};
+use dto::csv_member::{CsvMember, SpreadsheetMembers};
+use dto::member_field::MemberField;
+use dto::membership_import::RejectedRow;
+use std::io::Cursor;
+
+/// Formats birthdates are usually written with when they are not stored as dates.
+const BIRTHDATE_FORMATS: [&str; 3] = ["%d/%m/%Y", "%d.%m.%Y", "%Y-%m-%d"];
+
+/// Read members to check from the first sheet of an `.xlsx`, `.xls` or `.ods` file.
+/// The first row is expected to hold headers, from which the field of each column is guessed.
+/// Several columns may hold the same field: the first non-empty one is then used.
+/// Rows that can't be read are left aside and reported.
+pub fn read_members_from_spreadsheet(file_content: Vec<u8>) -> Result<SpreadsheetMembers> {
+    let mut workbook = open_workbook_auto_from_rs(Cursor::new(file_content)).map_err(
+        log_message_and_return("Can't open spreadsheet", MalformedSpreadsheet),
+    )?;
+    let range = workbook
+        .worksheet_range_at(0)
+        .ok_or(MalformedSpreadsheet)?
+        .map_err(log_message_and_return(
+            "Can't read spreadsheet first sheet",
+            MalformedSpreadsheet,
+        ))?;
+    // Lines are reported as the user sees them in the spreadsheet, including the headers
+    let first_line = range.start().map(|(row, _)| row as u64 + 1).unwrap_or(1);
+
+    let mut rows: RangeDeserializer<'_, Data, Vec<Data>> = RangeDeserializerBuilder::new()
+        .has_headers(false)
+        .from_range(&range)
+        .map_err(log_message_and_return(
+            "Can't read spreadsheet content",
+            MalformedSpreadsheet,
+        ))?;
+    let headers: Vec<String> = match rows.next() {
+        Some(Ok(headers)) => headers.iter().map(|header| header.to_string()).collect(),
+        _ => return Err(NoKnownColumn.into()),
+    };
+    let columns: Vec<Option<MemberField>> = headers
+        .iter()
+        .map(|header| MemberField::guess(header))
+        .collect();
+    if columns.iter().all(Option::is_none) {
+        return Err(NoKnownColumn.into());
+    }
+
+    let mut members = vec![];
+    let mut rejected_rows = vec![];
+    for (index, row) in rows.enumerate() {
+        let line = first_line + index as u64 + 1;
+        let result = row
+            .map_err(|error| RejectedRow::new(line, None, error.to_string()))
+            .and_then(|row| to_csv_member(&row, &headers, &columns, line));
+        match result {
+            Ok(Some(member)) => members.push(member),
+            Ok(None) => {}
+            Err(rejected_row) => rejected_rows.push(rejected_row),
+        }
+    }
+
+    Ok(SpreadsheetMembers::new(members, rejected_rows))
+}
+
+/// Build the member held by a row, or `None` if the row is empty.
+fn to_csv_member(
+    row: &[Data],
+    headers: &[String],
+    columns: &[Option<MemberField>],
+    line: u64,
+) -> std::result::Result<Option<CsvMember>, RejectedRow> {
+    let cells = |field: MemberField| {
+        columns
+            .iter()
+            .enumerate()
+            .filter(move |(_, column)| **column == Some(field))
+            .filter_map(|(index, _)| row.get(index).map(|cell| (index, cell)))
+            .filter(|(_, cell)| !to_text(cell).is_empty())
+    };
+    let get = |field: MemberField| cells(field).next().map(|(_, cell)| to_text(cell));
+
+    let birthdate = match cells(MemberField::Birthdate).next() {
+        Some((index, cell)) => Some(to_date(cell).ok_or_else(|| {
+            RejectedRow::new(
+                line,
+                headers.get(index).cloned(),
+                format!("Invalid birthdate [value: {}]", to_text(cell)),
+            )
+        })?),
+        None => None,
+    };
+    let member = CsvMember::new(
+        get(MemberField::MembershipNumber),
+        get(MemberField::Identity),
+        get(MemberField::LastName),
+        get(MemberField::FirstName),
+        birthdate,
+        get(MemberField::EmailAddress),
+        get(MemberField::Club),
+    );
+
+    if member == CsvMember::new(None, None, None, None, None, None, None) {
+        Ok(None)
+    } else {
+        Ok(Some(member))
+    }
+}
+
+fn to_text(cell: &Data) -> String {
+    cell.to_string().trim().to_owned()
+}
+
+/// Birthdates may either be stored as dates, or written as text.
+fn to_date(cell: &Data) -> Option<NaiveDate> {
+    match cell {
+        // Spreadsheets store dates as a number of days since 1899-12-30
+        Data::DateTime(date_time) => NaiveDate::from_ymd_opt(1899, 12, 30)?
+            .checked_add_days(Days::new(date_time.as_f64().trunc() as u64)),
+        Data::DateTimeIso(date_time) => date_time
+            .get(..10)
+            .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()),
+        _ => {
+            let text = to_text(cell);
+            BIRTHDATE_FORMATS
+                .iter()
+                .find_map(|format| NaiveDate::parse_from_str(&text, format).ok())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod read_members_from_spreadsheet {
+        use crate::membership::spreadsheet::read_members_from_spreadsheet;
+        use chrono::NaiveDate;
+        use dto::csv_member::CsvMember;
+        use std::fs;
+
+        #[test]
+        fn success() {
+            let file_content = fs::read("test/resources/uda_members.xls").unwrap();
+
+            let result = read_members_from_spreadsheet(file_content).unwrap();
+            assert!(result.members().contains(&CsvMember::new(
+                Some("123456".to_owned()),
+                None,
+                Some("Doe".to_owned()),
+                Some("Jon".to_owned()),
+                NaiveDate::from_ymd_opt(1983, 2, 1),
+                Some("jon.doe@email.com".to_owned()),
+                Some("Le club de test".to_owned()),
+            )));
+            assert!(result.rejected_rows().is_empty());
+        }
+
+        #[test]
+        fn fail_when_not_a_spreadsheet() {
+            assert!(read_members_from_spreadsheet(b"Nom;Prenom".to_vec()).is_err());
+        }
+    }
+
+    mod to_csv_member {
+        use crate::membership::spreadsheet::to_csv_member;
+        use calamine::Data;
+        use chrono::NaiveDate;
+        use dto::csv_member::CsvMember;
+        use dto::member_field::MemberField::{Birthdate, FirstName, LastName, MembershipNumber};
+
+        fn headers() -> Vec<String> {
+            vec![
+                "Licence".to_owned(),
+                "Nom".to_owned(),
+                "Prénom".to_owned(),
+                "Date de naissance".to_owned(),
+                "Numéro d'adhérent".to_owned(),
+            ]
+        }
+
+        fn columns() -> Vec<Option<dto::member_field::MemberField>> {
+            vec![
+                Some(MembershipNumber),
+                Some(LastName),
+                Some(FirstName),
+                Some(Birthdate),
+                Some(MembershipNumber),
+            ]
+        }
+
+        #[test]
+        fn success() {
+            let row = vec![
+                Data::Float(123456.0),
+                Data::String(" Doe ".to_owned()),
+                Data::String("Jon".to_owned()),
+                Data::String("01/02/1980".to_owned()),
+                Data::Empty,
+            ];
+
+            let member = to_csv_member(&row, &headers(), &columns(), 2).unwrap();
+            assert_eq!(
+                Some(CsvMember::new(
+                    Some("123456".to_owned()),
+                    None,
+                    Some("Doe".to_owned()),
+                    Some("Jon".to_owned()),
+                    NaiveDate::from_ymd_opt(1980, 2, 1),
+                    None,
+                    None,
+                )),
+                member
+            );
+        }
+
+        #[test]
+        fn should_use_first_non_empty_column() {
+            let row = vec![
+                Data::Empty,
+                Data::String("Doe".to_owned()),
+                Data::Empty,
+                Data::Empty,
+                Data::String("654321".to_owned()),
+            ];
+
+            let member = to_csv_member(&row, &headers(), &columns(), 2)
+                .unwrap()
+                .unwrap();
+            assert_eq!(&Some("654321".to_owned()), member.membership_num());
+        }
+
+        #[test]
+        fn should_skip_empty_row() {
+            let row = vec![Data::Empty, Data::String(" ".to_owned())];
+
+            assert_eq!(
+                None,
+                to_csv_member(&row, &headers(), &columns(), 2).unwrap()
+            );
+        }
+
+        #[test]
+        fn fail_when_birthdate_is_invalid() {
+            let row = vec![
+                Data::Empty,
+                Data::String("Doe".to_owned()),
+                Data::Empty,
+                Data::String("not a date".to_owned()),
+            ];
+
+            let rejected_row = to_csv_member(&row, &headers(), &columns(), 5).unwrap_err();
+            assert_eq!(&5, rejected_row.line());
+            assert_eq!(&Some("Date de naissance".to_owned()), rejected_row.column());
+        }
+    }
+
+    mod to_date {
+        use crate::membership::spreadsheet::to_date;
+        use calamine::{Data, ExcelDateTime, ExcelDateTimeType};
+        use chrono::NaiveDate;
+
+        #[test]
+        fn excel_date() {
+            let cell = Data::DateTime(ExcelDateTime::new(
+                29252.0,
+                ExcelDateTimeType::DateTime,
+                false,
+            ));
+            assert_eq!(NaiveDate::from_ymd_opt(1980, 2, 1), to_date(&cell));
+        }
+
+        #[test]
+        fn iso_date() {
+            let cell = Data::DateTimeIso("1980-02-01T00:00:00".to_owned());
+            assert_eq!(NaiveDate::from_ymd_opt(1980, 2, 1), to_date(&cell));
+        }
+
+        #[test]
+        fn text() {
+            let cell = Data::String("01.02.1980".to_owned());
+            assert_eq!(NaiveDate::from_ymd_opt(1980, 2, 1), to_date(&cell));
+        }
+    }
+}
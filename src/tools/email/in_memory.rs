@@ -0,0 +1,67 @@
+use crate::tools::email::Error::CantWriteMessage;
+use crate::tools::email::{EmailTransport, Result};
+use crate::tools::log_message_and_return;
+use mail_send::mail_builder::MessageBuilder;
+use std::sync::{Arc, Mutex};
+
+/// Keep messages in memory instead of sending them, so that they can be checked, e.g. in tests.
+/// Clones share the same messages.
+#[derive(Clone, Default)]
+pub struct InMemoryTransport {
+    messages: Arc<Mutex<Vec<String>>>,
+}
+
+impl InMemoryTransport {
+    /// The raw messages "sent" so far, in order.
+    #[cfg(test)]
+    pub fn sent_messages(&self) -> Vec<String> {
+        self.messages.lock().unwrap().clone()
+    }
+}
+
+#[rocket::async_trait]
+impl EmailTransport for InMemoryTransport {
+    async fn send(&self, messages: Vec<MessageBuilder<'_>>) -> Result<Vec<Result<()>>> {
+        let mut sent_messages = self.messages.lock().unwrap();
+        Ok(messages
+            .into_iter()
+            .map(|message| {
+                let message = message.write_to_string().map_err(log_message_and_return(
+                    "Couldn't build message",
+                    CantWriteMessage,
+                ))?;
+                sent_messages.push(message);
+                Ok(())
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tools::email::in_memory::InMemoryTransport;
+    use crate::tools::email::tests::{get_attachment, get_email, get_sender_args};
+    use crate::tools::email::{EmailTransport, create_message};
+    use crate::tools::env_args::with_env_args;
+    use rocket::futures::executor::block_on;
+
+    #[test]
+    fn should_keep_sent_messages() {
+        let transport = InMemoryTransport::default();
+        let email = get_email();
+        let attachments = [get_attachment()];
+
+        let results = with_env_args(get_sender_args(), || {
+            let message = create_message(&email, &attachments).unwrap();
+            block_on(transport.clone().send(vec![message]))
+        })
+        .unwrap();
+
+        assert_eq!(vec![Ok(())], results);
+        let messages = transport.sent_messages();
+        assert_eq!(1, messages.len());
+        assert!(messages[0].contains(&format!("Subject: {}", email.subject())));
+        assert!(messages[0].contains("multipart/alternative"));
+        assert!(messages[0].contains("filename=\"rules.pdf\""));
+    }
+}
@@ -0,0 +1,122 @@
+use crate::tools::email::Error::{CantOpenMailbox, CantWriteMessage};
+use crate::tools::email::{EmailTransport, Result};
+use crate::tools::log_message_and_return;
+use chrono::Utc;
+use mail_send::mail_builder::MessageBuilder;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Append messages to a local mbox file instead of sending them, e.g. for demo mode.
+/// The file can be opened with most email clients, e.g. Thunderbird or `mutt -f`.
+pub struct MboxTransport {
+    path: PathBuf,
+}
+
+impl MboxTransport {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+#[rocket::async_trait]
+impl EmailTransport for MboxTransport {
+    async fn send(&self, messages: Vec<MessageBuilder<'_>>) -> Result<Vec<Result<()>>> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(log_message_and_return(
+                "Couldn't open mailbox",
+                CantOpenMailbox,
+            ))?;
+
+        let results = messages
+            .into_iter()
+            .map(|message| {
+                let message = message.write_to_vec().map_err(log_message_and_return(
+                    "Couldn't build message",
+                    CantWriteMessage,
+                ))?;
+                file.write_all(to_mbox_entry(&message).as_bytes())
+                    .map_err(log_message_and_return(
+                        "Couldn't write message to mailbox",
+                        CantWriteMessage,
+                    ))
+            })
+            .collect();
+        debug!("Wrote messages to mailbox [path: {:?}]", self.path);
+        Ok(results)
+    }
+}
+
+/// Format a message as an mboxrd entry: a "From " separator line, then the message with Unix line endings,
+/// where lines starting with "From " (after any number of ">") are escaped with one more ">".
+fn to_mbox_entry(message: &[u8]) -> String {
+    let message = String::from_utf8_lossy(message).replace("\r\n", "\n");
+    let mut entry = format!(
+        "From MAILER-DAEMON {}\n",
+        Utc::now().format("%a %b %e %H:%M:%S %Y")
+    );
+    for line in message.lines() {
+        if line.trim_start_matches('>').starts_with("From ") {
+            entry.push('>');
+        }
+        entry.push_str(line);
+        entry.push('\n');
+    }
+    entry.push('\n');
+    entry
+}
+
+#[cfg(test)]
+mod tests {
+    mod send {
+        use crate::tools::email::mbox::MboxTransport;
+        use crate::tools::email::tests::{get_email, get_sender_args};
+        use crate::tools::email::{EmailTransport, create_message};
+        use crate::tools::env_args::with_env_args;
+        use crate::tools::test::tests::temp_dir;
+        use rocket::futures::executor::block_on;
+        use std::fs;
+
+        #[test]
+        fn success() {
+            let path = temp_dir().join("emails.mbox");
+            let transport = MboxTransport::new(path.clone());
+            let email = get_email();
+
+            for _ in 0..2 {
+                let results = with_env_args(get_sender_args(), || {
+                    let message = create_message(&email, &[]).unwrap();
+                    block_on(transport.send(vec![message]))
+                })
+                .unwrap();
+                assert_eq!(vec![Ok(())], results);
+            }
+
+            let mailbox = fs::read_to_string(path).unwrap();
+            assert_eq!(2, mailbox.matches("\nFrom MAILER-DAEMON ").count() + 1);
+            assert!(mailbox.starts_with("From MAILER-DAEMON "));
+            assert!(mailbox.contains(email.recipient().as_str()));
+            assert!(mailbox.contains(&format!("Subject: {}", email.subject())));
+        }
+    }
+
+    mod to_mbox_entry {
+        use crate::tools::email::mbox::to_mbox_entry;
+
+        #[test]
+        fn should_escape_from_lines() {
+            let entry =
+                to_mbox_entry(b"Subject: Test\r\n\r\nFrom here\r\n>From there\r\nFromage\r\n");
+
+            let (separator, message) = entry.split_once('\n').unwrap();
+            assert!(separator.starts_with("From MAILER-DAEMON "));
+            assert_eq!(
+                "Subject: Test\n\n>From here\n>>From there\nFromage\n\n",
+                message
+            );
+        }
+    }
+}
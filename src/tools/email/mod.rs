@@ -1,86 +1,108 @@
 use crate::tools::email::Error::{
-    CantConnectToSmtpServer, CantSendMessage, MissingEmailSenderAddress, MissingEmailSenderName,
-    MissingSmtpLogin, MissingSmtpPassword,
+    MessageRejected, MissingEmailSenderAddress, MissingEmailSenderName, MissingSmtpLogin,
+    MissingSmtpPassword,
 };
+use crate::tools::email::in_memory::InMemoryTransport;
+use crate::tools::email::mbox::MboxTransport;
+use crate::tools::email::smtp::SmtpTransport;
 use crate::tools::env_args::{retrieve_arg_value, retrieve_expected_arg_value};
-use crate::tools::log_message_and_return;
-use mail_send::SmtpClientBuilder;
+use derive_getters::Getters;
+use dto::email::PersonalizedEmail;
 use mail_send::mail_builder::MessageBuilder;
+use std::path::PathBuf;
+
+pub mod in_memory;
+pub mod mbox;
+pub mod smtp;
 
 type Result<T, E = Error> = std::result::Result<T, E>;
 
 const EMAIL_SENDER_NAME_ARG: &str = "--email-sender-name";
 const EMAIL_SENDER_ADDRESS_ARG: &str = "--email-sender-address";
 const REPLY_TO_ARG: &str = "--reply-to";
-const SMTP_SERVER_ARG: &str = "--smtp-server";
-const SMTP_PORT_ARG: &str = "--smtp-port";
-const SMTP_LOGIN_ARG: &str = "--smtp-login";
-const SMTP_PASSWORD_ARG: &str = "--smtp-password";
-const DEFAULT_SMTP_SERVER: &str = "smtp.gmail.com";
-const DEFAULT_SMTP_PORT: u16 = 587;
-
-pub async fn send_email(recipients: &[&str], subject: &str, text_body: &str) -> Result<()> {
-    let message = create_message(recipients, subject, text_body)?;
-    create_smtp_client_and_send_email(message).await
+const EMAIL_TRANSPORT_ARG: &str = "--email-transport";
+const EMAIL_MBOX_PATH_ARG: &str = "--email-mbox-path";
+#[cfg(not(feature = "demo"))]
+const DEFAULT_EMAIL_TRANSPORT: &str = "smtp";
+/// Demo mode never sends real emails.
+#[cfg(feature = "demo")]
+const DEFAULT_EMAIL_TRANSPORT: &str = "mbox";
+const DEFAULT_EMAIL_MBOX_PATH: &str = "emails.mbox";
+
+/// How messages actually leave the app.
+#[rocket::async_trait]
+pub trait EmailTransport: Send + Sync {
+    /// Send the messages, and return the result of each of them, in order.
+    /// A message failing doesn't prevent the next ones from being sent.
+    async fn send(&self, messages: Vec<MessageBuilder<'_>>) -> Result<Vec<Result<()>>>;
+}
+
+/// Build the transport chosen in args: `smtp` (default), `mbox` or `memory`.
+pub fn build_email_transport() -> Box<dyn EmailTransport> {
+    let transport = retrieve_email_transport();
+    match transport.as_str() {
+        "smtp" => Box::new(SmtpTransport),
+        "mbox" => Box::new(MboxTransport::new(retrieve_email_mbox_path())),
+        "memory" => Box::new(InMemoryTransport::default()),
+        _ => {
+            warn!("Unknown email transport, falling back to SMTP [transport: {transport}]");
+            Box::new(SmtpTransport)
+        }
+    }
 }
 
-async fn create_smtp_client_and_send_email(message: MessageBuilder<'_>) -> Result<()> {
-    let smtp_server = retrieve_smtp_server();
-    let smtp_port = retrieve_smtp_port();
-    let smtp_login = retrieve_smtp_login()?;
-    let smtp_password = retrieve_smtp_password()?;
-    let smtp_client = SmtpClientBuilder::new(smtp_server, smtp_port)
-        .implicit_tls(false)
-        .credentials((smtp_login, smtp_password))
-        .connect()
-        .await;
-
-    smtp_client
-        .map_err(log_message_and_return(
-            "Couldn't connect to SMTP server",
-            CantConnectToSmtpServer,
-        ))?
-        .send(message)
-        .await
-        .map_err(log_message_and_return(
-            "Couldn't send message",
-            CantSendMessage,
-        ))
+/// A file attached to every email of a notification, e.g. the rules of the event.
+#[derive(Debug, Getters, Clone, PartialEq)]
+pub struct EmailAttachment {
+    file_name: String,
+    content_type: String,
+    content: Vec<u8>,
 }
 
-fn create_message<'a>(
-    recipients: &'a [&str],
-    subject: &'a str,
-    text_body: &'a str,
+impl EmailAttachment {
+    pub fn new(file_name: String, content_type: String, content: Vec<u8>) -> Self {
+        Self {
+            file_name,
+            content_type,
+            content,
+        }
+    }
+}
+
+/// Build a multipart message, with both the HTML body and its plain-text alternative.
+pub fn create_message<'a>(
+    email: &'a PersonalizedEmail,
+    attachments: &'a [EmailAttachment],
 ) -> Result<MessageBuilder<'a>> {
     let sender_name = retrieve_email_sender_name()?;
     let sender_address = retrieve_email_sender_address()?;
     let reply_to_address = retrieve_reply_to().unwrap_or_else(|| sender_address.clone());
 
-    Ok(MessageBuilder::new()
+    let message = MessageBuilder::new()
         .from((sender_name, sender_address))
-        .reply_to(reply_to_address.clone())
-        .to(reply_to_address)
-        .bcc(Vec::from(recipients))
-        .subject(subject)
-        .text_body(text_body))
+        .reply_to(reply_to_address)
+        .to(email.recipient().as_str())
+        .subject(email.subject())
+        .text_body(email.body())
+        .html_body(email.html_body());
+    Ok(attachments.iter().fold(message, |message, attachment| {
+        message.attachment(
+            attachment.content_type().as_str(),
+            attachment.file_name().as_str(),
+            attachment.content().as_slice(),
+        )
+    }))
 }
 
 // region Retrieve args
-fn retrieve_smtp_server() -> String {
-    retrieve_arg_value(SMTP_SERVER_ARG).unwrap_or(DEFAULT_SMTP_SERVER.to_owned())
-}
-fn retrieve_smtp_port() -> u16 {
-    retrieve_arg_value(SMTP_PORT_ARG)
-        .and_then(|port| port.parse::<u16>().ok())
-        .unwrap_or(DEFAULT_SMTP_PORT)
-}
-fn retrieve_smtp_login() -> Result<String> {
-    retrieve_expected_arg_value(SMTP_LOGIN_ARG, MissingSmtpLogin)
+fn retrieve_email_transport() -> String {
+    retrieve_arg_value(EMAIL_TRANSPORT_ARG).unwrap_or(DEFAULT_EMAIL_TRANSPORT.to_owned())
 }
 
-fn retrieve_smtp_password() -> Result<String> {
-    retrieve_expected_arg_value(SMTP_PASSWORD_ARG, MissingSmtpPassword)
+fn retrieve_email_mbox_path() -> PathBuf {
+    PathBuf::from(
+        retrieve_arg_value(EMAIL_MBOX_PATH_ARG).unwrap_or(DEFAULT_EMAIL_MBOX_PATH.to_owned()),
+    )
 }
 
 fn retrieve_email_sender_name() -> Result<String> {
@@ -96,57 +118,76 @@ fn retrieve_reply_to() -> Option<String> {
 }
 // endregion
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Error {
     MissingEmailSenderName,
     MissingEmailSenderAddress,
     MissingSmtpLogin,
     MissingSmtpPassword,
     CantConnectToSmtpServer,
+    CantOpenMailbox,
     CantSendMessage,
+    CantWriteMessage,
+    MessageRejected,
+}
+
+impl Error {
+    /// Whether sending the email again can't succeed, unlike e.g. when the SMTP server is temporarily unavailable.
+    /// A missing configuration won't show up before the app is restarted, so it is permanent as well.
+    pub fn is_permanent(&self) -> bool {
+        matches!(
+            self,
+            MessageRejected
+                | MissingEmailSenderName
+                | MissingEmailSenderAddress
+                | MissingSmtpLogin
+                | MissingSmtpPassword
+        )
+    }
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use crate::tools::env_args::with_env_args;
     use mail_send::mail_builder::mime::BodyPart;
     use parameterized::{ide, parameterized};
-    use rocket::futures::executor::block_on;
 
     ide!();
 
-    const TEST_SMTP_SERVER: &str = "sandbox.smtp.mailtrap.io";
-    const TEST_SMTP_PORT: u16 = 25;
     const TEST_EMAIL_SENDER_NAME: &str = "Sender";
     const TEST_EMAIL_SENDER_ADDRESS: &str = "sender@address.com";
     const TEST_REPLY_TO: &str = "sender+reply-to@address.com";
-    const TEST_RECIPIENTS: &[&str] = &["recipient@address.com"];
+    const TEST_RECIPIENT: &str = "recipient@address.com";
     const TEST_SUBJECT: &str = "This is a subject";
     const TEST_TEXT_BODY: &str = "This is a slightly less important email";
+    const TEST_HTML_BODY: &str = "<p>This is a slightly less important email</p>";
+
+    pub(crate) fn get_email() -> PersonalizedEmail {
+        PersonalizedEmail::new(
+            TEST_RECIPIENT.to_owned(),
+            TEST_SUBJECT.to_owned(),
+            TEST_TEXT_BODY.to_owned(),
+            TEST_HTML_BODY.to_owned(),
+        )
+    }
 
-    fn get_args() -> Vec<String> {
+    pub(crate) fn get_attachment() -> EmailAttachment {
+        EmailAttachment::new(
+            "rules.pdf".to_owned(),
+            "application/pdf".to_owned(),
+            b"%PDF-1.4".to_vec(),
+        )
+    }
+
+    pub(crate) fn get_sender_args() -> Vec<String> {
         vec![
-            format!("{SMTP_SERVER_ARG}={TEST_SMTP_SERVER}"),
-            format!("{SMTP_PORT_ARG}={TEST_SMTP_PORT}"),
             format!("{EMAIL_SENDER_NAME_ARG}={TEST_EMAIL_SENDER_NAME}"),
             format!("{EMAIL_SENDER_ADDRESS_ARG}={TEST_EMAIL_SENDER_ADDRESS}"),
             format!("{REPLY_TO_ARG}={TEST_REPLY_TO}"),
         ]
     }
 
-    // region send_email
-    #[async_test]
-    #[ignore]
-    async fn should_send_email() {
-        let args = get_args();
-        with_env_args(args, || {
-            block_on(send_email(TEST_RECIPIENTS, TEST_SUBJECT, TEST_TEXT_BODY))
-        })
-        .unwrap();
-    }
-    // endregion
-
     // region create_message
     #[test]
     fn should_create_message() {
@@ -156,7 +197,9 @@ mod tests {
         let sender_address_arg = format!("{EMAIL_SENDER_ADDRESS_ARG}={sender_address}");
         let args = vec![sender_name_arg, sender_address_arg];
 
-        let function = || create_message(TEST_RECIPIENTS, TEST_SUBJECT, TEST_TEXT_BODY);
+        let email = get_email();
+        let attachments = [get_attachment()];
+        let function = || create_message(&email, &attachments);
         let result = with_env_args(args, function);
 
         assert!(result.is_ok());
@@ -166,6 +209,18 @@ mod tests {
             BodyPart::Binary(_) => panic!("Unexpected binary part"),
             BodyPart::Multipart(_) => panic!("Unexpected multipart part"),
         };
+        match result.clone().html_body.unwrap().contents {
+            BodyPart::Text(html) => assert_eq!(TEST_HTML_BODY, html),
+            BodyPart::Binary(_) => panic!("Unexpected binary part"),
+            BodyPart::Multipart(_) => panic!("Unexpected multipart part"),
+        };
+        let attachments = result.attachments.unwrap();
+        assert_eq!(1, attachments.len());
+        match &attachments[0].contents {
+            BodyPart::Binary(content) => assert_eq!(b"%PDF-1.4", content.as_ref()),
+            BodyPart::Text(_) => panic!("Unexpected text part"),
+            BodyPart::Multipart(_) => panic!("Unexpected multipart part"),
+        };
     }
 
     #[parameterized(
@@ -181,7 +236,8 @@ mod tests {
         }
     )]
     fn should_fail_to_create_message(args: Vec<String>, expected_error: Error) {
-        let function = || create_message(TEST_RECIPIENTS, TEST_SUBJECT, TEST_TEXT_BODY);
+        let email = get_email();
+        let function = || create_message(&email, &[]);
         let result = with_env_args(args, function);
 
         let error = result.unwrap_err();
@@ -192,16 +248,16 @@ mod tests {
     // region Retrieve args
     #[parameterized(
         args = {
-            vec![format!("{SMTP_SERVER_ARG}={TEST_SMTP_SERVER}")],
-            vec![format!("{SMTP_PORT_ARG}={TEST_SMTP_PORT}")],
+            vec![format!("{EMAIL_TRANSPORT_ARG}=mbox")],
+            vec![format!("{EMAIL_MBOX_PATH_ARG}=/tmp/test.mbox")],
         },
         function = {
-            &retrieve_smtp_server,
-            & || retrieve_smtp_port().to_string(),
+            &retrieve_email_transport,
+            & || retrieve_email_mbox_path().to_string_lossy().into_owned(),
         },
         expected_result = {
-            TEST_SMTP_SERVER.to_owned(),
-            TEST_SMTP_PORT.to_string(),
+            "mbox".to_owned(),
+            "/tmp/test.mbox".to_owned(),
         }
     )]
     fn should_retrieve_optional_arg(
@@ -220,12 +276,12 @@ mod tests {
             vec![],
         },
         function = {
-            &retrieve_smtp_server,
-            & || retrieve_smtp_port().to_string(),
+            &retrieve_email_transport,
+            & || retrieve_email_mbox_path().to_string_lossy().into_owned(),
         },
         expected_result = {
-            DEFAULT_SMTP_SERVER.to_owned(),
-            DEFAULT_SMTP_PORT.to_string(),
+            DEFAULT_EMAIL_TRANSPORT.to_owned(),
+            DEFAULT_EMAIL_MBOX_PATH.to_owned(),
         }
     )]
     fn should_retrieve_default_value_for_optional_arg(
@@ -0,0 +1,159 @@
+use crate::tools::email::Error::{
+    CantConnectToSmtpServer, CantSendMessage, MessageRejected, MissingSmtpLogin,
+    MissingSmtpPassword,
+};
+use crate::tools::email::{EmailTransport, Result};
+use crate::tools::env_args::{retrieve_arg_value, retrieve_expected_arg_value};
+use crate::tools::log_message_and_return;
+use mail_send::SmtpClientBuilder;
+use mail_send::mail_builder::MessageBuilder;
+
+const SMTP_SERVER_ARG: &str = "--smtp-server";
+const SMTP_PORT_ARG: &str = "--smtp-port";
+const SMTP_LOGIN_ARG: &str = "--smtp-login";
+const SMTP_PASSWORD_ARG: &str = "--smtp-password";
+const DEFAULT_SMTP_SERVER: &str = "smtp.gmail.com";
+const DEFAULT_SMTP_PORT: u16 = 587;
+
+/// Send messages through the SMTP server given as args.
+pub struct SmtpTransport;
+
+#[rocket::async_trait]
+impl EmailTransport for SmtpTransport {
+    /// Send each message over a single connection to the SMTP server.
+    /// An error is only returned if none of them can be sent, e.g. when the SMTP server can't be reached.
+    async fn send(&self, messages: Vec<MessageBuilder<'_>>) -> Result<Vec<Result<()>>> {
+        let smtp_server = retrieve_smtp_server();
+        let smtp_port = retrieve_smtp_port();
+        let smtp_login = retrieve_smtp_login()?;
+        let smtp_password = retrieve_smtp_password()?;
+        let mut smtp_client = SmtpClientBuilder::new(smtp_server, smtp_port)
+            .implicit_tls(false)
+            .credentials((smtp_login, smtp_password))
+            .connect()
+            .await
+            .map_err(log_message_and_return(
+                "Couldn't connect to SMTP server",
+                CantConnectToSmtpServer,
+            ))?;
+
+        let mut results = vec![];
+        for message in messages {
+            let result = smtp_client.send(message).await.map_err(|error| {
+                error!("Couldn't send message\n{error:#?}");
+                match error {
+                    // 5xx replies are permanent failures, sending the message again would fail the same way.
+                    mail_send::Error::UnexpectedReply(response) if response.code() >= 500 => {
+                        MessageRejected
+                    }
+                    _ => CantSendMessage,
+                }
+            });
+            results.push(result);
+        }
+        Ok(results)
+    }
+}
+
+// region Retrieve args
+fn retrieve_smtp_server() -> String {
+    retrieve_arg_value(SMTP_SERVER_ARG).unwrap_or(DEFAULT_SMTP_SERVER.to_owned())
+}
+fn retrieve_smtp_port() -> u16 {
+    retrieve_arg_value(SMTP_PORT_ARG)
+        .and_then(|port| port.parse::<u16>().ok())
+        .unwrap_or(DEFAULT_SMTP_PORT)
+}
+fn retrieve_smtp_login() -> Result<String> {
+    retrieve_expected_arg_value(SMTP_LOGIN_ARG, MissingSmtpLogin)
+}
+
+fn retrieve_smtp_password() -> Result<String> {
+    retrieve_expected_arg_value(SMTP_PASSWORD_ARG, MissingSmtpPassword)
+}
+// endregion
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::email::create_message;
+    use crate::tools::email::tests::{get_attachment, get_email, get_sender_args};
+    use crate::tools::env_args::with_env_args;
+    use parameterized::{ide, parameterized};
+    use rocket::futures::executor::block_on;
+
+    ide!();
+
+    const TEST_SMTP_SERVER: &str = "sandbox.smtp.mailtrap.io";
+    const TEST_SMTP_PORT: u16 = 25;
+
+    // region send
+    #[async_test]
+    #[ignore]
+    async fn should_send_email() {
+        let mut args = vec![
+            format!("{SMTP_SERVER_ARG}={TEST_SMTP_SERVER}"),
+            format!("{SMTP_PORT_ARG}={TEST_SMTP_PORT}"),
+        ];
+        args.extend(get_sender_args());
+        let email = get_email();
+        let attachments = [get_attachment()];
+        let results = with_env_args(args, || {
+            let message = create_message(&email, &attachments).unwrap();
+            block_on(SmtpTransport.send(vec![message]))
+        })
+        .unwrap();
+        assert_eq!(vec![Ok(())], results);
+    }
+    // endregion
+
+    // region Retrieve args
+    #[parameterized(
+        args = {
+            vec![format!("{SMTP_SERVER_ARG}={TEST_SMTP_SERVER}")],
+            vec![format!("{SMTP_PORT_ARG}={TEST_SMTP_PORT}")],
+        },
+        function = {
+            &retrieve_smtp_server,
+            & || retrieve_smtp_port().to_string(),
+        },
+        expected_result = {
+            TEST_SMTP_SERVER.to_owned(),
+            TEST_SMTP_PORT.to_string(),
+        }
+    )]
+    fn should_retrieve_optional_arg(
+        args: Vec<String>,
+        function: &dyn Fn() -> String,
+        expected_result: String,
+    ) {
+        let result = with_env_args(args, function);
+
+        assert_eq!(expected_result, result);
+    }
+
+    #[parameterized(
+        args = {
+            vec![],
+            vec![],
+        },
+        function = {
+            &retrieve_smtp_server,
+            & || retrieve_smtp_port().to_string(),
+        },
+        expected_result = {
+            DEFAULT_SMTP_SERVER.to_owned(),
+            DEFAULT_SMTP_PORT.to_string(),
+        }
+    )]
+    fn should_retrieve_default_value_for_optional_arg(
+        args: Vec<String>,
+        function: &dyn Fn() -> String,
+        expected_result: String,
+    ) {
+        let result = with_env_args(args, function);
+
+        assert_eq!(expected_result, result);
+    }
+    // endregion
+}
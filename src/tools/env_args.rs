@@ -61,6 +61,11 @@ pub fn retrieve_expected_arg_value<E>(arg_name: &str, error_if_missing: E) -> Re
     retrieve_arg_value(arg_name).ok_or(error_if_missing)
 }
 
+/// Whether a flag or a subcommand has been passed to the app, e.g. `--dry-run`.
+pub fn has_arg(arg_name: &str) -> bool {
+    get_env_args().iter().any(|arg| arg == arg_name)
+}
+
 #[cfg(not(test))]
 fn get_env_args() -> Vec<String> {
     env::args().collect()
@@ -120,7 +125,9 @@ where
 pub mod tests {
     use parameterized::{ide, parameterized};
 
-    use crate::tools::env_args::{retrieve_arg_value, retrieve_expected_arg_value, with_env_args};
+    use crate::tools::env_args::{
+        has_arg, retrieve_arg_value, retrieve_expected_arg_value, with_env_args,
+    };
 
     ide!();
 
@@ -159,4 +166,18 @@ pub mod tests {
 
         assert_eq!(error, result);
     }
+
+    #[test]
+    fn should_have_arg() {
+        let args = vec!["--dry-run".to_owned()];
+
+        assert!(with_env_args(args, || has_arg("--dry-run")));
+    }
+
+    #[test]
+    fn should_not_have_arg() {
+        let args = vec!["--dry-run=false".to_owned()];
+
+        assert!(!with_env_args(args, || has_arg("--dry-run")));
+    }
 }
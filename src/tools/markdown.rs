@@ -0,0 +1,89 @@
+use pulldown_cmark::{CowStr, Event, Parser, Tag, TagEnd, html};
+
+/// Convert Markdown to HTML, e.g. for the body of an email.
+/// Single line breaks are kept, as organizers expect the email to look like what they typed.
+/// Raw HTML is escaped rather than rendered, so that it can't be injected through template variables.
+pub fn markdown_to_html(markdown: &str) -> String {
+    let events = Parser::new(markdown).map(|event| match event {
+        Event::SoftBreak => Event::HardBreak,
+        Event::Html(html) | Event::InlineHtml(html) => Event::Text(html),
+        event => event,
+    });
+
+    let mut html = String::new();
+    html::push_html(&mut html, events);
+    html
+}
+
+/// Convert Markdown to plain text, e.g. for the alternative to the HTML body of an email.
+/// Formatting is dropped, and the URLs of links are written after their text.
+pub fn markdown_to_text(markdown: &str) -> String {
+    let mut text = String::new();
+    // URLs of the links being written, with the position of their text
+    let mut links: Vec<(CowStr, usize)> = vec![];
+    for event in Parser::new(markdown) {
+        match event {
+            Event::Text(content)
+            | Event::Code(content)
+            | Event::Html(content)
+            | Event::InlineHtml(content) => text.push_str(&content),
+            Event::SoftBreak | Event::HardBreak => text.push('\n'),
+            Event::Rule => text.push_str("---\n\n"),
+            Event::Start(Tag::Item) => text.push_str("- "),
+            Event::Start(Tag::Link { dest_url, .. }) => links.push((dest_url, text.len())),
+            Event::End(TagEnd::Link) => {
+                if let Some((url, start)) = links.pop() {
+                    // Autolinks are already written as their URL
+                    if &text[start..] != url.as_ref() {
+                        text.push_str(&format!(" ({url})"));
+                    }
+                }
+            }
+            Event::End(TagEnd::Item) => text.push('\n'),
+            Event::End(
+                TagEnd::Paragraph | TagEnd::Heading(_) | TagEnd::List(_) | TagEnd::CodeBlock,
+            ) if !text.ends_with("\n\n") => {
+                text.push_str(if text.ends_with('\n') { "\n" } else { "\n\n" });
+            }
+            _ => {}
+        }
+    }
+
+    text.trim_end().to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    mod markdown_to_html {
+        use crate::tools::markdown::markdown_to_html;
+
+        #[test]
+        fn success() {
+            assert_eq!(
+                "<p>Bonjour <strong>Jon</strong>,<br />\nà bientôt</p>\n",
+                markdown_to_html("Bonjour **Jon**,\nà bientôt")
+            );
+        }
+
+        #[test]
+        fn should_escape_html() {
+            assert_eq!(
+                "<p>Bonjour &lt;b&gt;Jon&lt;/b&gt;</p>\n",
+                markdown_to_html("Bonjour <b>Jon</b>")
+            );
+        }
+    }
+
+    mod markdown_to_text {
+        use crate::tools::markdown::markdown_to_text;
+
+        #[test]
+        fn success() {
+            let markdown = "# Licence\n\nBonjour **Jon**,\nvoir [le site](https://monocycle.info) :\n\n- un\n- deux\n\n<https://monocycle.info>";
+            assert_eq!(
+                "Licence\n\nBonjour Jon,\nvoir le site (https://monocycle.info) :\n\n- un\n- deux\n\nhttps://monocycle.info",
+                markdown_to_text(markdown)
+            );
+        }
+    }
+}
@@ -1,5 +1,7 @@
 pub mod email;
 pub mod env_args;
+pub mod markdown;
+pub mod pdf;
 pub mod test;
 pub mod web;
 
@@ -43,9 +45,39 @@ pub fn normalize(string: &str) -> String {
     }
 }
 
+/// Compute how similar two strings are, as a percentage.
+/// It is based on the Levenshtein distance between both strings, relative to the longest one.
+/// Empty strings are never considered similar to anything.
+pub fn similarity(left: &str, right: &str) -> u8 {
+    let left = left.chars().collect::<Vec<_>>();
+    let right = right.chars().collect::<Vec<_>>();
+    if left.is_empty() || right.is_empty() {
+        return 0;
+    }
+
+    let mut previous_row = (0..=right.len()).collect::<Vec<_>>();
+    for (i, left_char) in left.iter().enumerate() {
+        let mut current_row = vec![i + 1; right.len() + 1];
+        for (j, right_char) in right.iter().enumerate() {
+            let substitution_cost = if left_char == right_char { 0 } else { 1 };
+            current_row[j + 1] = (previous_row[j] + substitution_cost)
+                .min(previous_row[j + 1] + 1)
+                .min(current_row[j] + 1);
+        }
+        previous_row = current_row;
+    }
+    let distance = previous_row[right.len()];
+    let max_length = left.len().max(right.len());
+
+    ((max_length - distance) * 100 / max_length) as u8
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::tools::{log_error_and_return, log_message, log_message_and_return};
+    use crate::tools::{log_error_and_return, log_message, log_message_and_return, similarity};
+    use parameterized::{ide, parameterized};
+
+    ide!();
 
     fn init() {
         let _ = env_logger::builder().is_test(true).try_init();
@@ -80,4 +112,26 @@ mod tests {
 
         assert_eq!(expected_return_value, result);
     }
+
+    #[parameterized(
+        strings = {
+            ("doejon", "doejon"),
+            ("doejon", "doejohn"),
+            ("doejon", "snowjonette"),
+            ("doejon", ""),
+            ("", ""),
+        },
+        expected_result = {
+            100,
+            85,
+            36,
+            0,
+            0,
+        }
+    )]
+    fn should_compute_similarity(strings: (&str, &str), expected_result: u8) {
+        let (left, right) = strings;
+        assert_eq!(expected_result, similarity(left, right));
+        assert_eq!(expected_result, similarity(right, left));
+    }
 }
@@ -0,0 +1,199 @@
+use encoding::all::WINDOWS_1252;
+use encoding::{EncoderTrap, Encoding};
+
+/// A4 landscape, in points.
+const PAGE_WIDTH: f32 = 842.0;
+const PAGE_HEIGHT: f32 = 595.0;
+const MARGIN: f32 = 40.0;
+
+/// A piece of text to write at a given position of a page, from its bottom-left corner.
+struct Text {
+    x: f32,
+    y: f32,
+    size: f32,
+    bold: bool,
+    content: String,
+}
+
+/// A bare PDF writer, only able to write text with the standard Helvetica fonts.
+/// It is enough for printable documents, and saves us from embedding fonts.
+/// Text is written top to bottom, and pages are added whenever the current one is full.
+pub struct PdfWriter {
+    pages: Vec<Vec<Text>>,
+    /// Vertical position of the next line on the current page.
+    y: f32,
+}
+
+impl Default for PdfWriter {
+    fn default() -> Self {
+        Self {
+            pages: vec![vec![]],
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+}
+
+impl PdfWriter {
+    /// Write a line of text, made of cells starting at the given horizontal offsets.
+    pub fn write_line(&mut self, cells: &[(f32, &str)], size: f32, bold: bool) {
+        let line_height = size * 1.4;
+        if self.y - line_height < MARGIN {
+            self.pages.push(vec![]);
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+        self.y -= line_height;
+
+        let y = self.y;
+        let page = self.pages.last_mut().expect("There is always a page");
+        for (x, content) in cells {
+            page.push(Text {
+                x: MARGIN + x,
+                y,
+                size,
+                bold,
+                content: (*content).to_owned(),
+            });
+        }
+    }
+
+    pub fn skip_line(&mut self, size: f32) {
+        self.y -= size * 1.4;
+    }
+
+    /// Build the PDF document.
+    /// Characters Windows-1252 can't encode are replaced by `?`.
+    pub fn build(self) -> Vec<u8> {
+        // Objects 1 to 4 are the catalog, the pages tree and both fonts.
+        // Each page is then made of the page itself, followed by its content.
+        let page_count = self.pages.len();
+        let mut objects: Vec<Vec<u8>> = vec![
+            b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {page_count} >>",
+                (0..page_count)
+                    .map(|index| format!("{} 0 R", 5 + index * 2))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            )
+            .into_bytes(),
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+                .to_vec(),
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+                .to_vec(),
+        ];
+        for (index, page) in self.pages.iter().enumerate() {
+            objects.push(
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                    6 + index * 2
+                )
+                .into_bytes(),
+            );
+            let content = build_page_content(page);
+            let mut stream = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+            stream.extend(content);
+            stream.extend(b"\nendstream");
+            objects.push(stream);
+        }
+
+        let mut document = b"%PDF-1.4\n".to_vec();
+        let mut offsets = vec![];
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(document.len());
+            document.extend(format!("{} 0 obj\n", index + 1).into_bytes());
+            document.extend(object);
+            document.extend(b"\nendobj\n");
+        }
+        let xref_offset = document.len();
+        document
+            .extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).into_bytes());
+        for offset in offsets {
+            document.extend(format!("{offset:010} 00000 n \n").into_bytes());
+        }
+        document.extend(
+            format!(
+                "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref_offset}\n%%EOF\n",
+                objects.len() + 1
+            )
+            .into_bytes(),
+        );
+        document
+    }
+}
+
+fn build_page_content(page: &[Text]) -> Vec<u8> {
+    let mut content = vec![];
+    for text in page {
+        let font = if text.bold { "F2" } else { "F1" };
+        content
+            .extend(format!("BT /{font} {} Tf {} {} Td (", text.size, text.x, text.y).into_bytes());
+        content.extend(escape(&text.content));
+        content.extend(b") Tj ET\n");
+    }
+    content
+}
+
+/// Encode the text as Helvetica expects it, and escape the characters delimiting PDF strings.
+fn escape(text: &str) -> Vec<u8> {
+    let encoded = WINDOWS_1252
+        .encode(text, EncoderTrap::Replace)
+        .unwrap_or_default();
+    let mut escaped = vec![];
+    for byte in encoded {
+        if matches!(byte, b'\\' | b'(' | b')') {
+            escaped.push(b'\\');
+        }
+        escaped.push(byte);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    mod build {
+        use crate::tools::pdf::PdfWriter;
+
+        fn contains(document: &[u8], expected: &[u8]) -> bool {
+            document
+                .windows(expected.len())
+                .any(|window| window == expected)
+        }
+
+        #[test]
+        fn success() {
+            let mut writer = PdfWriter::default();
+            writer.write_line(&[(0.0, "Prénom"), (100.0, "Nom (usage)")], 10.0, true);
+
+            let document = writer.build();
+            assert!(document.starts_with(b"%PDF-1.4\n"));
+            assert!(document.ends_with(b"%%EOF\n"));
+            // `é` is encoded as 0xE9 in Windows-1252
+            assert!(contains(&document, b"(Pr\xE9nom) Tj"));
+            assert!(contains(&document, b"(Nom \\(usage\\)) Tj"));
+            assert!(contains(&document, b"/Count 1"));
+        }
+
+        #[test]
+        fn should_add_pages_when_full() {
+            let mut writer = PdfWriter::default();
+            for _ in 0..100 {
+                writer.write_line(&[(0.0, "Line")], 10.0, false);
+            }
+
+            let document = writer.build();
+            assert!(contains(&document, b"/Count 3"));
+        }
+
+        #[test]
+        fn should_reference_objects_at_their_offset() {
+            let document = PdfWriter::default().build();
+
+            let text = String::from_utf8_lossy(&document);
+            let xref = text.split("xref\n").nth(1).unwrap();
+            for (index, line) in xref.lines().skip(2).take(6).enumerate() {
+                let offset: usize = line[..10].parse().unwrap();
+                assert!(text[offset..].starts_with(&format!("{} 0 obj", index + 1)));
+            }
+        }
+    }
+}
@@ -1,7 +1,15 @@
-use crate::tools::log_message_and_return;
+use crate::tools::{log_error_and_return, log_message_and_return};
 use crate::web::error::WebError;
 use crate::web::error::WebError::CantCreateClient;
-use reqwest::Client;
+use reqwest::cookie::{CookieStore, Jar};
+use reqwest::{Client, Url};
+use rocket::data::{Data, ToByteUnit};
+use rocket::http::Status;
+use rocket::serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+const MAX_UPLOADED_FILE_SIZE_IN_MEBIBYTES: u64 = 10;
 
 pub fn build_client() -> Result<Client, WebError> {
     reqwest::ClientBuilder::new()
@@ -12,3 +20,140 @@ pub fn build_client() -> Result<Client, WebError> {
             CantCreateClient,
         ))
 }
+
+/// Build a client whose cookies can be read afterward,
+/// e.g. to keep the session opened by logging in to another server.
+pub fn build_client_with_cookie_jar() -> Result<(Client, Arc<Jar>), WebError> {
+    let jar = Arc::new(Jar::default());
+    let client = reqwest::ClientBuilder::new()
+        .cookie_provider(Arc::clone(&jar))
+        .build()
+        .map_err(log_message_and_return(
+            "Can't build HTTP client.",
+            CantCreateClient,
+        ))?;
+    Ok((client, jar))
+}
+
+/// Build a client sending the cookies of a session previously opened on the server at `url`.
+pub fn build_client_resuming_session(
+    url: &str,
+    session_cookies: &SessionCookies,
+) -> Result<Client, WebError> {
+    let url = parse_url(url)?;
+    let jar = Jar::default();
+    session_cookies
+        .0
+        .split("; ")
+        .filter(|cookie| !cookie.is_empty())
+        .for_each(|cookie| jar.add_cookie_str(cookie, &url));
+    reqwest::ClientBuilder::new()
+        .cookie_provider(Arc::new(jar))
+        .build()
+        .map_err(log_message_and_return(
+            "Can't build HTTP client.",
+            CantCreateClient,
+        ))
+}
+
+/// Cookies of a session opened on another server, such as Fileo or UDA.
+/// They are kept instead of the password of the user, until the other server makes them expire.
+#[derive(Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct SessionCookies(String);
+
+impl SessionCookies {
+    /// Retrieve the cookies the jar would send to `url`.
+    pub fn from_jar(jar: &Jar, url: &str) -> Result<Self, WebError> {
+        let url = parse_url(url)?;
+        let cookies = jar
+            .cookies(&url)
+            .and_then(|header| header.to_str().map(str::to_owned).ok())
+            .unwrap_or_default();
+        Ok(Self(cookies))
+    }
+}
+
+impl Debug for SessionCookies {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Session Cookies {{MASKED}}")
+    }
+}
+
+#[cfg(test)]
+impl SessionCookies {
+    pub fn new(cookies: &str) -> Self {
+        Self(cookies.to_owned())
+    }
+}
+
+fn parse_url(url: &str) -> Result<Url, WebError> {
+    Url::parse(url).map_err(log_message_and_return(
+        "Can't parse URL of the other server.",
+        CantCreateClient,
+    ))
+}
+
+/// Read a file uploaded as the body of a request.
+/// Files larger than 10 MiB are refused.
+pub async fn read_uploaded_file(file: Data<'_>) -> Result<Vec<u8>, Status> {
+    let file_content = file
+        .open(MAX_UPLOADED_FILE_SIZE_IN_MEBIBYTES.mebibytes())
+        .into_bytes()
+        .await
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    if !file_content.is_complete() {
+        return Err(Status::PayloadTooLarge);
+    }
+
+    Ok(file_content.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    mod session_cookies {
+        use crate::tools::web::{SessionCookies, build_client_resuming_session};
+        use reqwest::Url;
+        use reqwest::cookie::Jar;
+        use wiremock::matchers::{header_regex, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        #[test]
+        fn from_jar() {
+            let jar = Jar::default();
+            let url = Url::parse("https://test.reg.unicycling-software.com").unwrap();
+            jar.add_cookie_str("_session_id=1234; Path=/", &url);
+            jar.add_cookie_str("locale=fr; Path=/", &url);
+
+            let cookies =
+                SessionCookies::from_jar(&jar, "https://test.reg.unicycling-software.com").unwrap();
+            assert!(cookies.0.contains("_session_id=1234"));
+            assert!(cookies.0.contains("locale=fr"));
+
+            let cookies = SessionCookies::from_jar(&jar, "https://other.com").unwrap();
+            assert_eq!(SessionCookies::default(), cookies);
+        }
+
+        #[test]
+        fn masked_when_debugged() {
+            let cookies = SessionCookies::new("_session_id=1234");
+            assert!(!format!("{cookies:?}").contains("1234"));
+        }
+
+        #[async_test]
+        async fn resume_session() {
+            let mock_server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .and(path("/"))
+                .and(header_regex("Cookie", "_session_id=1234"))
+                .and(header_regex("Cookie", "locale=fr"))
+                .respond_with(ResponseTemplate::new(200))
+                .mount(&mock_server)
+                .await;
+
+            let cookies = SessionCookies::new("_session_id=1234; locale=fr");
+            let client = build_client_resuming_session(&mock_server.uri(), &cookies).unwrap();
+            let response = client.get(mock_server.uri()).send().await.unwrap();
+            assert_eq!(200, response.status().as_u16());
+        }
+    }
+}
@@ -1,18 +1,18 @@
-use crate::uda::credentials::UdaCredentials;
+use crate::uda::session::UdaSession;
 use crate::web::authentication;
 use rocket::request::FromRequest;
 use rocket::{Request, request};
 
 pub const AUTHENTICATION_COOKIE: &str = "UDA-Authentication";
 
-/// If an endpoint requires UDA credentials to be called,
-/// then its implementation should require a [UdaCredentials] parameter.
-/// Rocket will summon this guard to ensure such credentials exists.
+/// If an endpoint requires the user to be logged in UDA to be called,
+/// then its implementation should require a [UdaSession] parameter.
+/// Rocket will summon this guard to ensure such a session exists.
 /// If it doesn't, then the caller receives an Unauthorized status.
 ///
 /// Currently, such authentication is passed from the caller to the server using a `UDA-Authentication` private cookie.
 #[rocket::async_trait]
-impl<'r> FromRequest<'r> for UdaCredentials {
+impl<'r> FromRequest<'r> for UdaSession {
     type Error = ();
 
     async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
@@ -22,8 +22,9 @@ impl<'r> FromRequest<'r> for UdaCredentials {
 
 #[cfg(test)]
 mod tests {
+    use crate::tools::web::SessionCookies;
     use crate::uda::authentication::AUTHENTICATION_COOKIE;
-    use crate::uda::credentials::UdaCredentials;
+    use crate::uda::session::UdaSession;
     use crate::web::credentials_storage::CredentialsStorage;
     use rocket::http::{Cookie, Status};
     use rocket::local::asynchronous::Client;
@@ -32,14 +33,16 @@ mod tests {
 
     #[async_test]
     async fn should_uda_request_succeed() {
-        let credentials = UdaCredentials::new(
+        let credentials = UdaSession::new(
             "https://convention.reg.unicycling-software.com".to_owned(),
             "test_login".to_owned(),
-            "test_password".to_owned(),
+            SessionCookies::default(),
         );
         let mut credentials_storage = CredentialsStorage::default();
         let uuid = "0ea9a5fb-0f46-4057-902a-2552ed956bde".to_owned();
-        credentials_storage.store(uuid.clone(), credentials.clone());
+        credentials_storage
+            .store(uuid.clone(), credentials.clone())
+            .unwrap();
         let credentials_storage_mutex = Mutex::new(credentials_storage);
 
         let rocket = rocket::build().manage(credentials_storage_mutex);
@@ -47,13 +50,13 @@ mod tests {
         let cookie = Cookie::new(AUTHENTICATION_COOKIE, uuid);
         let request = client.get("http://localhost").cookie(cookie.clone());
 
-        let outcome = UdaCredentials::from_request(&request).await;
+        let outcome = UdaSession::from_request(&request).await;
         assert!(outcome.is_success());
         assert_eq!(credentials, outcome.succeeded().unwrap());
     }
     #[async_test]
     async fn should_uda_request_fail_when_no_matching_credentials() {
-        let credentials_storage = CredentialsStorage::<UdaCredentials>::default();
+        let credentials_storage = CredentialsStorage::<UdaSession>::default();
         let credentials_uuid = "0ea9a5fb-0f46-4057-902a-2552ed956bde".to_owned();
         let credentials_storage_mutex = Mutex::new(credentials_storage);
 
@@ -62,21 +65,21 @@ mod tests {
         let cookie = Cookie::new(AUTHENTICATION_COOKIE, credentials_uuid);
         let request = client.get("http://localhost").cookie(cookie);
 
-        let outcome = UdaCredentials::from_request(&request).await;
+        let outcome = UdaSession::from_request(&request).await;
         assert!(outcome.is_forward());
         assert_eq!(Status::Unauthorized, outcome.forwarded().unwrap());
     }
 
     #[async_test]
     async fn should_uda_request_fail_when_no_header() {
-        let credentials_storage = CredentialsStorage::<UdaCredentials>::default();
+        let credentials_storage = CredentialsStorage::<UdaSession>::default();
         let credentials_storage_mutex = Mutex::new(credentials_storage);
 
         let rocket = rocket::build().manage(credentials_storage_mutex);
         let client = Client::tracked(rocket).await.unwrap();
         let request = client.get("http://localhost");
 
-        let outcome = UdaCredentials::from_request(&request).await;
+        let outcome = UdaSession::from_request(&request).await;
         assert!(outcome.is_forward());
         assert_eq!(Status::Unauthorized, outcome.forwarded().unwrap());
     }
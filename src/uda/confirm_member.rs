@@ -2,7 +2,10 @@ use crate::error::{ApplicationError, Result};
 use crate::tools::{log_error_and_return, log_message_and_return};
 use crate::uda::error::UdaError;
 use crate::uda::error::UdaError::{MemberConfirmationFailed, OrganizationMembershipsAccessFailed};
-use crate::web::error::WebError::{CantReadPageContent, ConnectionFailed, LackOfPermissions};
+use crate::uda::login::is_redirected_to_sign_in;
+use crate::web::error::WebError::{
+    CantReadPageContent, ConnectionFailed, LackOfPermissions, SessionExpired,
+};
 use reqwest::{Client, StatusCode};
 use rocket::form::validate::Contains;
 use scraper::{Html, Selector};
@@ -85,6 +88,11 @@ async fn get_csrf_token(client: &Client, base_url: &str) -> Result<String> {
         .await
         .map_err(log_error_and_return(OrganizationMembershipsAccessFailed))?;
 
+    if is_redirected_to_sign_in(&response) {
+        debug!("UDA session has expired [uda_url: {base_url}]");
+        return Err(ApplicationError::from(SessionExpired));
+    }
+
     let status = response.status();
     if status.is_success() {
         let body = response.text().await.map_err(log_message_and_return(
@@ -372,6 +380,22 @@ I'm sorry, but the page you are searching for was not found.
             .unwrap_err();
         assert!(matches!(error, Web(LackOfPermissions)));
     }
+
+    #[async_test]
+    async fn should_fail_to_get_csrf_token_when_session_has_expired() {
+        let mock_server = MockServer::start().await;
+        let client = build_client().unwrap();
+        crate::uda::login::tests::setup_expired_session(
+            &mock_server,
+            "/en/organization_memberships",
+        )
+        .await;
+
+        let error = get_csrf_token(&client, &mock_server.uri())
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Web(SessionExpired)));
+    }
     // endregion
 
     //region retrieve_csrf_from_html
@@ -2,6 +2,8 @@ use derive_getters::Getters;
 use rocket::serde::{Deserialize, Serialize};
 use std::fmt::{Debug, Formatter};
 
+/// Login and password of a UDA user, only used to log in.
+/// Once logged in, a [UdaSession](crate::uda::session::UdaSession) is kept instead.
 #[derive(Serialize, Deserialize, Getters, PartialEq, Clone, Default)]
 pub struct UdaCredentials {
     /// Should be something like `https://cfm2019training.reg.unicycling-software.com`
@@ -29,7 +31,7 @@ impl Debug for UdaCredentials {
         write!(
             f,
             "Uda Credentials {{uda={}, login={}, password=MASKED}}",
-            self.password, self.login
+            self.uda_url, self.login
         )
     }
 }
@@ -44,3 +46,22 @@ impl UdaCredentials {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    mod debug {
+        use crate::uda::credentials::UdaCredentials;
+
+        #[test]
+        fn mask_password() {
+            let credentials = UdaCredentials::new(
+                "https://cfm2019training.reg.unicycling-software.com".to_owned(),
+                "login".to_owned(),
+                "secret".to_owned(),
+            );
+            let debugged = format!("{credentials:?}");
+            assert!(debugged.contains("https://cfm2019training.reg.unicycling-software.com"));
+            assert!(!debugged.contains("secret"));
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use chrono::NaiveDate;
 use derive_getters::Getters;
 use dto::uda_member::UdaMember;
 use serde::{Deserialize, Serialize};
@@ -36,8 +37,19 @@ pub struct ImportedUdaMember {
     confirmed: bool,
 }
 
+/// Birthdays are formatted as `dd.mm.yyyy` in UDA's exports.
+const BIRTHDAY_FORMAT: &str = "%d.%m.%Y";
+
 impl From<ImportedUdaMember> for UdaMember {
     fn from(imported_member: ImportedUdaMember) -> Self {
+        let birthdate = NaiveDate::parse_from_str(&imported_member.birthday, BIRTHDAY_FORMAT)
+            .inspect_err(|error| {
+                warn!(
+                    "Can't parse birthday of UDA member. Ignoring it. [id: {}, error: {:?}]",
+                    imported_member.id, error
+                )
+            })
+            .ok();
         UdaMember::new(
             imported_member.id,
             imported_member
@@ -45,6 +57,7 @@ impl From<ImportedUdaMember> for UdaMember {
                 .or(imported_member.system_organization_membership),
             imported_member.first_name,
             imported_member.last_name,
+            birthdate,
             imported_member.email,
             imported_member.club,
             imported_member.confirmed,
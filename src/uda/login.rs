@@ -1,7 +1,7 @@
 use crate::error::{ApplicationError, Result};
 use crate::tools::{log_error_and_return, log_message_and_return};
 use crate::web::error::WebError::{ConnectionFailed, WrongCredentials};
-use reqwest::Client;
+use reqwest::{Client, Response};
 use scraper::{Html, Selector};
 
 /// Log into UDA and makes given client able to request pages that require authentication.
@@ -22,6 +22,11 @@ pub async fn authenticate_into_uda(
         )))
 }
 
+/// Once the session has expired, UDA redirects any page requiring authentication to its sign-in page.
+pub(crate) fn is_redirected_to_sign_in(response: &Response) -> bool {
+    response.url().path().ends_with("/users/sign_in")
+}
+
 async fn get_authenticity_token(client: &Client, base_url: &str) -> Result<String> {
     let url = format!("{base_url}/en/users/sign_in");
     let response = client
@@ -136,13 +141,30 @@ pub mod tests {
         Mock::given(method("POST"))
             .and(path("/en/users/sign_in"))
             .and(body_string(&params))
-            .respond_with(ResponseTemplate::new(200).set_body_string("Signed in successfully"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("Set-Cookie", "_session_id=1234; Path=/")
+                    .set_body_string("Signed in successfully"),
+            )
             .mount(mock_server)
             .await;
 
         UdaCredentials::new(mock_server.uri(), login.to_owned(), password.to_owned())
     }
 
+    /// Make `page_path` redirect to the sign-in page, as UDA does once the session has expired.
+    pub async fn setup_expired_session(mock_server: &MockServer, page_path: &str) {
+        setup_authenticity_token(mock_server).await;
+        Mock::given(method("GET"))
+            .and(path(page_path))
+            .respond_with(ResponseTemplate::new(302).insert_header(
+                "Location",
+                format!("{}/en/users/sign_in", mock_server.uri()),
+            ))
+            .mount(mock_server)
+            .await;
+    }
+
     pub(crate) async fn setup_authenticity_token(mock_server: &MockServer) -> String {
         let body = format!(
             r#"<html><body><input name="authenticity_token" value="{AUTHENTICITY_TOKEN}"></body></html>"#
@@ -7,3 +7,4 @@ mod imported_uda_member;
 pub mod instances;
 pub mod login;
 pub(crate) mod retrieve_members;
+pub(crate) mod session;
@@ -2,7 +2,8 @@ use crate::error::{ApplicationError, Result};
 use crate::tools::{log_error_and_return, log_message_and_return};
 use crate::uda::error::UdaError::{MalformedXlsFile, OrganizationMembershipsAccessFailed};
 use crate::uda::imported_uda_member::ImportedUdaMember;
-use crate::web::error::WebError::LackOfPermissions;
+use crate::uda::login::is_redirected_to_sign_in;
+use crate::web::error::WebError::{LackOfPermissions, SessionExpired};
 use calamine::{
     Data, RangeDeserializer, RangeDeserializerBuilder, Reader, Xls, open_workbook_from_rs,
 };
@@ -20,6 +21,11 @@ pub async fn retrieve_members(client: &Client, base_url: &str) -> Result<Vec<Uda
         .await
         .map_err(log_error_and_return(OrganizationMembershipsAccessFailed))?;
 
+    if is_redirected_to_sign_in(&response) {
+        debug!("UDA session has expired [uda_url: {base_url}]");
+        return Err(ApplicationError::from(SessionExpired));
+    }
+
     let status = response.status();
     if status.is_success() {
         let body = response.bytes().await.map_err(log_message_and_return(
@@ -88,6 +94,7 @@ fn retrieve_imported_members_from_xls<T: AsRef<[u8]>>(
 
 #[cfg(test)]
 pub mod tests {
+    use chrono::NaiveDate;
     use dto::uda_member::UdaMember;
     use wiremock::matchers::{method, path};
     use wiremock::{Mock, MockServer, ResponseTemplate};
@@ -103,6 +110,7 @@ pub mod tests {
                 Some("123456".to_owned()),
                 "Jon".to_owned(),
                 "Doe".to_owned(),
+                NaiveDate::from_ymd_opt(1983, 2, 1),
                 "jon.doe@email.com".to_owned(),
                 Some("Le club de test".to_owned()),
                 true,
@@ -112,6 +120,7 @@ pub mod tests {
                 Some("654321".to_owned()),
                 "Jonette".to_owned(),
                 "Snow".to_owned(),
+                NaiveDate::from_ymd_opt(1990, 11, 12),
                 "jonette.snow@email.com".to_owned(),
                 None,
                 false,
@@ -121,6 +130,7 @@ pub mod tests {
                 Some("456789".to_owned()),
                 "Kris".to_owned(),
                 "Holm".to_owned(),
+                NaiveDate::from_ymd_opt(1975, 8, 10),
                 "kris.holm@email.com".to_owned(),
                 Some("KH Team".to_owned()),
                 true,
@@ -144,9 +154,10 @@ pub mod tests {
         use crate::error::ApplicationError::{Uda, Web};
         use crate::tools::web::build_client;
         use crate::uda::error::UdaError;
+        use crate::uda::login::tests::setup_expired_session;
         use crate::uda::retrieve_members::retrieve_members;
         use crate::uda::retrieve_members::tests::setup_member_retrieval;
-        use crate::web::error::WebError::LackOfPermissions;
+        use crate::web::error::WebError::{LackOfPermissions, SessionExpired};
         use UdaError::OrganizationMembershipsAccessFailed;
         use wiremock::matchers::{method, path};
         use wiremock::{Mock, MockServer, ResponseTemplate};
@@ -191,6 +202,18 @@ pub mod tests {
                 .unwrap_err();
             assert!(matches!(error, Web(LackOfPermissions)));
         }
+
+        #[async_test]
+        async fn fail_when_session_has_expired() {
+            let mock_server = MockServer::start().await;
+            let client = build_client().unwrap();
+            setup_expired_session(&mock_server, "/en/organization_memberships/export.xls").await;
+
+            let error = retrieve_members(&client, &mock_server.uri())
+                .await
+                .unwrap_err();
+            assert!(matches!(error, Web(SessionExpired)));
+        }
     }
 
     mod retrieve_imported_members_from_xls {
@@ -0,0 +1,63 @@
+use crate::tools::web::SessionCookies;
+use crate::web::credentials_storage::sqlite::StoredCredentials;
+use derive_getters::Getters;
+use rocket::serde::{Deserialize, Serialize};
+
+/// What is kept of a user once logged in a UDA instance:
+/// the session UDA has opened for them, rather than their password.
+#[derive(Serialize, Deserialize, Getters, PartialEq, Clone, Debug)]
+pub struct UdaSession {
+    /// Such as `https://cfm2019training.reg.unicycling-software.com`.
+    #[getter(skip)]
+    uda_url: String,
+    login: String,
+    cookies: SessionCookies,
+}
+
+impl UdaSession {
+    pub fn new(uda_url: String, login: String, cookies: SessionCookies) -> Self {
+        Self {
+            uda_url,
+            login,
+            cookies,
+        }
+    }
+
+    #[cfg(not(feature = "demo"))]
+    pub fn uda_url(&self) -> &String {
+        &self.uda_url
+    }
+
+    #[cfg(feature = "demo")]
+    pub fn uda_url(&self) -> &String {
+        crate::demo_mock_server::UDA_MOCK_SERVER_URI.get().unwrap()
+    }
+
+    /// The slug of the UDA instance, such as `cfm2019training` for `https://cfm2019training.reg.unicycling-software.com`.
+    pub fn instance_slug(&self) -> String {
+        let host = self.uda_url.split("://").last().unwrap_or_default();
+        host.split('.').next().unwrap_or_default().to_owned()
+    }
+}
+
+impl StoredCredentials for UdaSession {
+    const KIND: &'static str = "UDA";
+}
+
+#[cfg(test)]
+mod tests {
+    mod instance_slug {
+        use crate::tools::web::SessionCookies;
+        use crate::uda::session::UdaSession;
+
+        #[test]
+        fn success() {
+            let session = UdaSession::new(
+                "https://cfm2019training.reg.unicycling-software.com".to_owned(),
+                "login".to_owned(),
+                SessionCookies::default(),
+            );
+            assert_eq!("cfm2019training", session.instance_slug());
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use crate::database::dao;
+use crate::error::Result;
+use crate::user::error::UserError;
+use crate::user::error::UserError::{CantHashPassword, InvalidPasswordHash, PasswordTooShort};
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::{Argon2, password_hash};
+use diesel::SqliteConnection;
+use dto::user::{NewUser, User, UserCredentials};
+
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+
+/// Create a local account, only storing the hash of its password.
+/// Return false if another account already has the same login.
+pub fn create_user(connection: &mut SqliteConnection, new_user: &NewUser) -> Result<bool> {
+    let credentials = new_user.credentials();
+    let password_hash = hash_password(credentials.password())?;
+    let created = dao::user_account::insert(
+        connection,
+        credentials.login(),
+        &password_hash,
+        new_user.roles(),
+    )?;
+
+    Ok(created)
+}
+
+/// Return the user matching the credentials, if any.
+pub fn authenticate(
+    connection: &mut SqliteConnection,
+    credentials: &UserCredentials,
+) -> Result<Option<User>> {
+    let Some(password_hash) =
+        dao::user_account::retrieve_password_hash(connection, credentials.login())?
+    else {
+        debug!("Unknown user [login: {}]", credentials.login());
+        return Ok(None);
+    };
+    if !verify_password(credentials.password(), &password_hash)? {
+        debug!("Wrong password [login: {}]", credentials.login());
+        return Ok(None);
+    }
+
+    Ok(dao::user_account::retrieve(
+        connection,
+        credentials.login(),
+    )?)
+}
+
+fn hash_password(password: &str) -> Result<String, UserError> {
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(PasswordTooShort);
+    }
+
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|error| CantHashPassword(error.to_string()))
+}
+
+fn verify_password(password: &str, password_hash: &str) -> Result<bool, UserError> {
+    let password_hash =
+        PasswordHash::new(password_hash).map_err(|error| InvalidPasswordHash(error.to_string()))?;
+    match Argon2::default().verify_password(password.as_bytes(), &password_hash) {
+        Ok(()) => Ok(true),
+        Err(password_hash::Error::Password) => Ok(false),
+        Err(error) => Err(InvalidPasswordHash(error.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod hash_password {
+        use crate::user::account::{hash_password, verify_password};
+        use crate::user::error::UserError::PasswordTooShort;
+
+        #[test]
+        fn success() {
+            let password_hash = hash_password("password").unwrap();
+
+            assert!(!password_hash.contains("password"));
+            assert_eq!(Ok(true), verify_password("password", &password_hash));
+            assert_eq!(Ok(false), verify_password("wrong password", &password_hash));
+        }
+
+        #[test]
+        fn salted() {
+            assert_ne!(
+                hash_password("password").unwrap(),
+                hash_password("password").unwrap()
+            );
+        }
+
+        #[test]
+        fn fail_when_too_short() {
+            assert_eq!(Err(PasswordTooShort), hash_password("short"));
+        }
+    }
+
+    mod authenticate {
+        use crate::database::with_temp_database;
+        use crate::user::account::{authenticate, create_user};
+        use dto::user::Role::Check;
+        use dto::user::{NewUser, User, UserCredentials};
+
+        fn credentials(login: &str, password: &str) -> UserCredentials {
+            UserCredentials::new(login.to_owned(), password.to_owned())
+        }
+
+        #[test]
+        fn success() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                let new_user = NewUser::new(credentials("jon", "password"), vec![Check]);
+                assert!(create_user(&mut connection, &new_user).unwrap());
+
+                assert_eq!(
+                    Some(User::new("jon".to_owned(), vec![Check])),
+                    authenticate(&mut connection, &credentials("jon", "password")).unwrap()
+                );
+            })
+        }
+
+        #[test]
+        fn fail_when_wrong_credentials() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                let new_user = NewUser::new(credentials("jon", "password"), vec![Check]);
+                create_user(&mut connection, &new_user).unwrap();
+
+                assert_eq!(
+                    None,
+                    authenticate(&mut connection, &credentials("jon", "wrong password")).unwrap()
+                );
+                assert_eq!(
+                    None,
+                    authenticate(&mut connection, &credentials("jonette", "password")).unwrap()
+                );
+            })
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use crate::database::dao;
+use crate::error::Result;
+use crate::user::error::UserError::InvalidApiTokenScope;
+use diesel::SqliteConnection;
+use dto::api_token::{API_TOKEN_SCOPES, CreatedApiToken, NewApiToken};
+use dto::user::User;
+use sha2::{Digest, Sha256};
+
+/// Tell tokens apart from other secrets, e.g. when they leak into a repository.
+const TOKEN_PREFIX: &str = "cnm_";
+
+/// Create a token, then return its value, which is the only time it is known in clear.
+/// Tokens are random enough for a plain SHA-256 hash to be enough, unlike passwords.
+pub fn create_api_token(
+    connection: &mut SqliteConnection,
+    new_token: &NewApiToken,
+    created_by: &str,
+) -> Result<CreatedApiToken> {
+    if let Some(scope) = new_token
+        .scopes()
+        .iter()
+        .find(|scope| !API_TOKEN_SCOPES.contains(scope))
+    {
+        return Err(InvalidApiTokenScope(scope.value().to_owned()).into());
+    }
+
+    let token = generate_token();
+    let token_id = dao::api_token::insert(
+        connection,
+        new_token.name(),
+        &hash_token(&token),
+        new_token.scopes(),
+        created_by,
+    )?;
+
+    Ok(CreatedApiToken::new(token_id, token))
+}
+
+/// Return the user a token acts as, if it matches an active token.
+/// Such a user is only granted the scopes of the token.
+pub fn authenticate_api_token(
+    connection: &mut SqliteConnection,
+    token: &str,
+) -> Result<Option<User>> {
+    let api_token = dao::api_token::retrieve_active_by_hash(connection, &hash_token(token))?;
+
+    Ok(api_token.map(|api_token| User::new(api_token.actor(), api_token.scopes().clone())))
+}
+
+fn generate_token() -> String {
+    let bytes: [u8; 32] = rand::random();
+    format!("{TOKEN_PREFIX}{}", to_hex(&bytes))
+}
+
+fn hash_token(token: &str) -> String {
+    to_hex(&Sha256::digest(token.as_bytes()))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    mod create_api_token {
+        use crate::database::with_temp_database;
+        use crate::error::ApplicationError;
+        use crate::user::api_token::{authenticate_api_token, create_api_token};
+        use crate::user::error::UserError::InvalidApiTokenScope;
+        use dto::api_token::NewApiToken;
+        use dto::user::Role::{Admin, Check};
+
+        #[test]
+        fn success() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                let new_token = NewApiToken::new("inscriptions".to_owned(), vec![Check]);
+
+                let first = create_api_token(&mut connection, &new_token, "jon").unwrap();
+                let second = create_api_token(&mut connection, &new_token, "jon").unwrap();
+
+                assert!(first.token().starts_with("cnm_"));
+                assert_eq!(68, first.token().len());
+                assert_ne!(first.token(), second.token());
+                assert!(
+                    authenticate_api_token(&mut connection, first.token())
+                        .unwrap()
+                        .is_some()
+                );
+            })
+        }
+
+        #[test]
+        fn fail_when_scope_is_not_allowed() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                let new_token = NewApiToken::new("admin".to_owned(), vec![Check, Admin]);
+
+                let result = create_api_token(&mut connection, &new_token, "jon");
+                assert!(matches!(
+                    result,
+                    Err(ApplicationError::User(InvalidApiTokenScope(_)))
+                ));
+            })
+        }
+    }
+
+    mod authenticate_api_token {
+        use crate::database::{dao, with_temp_database};
+        use crate::user::api_token::{authenticate_api_token, create_api_token};
+        use dto::api_token::NewApiToken;
+        use dto::user::Role::{Check, LookUp};
+        use dto::user::User;
+
+        #[test]
+        fn success() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                let new_token = NewApiToken::new("inscriptions".to_owned(), vec![Check, LookUp]);
+                let created = create_api_token(&mut connection, &new_token, "jon").unwrap();
+
+                let expected = User::new(
+                    format!("Jeton n°{} (inscriptions)", created.id()),
+                    vec![Check, LookUp],
+                );
+                assert_eq!(
+                    Some(expected),
+                    authenticate_api_token(&mut connection, created.token()).unwrap()
+                );
+            })
+        }
+
+        #[test]
+        fn fail_when_unknown() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+
+                assert_eq!(
+                    None,
+                    authenticate_api_token(&mut connection, "cnm_unknown").unwrap()
+                );
+            })
+        }
+
+        #[test]
+        fn fail_when_revoked() {
+            with_temp_database(|pool| {
+                let mut connection = pool.get().unwrap();
+                let new_token = NewApiToken::new("inscriptions".to_owned(), vec![Check]);
+                let created = create_api_token(&mut connection, &new_token, "jon").unwrap();
+                dao::api_token::revoke(&mut connection, *created.id()).unwrap();
+
+                assert_eq!(
+                    None,
+                    authenticate_api_token(&mut connection, created.token()).unwrap()
+                );
+            })
+        }
+    }
+}
@@ -0,0 +1,382 @@
+use crate::database::dao;
+use crate::database::error::DatabaseError;
+use crate::error::ApplicationError;
+use crate::tools::log_error_and_return;
+use crate::user::api_token::authenticate_api_token;
+use crate::user::session::UserSession;
+use crate::web::authentication;
+use diesel::SqliteConnection;
+use diesel::r2d2::{ConnectionManager, Pool};
+use dto::user::{Role, User};
+use rocket::http::Status;
+use rocket::outcome::{Outcome, try_outcome};
+use rocket::request::FromRequest;
+use rocket::{Request, State, request};
+use std::marker::PhantomData;
+
+pub const AUTHENTICATION_COOKIE: &str = "User-Authentication";
+
+/// If an endpoint requires the user to be logged in, whatever their roles,
+/// then its implementation should require a [UserSession] parameter.
+/// If the user isn't logged in, then the request is forwarded with an Unauthorized status.
+///
+/// Currently, such authentication is passed from the caller to the server using a `User-Authentication` private cookie.
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for UserSession {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        authentication::from_request(req, AUTHENTICATION_COOKIE).await
+    }
+}
+
+/// A role an endpoint requires, see [Authorized].
+pub trait RequiredRole {
+    const ROLE: Role;
+}
+
+pub struct IsAdmin;
+impl RequiredRole for IsAdmin {
+    const ROLE: Role = Role::Admin;
+}
+
+pub struct CanRefreshMemberships;
+impl RequiredRole for CanRefreshMemberships {
+    const ROLE: Role = Role::RefreshMemberships;
+}
+
+pub struct CanCheck;
+impl RequiredRole for CanCheck {
+    const ROLE: Role = Role::Check;
+}
+
+pub struct CanLookUp;
+impl RequiredRole for CanLookUp {
+    const ROLE: Role = Role::LookUp;
+}
+
+pub struct CanEmail;
+impl RequiredRole for CanEmail {
+    const ROLE: Role = Role::Email;
+}
+
+/// A logged-in user who has been granted the role `R`, e.g. `Authorized<CanCheck>`.
+/// Roles are read from the database on each request.
+///
+/// Scripts may rather send an API token as a bearer header, see [authentication::get_bearer_token].
+/// They then act as a user who is only granted the scopes of the token.
+///
+/// If the user isn't logged in, if their account has been deleted since, or if the token is unknown or revoked,
+/// then the request is forwarded with an Unauthorized status, so that they can be sent to the login page.
+/// If they lack the role, then the request fails with a Forbidden status.
+pub struct Authorized<R> {
+    user: User,
+    role: PhantomData<fn() -> R>,
+}
+
+impl<R> Authorized<R> {
+    pub fn login(&self) -> &str {
+        self.user.login()
+    }
+}
+
+#[rocket::async_trait]
+impl<'r, R: RequiredRole> FromRequest<'r> for Authorized<R> {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let pool = try_outcome!(
+            req.guard::<&State<Pool<ConnectionManager<SqliteConnection>>>>()
+                .await
+        );
+        let user = if let Some(token) = authentication::get_bearer_token(req) {
+            pool.get()
+                .map_err(|error| ApplicationError::from(DatabaseError::from(error)))
+                .and_then(|mut connection| authenticate_api_token(&mut connection, token))
+        } else {
+            let session = try_outcome!(req.guard::<UserSession>().await);
+            pool.get()
+                .map_err(DatabaseError::from)
+                .and_then(|mut connection| {
+                    dao::user_account::retrieve(&mut connection, session.login())
+                })
+                .map_err(ApplicationError::from)
+        };
+
+        match user {
+            Ok(None) => {
+                debug!("User no longer exists, or API token is unknown or revoked");
+                Outcome::Forward(Status::Unauthorized)
+            }
+            Ok(Some(user)) if user.has_role(R::ROLE) => Outcome::Success(Self {
+                user,
+                role: PhantomData,
+            }),
+            Ok(Some(user)) => {
+                debug!(
+                    "User lacks role [login: {}, role: {}]",
+                    user.login(),
+                    R::ROLE.value()
+                );
+                Outcome::Error((Status::Forbidden, ()))
+            }
+            Err(error) => {
+                log_error_and_return(Outcome::Error((Status::InternalServerError, ())))(error)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::database::dao;
+    use crate::user::session::UserSession;
+    use crate::web::credentials_storage::CredentialsStorage;
+    use diesel::SqliteConnection;
+    use diesel::r2d2::{ConnectionManager, Pool};
+    use dto::user::Role;
+    use rocket::Route;
+    use rocket::local::asynchronous::Client;
+    use std::sync::Mutex;
+
+    pub(crate) const USER_UUID: &str = "4f8a7c0e-3b7f-4a5e-9d0c-6c2b1e9d8a01";
+
+    /// Create a `test_login` user with the roles, and log them in under [USER_UUID].
+    pub(crate) fn log_in(
+        pool: &Pool<ConnectionManager<SqliteConnection>>,
+        roles: &[Role],
+    ) -> Mutex<CredentialsStorage<UserSession>> {
+        let mut connection = pool.get().unwrap();
+        dao::user_account::insert(&mut connection, "test_login", "hash", roles).unwrap();
+
+        let mut storage = CredentialsStorage::default();
+        storage
+            .store(
+                USER_UUID.to_owned(),
+                UserSession::new("test_login".to_owned()),
+            )
+            .unwrap();
+        Mutex::new(storage)
+    }
+
+    /// Build a client mounting the routes, along with a `test_login` user logged in with the roles.
+    pub(crate) async fn build_client(
+        pool: Pool<ConnectionManager<SqliteConnection>>,
+        roles: &[Role],
+        routes: Vec<Route>,
+    ) -> Client {
+        let storage = log_in(&pool, roles);
+        let rocket = rocket::build()
+            .manage(pool)
+            .manage(storage)
+            .mount("/", routes);
+        Client::tracked(rocket).await.unwrap()
+    }
+
+    mod authorized {
+        use crate::database::{dao, with_temp_database};
+        use crate::user::authentication::tests::{USER_UUID, log_in};
+        use crate::user::authentication::{AUTHENTICATION_COOKIE, Authorized, CanCheck};
+        use crate::user::session::UserSession;
+        use crate::web::credentials_storage::CredentialsStorage;
+        use diesel::SqliteConnection;
+        use diesel::r2d2::{ConnectionManager, Pool};
+        use dto::user::Role;
+        use rocket::http::Status;
+        use rocket::local::asynchronous::Client;
+        use rocket::request::FromRequest;
+        use rocket::tokio::runtime::Runtime;
+        use std::sync::Mutex;
+
+        async fn build_client(
+            pool: Pool<ConnectionManager<SqliteConnection>>,
+            roles: &[Role],
+        ) -> Client {
+            let storage = log_in(&pool, roles);
+            let rocket = rocket::build().manage(pool).manage(storage);
+            Client::tracked(rocket).await.unwrap()
+        }
+
+        #[test]
+        fn success() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool, &[Role::Check]).await;
+                let request = client
+                    .get("http://localhost")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID));
+
+                let outcome = Authorized::<CanCheck>::from_request(&request).await;
+                assert!(outcome.is_success());
+                assert_eq!("test_login", outcome.succeeded().unwrap().login());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn success_when_admin() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool, &[Role::Admin]).await;
+                let request = client
+                    .get("http://localhost")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID));
+
+                let outcome = Authorized::<CanCheck>::from_request(&request).await;
+                assert!(outcome.is_success());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn forbidden_when_lacking_role() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool, &[Role::LookUp]).await;
+                let request = client
+                    .get("http://localhost")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID));
+
+                let outcome = Authorized::<CanCheck>::from_request(&request).await;
+                assert!(outcome.is_error());
+                assert_eq!(Status::Forbidden, outcome.failed().unwrap().0);
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn unauthorized_when_not_logged_in() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let rocket = rocket::build()
+                    .manage(pool)
+                    .manage(Mutex::new(CredentialsStorage::<UserSession>::default()));
+                let client = Client::tracked(rocket).await.unwrap();
+                let request = client
+                    .get("http://localhost")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID));
+
+                let outcome = Authorized::<CanCheck>::from_request(&request).await;
+                assert!(outcome.is_forward());
+                assert_eq!(Status::Unauthorized, outcome.forwarded().unwrap());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn unauthorized_when_user_has_been_deleted() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool.clone(), &[Role::Check]).await;
+                dao::user_account::delete(&mut pool.get().unwrap(), "test_login").unwrap();
+                let request = client
+                    .get("http://localhost")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID));
+
+                let outcome = Authorized::<CanCheck>::from_request(&request).await;
+                assert!(outcome.is_forward());
+                assert_eq!(Status::Unauthorized, outcome.forwarded().unwrap());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+    }
+
+    mod authorized_with_api_token {
+        use crate::database::{dao, with_temp_database};
+        use crate::user::api_token::create_api_token;
+        use crate::user::authentication::{Authorized, CanCheck, CanLookUp};
+        use crate::user::session::UserSession;
+        use crate::web::credentials_storage::CredentialsStorage;
+        use diesel::SqliteConnection;
+        use diesel::r2d2::{ConnectionManager, Pool};
+        use dto::api_token::{CreatedApiToken, NewApiToken};
+        use dto::user::Role;
+        use rocket::http::{Header, Status};
+        use rocket::local::asynchronous::Client;
+        use rocket::request::FromRequest;
+        use rocket::tokio::runtime::Runtime;
+        use std::sync::Mutex;
+
+        async fn build_client(
+            pool: Pool<ConnectionManager<SqliteConnection>>,
+            scopes: Vec<Role>,
+        ) -> (Client, CreatedApiToken) {
+            let new_token = NewApiToken::new("inscriptions".to_owned(), scopes);
+            let created = create_api_token(&mut pool.get().unwrap(), &new_token, "jon").unwrap();
+            let rocket = rocket::build()
+                .manage(pool)
+                .manage(Mutex::new(CredentialsStorage::<UserSession>::default()));
+            (Client::tracked(rocket).await.unwrap(), created)
+        }
+
+        fn bearer(token: &str) -> Header<'static> {
+            Header::new("Authorization", format!("Bearer {token}"))
+        }
+
+        #[test]
+        fn success() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let (client, created) = build_client(pool, vec![Role::Check]).await;
+                let request = client
+                    .get("http://localhost")
+                    .header(bearer(created.token()));
+
+                let outcome = Authorized::<CanCheck>::from_request(&request).await;
+                assert!(outcome.is_success());
+                assert_eq!(
+                    format!("Jeton n°{} (inscriptions)", created.id()),
+                    outcome.succeeded().unwrap().login()
+                );
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn forbidden_when_out_of_scope() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let (client, created) = build_client(pool, vec![Role::Check]).await;
+                let request = client
+                    .get("http://localhost")
+                    .header(bearer(created.token()));
+
+                let outcome = Authorized::<CanLookUp>::from_request(&request).await;
+                assert!(outcome.is_error());
+                assert_eq!(Status::Forbidden, outcome.failed().unwrap().0);
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn unauthorized_when_revoked() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let (client, created) = build_client(pool.clone(), vec![Role::Check]).await;
+                dao::api_token::revoke(&mut pool.get().unwrap(), *created.id()).unwrap();
+                let request = client
+                    .get("http://localhost")
+                    .header(bearer(created.token()));
+
+                let outcome = Authorized::<CanCheck>::from_request(&request).await;
+                assert!(outcome.is_forward());
+                assert_eq!(Status::Unauthorized, outcome.forwarded().unwrap());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn unauthorized_when_unknown() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let (client, _) = build_client(pool, vec![Role::Check]).await;
+                let request = client.get("http://localhost").header(bearer("cnm_unknown"));
+
+                let outcome = Authorized::<CanCheck>::from_request(&request).await;
+                assert!(outcome.is_forward());
+                assert_eq!(Status::Unauthorized, outcome.forwarded().unwrap());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+    }
+}
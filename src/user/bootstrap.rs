@@ -0,0 +1,90 @@
+use crate::database::dao;
+use crate::database::error::DatabaseError;
+use crate::error::Result;
+use crate::tools::env_args::retrieve_arg_value;
+use crate::user::account::create_user;
+use diesel::SqliteConnection;
+use diesel::r2d2::{ConnectionManager, Pool};
+use dto::user::{NewUser, Role, UserCredentials};
+
+const ADMIN_LOGIN_ARG: &str = "--admin-login";
+const ADMIN_PASSWORD_ARG: &str = "--admin-password";
+
+/// Create the first admin from the `--admin-login` and `--admin-password` args,
+/// unless some local account already exists.
+/// Other accounts can then be managed from the admin page.
+pub fn create_first_admin(pool: &Pool<ConnectionManager<SqliteConnection>>) -> Result<()> {
+    let mut connection = pool.get().map_err(DatabaseError::from)?;
+    if dao::user_account::count(&mut connection)? > 0 {
+        return Ok(());
+    }
+
+    let (Some(login), Some(password)) = (
+        retrieve_arg_value(ADMIN_LOGIN_ARG),
+        retrieve_arg_value(ADMIN_PASSWORD_ARG),
+    ) else {
+        warn!(
+            "No user account exists yet, pass {ADMIN_LOGIN_ARG} and {ADMIN_PASSWORD_ARG} to create an admin"
+        );
+        return Ok(());
+    };
+
+    let admin = NewUser::new(UserCredentials::new(login, password), vec![Role::Admin]);
+    create_user(&mut connection, &admin)?;
+    info!(
+        "First admin has been created [login: {}]",
+        admin.credentials().login()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::{dao, with_temp_database};
+    use crate::tools::env_args::with_env_args;
+    use crate::user::bootstrap::create_first_admin;
+    use dto::user::{Role, User};
+
+    #[test]
+    fn create_admin() {
+        with_temp_database(|pool| {
+            let args = vec![
+                "--admin-login=admin".to_owned(),
+                "--admin-password=password".to_owned(),
+            ];
+            with_env_args(args, || create_first_admin(&pool)).unwrap();
+
+            let mut connection = pool.get().unwrap();
+            assert_eq!(
+                vec![User::new("admin".to_owned(), vec![Role::Admin])],
+                dao::user_account::retrieve_all(&mut connection).unwrap()
+            );
+        })
+    }
+
+    #[test]
+    fn do_nothing_when_users_exist() {
+        with_temp_database(|pool| {
+            let mut connection = pool.get().unwrap();
+            dao::user_account::insert(&mut connection, "jon", "hash", &[Role::Check]).unwrap();
+
+            let args = vec![
+                "--admin-login=admin".to_owned(),
+                "--admin-password=password".to_owned(),
+            ];
+            with_env_args(args, || create_first_admin(&pool)).unwrap();
+
+            assert_eq!(1, dao::user_account::count(&mut connection).unwrap());
+        })
+    }
+
+    #[test]
+    fn do_nothing_without_args() {
+        with_temp_database(|pool| {
+            create_first_admin(&pool).unwrap();
+
+            let mut connection = pool.get().unwrap();
+            assert_eq!(0, dao::user_account::count(&mut connection).unwrap());
+        })
+    }
+}
@@ -0,0 +1,13 @@
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum UserError {
+    #[error("The password is too short.")]
+    PasswordTooShort,
+    #[error("The password can't be hashed.")]
+    CantHashPassword(String),
+    #[error("The stored password hash can't be read.")]
+    InvalidPasswordHash(String),
+    #[error("API tokens can't be granted this scope.")]
+    InvalidApiTokenScope(String),
+}
@@ -0,0 +1,6 @@
+pub(crate) mod account;
+pub(crate) mod api_token;
+pub(crate) mod authentication;
+pub(crate) mod bootstrap;
+pub mod error;
+pub(crate) mod session;
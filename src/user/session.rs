@@ -0,0 +1,20 @@
+use crate::web::credentials_storage::sqlite::StoredCredentials;
+use derive_getters::Getters;
+use rocket::serde::{Deserialize, Serialize};
+
+/// What is kept of a local user once logged in.
+/// Their roles are not kept, so that any change made by an admin applies straight away.
+#[derive(Serialize, Deserialize, Getters, PartialEq, Clone, Debug)]
+pub struct UserSession {
+    login: String,
+}
+
+impl UserSession {
+    pub fn new(login: String) -> Self {
+        Self { login }
+    }
+}
+
+impl StoredCredentials for UserSession {
+    const KIND: &'static str = "User";
+}
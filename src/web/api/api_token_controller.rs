@@ -0,0 +1,254 @@
+use crate::database::dao;
+use crate::error::ApplicationError;
+use crate::tools::log_error_and_return;
+use crate::user::api_token::create_api_token;
+use crate::user::authentication::{Authorized, IsAdmin};
+use crate::user::error::UserError;
+use crate::web::audit::record_audit_log;
+use diesel::SqliteConnection;
+use diesel::r2d2::{ConnectionManager, Pool};
+use dto::api_token::{CreatedApiToken, NewApiToken};
+use dto::audit_log::AuditAction;
+use rocket::State;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+
+/// Create an API token, scoped to some endpoint families.
+/// The token is returned once, it can't be retrieved afterward.
+#[post("/api-tokens", format = "application/json", data = "<new_token>")]
+pub async fn create(
+    pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
+    new_token: Json<NewApiToken>,
+    admin: Authorized<IsAdmin>,
+) -> Result<(Status, Json<CreatedApiToken>), Status> {
+    let name = new_token.name().trim();
+    if name.is_empty() || new_token.scopes().is_empty() {
+        debug!("Can't create API token without name nor scope [token: {new_token:?}]");
+        return Err(Status::BadRequest);
+    }
+
+    let mut connection = pool
+        .get()
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    let new_token = NewApiToken::new(name.to_owned(), new_token.scopes().clone());
+    let created = create_api_token(&mut connection, &new_token, admin.login()).map_err(
+        |error| match error {
+            ApplicationError::User(UserError::InvalidApiTokenScope(scope)) => {
+                debug!("API tokens can't be granted this scope [scope: {scope}]");
+                Status::BadRequest
+            }
+            _ => log_error_and_return(Status::InternalServerError)(error),
+        },
+    )?;
+
+    record_audit_log(
+        pool.inner(),
+        admin.login(),
+        AuditAction::CreateApiToken,
+        &[created.id().to_string()],
+    );
+    Ok((Status::Created, Json(created)))
+}
+
+/// Revoke an API token, so that it can no longer be used.
+#[delete("/api-tokens/<id>")]
+pub async fn revoke(
+    pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
+    id: i32,
+    admin: Authorized<IsAdmin>,
+) -> Result<Status, Status> {
+    let mut connection = pool
+        .get()
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    let revoked = dao::api_token::revoke(&mut connection, id)
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    if !revoked {
+        debug!("API token doesn't exist or is already revoked [id: {id}]");
+        return Err(Status::NotFound);
+    }
+
+    record_audit_log(
+        pool.inner(),
+        admin.login(),
+        AuditAction::RevokeApiToken,
+        &[id.to_string()],
+    );
+    Ok(Status::Ok)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::web::api::api_token_controller;
+    use rocket::Route;
+
+    /// The routes mounted by the test client.
+    fn routes() -> Vec<Route> {
+        routes![api_token_controller::create, api_token_controller::revoke]
+    }
+
+    mod create {
+        use crate::database::{dao, with_temp_database};
+        use crate::user::api_token::authenticate_api_token;
+        use crate::user::authentication::AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::{USER_UUID, build_client};
+        use crate::web::api::api_token_controller::tests::routes;
+        use diesel::SqliteConnection;
+        use diesel::r2d2::{ConnectionManager, Pool};
+        use dto::api_token::CreatedApiToken;
+        use dto::audit_log::{AuditAction, AuditLogFilter};
+        use dto::user::Role;
+        use rocket::http::{ContentType, Status};
+        use rocket::serde::json::{self, json};
+        use rocket::tokio::runtime::Runtime;
+
+        fn new_token(name: &str, scopes: &[&str]) -> String {
+            json!({"name": name, "scopes": scopes}).to_string()
+        }
+
+        #[test]
+        fn success() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool.clone(), &[Role::Admin], routes()).await;
+
+                let response = client
+                    .post("/api-tokens")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .header(ContentType::JSON)
+                    .body(new_token(" inscriptions ", &["Check", "LookUp"]))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Created, response.status());
+                let created: CreatedApiToken =
+                    json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+                let mut connection = pool.get().unwrap();
+                let user = authenticate_api_token(&mut connection, created.token())
+                    .unwrap()
+                    .unwrap();
+                assert!(user.login().contains("inscriptions"));
+                assert_eq!(&vec![Role::Check, Role::LookUp], user.roles());
+                let audit_log =
+                    dao::audit_log::retrieve(&mut connection, &AuditLogFilter::default()).unwrap();
+                assert_eq!(&AuditAction::CreateApiToken, audit_log[0].action());
+                assert_eq!("test_login", audit_log[0].actor());
+                assert_eq!(&vec![created.id().to_string()], audit_log[0].targets());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn bad_request_when_scope_is_not_allowed() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool, &[Role::Admin], routes()).await;
+
+                let response = client
+                    .post("/api-tokens")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .header(ContentType::JSON)
+                    .body(new_token("inscriptions", &["Check", "Admin"]))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::BadRequest, response.status());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn bad_request_without_scope() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool, &[Role::Admin], routes()).await;
+
+                let response = client
+                    .post("/api-tokens")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .header(ContentType::JSON)
+                    .body(new_token("inscriptions", &[]))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::BadRequest, response.status());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn forbidden_when_not_admin() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool, &[Role::Check, Role::LookUp], routes()).await;
+
+                let response = client
+                    .post("/api-tokens")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .header(ContentType::JSON)
+                    .body(new_token("inscriptions", &["Check"]))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Forbidden, response.status());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+    }
+
+    mod revoke {
+        use crate::database::{dao, with_temp_database};
+        use crate::user::authentication::AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::{USER_UUID, build_client};
+        use crate::web::api::api_token_controller::tests::routes;
+        use diesel::SqliteConnection;
+        use diesel::r2d2::{ConnectionManager, Pool};
+        use dto::audit_log::{AuditAction, AuditLogFilter};
+        use dto::user::Role;
+        use rocket::http::Status;
+        use rocket::tokio::runtime::Runtime;
+
+        #[test]
+        fn success() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool.clone(), &[Role::Admin], routes()).await;
+                let mut connection = pool.get().unwrap();
+                let id = dao::api_token::insert(
+                    &mut connection,
+                    "inscriptions",
+                    "hash",
+                    &[Role::Check],
+                    "jon",
+                )
+                .unwrap();
+
+                let response = client
+                    .delete(format!("/api-tokens/{id}"))
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Ok, response.status());
+
+                assert!(dao::api_token::retrieve_all(&mut connection).unwrap()[0].is_revoked());
+                let audit_log =
+                    dao::audit_log::retrieve(&mut connection, &AuditLogFilter::default()).unwrap();
+                assert_eq!(&AuditAction::RevokeApiToken, audit_log[0].action());
+                assert_eq!(&vec![id.to_string()], audit_log[0].targets());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn not_found() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool, &[Role::Admin], routes()).await;
+
+                let response = client
+                    .delete("/api-tokens/42")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::NotFound, response.status());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+    }
+}
@@ -0,0 +1,247 @@
+use crate::database::dao;
+use crate::membership::check_session::compare_check_sessions;
+use crate::tools::log_error_and_return;
+use crate::user::authentication::{Authorized, CanCheck};
+use diesel::SqliteConnection;
+use diesel::r2d2::{ConnectionManager, Pool};
+use dto::check_session::CheckSession;
+use rocket::State;
+use rocket::http::Status;
+use rocket::serde::json::json;
+
+/// List past check sessions, the most recent first, without their results.
+#[get("/check-sessions")]
+pub async fn list_check_sessions(
+    pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
+    _user: Authorized<CanCheck>,
+) -> Result<String, Status> {
+    let mut connection = pool
+        .get()
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    let check_sessions = dao::check_session::retrieve_all(&mut connection)
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+
+    Ok(json!(check_sessions).to_string())
+}
+
+/// Reopen a past check session, along with its results.
+#[get("/check-sessions/<session_id>")]
+pub async fn get_check_session(
+    pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
+    session_id: i32,
+    _user: Authorized<CanCheck>,
+) -> Result<String, Status> {
+    let check_session = retrieve_check_session(pool, session_id)?;
+
+    Ok(json!(check_session).to_string())
+}
+
+/// List members whose status has changed between two check sessions.
+#[get("/check-sessions/<previous_session_id>/compare/<current_session_id>")]
+pub async fn compare_sessions(
+    pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
+    previous_session_id: i32,
+    current_session_id: i32,
+    _user: Authorized<CanCheck>,
+) -> Result<String, Status> {
+    let previous_session = retrieve_check_session(pool, previous_session_id)?;
+    let current_session = retrieve_check_session(pool, current_session_id)?;
+
+    let comparison = compare_check_sessions(&previous_session, &current_session);
+    Ok(json!(comparison).to_string())
+}
+
+fn retrieve_check_session(
+    pool: &Pool<ConnectionManager<SqliteConnection>>,
+    session_id: i32,
+) -> Result<CheckSession, Status> {
+    let mut connection = pool
+        .get()
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    dao::check_session::retrieve(&mut connection, session_id)
+        .map_err(log_error_and_return(Status::InternalServerError))?
+        .ok_or_else(|| {
+            debug!("Check session doesn't exist [id: {session_id}]");
+            Status::NotFound
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::user::authentication::tests::log_in;
+    use crate::web::api::check_sessions_controller;
+    use chrono::NaiveDate;
+    use diesel::SqliteConnection;
+    use diesel::r2d2::{ConnectionManager, Pool};
+    use dto::check_session::CheckSessionResults;
+    use dto::checked_member::{CheckResult, CheckedMember};
+    use dto::csv_member::CsvMember;
+    use dto::event_period::EventPeriod;
+    use dto::user::Role;
+    use rocket::local::asynchronous::Client;
+
+    fn results() -> CheckSessionResults {
+        CheckSessionResults::Csv(vec![CheckedMember::new(
+            CsvMember::new(
+                Some("123456".to_owned()),
+                None,
+                Some("Doe".to_owned()),
+                Some("Jon".to_owned()),
+                None,
+                None,
+                None,
+            ),
+            CheckResult::NoMatch,
+            &EventPeriod::single_day(NaiveDate::from_ymd_opt(2025, 7, 5).unwrap()),
+            30,
+        )])
+    }
+
+    /// Record a session, then build a client allowed to read it.
+    async fn build_client(pool: Pool<ConnectionManager<SqliteConnection>>) -> (Client, i32) {
+        let mut connection = pool.get().unwrap();
+        let event_period = EventPeriod::single_day(NaiveDate::from_ymd_opt(2025, 7, 5).unwrap());
+        let session_id = crate::database::dao::check_session::insert(
+            &mut connection,
+            "jon",
+            &event_period,
+            &results(),
+        )
+        .unwrap();
+
+        let storage = log_in(&pool, &[Role::Check]);
+        let rocket = rocket::build().manage(storage).manage(pool).mount(
+            "/",
+            routes![
+                check_sessions_controller::list_check_sessions,
+                check_sessions_controller::get_check_session,
+                check_sessions_controller::compare_sessions
+            ],
+        );
+
+        (Client::tracked(rocket).await.unwrap(), session_id)
+    }
+
+    mod list_check_sessions {
+        use crate::database::with_temp_database;
+        use crate::user::authentication::AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::USER_UUID;
+        use crate::web::api::check_sessions_controller::tests::build_client;
+        use diesel::SqliteConnection;
+        use diesel::r2d2::{ConnectionManager, Pool};
+        use dto::check_session::CheckSessionSummary;
+        use rocket::http::Status;
+        use rocket::tokio::runtime::Runtime;
+
+        #[test]
+        fn success() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let (client, session_id) = build_client(pool).await;
+
+                let response = client
+                    .get("/check-sessions")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Ok, response.status());
+
+                let sessions: Vec<CheckSessionSummary> = response.into_json().await.unwrap();
+                assert_eq!(1, sessions.len());
+                assert_eq!(&session_id, sessions[0].id());
+                assert_eq!("jon", sessions[0].user());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn fail_when_unauthenticated() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let (client, _) = build_client(pool).await;
+
+                let response = client.get("/check-sessions").dispatch().await;
+                assert_eq!(Status::Unauthorized, response.status());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+    }
+
+    mod get_check_session {
+        use crate::database::with_temp_database;
+        use crate::user::authentication::AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::USER_UUID;
+        use crate::web::api::check_sessions_controller::tests::{build_client, results};
+        use diesel::SqliteConnection;
+        use diesel::r2d2::{ConnectionManager, Pool};
+        use dto::check_session::CheckSession;
+        use rocket::http::Status;
+        use rocket::tokio::runtime::Runtime;
+
+        #[test]
+        fn success() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let (client, session_id) = build_client(pool).await;
+
+                let response = client
+                    .get(format!("/check-sessions/{session_id}"))
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Ok, response.status());
+
+                let session: CheckSession = response.into_json().await.unwrap();
+                assert_eq!(&results(), session.results());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn not_found() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let (client, session_id) = build_client(pool).await;
+
+                let response = client
+                    .get(format!("/check-sessions/{}", session_id + 1))
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::NotFound, response.status());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+    }
+
+    mod compare_sessions {
+        use crate::database::with_temp_database;
+        use crate::user::authentication::AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::USER_UUID;
+        use crate::web::api::check_sessions_controller::tests::build_client;
+        use diesel::SqliteConnection;
+        use diesel::r2d2::{ConnectionManager, Pool};
+        use dto::check_session::CheckSessionComparison;
+        use rocket::http::Status;
+        use rocket::tokio::runtime::Runtime;
+
+        #[test]
+        fn success() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let (client, session_id) = build_client(pool).await;
+
+                let response = client
+                    .get(format!("/check-sessions/{session_id}/compare/{session_id}"))
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Ok, response.status());
+
+                let comparison: CheckSessionComparison = response.into_json().await.unwrap();
+                assert!(comparison.changed_members().is_empty());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+    }
+}
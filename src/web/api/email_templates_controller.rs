@@ -0,0 +1,395 @@
+use crate::database::dao;
+use crate::membership::notification::check_email_template;
+use crate::tools::log_error_and_return;
+use crate::user::authentication::{Authorized, CanEmail};
+use diesel::SqliteConnection;
+use diesel::r2d2::{ConnectionManager, Pool};
+use dto::email_template::{EmailTemplate, NewEmailTemplate};
+use rocket::State;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+
+/// List the templates notifications can be written from, sorted by name.
+#[get("/email-templates")]
+pub async fn list(
+    pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
+    _user: Authorized<CanEmail>,
+) -> Result<Json<Vec<EmailTemplate>>, Status> {
+    let mut connection = pool
+        .get()
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    dao::email_template::retrieve_all(&mut connection)
+        .map(Json)
+        .map_err(log_error_and_return(Status::InternalServerError))
+}
+
+/// Create a template, then return its ID.
+/// Fail with a Conflict status if the name is already taken.
+#[post("/email-templates", format = "application/json", data = "<template>")]
+pub async fn create(
+    pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
+    template: Json<NewEmailTemplate>,
+    _user: Authorized<CanEmail>,
+) -> Result<(Status, Json<i32>), Status> {
+    let template = validate(&template)?;
+
+    let mut connection = pool
+        .get()
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    if dao::email_template::is_name_taken(&mut connection, template.name(), None)
+        .map_err(log_error_and_return(Status::InternalServerError))?
+    {
+        debug!("Email template already exists [name: {}]", template.name());
+        return Err(Status::Conflict);
+    }
+    let template_id = dao::email_template::insert(&mut connection, &template)
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    Ok((Status::Created, Json(template_id)))
+}
+
+/// Replace the name, the subject, the body and the use case of a template.
+#[put(
+    "/email-templates/<id>",
+    format = "application/json",
+    data = "<template>"
+)]
+pub async fn update(
+    pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
+    id: i32,
+    template: Json<NewEmailTemplate>,
+    _user: Authorized<CanEmail>,
+) -> Result<Status, Status> {
+    let template = validate(&template)?;
+
+    let mut connection = pool
+        .get()
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    if dao::email_template::is_name_taken(&mut connection, template.name(), Some(id))
+        .map_err(log_error_and_return(Status::InternalServerError))?
+    {
+        debug!("Email template already exists [name: {}]", template.name());
+        return Err(Status::Conflict);
+    }
+    let updated = dao::email_template::update(&mut connection, id, &template)
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    if !updated {
+        debug!("Email template doesn't exist [id: {id}]");
+        return Err(Status::NotFound);
+    }
+    Ok(Status::Ok)
+}
+
+#[delete("/email-templates/<id>")]
+pub async fn delete(
+    pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
+    id: i32,
+    _user: Authorized<CanEmail>,
+) -> Result<Status, Status> {
+    let mut connection = pool
+        .get()
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    let deleted = dao::email_template::delete(&mut connection, id)
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    if !deleted {
+        debug!("Email template doesn't exist [id: {id}]");
+        return Err(Status::NotFound);
+    }
+    Ok(Status::Ok)
+}
+
+/// A template needs a name and a subject, and must be renderable, so that it doesn't fail once picked.
+/// Rendering rejects templates calling functions or filters that aren't allowed, such as `get_env`,
+/// so that none is saved to be replayed on every notification.
+fn validate(template: &NewEmailTemplate) -> Result<NewEmailTemplate, Status> {
+    let name = template.name().trim();
+    if name.is_empty() || template.subject().trim().is_empty() {
+        debug!("Can't save email template without name nor subject [template: {template:?}]");
+        return Err(Status::BadRequest);
+    }
+    check_email_template(template.subject(), template.body()).map_err(|error| {
+        debug!("Invalid email template [name: {name}, error: {error:?}]");
+        Status::BadRequest
+    })?;
+
+    Ok(NewEmailTemplate::new(
+        name.to_owned(),
+        template.subject().to_owned(),
+        template.body().to_owned(),
+        *template.use_case(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::web::api::email_templates_controller;
+    use rocket::Route;
+    use rocket::serde::json::json;
+
+    /// The routes mounted by the test client.
+    fn routes() -> Vec<Route> {
+        routes![
+            email_templates_controller::list,
+            email_templates_controller::create,
+            email_templates_controller::update,
+            email_templates_controller::delete,
+        ]
+    }
+
+    fn new_template(name: &str, body: &str) -> String {
+        json!({"name": name, "subject": "Votre licence", "body": body, "use_case": "Expired"})
+            .to_string()
+    }
+
+    mod list {
+        use crate::database::with_temp_database;
+        use crate::user::authentication::AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::{USER_UUID, build_client};
+        use crate::web::api::email_templates_controller::tests::routes;
+        use diesel::SqliteConnection;
+        use diesel::r2d2::{ConnectionManager, Pool};
+        use dto::email_template::EmailTemplate;
+        use dto::user::Role;
+        use rocket::http::Status;
+        use rocket::serde::json;
+        use rocket::tokio::runtime::Runtime;
+
+        #[test]
+        fn success() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool, &[Role::Email], routes()).await;
+
+                let response = client
+                    .get("/email-templates")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Ok, response.status());
+                let templates: Vec<EmailTemplate> =
+                    json::from_str(&response.into_string().await.unwrap()).unwrap();
+                assert_eq!(3, templates.len());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn forbidden_without_role() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool, &[Role::Check], routes()).await;
+
+                let response = client
+                    .get("/email-templates")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Forbidden, response.status());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+    }
+
+    mod create {
+        use crate::database::{dao, with_temp_database};
+        use crate::user::authentication::AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::{USER_UUID, build_client};
+        use crate::web::api::email_templates_controller::tests::{new_template, routes};
+        use diesel::SqliteConnection;
+        use diesel::r2d2::{ConnectionManager, Pool};
+        use dto::email_template::EmailTemplateUseCase::Expired;
+        use dto::user::Role;
+        use rocket::http::{ContentType, Status};
+        use rocket::serde::json;
+        use rocket::tokio::runtime::Runtime;
+
+        #[test]
+        fn success() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool.clone(), &[Role::Email], routes()).await;
+
+                let response = client
+                    .post("/email-templates")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .header(ContentType::JSON)
+                    .body(new_template(" Relance ", "Bonjour {{ first_name }}"))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Created, response.status());
+                let template_id: i32 =
+                    json::from_str(&response.into_string().await.unwrap()).unwrap();
+
+                let mut connection = pool.get().unwrap();
+                let templates = dao::email_template::retrieve_all(&mut connection).unwrap();
+                let template = templates
+                    .iter()
+                    .find(|template| *template.id() == template_id)
+                    .unwrap();
+                assert_eq!("Relance", template.name());
+                assert_eq!(&Some(Expired), template.use_case());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn conflict_when_name_taken() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool, &[Role::Email], routes()).await;
+
+                let response = client
+                    .post("/email-templates")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .header(ContentType::JSON)
+                    .body(new_template("Licence expirée", "Bonjour"))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Conflict, response.status());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn bad_request_when_invalid() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool, &[Role::Email], routes()).await;
+
+                for body in [
+                    new_template(" ", "Bonjour"),
+                    new_template("Relance", "Bonjour {{ nickname }}"),
+                    new_template("Relance", r#"{{ get_env(name="ROCKET_SECRET_KEY") }}"#),
+                    new_template("Relance", "{{ club | json_encode }}"),
+                ] {
+                    let response = client
+                        .post("/email-templates")
+                        .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                        .header(ContentType::JSON)
+                        .body(body)
+                        .dispatch()
+                        .await;
+                    assert_eq!(Status::BadRequest, response.status());
+                }
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+    }
+
+    mod update {
+        use crate::database::{dao, with_temp_database};
+        use crate::user::authentication::AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::{USER_UUID, build_client};
+        use crate::web::api::email_templates_controller::tests::{new_template, routes};
+        use diesel::SqliteConnection;
+        use diesel::r2d2::{ConnectionManager, Pool};
+        use dto::user::Role;
+        use rocket::http::{ContentType, Status};
+        use rocket::tokio::runtime::Runtime;
+
+        #[test]
+        fn success() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool.clone(), &[Role::Email], routes()).await;
+                let mut connection = pool.get().unwrap();
+                let template_id =
+                    *dao::email_template::retrieve_all(&mut connection).unwrap()[0].id();
+
+                let response = client
+                    .put(format!("/email-templates/{template_id}"))
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .header(ContentType::JSON)
+                    .body(new_template("Relance", "Bonjour"))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Ok, response.status());
+                let templates = dao::email_template::retrieve_all(&mut connection).unwrap();
+                assert!(
+                    templates
+                        .iter()
+                        .any(|template| template.name() == "Relance")
+                );
+
+                let response = client
+                    .put("/email-templates/999")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .header(ContentType::JSON)
+                    .body(new_template("Autre", "Bonjour"))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::NotFound, response.status());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn bad_request_when_calling_function() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool.clone(), &[Role::Email], routes()).await;
+                let mut connection = pool.get().unwrap();
+                let template =
+                    dao::email_template::retrieve_all(&mut connection).unwrap()[0].clone();
+
+                let response = client
+                    .put(format!("/email-templates/{}", template.id()))
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .header(ContentType::JSON)
+                    .body(new_template(
+                        "Relance",
+                        r#"{{ get_env(name="ROCKET_SECRET_KEY") }}"#,
+                    ))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::BadRequest, response.status());
+                let templates = dao::email_template::retrieve_all(&mut connection).unwrap();
+                assert!(templates.contains(&template));
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+    }
+
+    mod delete {
+        use crate::database::{dao, with_temp_database};
+        use crate::user::authentication::AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::{USER_UUID, build_client};
+        use crate::web::api::email_templates_controller::tests::routes;
+        use diesel::SqliteConnection;
+        use diesel::r2d2::{ConnectionManager, Pool};
+        use dto::user::Role;
+        use rocket::http::Status;
+        use rocket::tokio::runtime::Runtime;
+
+        #[test]
+        fn success() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool.clone(), &[Role::Email], routes()).await;
+                let mut connection = pool.get().unwrap();
+                let template_id =
+                    *dao::email_template::retrieve_all(&mut connection).unwrap()[0].id();
+
+                let url = format!("/email-templates/{template_id}");
+                let response = client
+                    .delete(url.clone())
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Ok, response.status());
+                let response = client
+                    .delete(url)
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::NotFound, response.status());
+                assert_eq!(
+                    2,
+                    dao::email_template::retrieve_all(&mut connection)
+                        .unwrap()
+                        .len()
+                );
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+    }
+}
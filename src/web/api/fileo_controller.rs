@@ -1,38 +1,52 @@
-use crate::database::dao::membership::replace_memberships;
 use crate::error::ApplicationError;
 use crate::fileo::authentication::AUTHENTICATION_COOKIE;
 use crate::fileo::credentials::FileoCredentials;
-use crate::fileo::download::{download_memberships_list, login_to_fileo};
+use crate::fileo::download::{download_memberships_list_resuming_session, login_to_fileo};
+use crate::fileo::error::FileoError;
+use crate::fileo::import::{import_file, import_parsed_memberships, preview_file};
+use crate::fileo::session::FileoSession;
 use crate::membership::config::MembershipsProviderConfig;
-use crate::tools::web::build_client;
+use crate::tools::web::{SessionCookies, build_client_with_cookie_jar, read_uploaded_file};
 use crate::tools::{log_error_and_return, log_message_and_return};
+use crate::user::authentication::{Authorized, CanRefreshMemberships};
+use crate::web::audit::record_audit_log;
+use crate::web::authentication;
 use crate::web::credentials_storage::CredentialsStorage;
 use crate::web::error::WebError;
 use diesel::SqliteConnection;
 use diesel::r2d2::{ConnectionManager, Pool};
+use dto::audit_log::AuditAction;
 use rocket::State;
+use rocket::data::Data;
 use rocket::http::{Cookie, CookieJar, Status};
-use rocket::serde::json::Json;
+use rocket::serde::json::{Json, json};
 use rocket::time::Duration;
 use std::sync::Mutex;
 use uuid::Uuid;
 
 /// Try and log a user onto Fileo app.
 /// If the login operation succeeds,
-/// then a new UUID is created and credentials are stored with this UUID.
+/// then a new UUID is created and the session opened on Fileo is stored with this UUID.
+/// The password itself is not kept.
 /// The UUID is returned to the caller through a private cookie, so that it is their new access token.
+/// Only users allowed to refresh memberships need to log into Fileo.
 #[post("/fileo/login", format = "application/json", data = "<credentials>")]
 pub async fn login(
     memberships_provider_config: &State<MembershipsProviderConfig>,
-    credentials_storage: &State<Mutex<CredentialsStorage<FileoCredentials>>>,
+    credentials_storage: &State<Mutex<CredentialsStorage<FileoSession>>>,
     cookie_jar: &CookieJar<'_>,
     credentials: Json<FileoCredentials>,
+    _user: Authorized<CanRefreshMemberships>,
 ) -> Result<(Status, ()), Status> {
-    let client = build_client().map_err(log_error_and_return(Status::InternalServerError))?;
+    let (client, fileo_cookie_jar) = build_client_with_cookie_jar()
+        .map_err(log_error_and_return(Status::InternalServerError))?;
     let host = memberships_provider_config.inner().host();
     let credentials = credentials.into_inner();
     match login_to_fileo(&client, host, &credentials).await {
         Ok(_) => {
+            let session_cookies = SessionCookies::from_jar(&fileo_cookie_jar, host)
+                .map_err(log_error_and_return(Status::InternalServerError))?;
+            let session = FileoSession::new(credentials.login().to_owned(), session_cookies);
             let mut mutex = credentials_storage
                 .lock()
                 .map_err(log_error_and_return(Status::InternalServerError))?;
@@ -40,8 +54,10 @@ pub async fn login(
             let cookie = Cookie::build((AUTHENTICATION_COOKIE.to_owned(), uuid.clone()))
                 .max_age(Duration::days(365))
                 .build();
+            (*mutex)
+                .store(uuid.clone(), session)
+                .map_err(log_error_and_return(Status::InternalServerError))?;
             cookie_jar.add_private(cookie);
-            (*mutex).store(uuid.clone(), credentials);
             Ok((Status::Ok, ()))
         }
         Err(ApplicationError::Web(WebError::LackOfPermissions)) => Err(Status::Forbidden),
@@ -50,30 +66,127 @@ pub async fn login(
     }
 }
 
+/// Log the user out of Fileo app, by revoking their access token.
+#[post("/fileo/logout")]
+pub async fn logout(
+    credentials_storage: &State<Mutex<CredentialsStorage<FileoSession>>>,
+    cookie_jar: &CookieJar<'_>,
+) -> Result<Status, Status> {
+    authentication::logout(
+        credentials_storage.inner(),
+        cookie_jar,
+        AUTHENTICATION_COOKIE,
+    )
+}
+
 /// Download memberships csv file from remote provided in config,
 /// write said file into filesystem
-/// and load it into memory.
+/// and import it into the database.
 /// Finally, clean all old memberships files.
+/// Return what the import has changed as a JSON-encoded string.
+/// This is the only endpoint requiring the user to be logged into Fileo.
+/// If the session opened on Fileo has expired, the user is logged out of Fileo and has to log in again.
 #[get("/fileo/memberships", format = "text/plain-text")]
 pub async fn download_memberships(
     memberships_provider_config: &State<MembershipsProviderConfig>,
     pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
-    credentials: FileoCredentials,
-) -> Result<Status, Status> {
-    let memberships = download_memberships_list(memberships_provider_config, &credentials)
-        .await
-        .map_err(log_message_and_return(
-            "Can't download memberships list",
-            Status::InternalServerError,
-        ))?;
+    credentials_storage: &State<Mutex<CredentialsStorage<FileoSession>>>,
+    cookie_jar: &CookieJar<'_>,
+    user: Authorized<CanRefreshMemberships>,
+    session: FileoSession,
+) -> Result<String, Status> {
+    let parsed_memberships =
+        download_memberships_list_resuming_session(memberships_provider_config, &session)
+            .await
+            .map_err(|error| match error {
+                ApplicationError::Web(WebError::SessionExpired) => {
+                    debug!("Fileo session has expired [user: {}]", session.login());
+                    authentication::revoke_expired_session(
+                        credentials_storage.inner(),
+                        cookie_jar,
+                        AUTHENTICATION_COOKIE,
+                    )
+                }
+                _ => log_message_and_return(
+                    "Can't download memberships list",
+                    Status::InternalServerError,
+                )(error),
+            })?;
 
     let mut connection = pool
         .get()
         .map_err(log_error_and_return(Status::InternalServerError))?;
-    replace_memberships(&mut connection, &memberships)
+    let diff = import_parsed_memberships(&mut connection, &parsed_memberships)
+        .map_err(to_import_status)?;
+
+    record_audit_log(
+        pool.inner(),
+        user.login(),
+        AuditAction::DownloadMemberships,
+        &[],
+    );
+    Ok(json!(diff).to_string())
+}
+
+/// Parse a Fileo CSV export uploaded by the user, without importing it.
+/// Return how many memberships have been read and which rows have been rejected
+/// as a JSON-encoded string.
+#[post("/fileo/memberships/upload/preview", data = "<file>")]
+pub async fn preview_uploaded_memberships(
+    _user: Authorized<CanRefreshMemberships>,
+    file: Data<'_>,
+) -> Result<String, Status> {
+    let file_content = read_uploaded_file(file).await?;
+    let preview = preview_file(&file_content).map_err(|error| {
+        debug!("Can't read uploaded memberships file: {error:?}");
+        Status::BadRequest
+    })?;
+
+    Ok(json!(preview).to_string())
+}
+
+/// Import a Fileo CSV export uploaded by the user,
+/// e.g. when Fileo can't be reached.
+/// Return what the import has changed as a JSON-encoded string.
+#[post("/fileo/memberships/upload", data = "<file>")]
+pub async fn upload_memberships(
+    pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
+    user: Authorized<CanRefreshMemberships>,
+    file: Data<'_>,
+) -> Result<String, Status> {
+    let file_content = read_uploaded_file(file).await?;
+    let mut connection = pool
+        .get()
         .map_err(log_error_and_return(Status::InternalServerError))?;
+    let diff = import_file(&mut connection, &file_content).map_err(|error| match error {
+        ApplicationError::Fileo(FileoError::WrongEncoding(_)) => {
+            debug!("Can't read uploaded memberships file: {error:?}");
+            Status::BadRequest
+        }
+        _ => to_import_status(error),
+    })?;
+
+    record_audit_log(
+        pool.inner(),
+        user.login(),
+        AuditAction::UploadMemberships,
+        &[],
+    );
+    Ok(json!(diff).to_string())
+}
 
-    Ok(Status::NoContent)
+/// An import refused because of too many rejected rows is reported as such,
+/// so that the user knows the memberships haven't been updated.
+fn to_import_status(error: ApplicationError) -> Status {
+    match error {
+        ApplicationError::Fileo(FileoError::TooManyRejectedRows(rejected_count, total_count)) => {
+            warn!(
+                "Memberships import refused, too many rows have been rejected [rejected: {rejected_count}, total: {total_count}]"
+            );
+            Status::UnprocessableEntity
+        }
+        _ => log_error_and_return(Status::InternalServerError)(error),
+    }
 }
 
 #[cfg(test)]
@@ -94,9 +207,13 @@ mod tests {
         Mock::given(method("POST"))
             .and(path("/page.php"))
             .and(body_string_contains("Action=connect_user"))
-            .respond_with(ResponseTemplate::new(200).set_body_string(
-                "Profil Commission thématique - UNSLL - Commission Nationale Monocycle",
-            ))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("Set-Cookie", "PHPSESSID=1234; Path=/")
+                    .set_body_string(
+                        "Profil Commission thématique - UNSLL - Commission Nationale Monocycle",
+                    ),
+            )
             .mount(mock_server)
             .await;
     }
@@ -105,6 +222,10 @@ mod tests {
         use crate::database::with_temp_database;
         use crate::fileo::authentication::AUTHENTICATION_COOKIE;
         use crate::fileo::credentials::FileoCredentials;
+        use crate::fileo::session::FileoSession;
+        use crate::tools::web::SessionCookies;
+        use crate::user::authentication::AUTHENTICATION_COOKIE as USER_AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::{USER_UUID, log_in};
         use crate::web::api::fileo_controller::login;
         use crate::web::api::fileo_controller::tests::{
             create_memberships_provider_test_config, setup_login,
@@ -112,6 +233,7 @@ mod tests {
         use crate::web::credentials_storage::CredentialsStorage;
         use diesel::SqliteConnection;
         use diesel::r2d2::{ConnectionManager, Pool};
+        use dto::user::Role;
         use reqwest::header::CONTENT_TYPE;
         use rocket::http::{ContentType, Header, Status};
         use rocket::local::asynchronous::Client;
@@ -132,17 +254,20 @@ mod tests {
                 let credentials =
                     FileoCredentials::new("test_login".to_owned(), "test_password".to_owned());
                 let credentials_storage_mutex =
-                    Mutex::new(CredentialsStorage::<FileoCredentials>::default());
+                    Mutex::new(CredentialsStorage::<FileoSession>::default());
+                let user_credentials_storage_mutex = log_in(&pool, &[Role::RefreshMemberships]);
 
                 let rocket = rocket::build()
                     .manage(config)
                     .manage(credentials_storage_mutex)
+                    .manage(user_credentials_storage_mutex)
                     .manage(pool)
                     .mount("/", routes![login]);
                 let client = Client::tracked(rocket).await.unwrap();
                 let credentials_as_json = json!(credentials).to_string();
                 let request = client
                     .post("/fileo/login")
+                    .cookie((USER_AUTHENTICATION_COOKIE, USER_UUID))
                     .body(credentials_as_json.as_bytes())
                     .header(Header::new(
                         CONTENT_TYPE.to_string(),
@@ -151,11 +276,27 @@ mod tests {
 
                 let response = request.dispatch().await;
                 assert_eq!(Status::Ok, response.status());
-                assert!(
-                    response
-                        .cookies()
-                        .get_private(AUTHENTICATION_COOKIE)
-                        .is_some()
+                let uuid = response
+                    .cookies()
+                    .get_private(AUTHENTICATION_COOKIE)
+                    .unwrap()
+                    .value()
+                    .to_owned();
+
+                let stored_session = client
+                    .rocket()
+                    .state::<Mutex<CredentialsStorage<FileoSession>>>()
+                    .unwrap()
+                    .lock()
+                    .unwrap()
+                    .get(&uuid)
+                    .unwrap();
+                assert_eq!(
+                    Some(FileoSession::new(
+                        "test_login".to_owned(),
+                        SessionCookies::new("PHPSESSID=1234")
+                    )),
+                    stored_session
                 );
             }
             with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
@@ -177,17 +318,20 @@ mod tests {
                 let credentials =
                     FileoCredentials::new("test_login".to_owned(), "test_password".to_owned());
                 let credentials_storage_mutex =
-                    Mutex::new(CredentialsStorage::<FileoCredentials>::default());
+                    Mutex::new(CredentialsStorage::<FileoSession>::default());
+                let user_credentials_storage_mutex = log_in(&pool, &[Role::RefreshMemberships]);
 
                 let rocket = rocket::build()
                     .manage(config)
                     .manage(credentials_storage_mutex)
+                    .manage(user_credentials_storage_mutex)
                     .manage(pool)
                     .mount("/", routes![login]);
                 let client = Client::tracked(rocket).await.unwrap();
                 let credentials_as_json = json!(credentials).to_string();
                 let request = client
                     .post("/fileo/login")
+                    .cookie((USER_AUTHENTICATION_COOKIE, USER_UUID))
                     .body(credentials_as_json.as_bytes())
                     .header(Header::new(
                         CONTENT_TYPE.to_string(),
@@ -225,17 +369,20 @@ mod tests {
                 let credentials =
                     FileoCredentials::new("test_login".to_owned(), "test_password".to_owned());
                 let credentials_storage_mutex =
-                    Mutex::new(CredentialsStorage::<FileoCredentials>::default());
+                    Mutex::new(CredentialsStorage::<FileoSession>::default());
+                let user_credentials_storage_mutex = log_in(&pool, &[Role::RefreshMemberships]);
 
                 let rocket = rocket::build()
                     .manage(config)
                     .manage(credentials_storage_mutex)
+                    .manage(user_credentials_storage_mutex)
                     .manage(pool)
                     .mount("/", routes![login]);
                 let client = Client::tracked(rocket).await.unwrap();
                 let credentials_as_json = json!(credentials).to_string();
                 let request = client
                     .post("/fileo/login")
+                    .cookie((USER_AUTHENTICATION_COOKIE, USER_UUID))
                     .body(credentials_as_json.as_bytes())
                     .header(Header::new(
                         CONTENT_TYPE.to_string(),
@@ -270,17 +417,20 @@ mod tests {
                 let credentials =
                     FileoCredentials::new("test_login".to_owned(), "test_password".to_owned());
                 let credentials_storage_mutex =
-                    Mutex::new(CredentialsStorage::<FileoCredentials>::default());
+                    Mutex::new(CredentialsStorage::<FileoSession>::default());
+                let user_credentials_storage_mutex = log_in(&pool, &[Role::RefreshMemberships]);
 
                 let rocket = rocket::build()
                     .manage(config)
                     .manage(credentials_storage_mutex)
+                    .manage(user_credentials_storage_mutex)
                     .manage(pool)
                     .mount("/", routes![login]);
                 let client = Client::tracked(rocket).await.unwrap();
                 let credentials_as_json = json!(credentials).to_string();
                 let request = client
                     .post("/fileo/login")
+                    .cookie((USER_AUTHENTICATION_COOKIE, USER_UUID))
                     .body(credentials_as_json.as_bytes())
                     .header(Header::new(
                         CONTENT_TYPE.to_string(),
@@ -300,28 +450,148 @@ mod tests {
         }
     }
 
+    mod logout {
+        use crate::fileo::authentication::AUTHENTICATION_COOKIE;
+        use crate::fileo::session::FileoSession;
+        use crate::tools::web::SessionCookies;
+        use crate::web::api::fileo_controller::logout;
+        use crate::web::credentials_storage::CredentialsStorage;
+        use rocket::http::{Cookie, Status};
+        use rocket::local::asynchronous::Client;
+        use std::sync::Mutex;
+
+        const UUID: &str = "0ea9a5fb-0f46-4057-902a-2552ed956bde";
+
+        async fn build_client() -> Client {
+            let mut credentials_storage = CredentialsStorage::default();
+            credentials_storage
+                .store(
+                    UUID.to_owned(),
+                    FileoSession::new("test_login".to_owned(), SessionCookies::default()),
+                )
+                .unwrap();
+
+            let rocket = rocket::build()
+                .manage(Mutex::new(credentials_storage))
+                .mount("/", routes![logout]);
+            Client::tracked(rocket).await.unwrap()
+        }
+
+        fn is_logged_in(client: &Client) -> bool {
+            client
+                .rocket()
+                .state::<Mutex<CredentialsStorage<FileoSession>>>()
+                .unwrap()
+                .lock()
+                .unwrap()
+                .get(UUID)
+                .unwrap()
+                .is_some()
+        }
+
+        #[async_test]
+        async fn success() {
+            let client = build_client().await;
+
+            let response = client
+                .post("/fileo/logout")
+                .cookie(Cookie::new(AUTHENTICATION_COOKIE, UUID))
+                .dispatch()
+                .await;
+
+            assert_eq!(Status::Ok, response.status());
+            assert!(!is_logged_in(&client));
+        }
+
+        #[async_test]
+        async fn success_when_not_logged_in() {
+            let client = build_client().await;
+
+            let response = client.post("/fileo/logout").dispatch().await;
+
+            assert_eq!(Status::Ok, response.status());
+            assert!(is_logged_in(&client));
+        }
+    }
+
     mod download_members {
         use crate::database::{dao, with_temp_database};
         use crate::fileo::authentication::AUTHENTICATION_COOKIE;
-        use crate::fileo::credentials::FileoCredentials;
+        use crate::fileo::session::FileoSession;
+        use crate::membership::config::MembershipsProviderConfig;
+        use crate::tools::web::SessionCookies;
+        use crate::user::authentication::AUTHENTICATION_COOKIE as USER_AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::{USER_UUID, log_in};
         use crate::web::api::fileo_controller::download_memberships;
-        use crate::web::api::fileo_controller::tests::{
-            create_memberships_provider_test_config, setup_login,
-        };
+        use crate::web::api::fileo_controller::tests::create_memberships_provider_test_config;
         use crate::web::credentials_storage::CredentialsStorage;
         use diesel::SqliteConnection;
         use diesel::r2d2::{ConnectionManager, Pool};
+        use dto::audit_log::{AuditAction, AuditLogFilter};
         use dto::membership::tests::{get_expected_membership, get_membership_as_csv};
+        use dto::membership_import::MembershipImportDiff;
+        use dto::user::Role;
         use encoding::all::ISO_8859_1;
         use encoding::{EncoderTrap, Encoding};
-        use rocket::State;
         use rocket::http::{Cookie, Status};
         use rocket::local::asynchronous::Client;
         use rocket::tokio::runtime::Runtime;
         use std::sync::Mutex;
-        use wiremock::matchers::{body_string_contains, method, path, query_param_contains};
+        use wiremock::matchers::{
+            body_string_contains, header, method, path, query_param_contains,
+        };
         use wiremock::{Mock, MockServer, ResponseTemplate};
 
+        const UUID: &str = "0ea9a5fb-0f46-4057-902a-2552ed956bde";
+
+        /// Build a client whose user has logged in Fileo before, opening the session `PHPSESSID=1234`.
+        async fn build_client(
+            pool: Pool<ConnectionManager<SqliteConnection>>,
+            config: MembershipsProviderConfig,
+        ) -> Client {
+            let session = FileoSession::new(
+                "fileo_login".to_owned(),
+                SessionCookies::new("PHPSESSID=1234"),
+            );
+            let mut credentials_storage = CredentialsStorage::default();
+            credentials_storage.store(UUID.to_owned(), session).unwrap();
+            let user_credentials_storage = log_in(&pool, &[Role::RefreshMemberships]);
+
+            let rocket = rocket::build()
+                .manage(config)
+                .manage(Mutex::new(credentials_storage))
+                .manage(user_credentials_storage)
+                .manage(pool)
+                .mount("/", routes![download_memberships]);
+            Client::tracked(rocket).await.unwrap()
+        }
+
+        async fn setup_load_list(mock_server: &MockServer) {
+            Mock::given(method("POST"))
+                .and(path("/page.php"))
+                .and(query_param_contains(
+                    "P",
+                    "bo/extranet/adhesion/annuaire/index",
+                ))
+                .and(body_string_contains("Action=adherent_filtrer"))
+                .and(header("Cookie", "PHPSESSID=1234"))
+                .respond_with(ResponseTemplate::new(200))
+                .mount(mock_server)
+                .await;
+        }
+
+        fn is_logged_in(client: &Client) -> bool {
+            client
+                .rocket()
+                .state::<Mutex<CredentialsStorage<FileoSession>>>()
+                .unwrap()
+                .lock()
+                .unwrap()
+                .get(UUID)
+                .unwrap()
+                .is_some()
+        }
+
         #[test]
         fn should_download_members() {
             async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
@@ -331,17 +601,7 @@ mod tests {
                 let download_filename = "download.csv";
                 let download_link = format!("{}/{download_filename}", mock_server.uri());
 
-                setup_login(&mock_server).await;
-                Mock::given(method("POST"))
-                    .and(path("/page.php"))
-                    .and(query_param_contains(
-                        "P",
-                        "bo/extranet/adhesion/annuaire/index",
-                    ))
-                    .and(body_string_contains("Action=adherent_filtrer"))
-                    .respond_with(ResponseTemplate::new(200))
-                    .mount(&mock_server)
-                    .await;
+                setup_load_list(&mock_server).await;
                 Mock::given(method("POST"))
                     .and(path("/includer.php"))
                     .and(query_param_contains("inc", "ajax/adherent/adherent_export"))
@@ -364,25 +624,20 @@ mod tests {
                     .mount(&mock_server)
                     .await;
 
-                let credentials =
-                    FileoCredentials::new("test_login".to_owned(), "test_password".to_owned());
-                let mut credentials_storage = CredentialsStorage::default();
-                let uuid = "0ea9a5fb-0f46-4057-902a-2552ed956bde".to_owned();
-                credentials_storage.store(uuid.clone(), credentials);
-                let credentials_storage_mutex = Mutex::new(credentials_storage);
-
-                let rocket = rocket::build()
-                    .manage(config)
-                    .manage(credentials_storage_mutex)
-                    .manage(pool)
-                    .mount("/", routes![download_memberships]);
-                let client = Client::tracked(rocket).await.unwrap();
-
-                let cookie = Cookie::new(AUTHENTICATION_COOKIE, uuid);
-                let request = client.get("/fileo/memberships").cookie(cookie);
+                let client = build_client(pool, config).await;
+                let cookie = Cookie::new(AUTHENTICATION_COOKIE, UUID);
+                let request = client
+                    .get("/fileo/memberships")
+                    .cookie(cookie)
+                    .cookie((USER_AUTHENTICATION_COOKIE, USER_UUID));
                 let response = request.dispatch().await;
 
-                assert_eq!(Status::NoContent, response.status());
+                assert_eq!(Status::Ok, response.status());
+                let diff: MembershipImportDiff = response.into_json().await.unwrap();
+                assert_eq!(
+                    MembershipImportDiff::new(vec![get_expected_membership()], vec![], vec![]),
+                    diff
+                );
 
                 let mut connection = client
                     .rocket()
@@ -392,6 +647,43 @@ mod tests {
                     .unwrap();
                 let membership = dao::membership::retrieve_memberships(&mut connection).unwrap();
                 assert_eq!(&get_expected_membership(), membership.first().unwrap());
+
+                let audit_log =
+                    dao::audit_log::retrieve(&mut connection, &AuditLogFilter::default()).unwrap();
+                assert_eq!("test_login", audit_log[0].actor());
+                assert_eq!(&AuditAction::DownloadMemberships, audit_log[0].action());
+            }
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn should_log_out_when_session_has_expired() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let mock_server = MockServer::start().await;
+
+                let config = create_memberships_provider_test_config(&mock_server.uri());
+                setup_load_list(&mock_server).await;
+                Mock::given(method("POST"))
+                    .and(path("/includer.php"))
+                    .and(query_param_contains("inc", "ajax/adherent/adherent_export"))
+                    .respond_with(ResponseTemplate::new(200).set_body_raw(
+                        r#"<form name="formConnecter" method="post"></form>"#,
+                        "text/html",
+                    ))
+                    .mount(&mock_server)
+                    .await;
+
+                let client = build_client(pool, config).await;
+                let cookie = Cookie::new(AUTHENTICATION_COOKIE, UUID);
+                let response = client
+                    .get("/fileo/memberships")
+                    .cookie(cookie)
+                    .cookie((USER_AUTHENTICATION_COOKIE, USER_UUID))
+                    .dispatch()
+                    .await;
+
+                assert_eq!(Status::Unauthorized, response.status());
+                assert!(!is_logged_in(&client));
             }
             with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
         }
@@ -402,17 +694,177 @@ mod tests {
                 let mock_server = MockServer::start().await;
 
                 let config = create_memberships_provider_test_config(&mock_server.uri());
+                let client = build_client(pool, config).await;
+                let cookie = Cookie::new(AUTHENTICATION_COOKIE, UUID);
+                let response = client
+                    .get("/fileo/memberships")
+                    .cookie(cookie)
+                    .cookie((USER_AUTHENTICATION_COOKIE, USER_UUID))
+                    .dispatch()
+                    .await;
 
-                let config_state = State::from(&config);
-                let credentials =
-                    FileoCredentials::new("test_login".to_owned(), "test_password".to_owned());
-                let pool_state = State::from(&pool);
+                assert_eq!(Status::InternalServerError, response.status());
+                assert!(is_logged_in(&client));
+            }
 
-                let result = download_memberships(config_state, pool_state, credentials).await;
-                assert_eq!(Status::InternalServerError, result.unwrap_err());
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn should_not_download_members_when_not_logged_into_fileo() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let mock_server = MockServer::start().await;
+
+                let config = create_memberships_provider_test_config(&mock_server.uri());
+                let client = build_client(pool, config).await;
+                let response = client
+                    .get("/fileo/memberships")
+                    .cookie((USER_AUTHENTICATION_COOKIE, USER_UUID))
+                    .dispatch()
+                    .await;
+
+                assert_eq!(Status::Unauthorized, response.status());
             }
 
             with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
         }
     }
+
+    mod upload_memberships {
+        use crate::database::{dao, with_temp_database};
+        use crate::user::authentication::AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::{USER_UUID, log_in};
+        use crate::web::api::fileo_controller::{preview_uploaded_memberships, upload_memberships};
+        use diesel::SqliteConnection;
+        use diesel::r2d2::{ConnectionManager, Pool};
+        use dto::membership::tests::{
+            get_expected_membership, get_malformed_membership_as_csv, get_membership_as_csv,
+        };
+        use dto::membership_import::{MembershipImportDiff, MembershipImportPreview};
+        use dto::user::Role;
+        use encoding::all::ISO_8859_1;
+        use encoding::{EncoderTrap, Encoding};
+        use rocket::http::{Cookie, Status};
+        use rocket::local::asynchronous::Client;
+        use rocket::tokio::runtime::Runtime;
+
+        /// Uploading memberships doesn't require to be logged into Fileo.
+        async fn build_client(pool: Pool<ConnectionManager<SqliteConnection>>) -> Client {
+            let credentials_storage = log_in(&pool, &[Role::RefreshMemberships]);
+
+            let rocket = rocket::build()
+                .manage(credentials_storage)
+                .manage(pool)
+                .mount(
+                    "/",
+                    routes![preview_uploaded_memberships, upload_memberships],
+                );
+            Client::tracked(rocket).await.unwrap()
+        }
+
+        fn encode(file_content: &str) -> Vec<u8> {
+            ISO_8859_1
+                .encode(file_content, EncoderTrap::Strict)
+                .unwrap()
+        }
+
+        #[test]
+        fn should_preview_uploaded_memberships() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool).await;
+                let file_content = format!(
+                    "{}\n{}",
+                    get_membership_as_csv(),
+                    get_malformed_membership_as_csv().lines().last().unwrap()
+                );
+
+                let response = client
+                    .post("/fileo/memberships/upload/preview")
+                    .cookie(Cookie::new(AUTHENTICATION_COOKIE, USER_UUID))
+                    .body(encode(&file_content))
+                    .dispatch()
+                    .await;
+
+                assert_eq!(Status::Ok, response.status());
+                let preview: MembershipImportPreview = response.into_json().await.unwrap();
+                assert_eq!(&1, preview.memberships_count());
+                assert_eq!(1, preview.rejected_rows().len());
+                assert!(preview.too_many_rejected_rows());
+
+                let mut connection = client
+                    .rocket()
+                    .state::<Pool<ConnectionManager<SqliteConnection>>>()
+                    .unwrap()
+                    .get()
+                    .unwrap();
+                let memberships = dao::membership::retrieve_memberships(&mut connection).unwrap();
+                assert!(memberships.is_empty());
+            }
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn should_upload_memberships() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool).await;
+
+                let response = client
+                    .post("/fileo/memberships/upload")
+                    .cookie(Cookie::new(AUTHENTICATION_COOKIE, USER_UUID))
+                    .body(encode(&get_membership_as_csv()))
+                    .dispatch()
+                    .await;
+
+                assert_eq!(Status::Ok, response.status());
+                let diff: MembershipImportDiff = response.into_json().await.unwrap();
+                assert_eq!(
+                    MembershipImportDiff::new(vec![get_expected_membership()], vec![], vec![]),
+                    diff
+                );
+
+                let mut connection = client
+                    .rocket()
+                    .state::<Pool<ConnectionManager<SqliteConnection>>>()
+                    .unwrap()
+                    .get()
+                    .unwrap();
+                let memberships = dao::membership::retrieve_memberships(&mut connection).unwrap();
+                assert_eq!(vec![get_expected_membership()], memberships);
+            }
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn fail_when_too_many_rejected_rows() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool).await;
+
+                let response = client
+                    .post("/fileo/memberships/upload")
+                    .cookie(Cookie::new(AUTHENTICATION_COOKIE, USER_UUID))
+                    .body(encode(&get_malformed_membership_as_csv()))
+                    .dispatch()
+                    .await;
+
+                assert_eq!(Status::UnprocessableEntity, response.status());
+            }
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn fail_when_not_authenticated() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool).await;
+
+                let response = client
+                    .post("/fileo/memberships/upload")
+                    .body(encode(&get_membership_as_csv()))
+                    .dispatch()
+                    .await;
+
+                assert_eq!(Status::Unauthorized, response.status());
+            }
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+    }
 }
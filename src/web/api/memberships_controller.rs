@@ -1,85 +1,431 @@
-use crate::fileo::credentials::FileoCredentials;
+use crate::database::dao;
+use crate::database::error::DatabaseError;
+use crate::error::ApplicationError;
 use crate::membership;
 use crate::membership::check::check_members;
-use crate::tools::email::send_email;
-use crate::tools::{log_error_and_return, log_message_and_return};
-use crate::uda::credentials::UdaCredentials;
+use crate::membership::check_session::compute_check_session_delta;
+use crate::membership::config::retrieve_expiring_soon_threshold;
+use crate::membership::error::MembershipError::InvalidEmailTemplate;
+use crate::membership::export::{ExportFormat, export_checked_members};
+use crate::membership::notification::{personalize_email, personalize_emails};
+use crate::membership::notification_queue::enqueue_notification;
+use crate::membership::spreadsheet::read_members_from_spreadsheet;
+use crate::tools::email::EmailAttachment;
+use crate::tools::log_error_and_return;
+use crate::tools::web::read_uploaded_file;
+use crate::uda::session::UdaSession;
+use crate::user::authentication::{Authorized, CanCheck, CanEmail, CanLookUp};
+use crate::web::audit::record_audit_log;
+use chrono::NaiveDate;
 use diesel::SqliteConnection;
 use diesel::r2d2::{ConnectionManager, Pool};
+use dto::audit_log::AuditAction;
+use dto::check_session::CheckSessionResults;
 use dto::checked_member::CheckedMember;
 use dto::csv_member::CsvMember;
-use dto::email::Email;
+use dto::email::{Email, NotificationStatus, PersonalizedEmail};
+use dto::event_period::EventPeriod;
 use dto::member_to_check::MemberToCheck;
 use dto::member_to_look_up::MemberToLookUp;
 use dto::uda_member::UdaMember;
+use rocket::Responder;
 use rocket::State;
-use rocket::http::Status;
+use rocket::data::{Data, ToByteUnit};
+use rocket::form::Form;
+use rocket::fs::TempFile;
+use rocket::http::{ContentType, Header, Status};
 use rocket::serde::json::{Json, json};
+use rocket::tokio::io::AsyncReadExt;
 
 /// Check members coming from a CSV file.
 /// Return the result as JSON-encoded string,
 /// within which each member having a valid membership has its last occurrence associated,
 /// while each member having no valid membership has no element associated.
+/// Memberships are checked against the event period, if any, or against today otherwise.
 #[post(
-    "/members/csv/check",
+    "/members/csv/check?<start_date>&<end_date>",
     format = "application/json",
     data = "<members_to_check>"
 )]
 pub async fn check_csv_members(
     pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
     members_to_check: Json<Vec<CsvMember>>,
-    _credentials: FileoCredentials,
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+    user: Authorized<CanCheck>,
 ) -> Result<String, Status> {
-    let result = check(pool.inner(), members_to_check.into_inner())?;
+    let event_period = parse_event_period(start_date, end_date)?;
+    let result = check(pool.inner(), members_to_check.into_inner(), &event_period)?;
+    let body = json!(result).to_string();
 
-    Ok(json!(result).to_string())
+    record_check_session(
+        pool.inner(),
+        user.login(),
+        &event_period,
+        &CheckSessionResults::Csv(result),
+    );
+    Ok(body)
 }
 
+/// Read members to check from an uploaded `.xlsx`, `.xls` or `.ods` file.
+/// Return them as JSON-encoded string, along with the rows that couldn't be read.
+#[post("/members/spreadsheet/load", data = "<file>")]
+pub async fn load_spreadsheet_members(
+    file: Data<'_>,
+    _user: Authorized<CanCheck>,
+) -> Result<String, Status> {
+    let file_content = read_uploaded_file(file).await?;
+    let members = read_members_from_spreadsheet(file_content).map_err(|error| {
+        debug!("Can't read members from spreadsheet [error: {error:?}]");
+        Status::BadRequest
+    })?;
+
+    Ok(json!(members).to_string())
+}
+
+/// Memberships are checked against the event period, if any, or against today otherwise.
 #[post(
-    "/members/uda/check",
+    "/members/uda/check?<start_date>&<end_date>",
     format = "application/json",
     data = "<members_to_check>"
 )]
 pub async fn check_uda_members(
     pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
     members_to_check: Json<Vec<UdaMember>>,
-    _fileo_credentials: FileoCredentials,
-    _uda_credentials: UdaCredentials,
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+    user: Authorized<CanCheck>,
+    uda_session: UdaSession,
+) -> Result<String, Status> {
+    let event_period = parse_event_period(start_date, end_date)?;
+    let result = check(pool.inner(), members_to_check.into_inner(), &event_period)?;
+    let body = json!(result).to_string();
+
+    record_check_session(
+        pool.inner(),
+        user.login(),
+        &event_period,
+        &CheckSessionResults::Uda(uda_session.instance_slug(), result),
+    );
+    Ok(body)
+}
+
+/// Compare the last check of an event for the current UDA instance with the previous one,
+/// to list newly registered participants, status changes and participants who dropped out.
+#[get("/members/uda/check/delta?<start_date>&<end_date>")]
+pub async fn get_uda_check_delta(
+    pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+    _user: Authorized<CanCheck>,
+    uda_session: UdaSession,
 ) -> Result<String, Status> {
-    let result = check(pool.inner(), members_to_check.into_inner())?;
+    let event_period = parse_event_period(start_date, end_date)?;
+    let mut connection = pool
+        .get()
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    let sessions = dao::check_session::retrieve_latest_for_uda_instance(
+        &mut connection,
+        &uda_session.instance_slug(),
+        &event_period,
+        2,
+    )
+    .map_err(log_error_and_return(Status::InternalServerError))?;
 
-    Ok(json!(result).to_string())
+    let Some(current) = sessions.first() else {
+        debug!("No check has been recorded for this event [event_period: {event_period:?}]");
+        return Err(Status::NotFound);
+    };
+    let delta = compute_check_session_delta(sessions.get(1), current);
+    Ok(json!(delta).to_string())
+}
+
+/// Check members coming from a CSV file, and export the result as a `csv`, `xlsx` or `pdf` file.
+#[post(
+    "/members/csv/check/export/<format>?<start_date>&<end_date>",
+    format = "application/json",
+    data = "<members_to_check>"
+)]
+pub async fn export_csv_members(
+    pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
+    members_to_check: Json<Vec<CsvMember>>,
+    format: &str,
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+    _user: Authorized<CanCheck>,
+) -> Result<ExportedFile, Status> {
+    let format = parse_export_format(format)?;
+    let event_period = parse_event_period(start_date, end_date)?;
+    let result = check(pool.inner(), members_to_check.into_inner(), &event_period)?;
+
+    export(&result, &event_period, format)
+}
+
+/// Check members coming from UDA, and export the result as a `csv`, `xlsx` or `pdf` file.
+#[post(
+    "/members/uda/check/export/<format>?<start_date>&<end_date>",
+    format = "application/json",
+    data = "<members_to_check>"
+)]
+pub async fn export_uda_members(
+    pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
+    members_to_check: Json<Vec<UdaMember>>,
+    format: &str,
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+    _user: Authorized<CanCheck>,
+    _uda_session: UdaSession,
+) -> Result<ExportedFile, Status> {
+    let format = parse_export_format(format)?;
+    let event_period = parse_event_period(start_date, end_date)?;
+    let result = check(pool.inner(), members_to_check.into_inner(), &event_period)?;
+
+    export(&result, &event_period, format)
+}
+
+/// A file to be downloaded by the user.
+#[derive(Responder)]
+pub struct ExportedFile {
+    content: Vec<u8>,
+    content_type: ContentType,
+    content_disposition: Header<'static>,
+}
+
+fn parse_export_format(format: &str) -> Result<ExportFormat, Status> {
+    ExportFormat::try_from(format).map_err(|error| {
+        debug!("{error}");
+        Status::BadRequest
+    })
+}
+
+fn export<T: MemberToCheck>(
+    checked_members: &[CheckedMember<T>],
+    event_period: &EventPeriod,
+    format: ExportFormat,
+) -> Result<ExportedFile, Status> {
+    let content = export_checked_members(checked_members, event_period, format)
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    let content_type = match format {
+        ExportFormat::Csv => ContentType::CSV,
+        ExportFormat::Xlsx => ContentType::new(
+            "application",
+            "vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        ),
+        ExportFormat::Pdf => ContentType::PDF,
+    };
+    let file_name = format!("verification-licences.{}", format.extension());
+
+    Ok(ExportedFile {
+        content,
+        content_type,
+        content_disposition: Header::new(
+            "Content-Disposition",
+            format!("attachment; filename=\"{file_name}\""),
+        ),
+    })
+}
+
+/// Record the check session, so that it can be reopened later on.
+/// Failing to do so doesn't fail the check, whose results are still returned.
+fn record_check_session(
+    pool: &Pool<ConnectionManager<SqliteConnection>>,
+    user_login: &str,
+    event_period: &EventPeriod,
+    results: &CheckSessionResults,
+) {
+    let recorded = pool
+        .get()
+        .map_err(DatabaseError::from)
+        .and_then(|mut connection| {
+            dao::check_session::insert(&mut connection, user_login, event_period, results)
+        });
+    if let Err(error) = recorded {
+        error!("Can't record check session [error: {error:?}]");
+    }
 }
 
 fn check<T: MemberToCheck>(
     pool: &Pool<ConnectionManager<SqliteConnection>>,
     members_to_check: Vec<T>,
+    event_period: &EventPeriod,
 ) -> Result<Vec<CheckedMember<T>>, Status> {
-    let checked_members = check_members(pool, members_to_check)
-        .map_err(log_error_and_return(Status::InternalServerError))?;
+    let expiring_soon_threshold = retrieve_expiring_soon_threshold();
+    let checked_members = check_members(
+        pool,
+        members_to_check,
+        event_period,
+        expiring_soon_threshold,
+    )
+    .map_err(log_error_and_return(Status::InternalServerError))?;
 
     Ok(checked_members)
 }
 
-/// Email all recipients specified as argument.
-#[post("/members/notify", format = "application/json", data = "<email>")]
-pub async fn notify_members(
+/// Build the event period from dates formatted as `YYYY-MM-DD`.
+/// Without any date, the event is considered to happen today.
+/// Without an end date, the event is considered to last a single day.
+fn parse_event_period(
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+) -> Result<EventPeriod, Status> {
+    let parse_date = |date: &str| {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|error| {
+            debug!("Can't parse event date [date: {date}, error: {error:?}]");
+            Status::BadRequest
+        })
+    };
+
+    match (start_date, end_date) {
+        (None, None) => Ok(EventPeriod::today()),
+        (Some(start_date), None) => Ok(EventPeriod::single_day(parse_date(start_date)?)),
+        (Some(start_date), Some(end_date)) => {
+            EventPeriod::new(parse_date(start_date)?, parse_date(end_date)?).ok_or_else(|| {
+                debug!("Event can't end before it starts [start: {start_date}, end: {end_date}]");
+                Status::BadRequest
+            })
+        }
+        (None, Some(end_date)) => {
+            debug!("Event can't have an end date without a start date [end: {end_date}]");
+            Err(Status::BadRequest)
+        }
+    }
+}
+
+/// The total size of the files attached to a notification.
+/// Email providers usually reject larger messages, once attachments are encoded.
+const MAX_ATTACHMENTS_SIZE_IN_MEBIBYTES: u64 = 10;
+
+/// A notification, sent as a multipart form so that files can be attached to it.
+#[derive(FromForm)]
+pub struct Notification<'r> {
     email: Json<Email>,
-    _credentials: FileoCredentials,
-) -> Result<(), String> {
-    let recipients = email
-        .recipients()
+    attachments: Vec<TempFile<'r>>,
+}
+
+/// Queue an email for each recipient, with the subject and the body rendered from their membership.
+/// Emails are sent in the background: the ID of the notification is returned, so that its progress can be followed.
+#[post("/members/notify", data = "<notification>")]
+pub async fn notify_members(
+    pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
+    notification: Form<Notification<'_>>,
+    user: Authorized<CanEmail>,
+) -> Result<Json<i32>, Status> {
+    let attachments = read_attachments(&notification.attachments).await?;
+
+    let mut connection = pool
+        .get()
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    let emails = personalize_emails(
+        &mut connection,
+        &notification.email,
+        retrieve_expiring_soon_threshold(),
+    )
+    .map_err(map_personalization_error)?;
+    let notification_id =
+        enqueue_notification(&mut connection, user.login(), &emails, &attachments)
+            .map_err(log_error_and_return(Status::InternalServerError))?;
+
+    let recipients: Vec<String> = emails
         .iter()
-        .map(|email| email.as_ref())
-        .collect::<Vec<&str>>();
-    send_email(recipients.as_ref(), email.subject(), email.body())
-        .await
-        .map_err(log_message_and_return(
-            "Couldn't send email",
-            "Email has not been sent.",
-        ))?;
-
-    Ok(())
+        .map(|email| email.recipient().to_owned())
+        .collect();
+    record_audit_log(
+        pool.inner(),
+        user.login(),
+        AuditAction::NotifyMembers,
+        &recipients,
+    );
+    Ok(Json(notification_id))
+}
+
+/// Tell whether each email of the notification has been sent, has failed, or is still pending.
+#[get("/members/notify/<notification_id>")]
+pub async fn get_notification_status(
+    pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
+    notification_id: i32,
+    _user: Authorized<CanEmail>,
+) -> Result<Json<NotificationStatus>, Status> {
+    let mut connection = pool
+        .get()
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    dao::notification::retrieve_status(&mut connection, notification_id)
+        .map_err(log_error_and_return(Status::InternalServerError))?
+        .map(Json)
+        .ok_or(Status::NotFound)
+}
+
+async fn read_attachments(files: &[TempFile<'_>]) -> Result<Vec<EmailAttachment>, Status> {
+    let total_size: u64 = files.iter().map(TempFile::len).sum();
+    if total_size > MAX_ATTACHMENTS_SIZE_IN_MEBIBYTES.mebibytes().as_u64() {
+        debug!("Attachments are too large [size: {total_size}]");
+        return Err(Status::PayloadTooLarge);
+    }
+
+    let mut attachments = vec![];
+    for file in files {
+        let content_type = file.content_type().cloned().unwrap_or(ContentType::Binary);
+        // Only the sanitized name is kept, the extension is deduced from the content type.
+        let name = file.name().unwrap_or("piece-jointe");
+        let file_name = match content_type.extension() {
+            Some(extension) => format!("{name}.{extension}"),
+            None => name.to_owned(),
+        };
+
+        let mut content = vec![];
+        file.open()
+            .await
+            .map_err(log_error_and_return(Status::InternalServerError))?
+            .read_to_end(&mut content)
+            .await
+            .map_err(log_error_and_return(Status::InternalServerError))?;
+        attachments.push(EmailAttachment::new(
+            file_name,
+            content_type.to_string(),
+            content,
+        ));
+    }
+    Ok(attachments)
+}
+
+/// Render the email the recipient at the given index would receive, without sending anything.
+#[post(
+    "/members/notify/preview?<index>",
+    format = "application/json",
+    data = "<email>"
+)]
+pub async fn preview_notification(
+    pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
+    email: Json<Email>,
+    index: usize,
+    _user: Authorized<CanEmail>,
+) -> Result<Json<PersonalizedEmail>, Status> {
+    let recipient = email.recipients().get(index).ok_or_else(|| {
+        debug!("No such recipient [index: {index}]");
+        Status::NotFound
+    })?;
+
+    let mut connection = pool
+        .get()
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    let personalized_email = personalize_email(
+        &mut connection,
+        &email,
+        recipient,
+        retrieve_expiring_soon_threshold(),
+    )
+    .map_err(map_personalization_error)?;
+    Ok(Json(personalized_email))
+}
+
+/// Templates written by the organizer may be wrong, which is their mistake rather than the server's one.
+fn map_personalization_error(error: ApplicationError) -> Status {
+    match error {
+        ApplicationError::Membership(InvalidEmailTemplate(error)) => {
+            debug!("Can't render email template [error: {error}]");
+            Status::BadRequest
+        }
+        _ => log_error_and_return(Status::InternalServerError)(error),
+    }
 }
 
 #[post(
@@ -90,7 +436,7 @@ pub async fn notify_members(
 pub async fn look_member_up(
     pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
     member_to_look_up: Json<MemberToLookUp>,
-    _credentials: FileoCredentials,
+    user: Authorized<CanLookUp>,
 ) -> Result<String, Status> {
     let member_to_look_up = member_to_look_up.into_inner();
 
@@ -108,38 +454,36 @@ pub async fn look_member_up(
     let memberships = membership::look_up::look_member_up(&mut connection, &member_to_look_up)
         .map_err(log_error_and_return(Status::InternalServerError))?;
 
+    let membership_numbers: Vec<String> = memberships
+        .iter()
+        .map(|membership| membership.membership_number().clone())
+        .collect();
+    record_audit_log(
+        pool.inner(),
+        user.login(),
+        AuditAction::LookMemberUp,
+        &membership_numbers,
+    );
     Ok(json!(memberships).to_string())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::fileo::credentials::FileoCredentials;
-    use crate::uda::credentials::UdaCredentials;
+    use crate::tools::web::SessionCookies;
+    use crate::uda::session::UdaSession;
     use crate::web::credentials_storage::CredentialsStorage;
     use std::sync::Mutex;
 
-    fn initialize_fileo_login() -> (String, Mutex<CredentialsStorage<FileoCredentials>>) {
-        let credentials =
-            FileoCredentials::new("test_login".to_owned(), "test_password".to_owned());
-
-        let uuid = "e9af5e0f-c441-4bcd-bf22-31cc5b1f2f9e".to_owned();
-        let mut storage = CredentialsStorage::<FileoCredentials>::default();
-        storage.store(uuid.clone(), credentials);
-
-        let storage_mutex = Mutex::new(storage);
-        (uuid, storage_mutex)
-    }
-
-    fn initialize_uda_login() -> (String, Mutex<CredentialsStorage<UdaCredentials>>) {
-        let credentials = UdaCredentials::new(
+    fn initialize_uda_login() -> (String, Mutex<CredentialsStorage<UdaSession>>) {
+        let session = UdaSession::new(
             "https://test.reg.unicycling-software.com".to_owned(),
             "login@test.com".to_owned(),
-            "password".to_owned(),
+            SessionCookies::default(),
         );
 
         let uuid = "e9af5e0f-c441-4bcd-bf22-31cc5b1f2f9e".to_owned();
-        let mut storage = CredentialsStorage::<UdaCredentials>::default();
-        storage.store(uuid.clone(), credentials);
+        let mut storage = CredentialsStorage::<UdaSession>::default();
+        storage.store(uuid.clone(), session).unwrap();
 
         let storage_mutex = Mutex::new(storage);
         (uuid, storage_mutex)
@@ -147,15 +491,17 @@ mod tests {
 
     mod check_members {
         use crate::database::with_temp_database;
+        use crate::membership::config::retrieve_expiring_soon_threshold;
+        use crate::user::authentication::tests::{USER_UUID, log_in};
         use crate::web::api::memberships_controller::check_uda_members;
-        use crate::web::api::memberships_controller::tests::{
-            initialize_fileo_login, initialize_uda_login,
-        };
+        use crate::web::api::memberships_controller::tests::initialize_uda_login;
         use diesel::SqliteConnection;
         use diesel::r2d2::{ConnectionManager, Pool};
         use dto::checked_member::{CheckResult, CheckedMember};
+        use dto::event_period::EventPeriod;
         use dto::membership::tests::get_expected_membership;
         use dto::uda_member::UdaMember;
+        use dto::user::Role;
         use rocket::http::hyper::header::CONTENT_TYPE;
         use rocket::http::{ContentType, Header, Status};
         use rocket::local::asynchronous::Client;
@@ -170,6 +516,7 @@ mod tests {
                     Some("123456".to_owned()),
                     "Jon".to_owned(),
                     "Doe".to_owned(),
+                    None,
                     "jon.doe@email.com".to_owned(),
                     Some("Le club de test".to_owned()),
                     true,
@@ -179,24 +526,25 @@ mod tests {
                     Some("654321".to_owned()),
                     "Jonette".to_owned(),
                     "Snow".to_owned(),
+                    None,
                     "jonette.snow@email.com".to_owned(),
                     None,
                     false,
                 );
                 let members = vec![member_1.clone(), member_2.clone()];
 
-                let (fileo_uuid, fileo_credentials_storage_mutex) = initialize_fileo_login();
+                let user_credentials_storage_mutex = log_in(&pool, &[Role::Check]);
                 let (uda_uuid, uda_credentials_storage_mutex) = initialize_uda_login();
 
                 let mut connection = pool.get().unwrap();
-                crate::database::dao::membership::replace_memberships(
+                crate::database::dao::membership::import_memberships(
                     &mut connection,
                     &[get_expected_membership()],
                 )
                 .unwrap();
 
                 let rocket = rocket::build()
-                    .manage(fileo_credentials_storage_mutex)
+                    .manage(user_credentials_storage_mutex)
                     .manage(uda_credentials_storage_mutex)
                     .manage(pool)
                     .mount("/", routes![check_uda_members]);
@@ -205,8 +553,8 @@ mod tests {
                 let request = client
                     .post("/members/uda/check")
                     .cookie((
-                        crate::fileo::authentication::AUTHENTICATION_COOKIE,
-                        fileo_uuid,
+                        crate::user::authentication::AUTHENTICATION_COOKIE,
+                        USER_UUID,
                     ))
                     .cookie((crate::uda::authentication::AUTHENTICATION_COOKIE, uda_uuid))
                     .body(json!(members).to_string().as_bytes())
@@ -222,8 +570,18 @@ mod tests {
                     response.into_json().await.unwrap();
                 assert_eq!(
                     vec![
-                        CheckedMember::new(member_1, CheckResult::Match(get_expected_membership())),
-                        CheckedMember::new(member_2, CheckResult::NoMatch),
+                        CheckedMember::new(
+                            member_1,
+                            CheckResult::Match(get_expected_membership()),
+                            &EventPeriod::today(),
+                            retrieve_expiring_soon_threshold(),
+                        ),
+                        CheckedMember::new(
+                            member_2,
+                            CheckResult::NoMatch,
+                            &EventPeriod::today(),
+                            retrieve_expiring_soon_threshold(),
+                        ),
                     ],
                     checked_members
                 )
@@ -231,33 +589,515 @@ mod tests {
 
             with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
         }
+
+        #[test]
+        fn bad_request_when_event_ends_before_starting() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let user_credentials_storage_mutex = log_in(&pool, &[Role::Check]);
+                let (uda_uuid, uda_credentials_storage_mutex) = initialize_uda_login();
+
+                let rocket = rocket::build()
+                    .manage(user_credentials_storage_mutex)
+                    .manage(uda_credentials_storage_mutex)
+                    .manage(pool)
+                    .mount("/", routes![check_uda_members]);
+
+                let client = Client::tracked(rocket).await.unwrap();
+                let request = client
+                    .post("/members/uda/check?start_date=2025-07-05&end_date=2025-07-01")
+                    .cookie((
+                        crate::user::authentication::AUTHENTICATION_COOKIE,
+                        USER_UUID,
+                    ))
+                    .cookie((crate::uda::authentication::AUTHENTICATION_COOKIE, uda_uuid))
+                    .body("[]")
+                    .header(Header::new(
+                        CONTENT_TYPE.to_string(),
+                        ContentType::JSON.to_string(),
+                    ));
+
+                let response = request.dispatch().await;
+                assert_eq!(Status::BadRequest, response.status());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+    }
+
+    mod get_uda_check_delta {
+        use crate::database::with_temp_database;
+        use crate::user::authentication::tests::{USER_UUID, log_in};
+        use crate::web::api::memberships_controller::tests::initialize_uda_login;
+        use crate::web::api::memberships_controller::{check_uda_members, get_uda_check_delta};
+        use diesel::SqliteConnection;
+        use diesel::r2d2::{ConnectionManager, Pool};
+        use dto::check_session::{CheckSessionDelta, ComparedMember};
+        use dto::membership_status::MemberStatus::Unknown;
+        use dto::uda_member::UdaMember;
+        use dto::user::Role;
+        use rocket::http::hyper::header::CONTENT_TYPE;
+        use rocket::http::{ContentType, Header, Status};
+        use rocket::local::asynchronous::Client;
+        use rocket::serde::json::json;
+        use rocket::tokio::runtime::Runtime;
+
+        async fn build_client(pool: Pool<ConnectionManager<SqliteConnection>>) -> (Client, String) {
+            let user_credentials_storage_mutex = log_in(&pool, &[Role::Check]);
+            let (uda_uuid, uda_credentials_storage_mutex) = initialize_uda_login();
+
+            let rocket = rocket::build()
+                .manage(user_credentials_storage_mutex)
+                .manage(uda_credentials_storage_mutex)
+                .manage(pool)
+                .mount("/", routes![check_uda_members, get_uda_check_delta]);
+
+            (Client::tracked(rocket).await.unwrap(), uda_uuid)
+        }
+
+        #[test]
+        fn success() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let member_1 = UdaMember::new(
+                    1,
+                    None,
+                    "Jon".to_owned(),
+                    "Doe".to_owned(),
+                    None,
+                    "jon.doe@email.com".to_owned(),
+                    None,
+                    true,
+                );
+                let member_2 = UdaMember::new(
+                    2,
+                    None,
+                    "Jonette".to_owned(),
+                    "Snow".to_owned(),
+                    None,
+                    "jonette.snow@email.com".to_owned(),
+                    None,
+                    false,
+                );
+                let (client, uda_uuid) = build_client(pool).await;
+
+                for members in [vec![member_1.clone()], vec![member_1, member_2]] {
+                    let response = client
+                        .post("/members/uda/check?start_date=2025-07-05")
+                        .cookie((
+                            crate::user::authentication::AUTHENTICATION_COOKIE,
+                            USER_UUID,
+                        ))
+                        .cookie((
+                            crate::uda::authentication::AUTHENTICATION_COOKIE,
+                            uda_uuid.clone(),
+                        ))
+                        .body(json!(members).to_string().as_bytes())
+                        .header(Header::new(
+                            CONTENT_TYPE.to_string(),
+                            ContentType::JSON.to_string(),
+                        ))
+                        .dispatch()
+                        .await;
+                    assert_eq!(Status::Ok, response.status());
+                }
+
+                let response = client
+                    .get("/members/uda/check/delta?start_date=2025-07-05")
+                    .cookie((
+                        crate::user::authentication::AUTHENTICATION_COOKIE,
+                        USER_UUID,
+                    ))
+                    .cookie((crate::uda::authentication::AUTHENTICATION_COOKIE, uda_uuid))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Ok, response.status());
+
+                let delta: CheckSessionDelta = response.into_json().await.unwrap();
+                assert!(delta.previous().is_some());
+                assert_eq!(
+                    &vec![ComparedMember::new(
+                        "Jonette Snow".to_owned(),
+                        None,
+                        Some(Unknown)
+                    )],
+                    delta.new_members()
+                );
+                assert!(delta.status_changes().is_empty());
+                assert!(delta.dropped_members().is_empty());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn not_found_without_any_check() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let (client, uda_uuid) = build_client(pool).await;
+
+                let response = client
+                    .get("/members/uda/check/delta?start_date=2025-07-05")
+                    .cookie((
+                        crate::user::authentication::AUTHENTICATION_COOKIE,
+                        USER_UUID,
+                    ))
+                    .cookie((crate::uda::authentication::AUTHENTICATION_COOKIE, uda_uuid))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::NotFound, response.status());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+    }
+
+    mod parse_event_period {
+        use crate::web::api::memberships_controller::parse_event_period;
+        use chrono::NaiveDate;
+        use dto::event_period::EventPeriod;
+        use rocket::http::Status;
+
+        #[test]
+        fn today_when_no_date() {
+            assert_eq!(Ok(EventPeriod::today()), parse_event_period(None, None));
+        }
+
+        #[test]
+        fn single_day_when_no_end_date() {
+            assert_eq!(
+                Ok(EventPeriod::single_day(
+                    NaiveDate::from_ymd_opt(2025, 7, 1).unwrap()
+                )),
+                parse_event_period(Some("2025-07-01"), None)
+            );
+        }
+
+        #[test]
+        fn period_when_both_dates() {
+            let expected_period = EventPeriod::new(
+                NaiveDate::from_ymd_opt(2025, 7, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2025, 7, 5).unwrap(),
+            )
+            .unwrap();
+            assert_eq!(
+                Ok(expected_period),
+                parse_event_period(Some("2025-07-01"), Some("2025-07-05"))
+            );
+        }
+
+        #[test]
+        fn fail_when_invalid_date() {
+            assert_eq!(
+                Err(Status::BadRequest),
+                parse_event_period(Some("01/07/2025"), None)
+            );
+        }
+
+        #[test]
+        fn fail_when_no_start_date() {
+            assert_eq!(
+                Err(Status::BadRequest),
+                parse_event_period(None, Some("2025-07-05"))
+            );
+        }
+    }
+
+    mod notify_members {
+        use crate::database::with_temp_database;
+        use crate::user::authentication::AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::{USER_UUID, log_in};
+        use crate::web::api::memberships_controller::{get_notification_status, notify_members};
+        use diesel::SqliteConnection;
+        use diesel::r2d2::{ConnectionManager, Pool};
+        use dto::email::EmailStatus::Pending;
+        use dto::email::{Email, EmailRecipient, NotificationStatus, RecipientStatus};
+        use dto::user::Role;
+        use rocket::http::{ContentType, Status};
+        use rocket::local::asynchronous::Client;
+        use rocket::serde::json::json;
+        use rocket::tokio::runtime::Runtime;
+
+        const BOUNDARY: &str = "notification-boundary";
+
+        async fn build_client(pool: Pool<ConnectionManager<SqliteConnection>>) -> Client {
+            let user_credentials_storage_mutex = log_in(&pool, &[Role::Email]);
+            let rocket = rocket::build()
+                .manage(user_credentials_storage_mutex)
+                .manage(pool)
+                .mount("/", routes![notify_members, get_notification_status]);
+
+            Client::tracked(rocket).await.unwrap()
+        }
+
+        fn email(body: &str) -> Email {
+            Email::new(
+                vec![EmailRecipient::new("jon.doe@address.com".to_owned(), None)],
+                "Licence".to_owned(),
+                body.to_owned(),
+                None,
+                None,
+            )
+        }
+
+        fn build_form(email: &Email, attachments: &[Vec<u8>]) -> Vec<u8> {
+            let mut form = format!(
+                "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"email\"\r\n\r\n{}\r\n",
+                json!(email)
+            )
+            .into_bytes();
+            for attachment in attachments {
+                form.extend_from_slice(
+                    format!("--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"attachments\"; filename=\"rules.pdf\"\r\nContent-Type: application/pdf\r\n\r\n").as_bytes(),
+                );
+                form.extend_from_slice(attachment);
+                form.extend_from_slice(b"\r\n");
+            }
+            form.extend_from_slice(format!("--{BOUNDARY}--\r\n").as_bytes());
+            form
+        }
+
+        fn content_type() -> ContentType {
+            ContentType::new("multipart", "form-data").with_params(("boundary", BOUNDARY))
+        }
+
+        #[test]
+        fn queue_emails() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool).await;
+
+                let response = client
+                    .post("/members/notify")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .header(content_type())
+                    .body(build_form(&email("Bonjour"), &[b"%PDF-1.4".to_vec()]))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Ok, response.status());
+                let notification_id: i32 = response.into_json().await.unwrap();
+
+                let response = client
+                    .get(format!("/members/notify/{notification_id}"))
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Ok, response.status());
+                assert_eq!(
+                    Some(NotificationStatus::new(
+                        notification_id,
+                        vec![RecipientStatus::new(
+                            "jon.doe@address.com".to_owned(),
+                            Pending,
+                            0,
+                            None
+                        )]
+                    )),
+                    response.into_json().await
+                );
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn not_found_when_unknown_notification() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool).await;
+
+                let response = client
+                    .get("/members/notify/1")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::NotFound, response.status());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn bad_request_when_invalid_template() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool).await;
+
+                let response = client
+                    .post("/members/notify")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .header(content_type())
+                    .body(build_form(
+                        &email("Bonjour {{ first_name"),
+                        &[b"%PDF-1.4".to_vec()],
+                    ))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::BadRequest, response.status());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn payload_too_large_when_attachments_are_too_large() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool).await;
+                let attachment = vec![0; 6 * 1024 * 1024];
+
+                let response = client
+                    .post("/members/notify")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .header(content_type())
+                    .body(build_form(
+                        &email("Bonjour"),
+                        &[attachment.clone(), attachment],
+                    ))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::PayloadTooLarge, response.status());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+    }
+
+    mod preview_notification {
+        use crate::database::{dao, with_temp_database};
+        use crate::membership::tests::jon_doe;
+        use crate::user::authentication::AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::{USER_UUID, log_in};
+        use crate::web::api::memberships_controller::preview_notification;
+        use chrono::NaiveDate;
+        use diesel::SqliteConnection;
+        use diesel::r2d2::{ConnectionManager, Pool};
+        use dto::email::{Email, EmailRecipient, PersonalizedEmail};
+        use dto::event_period::EventPeriod;
+        use dto::user::Role;
+        use rocket::http::{ContentType, Status};
+        use rocket::local::asynchronous::Client;
+        use rocket::serde::json::json;
+        use rocket::tokio::runtime::Runtime;
+
+        async fn build_client(pool: Pool<ConnectionManager<SqliteConnection>>) -> Client {
+            let user_credentials_storage_mutex = log_in(&pool, &[Role::Email]);
+            let rocket = rocket::build()
+                .manage(user_credentials_storage_mutex)
+                .manage(pool)
+                .mount("/", routes![preview_notification]);
+
+            Client::tracked(rocket).await.unwrap()
+        }
+
+        fn email(body: &str) -> Email {
+            Email::new(
+                vec![EmailRecipient::new(
+                    "jon.doe@address.com".to_owned(),
+                    Some(jon_doe().membership_number().to_owned()),
+                )],
+                "Licence".to_owned(),
+                body.to_owned(),
+                None,
+                Some(EventPeriod::single_day(
+                    NaiveDate::from_ymd_opt(2025, 6, 1).unwrap(),
+                )),
+            )
+        }
+
+        #[test]
+        fn success() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let mut connection = pool.get().unwrap();
+                dao::membership::import_memberships(&mut connection, &[jon_doe()]).unwrap();
+                let client = build_client(pool).await;
+
+                let response = client
+                    .post("/members/notify/preview?index=0")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .header(ContentType::JSON)
+                    .body(json!(email("Bonjour {{ first_name }}")).to_string())
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Ok, response.status());
+
+                let personalized_email: PersonalizedEmail = response.into_json().await.unwrap();
+                assert_eq!(
+                    PersonalizedEmail::new(
+                        "jon.doe@address.com".to_owned(),
+                        "Licence".to_owned(),
+                        "Bonjour Jon".to_owned(),
+                        "<p>Bonjour Jon</p>\n".to_owned()
+                    ),
+                    personalized_email
+                );
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn not_found_when_unknown_recipient() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool).await;
+
+                let response = client
+                    .post("/members/notify/preview?index=1")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .header(ContentType::JSON)
+                    .body(json!(email("Bonjour")).to_string())
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::NotFound, response.status());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn bad_request_when_invalid_template() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool).await;
+
+                let response = client
+                    .post("/members/notify/preview?index=0")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .header(ContentType::JSON)
+                    .body(json!(email("Bonjour {{ first_name")).to_string())
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::BadRequest, response.status());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
     }
 
     mod look_member_up {
         use crate::database::{dao, with_temp_database};
-        use crate::fileo::authentication::AUTHENTICATION_COOKIE;
         use crate::membership::tests::{
             jon_doe, jon_doe_previous_membership, jonette_snow, other_jon_doe,
         };
+        use crate::user::api_token::create_api_token;
+        use crate::user::authentication::AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::{USER_UUID, log_in};
+        use crate::user::session::UserSession;
         use crate::web::api::memberships_controller::look_member_up;
-        use crate::web::api::memberships_controller::tests::initialize_fileo_login;
+        use crate::web::credentials_storage::CredentialsStorage;
         use diesel::SqliteConnection;
         use diesel::r2d2::{ConnectionManager, Pool};
+        use dto::api_token::NewApiToken;
+        use dto::audit_log::{AuditAction, AuditLogFilter};
         use dto::member_to_look_up::MemberToLookUp;
         use dto::membership::Membership;
+        use dto::user::Role;
         use rocket::http::hyper::header::CONTENT_TYPE;
         use rocket::http::{ContentType, Header, Status};
         use rocket::local::asynchronous::Client;
         use rocket::serde::json::json;
         use rocket::tokio::runtime::Runtime;
+        use std::sync::Mutex;
 
         #[test]
         fn success() {
             async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
-                let (fileo_uuid, fileo_credentials_storage_mutex) = initialize_fileo_login();
+                let user_credentials_storage_mutex = log_in(&pool, &[Role::LookUp]);
 
                 let mut connection = pool.get().unwrap();
-                dao::membership::replace_memberships(
+                dao::membership::import_memberships(
                     &mut connection,
                     &[
                         jon_doe(),
@@ -269,7 +1109,7 @@ mod tests {
                 .unwrap();
 
                 let rocket = rocket::build()
-                    .manage(fileo_credentials_storage_mutex)
+                    .manage(user_credentials_storage_mutex)
                     .manage(pool)
                     .mount("/", routes![look_member_up]);
 
@@ -279,7 +1119,7 @@ mod tests {
                     MemberToLookUp::new(Some(jon_doe().membership_number().to_owned()), None, None);
                 let request = client
                     .post("/members/lookup")
-                    .cookie((AUTHENTICATION_COOKIE, fileo_uuid))
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
                     .body(json!(member_to_look_up).to_string().as_bytes())
                     .header(Header::new(
                         CONTENT_TYPE.to_string(),
@@ -293,7 +1133,65 @@ mod tests {
                 assert_eq!(
                     vec![jon_doe_previous_membership(), jon_doe()],
                     matching_memberships
-                )
+                );
+
+                let mut connection = client
+                    .rocket()
+                    .state::<Pool<ConnectionManager<SqliteConnection>>>()
+                    .unwrap()
+                    .get()
+                    .unwrap();
+                let audit_log =
+                    dao::audit_log::retrieve(&mut connection, &AuditLogFilter::default()).unwrap();
+                assert_eq!(1, audit_log.len());
+                assert_eq!("test_login", audit_log[0].actor());
+                assert_eq!(&AuditAction::LookMemberUp, audit_log[0].action());
+                assert_eq!(
+                    &vec![jon_doe().membership_number().to_owned(); 2],
+                    audit_log[0].targets()
+                );
+            }
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn success_with_api_token() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let mut connection = pool.get().unwrap();
+                dao::membership::import_memberships(&mut connection, &[jon_doe()]).unwrap();
+                let new_token = NewApiToken::new("inscriptions".to_owned(), vec![Role::LookUp]);
+                let created = create_api_token(&mut connection, &new_token, "jon").unwrap();
+
+                let rocket = rocket::build()
+                    .manage(Mutex::new(CredentialsStorage::<UserSession>::default()))
+                    .manage(pool)
+                    .mount("/", routes![look_member_up]);
+
+                let client = Client::tracked(rocket).await.unwrap();
+
+                let member_to_look_up =
+                    MemberToLookUp::new(Some(jon_doe().membership_number().to_owned()), None, None);
+                let request = client
+                    .post("/members/lookup")
+                    .header(Header::new(
+                        "Authorization",
+                        format!("Bearer {}", created.token()),
+                    ))
+                    .body(json!(member_to_look_up).to_string().as_bytes())
+                    .header(Header::new(
+                        CONTENT_TYPE.to_string(),
+                        ContentType::JSON.to_string(),
+                    ));
+
+                let response = request.dispatch().await;
+                assert_eq!(Status::Ok, response.status());
+
+                let audit_log =
+                    dao::audit_log::retrieve(&mut connection, &AuditLogFilter::default()).unwrap();
+                assert_eq!(
+                    &format!("Jeton n°{} (inscriptions)", created.id()),
+                    audit_log[0].actor()
+                );
             }
             with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
         }
@@ -301,9 +1199,9 @@ mod tests {
         #[test]
         fn bad_request() {
             async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
-                let (fileo_uuid, fileo_credentials_storage_mutex) = initialize_fileo_login();
+                let user_credentials_storage_mutex = log_in(&pool, &[Role::LookUp]);
                 let mut connection = pool.get().unwrap();
-                dao::membership::replace_memberships(
+                dao::membership::import_memberships(
                     &mut connection,
                     &[
                         jon_doe(),
@@ -315,7 +1213,7 @@ mod tests {
                 .unwrap();
 
                 let rocket = rocket::build()
-                    .manage(fileo_credentials_storage_mutex)
+                    .manage(user_credentials_storage_mutex)
                     .manage(pool)
                     .mount("/", routes![look_member_up]);
 
@@ -324,7 +1222,7 @@ mod tests {
                 let member_to_look_up = MemberToLookUp::new(None, None, None);
                 let request = client
                     .post("/members/lookup")
-                    .cookie((AUTHENTICATION_COOKIE, fileo_uuid))
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
                     .body(json!(member_to_look_up).to_string().as_bytes())
                     .header(Header::new(
                         CONTENT_TYPE.to_string(),
@@ -337,4 +1235,179 @@ mod tests {
             with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
         }
     }
+
+    mod export_csv_members {
+        use crate::database::with_temp_database;
+        use crate::user::authentication::AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::{USER_UUID, log_in};
+        use crate::web::api::memberships_controller::export_csv_members;
+        use diesel::SqliteConnection;
+        use diesel::r2d2::{ConnectionManager, Pool};
+        use dto::csv_member::CsvMember;
+        use dto::membership::tests::get_expected_membership;
+        use dto::user::Role;
+        use rocket::http::{ContentType, Status};
+        use rocket::local::asynchronous::Client;
+        use rocket::serde::json::json;
+        use rocket::tokio::runtime::Runtime;
+
+        async fn build_client(pool: Pool<ConnectionManager<SqliteConnection>>) -> Client {
+            let user_credentials_storage_mutex = log_in(&pool, &[Role::Check]);
+            let mut connection = pool.get().unwrap();
+            crate::database::dao::membership::import_memberships(
+                &mut connection,
+                &[get_expected_membership()],
+            )
+            .unwrap();
+
+            let rocket = rocket::build()
+                .manage(user_credentials_storage_mutex)
+                .manage(pool)
+                .mount("/", routes![export_csv_members]);
+
+            Client::tracked(rocket).await.unwrap()
+        }
+
+        fn members() -> String {
+            json!(vec![CsvMember::new(
+                Some("123456".to_owned()),
+                None,
+                Some("Doe".to_owned()),
+                Some("Jon".to_owned()),
+                None,
+                None,
+                None,
+            )])
+            .to_string()
+        }
+
+        #[test]
+        fn success() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool).await;
+
+                let response = client
+                    .post("/members/csv/check/export/csv")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .header(ContentType::JSON)
+                    .body(members())
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Ok, response.status());
+                assert_eq!(Some(ContentType::CSV), response.content_type());
+                assert_eq!(
+                    Some("attachment; filename=\"verification-licences.csv\""),
+                    response.headers().get_one("Content-Disposition")
+                );
+
+                let content = response.into_string().await.unwrap();
+                assert!(content.contains("123456;Doe;Jon;;;;Correspondance;123456;"));
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn bad_request_when_unknown_format() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool).await;
+
+                let response = client
+                    .post("/members/csv/check/export/docx")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .header(ContentType::JSON)
+                    .body(members())
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::BadRequest, response.status());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+    }
+
+    mod load_spreadsheet_members {
+        use crate::database::with_temp_database;
+        use crate::user::authentication::AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::{USER_UUID, log_in};
+        use crate::web::api::memberships_controller::load_spreadsheet_members;
+        use diesel::SqliteConnection;
+        use diesel::r2d2::{ConnectionManager, Pool};
+        use dto::csv_member::SpreadsheetMembers;
+        use dto::user::Role;
+        use rocket::http::Status;
+        use rocket::local::asynchronous::Client;
+        use rocket::tokio::runtime::Runtime;
+        use std::fs;
+
+        async fn build_client(pool: Pool<ConnectionManager<SqliteConnection>>) -> Client {
+            let user_credentials_storage_mutex = log_in(&pool, &[Role::Check]);
+            let rocket = rocket::build()
+                .manage(user_credentials_storage_mutex)
+                .manage(pool)
+                .mount("/", routes![load_spreadsheet_members]);
+
+            Client::tracked(rocket).await.unwrap()
+        }
+
+        #[test]
+        fn success() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool).await;
+                let file_content = fs::read("test/resources/uda_members.xls").unwrap();
+
+                let response = client
+                    .post("/members/spreadsheet/load")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .body(file_content)
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Ok, response.status());
+
+                let members: SpreadsheetMembers = response.into_json().await.unwrap();
+                assert!(!members.members().is_empty());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn bad_request_when_not_a_spreadsheet() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool).await;
+
+                let response = client
+                    .post("/members/spreadsheet/load")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .body("Nom;Prénom")
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::BadRequest, response.status());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn forbidden_when_lacking_role() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let user_credentials_storage_mutex = log_in(&pool, &[Role::LookUp]);
+                let rocket = rocket::build()
+                    .manage(user_credentials_storage_mutex)
+                    .manage(pool)
+                    .mount("/", routes![load_spreadsheet_members]);
+                let client = Client::tracked(rocket).await.unwrap();
+
+                let response = client
+                    .post("/members/spreadsheet/load")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .body("Nom;Prénom")
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Forbidden, response.status());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+    }
 }
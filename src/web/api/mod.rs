@@ -1,4 +1,8 @@
+mod api_token_controller;
+mod check_sessions_controller;
+mod email_templates_controller;
 mod fileo_controller;
 mod memberships_controller;
 pub mod server;
 mod uda_controller;
+mod user_controller;
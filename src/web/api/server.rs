@@ -1,9 +1,18 @@
-use crate::fileo::credentials::FileoCredentials;
+use crate::database::dao;
+use crate::database::error::DatabaseError;
+use crate::fileo::session::FileoSession;
 use crate::membership::config::MembershipsProviderConfig;
-use crate::uda::credentials::UdaCredentials;
-use crate::web::api::{fileo_controller, memberships_controller, uda_controller};
+use crate::uda::session::UdaSession;
+use crate::user::session::UserSession;
+use crate::web::api::{
+    api_token_controller, check_sessions_controller, email_templates_controller, fileo_controller,
+    memberships_controller, uda_controller, user_controller,
+};
 use crate::web::credentials_storage::CredentialsStorage;
+use crate::web::credentials_storage::sqlite::{SqliteCredentialsBackend, StoredCredentials};
 use crate::web::server::Server;
+use diesel::SqliteConnection;
+use diesel::r2d2::{ConnectionManager, Pool};
 use dto::uda_instance::InstancesList;
 use regex::Regex;
 use rocket::{Build, Rocket};
@@ -20,31 +29,92 @@ impl ApiServer {
 impl Server for ApiServer {
     fn configure(&self, rocket_build: Rocket<Build>) -> Rocket<Build> {
         let members_provider_config = build_members_provider_config();
+        let fileo_credentials_storage = build_credentials_storage::<FileoSession>(&rocket_build);
+        let uda_credentials_storage = build_credentials_storage::<UdaSession>(&rocket_build);
+        let user_credentials_storage = build_credentials_storage::<UserSession>(&rocket_build);
 
         rocket_build
             .manage(members_provider_config)
             .manage(build_uda_configuration())
-            .manage(Mutex::new(CredentialsStorage::<FileoCredentials>::default()))
-            .manage(Mutex::new(CredentialsStorage::<UdaCredentials>::default()))
+            .manage(Mutex::new(fileo_credentials_storage))
+            .manage(Mutex::new(uda_credentials_storage))
+            .manage(Mutex::new(user_credentials_storage))
             .manage(Mutex::new(InstancesList::default()))
             .mount(
                 "/api/",
                 routes![
                     memberships_controller::check_csv_members,
+                    memberships_controller::load_spreadsheet_members,
                     memberships_controller::check_uda_members,
+                    memberships_controller::get_uda_check_delta,
+                    memberships_controller::export_csv_members,
+                    memberships_controller::export_uda_members,
                     memberships_controller::notify_members,
+                    memberships_controller::preview_notification,
+                    memberships_controller::get_notification_status,
                     memberships_controller::look_member_up,
+                    email_templates_controller::list,
+                    email_templates_controller::create,
+                    email_templates_controller::update,
+                    email_templates_controller::delete,
+                    check_sessions_controller::list_check_sessions,
+                    check_sessions_controller::get_check_session,
+                    check_sessions_controller::compare_sessions,
                     fileo_controller::login,
+                    fileo_controller::logout,
                     fileo_controller::download_memberships,
+                    fileo_controller::preview_uploaded_memberships,
+                    fileo_controller::upload_memberships,
                     uda_controller::login,
+                    uda_controller::logout,
                     uda_controller::retrieve_members_to_check,
                     uda_controller::confirm_members,
                     uda_controller::list_instances,
+                    user_controller::login,
+                    user_controller::logout,
+                    user_controller::create,
+                    user_controller::update_roles,
+                    user_controller::delete,
+                    api_token_controller::create,
+                    api_token_controller::revoke,
                 ],
             )
     }
 }
 
+/// Credentials are stored into the database when a secret key is configured, so that they survive restarts.
+/// Otherwise, e.g. in development, they are only kept in memory.
+fn build_credentials_storage<C: StoredCredentials + Clone + 'static>(
+    rocket_build: &Rocket<Build>,
+) -> CredentialsStorage<C> {
+    let Some(pool) = rocket_build.state::<Pool<ConnectionManager<SqliteConnection>>>() else {
+        return CredentialsStorage::default();
+    };
+    let Ok(secret_key) = rocket_build.figment().extract_inner::<String>("secret_key") else {
+        warn!("No secret key is configured, credentials will only be kept in memory");
+        return CredentialsStorage::default();
+    };
+
+    match SqliteCredentialsBackend::new(pool.clone(), &secret_key) {
+        Ok(backend) => {
+            if let Err(error) =
+                pool.get()
+                    .map_err(DatabaseError::from)
+                    .and_then(|mut connection| {
+                        dao::credentials_session::delete_expired(&mut connection)
+                    })
+            {
+                error!("Can't remove expired credentials [error: {error:?}]");
+            }
+            CredentialsStorage::new(backend)
+        }
+        Err(error) => {
+            warn!("Credentials will only be kept in memory [error: {error:?}]");
+            CredentialsStorage::default()
+        }
+    }
+}
+
 fn build_members_provider_config() -> MembershipsProviderConfig {
     MembershipsProviderConfig::new(get_fileo_host(), get_download_link_regex())
 }
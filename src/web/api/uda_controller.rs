@@ -2,7 +2,9 @@ use crate::database::dao::last_update::UpdatableElement::UdaInstances;
 use crate::database::dao::last_update::get_last_update;
 use crate::error::ApplicationError;
 use crate::error::ApplicationError::Web;
-use crate::tools::web::build_client;
+use crate::tools::web::{
+    SessionCookies, build_client, build_client_resuming_session, build_client_with_cookie_jar,
+};
 use crate::tools::{log_error, log_error_and_return};
 use crate::uda::authentication::AUTHENTICATION_COOKIE;
 use crate::uda::configuration::Configuration;
@@ -11,10 +13,15 @@ use crate::uda::credentials::UdaCredentials;
 use crate::uda::instances::retrieve_uda_instances;
 use crate::uda::login::authenticate_into_uda;
 use crate::uda::retrieve_members::retrieve_members;
+use crate::uda::session::UdaSession;
+use crate::user::authentication::{Authorized, CanCheck};
+use crate::web::audit::record_audit_log;
+use crate::web::authentication;
 use crate::web::credentials_storage::CredentialsStorage;
-use crate::web::error::WebError::{ConnectionFailed, LackOfPermissions};
+use crate::web::error::WebError::{ConnectionFailed, LackOfPermissions, SessionExpired};
 use diesel::SqliteConnection;
 use diesel::r2d2::ConnectionManager;
+use dto::audit_log::AuditAction;
 use dto::uda_instance::InstancesList;
 use r2d2::Pool;
 use reqwest::Client;
@@ -28,16 +35,27 @@ use uuid::Uuid;
 
 /// Try and log a user onto UDA app.
 /// If the login operation succeeds,
-/// then a new UUID is created and credentials are stored with this UUID.
+/// then a new UUID is created and the session opened on UDA is stored with this UUID.
+/// The password itself is not kept.
 /// The UUID is returned to the caller through a private cookie, so that it is their new access token.
 #[post("/uda/login", format = "application/json", data = "<credentials>")]
 pub async fn login(
-    credentials_storage: &State<Mutex<CredentialsStorage<UdaCredentials>>>,
+    credentials_storage: &State<Mutex<CredentialsStorage<UdaSession>>>,
     cookie_jar: &CookieJar<'_>,
     credentials: Json<UdaCredentials>,
+    _user: Authorized<CanCheck>,
 ) -> Result<Status, Status> {
-    let client = build_client().map_err(log_error_and_return(Status::InternalServerError))?;
+    let (client, uda_cookie_jar) = build_client_with_cookie_jar()
+        .map_err(log_error_and_return(Status::InternalServerError))?;
     authenticate(&client, &credentials).await?;
+    let session_cookies = SessionCookies::from_jar(&uda_cookie_jar, credentials.uda_url())
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    let session = UdaSession::new(
+        credentials.uda_url().to_owned(),
+        credentials.login().to_owned(),
+        session_cookies,
+    );
+
     let mut mutex = credentials_storage
         .lock()
         .map_err(log_error_and_return(Status::InternalServerError))?;
@@ -45,19 +63,45 @@ pub async fn login(
     let cookie = Cookie::build((AUTHENTICATION_COOKIE.to_owned(), uuid.clone()))
         .max_age(Duration::days(365))
         .build();
+    (*mutex)
+        .store(uuid.clone(), session)
+        .map_err(log_error_and_return(Status::InternalServerError))?;
     cookie_jar.add_private(cookie);
-    (*mutex).store(uuid.clone(), credentials.into_inner());
     Ok(Status::Ok)
 }
 
+/// Log the user out of UDA app, by revoking their access token.
+#[post("/uda/logout")]
+pub async fn logout(
+    credentials_storage: &State<Mutex<CredentialsStorage<UdaSession>>>,
+    cookie_jar: &CookieJar<'_>,
+) -> Result<Status, Status> {
+    authentication::logout(
+        credentials_storage.inner(),
+        cookie_jar,
+        AUTHENTICATION_COOKIE,
+    )
+}
+
 /// Retrieve all members from UDA's organisation membership page if authorized.
+/// If the session opened on UDA has expired, the user is logged out and has to log in again.
 #[get("/uda/retrieve")]
-pub async fn retrieve_members_to_check(credentials: UdaCredentials) -> Result<String, Status> {
-    let client = build_client().map_err(log_error_and_return(Status::InternalServerError))?;
-    authenticate(&client, &credentials).await?;
-    let url = credentials.uda_url();
+pub async fn retrieve_members_to_check(
+    credentials_storage: &State<Mutex<CredentialsStorage<UdaSession>>>,
+    cookie_jar: &CookieJar<'_>,
+    _user: Authorized<CanCheck>,
+    session: UdaSession,
+) -> Result<String, Status> {
+    let client = build_client_resuming_session(session.uda_url(), session.cookies())
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    let url = session.uda_url();
     match retrieve_members(&client, url).await {
         Ok(members) => Ok(json!(members).to_string()),
+        Err(Web(SessionExpired)) => Err(authentication::revoke_expired_session(
+            credentials_storage.inner(),
+            cookie_jar,
+            AUTHENTICATION_COOKIE,
+        )),
         Err(Web(LackOfPermissions)) => Err(Status::Unauthorized),
         Err(_) => Err(Status::BadGateway),
     }
@@ -71,13 +115,18 @@ pub async fn retrieve_members_to_check(credentials: UdaCredentials) -> Result<St
 ///     "nok": [id_3, ...]
 /// }
 /// ```
+/// If the session opened on UDA has expired, the user is logged out and has to log in again.
 #[post("/uda/confirm", format = "application/json", data = "<members_ids>")]
 pub async fn confirm_members(
+    pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
+    credentials_storage: &State<Mutex<CredentialsStorage<UdaSession>>>,
+    cookie_jar: &CookieJar<'_>,
     members_ids: Json<Vec<u16>>,
-    credentials: UdaCredentials,
+    user: Authorized<CanCheck>,
+    session: UdaSession,
 ) -> (Status, Value) {
     let members_ids = members_ids.into_inner();
-    let client = match build_client() {
+    let client = match build_client_resuming_session(session.uda_url(), session.cookies()) {
         Ok(client) => client,
         Err(error) => {
             log_error(error);
@@ -87,23 +136,25 @@ pub async fn confirm_members(
             );
         }
     };
-
-    if let Err(status) = authenticate(&client, &credentials).await {
-        return (status, json!({"ok": Vec::<u16>::new(), "nok":members_ids}));
-    };
-    let url = credentials.uda_url();
+    let url = session.uda_url();
 
     let mut not_marked_ids = vec![];
     let mut errors = vec![];
-    for id in &members_ids {
+    for (index, id) in members_ids.iter().enumerate() {
         let result = confirm_member(&client, url, *id).await;
         if let Err(error) = result {
             debug!(
                 "Member has not been confirmed. [member_id: {id}, error: {:?}]",
                 error
             );
-            not_marked_ids.push(*id);
+            let session_has_expired = matches!(error, Web(SessionExpired));
             errors.push(error);
+            if session_has_expired {
+                // Next members can't be confirmed either.
+                not_marked_ids.extend_from_slice(&members_ids[index..]);
+                break;
+            }
+            not_marked_ids.push(*id);
         }
     }
 
@@ -112,15 +163,31 @@ pub async fn confirm_members(
         .filter(|id| !not_marked_ids.contains(**id))
         .copied()
         .collect();
+    if !marked_ids.is_empty() {
+        let targets: Vec<String> = marked_ids.iter().map(u16::to_string).collect();
+        record_audit_log(
+            pool.inner(),
+            user.login(),
+            AuditAction::ConfirmUdaMembers,
+            &targets,
+        );
+    }
 
-    (
-        if not_marked_ids.is_empty() {
-            Status::Ok
-        } else {
-            from_vec_of_errors_to_status(&errors)
-        },
-        json!({"ok": marked_ids, "nok":not_marked_ids}),
-    )
+    let status = if not_marked_ids.is_empty() {
+        Status::Ok
+    } else if errors
+        .iter()
+        .any(|error| matches!(*error, Web(SessionExpired)))
+    {
+        authentication::revoke_expired_session(
+            credentials_storage.inner(),
+            cookie_jar,
+            AUTHENTICATION_COOKIE,
+        )
+    } else {
+        from_vec_of_errors_to_status(&errors)
+    };
+    (status, json!({"ok": marked_ids, "nok": not_marked_ids}))
 }
 
 /// Retrieve and return a list of all existing UDA instances, alongside with the last update date
@@ -129,6 +196,7 @@ pub async fn confirm_members(
 pub async fn list_instances(
     pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
     configuration: &State<Configuration>,
+    _user: Authorized<CanCheck>,
 ) -> Result<Value, Status> {
     let client = build_client().map_err(log_error_and_return(Status::InternalServerError))?;
     let instances = retrieve_uda_instances(pool, &client, configuration.inner())
@@ -173,283 +241,560 @@ fn from_vec_of_errors_to_status(errors: &[ApplicationError]) -> Status {
 
 #[cfg(test)]
 mod tests {
+    use crate::tools::web::SessionCookies;
+    use crate::uda::session::UdaSession;
+    use crate::user::authentication::tests::log_in;
+    use crate::web::credentials_storage::CredentialsStorage;
+    use diesel::SqliteConnection;
+    use diesel::r2d2::ConnectionManager;
+    use dto::user::Role;
+    use r2d2::Pool;
+    use rocket::local::asynchronous::Client;
+    use rocket::{Build, Rocket};
+    use std::sync::Mutex;
+    use wiremock::MockServer;
+
+    const UUID: &str = "e9af5e0f-c441-4bcd-bf22-31cc5b1f2f9e";
+
     mod login {
+        use crate::database::with_temp_database;
+        use crate::tools::web::SessionCookies;
         use crate::uda::authentication::AUTHENTICATION_COOKIE;
         use crate::uda::credentials::UdaCredentials;
         use crate::uda::login::tests::setup_authentication;
+        use crate::uda::session::UdaSession;
+        use crate::user::authentication::AUTHENTICATION_COOKIE as USER_AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::{USER_UUID, log_in};
         use crate::web::api::uda_controller::login;
         use crate::web::credentials_storage::CredentialsStorage;
+        use diesel::SqliteConnection;
+        use diesel::r2d2::ConnectionManager;
+        use dto::user::Role;
+        use r2d2::Pool;
         use rocket::http::hyper::header::CONTENT_TYPE;
         use rocket::http::{ContentType, Header, Status};
         use rocket::local::asynchronous::Client;
         use rocket::serde::json::json;
+        use rocket::tokio::runtime::Runtime;
         use std::sync::Mutex;
         use wiremock::matchers::{body_string, method, path};
         use wiremock::{Mock, MockServer, ResponseTemplate};
 
-        #[async_test]
-        async fn success() {
-            let mock_server = MockServer::start().await;
-            setup_authentication(&mock_server).await;
+        #[test]
+        fn success() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let mock_server = MockServer::start().await;
+                setup_authentication(&mock_server).await;
 
-            let credentials =
-                UdaCredentials::new(mock_server.uri(), "login".to_owned(), "password".to_owned());
-            let credentials_storage_mutex =
-                Mutex::new(CredentialsStorage::<UdaCredentials>::default());
+                let credentials = UdaCredentials::new(
+                    mock_server.uri(),
+                    "login".to_owned(),
+                    "password".to_owned(),
+                );
+                let credentials_storage_mutex =
+                    Mutex::new(CredentialsStorage::<UdaSession>::default());
 
-            let rocket = rocket::build()
-                .manage(credentials_storage_mutex)
-                .mount("/", routes![login]);
-            let client = Client::tracked(rocket).await.unwrap();
-            let credentials_as_json = json!(credentials).to_string();
-            let request = client
-                .post("/uda/login")
-                .body(credentials_as_json.as_bytes())
-                .header(Header::new(
-                    CONTENT_TYPE.to_string(),
-                    ContentType::JSON.to_string(),
-                ));
-
-            let response = request.dispatch().await;
-            assert_eq!(Status::Ok, response.status());
-            assert!(
-                response
+                let rocket = rocket::build()
+                    .manage(credentials_storage_mutex)
+                    .manage(log_in(&pool, &[Role::Check]))
+                    .manage(pool)
+                    .mount("/", routes![login]);
+                let client = Client::tracked(rocket).await.unwrap();
+                let credentials_as_json = json!(credentials).to_string();
+                let request = client
+                    .post("/uda/login")
+                    .cookie((USER_AUTHENTICATION_COOKIE, USER_UUID))
+                    .body(credentials_as_json.as_bytes())
+                    .header(Header::new(
+                        CONTENT_TYPE.to_string(),
+                        ContentType::JSON.to_string(),
+                    ));
+
+                let response = request.dispatch().await;
+                assert_eq!(Status::Ok, response.status());
+                let uuid = response
                     .cookies()
                     .get_private(AUTHENTICATION_COOKIE)
-                    .is_some()
-            );
+                    .unwrap()
+                    .value()
+                    .to_owned();
+
+                let stored_session = client
+                    .rocket()
+                    .state::<Mutex<CredentialsStorage<UdaSession>>>()
+                    .unwrap()
+                    .lock()
+                    .unwrap()
+                    .get(&uuid)
+                    .unwrap();
+                assert_eq!(
+                    Some(UdaSession::new(
+                        mock_server.uri(),
+                        "login".to_owned(),
+                        SessionCookies::new("_session_id=1234")
+                    )),
+                    stored_session
+                );
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
         }
 
-        #[async_test]
-        async fn fail_when_bad_gateway() {
-            let mock_server = MockServer::start().await;
+        #[test]
+        fn fail_when_bad_gateway() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let mock_server = MockServer::start().await;
 
-            let body = "<html><body>Where do you think you are, son?</body></html>".to_string();
-            Mock::given(method("GET"))
-                .and(path("/en/users/sign_in"))
-                .respond_with(ResponseTemplate::new(200).set_body_string(&body))
-                .mount(&mock_server)
-                .await;
+                let body = "<html><body>Where do you think you are, son?</body></html>".to_string();
+                Mock::given(method("GET"))
+                    .and(path("/en/users/sign_in"))
+                    .respond_with(ResponseTemplate::new(200).set_body_string(&body))
+                    .mount(&mock_server)
+                    .await;
 
-            let credentials =
-                UdaCredentials::new(mock_server.uri(), "login".to_owned(), "password".to_owned());
-            let credentials_storage_mutex =
-                Mutex::new(CredentialsStorage::<UdaCredentials>::default());
+                let credentials = UdaCredentials::new(
+                    mock_server.uri(),
+                    "login".to_owned(),
+                    "password".to_owned(),
+                );
+                let credentials_storage_mutex =
+                    Mutex::new(CredentialsStorage::<UdaSession>::default());
+
+                let rocket = rocket::build()
+                    .manage(credentials_storage_mutex)
+                    .manage(log_in(&pool, &[Role::Check]))
+                    .manage(pool)
+                    .mount("/", routes![login]);
+                let client = Client::tracked(rocket).await.unwrap();
+                let credentials_as_json = json!(credentials).to_string();
+                let request = client
+                    .post("/uda/login")
+                    .cookie((USER_AUTHENTICATION_COOKIE, USER_UUID))
+                    .body(credentials_as_json.as_bytes())
+                    .header(Header::new(
+                        CONTENT_TYPE.to_string(),
+                        ContentType::JSON.to_string(),
+                    ));
+
+                let response = request.dispatch().await;
+                assert_eq!(Status::BadGateway, response.status());
+                assert!(
+                    response
+                        .cookies()
+                        .get_private(AUTHENTICATION_COOKIE)
+                        .is_none()
+                );
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn fail_when_unauthorized() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let mock_server = MockServer::start().await;
+                let authenticity_token = "BDv-07yMs8kMDnRn2hVgpSmqn88V_XhCZxImtcXr3u6OOmpnsy0WpFD49rTOuOEfJG_PptBBJag094Vd0uuyZg";
+
+                let body = format!(
+                    r#"<html><body><input name="authenticity_token" value="{authenticity_token}"></body></html>"#
+                );
+                Mock::given(method("GET"))
+                    .and(path("/en/users/sign_in"))
+                    .respond_with(ResponseTemplate::new(200).set_body_string(&body))
+                    .mount(&mock_server)
+                    .await;
+
+                let params = format!(
+                    "user%5Bemail%5D=wrong_login&user%5Bpassword%5D=password&authenticity_token={authenticity_token}&utf8=%E2%9C%93"
+                );
+                Mock::given(method("POST"))
+                    .and(path("/en/users/sign_in"))
+                    .and(body_string(&params))
+                    .respond_with(
+                        ResponseTemplate::new(200).set_body_string("Signed in successfully"),
+                    )
+                    .mount(&mock_server)
+                    .await;
+
+                let credentials = UdaCredentials::new(
+                    mock_server.uri(),
+                    "login".to_owned(),
+                    "password".to_owned(),
+                );
+                let credentials_storage_mutex =
+                    Mutex::new(CredentialsStorage::<UdaSession>::default());
+
+                let rocket = rocket::build()
+                    .manage(credentials_storage_mutex)
+                    .manage(log_in(&pool, &[Role::Check]))
+                    .manage(pool)
+                    .mount("/", routes![login]);
+                let client = Client::tracked(rocket).await.unwrap();
+                let credentials_as_json = json!(credentials).to_string();
+                let request = client
+                    .post("/uda/login")
+                    .cookie((USER_AUTHENTICATION_COOKIE, USER_UUID))
+                    .body(credentials_as_json.as_bytes())
+                    .header(Header::new(
+                        CONTENT_TYPE.to_string(),
+                        ContentType::JSON.to_string(),
+                    ));
+
+                let response = request.dispatch().await;
+                assert_eq!(Status::Unauthorized, response.status());
+                assert!(
+                    response
+                        .cookies()
+                        .get_private(AUTHENTICATION_COOKIE)
+                        .is_none()
+                );
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+    }
+
+    mod logout {
+        use crate::tools::web::SessionCookies;
+        use crate::uda::authentication::AUTHENTICATION_COOKIE;
+        use crate::uda::session::UdaSession;
+        use crate::web::api::uda_controller::logout;
+        use crate::web::credentials_storage::CredentialsStorage;
+        use rocket::http::{Cookie, Status};
+        use rocket::local::asynchronous::Client;
+        use std::sync::Mutex;
+
+        const UUID: &str = "0ea9a5fb-0f46-4057-902a-2552ed956bde";
+
+        async fn build_client() -> Client {
+            let mut credentials_storage = CredentialsStorage::default();
+            credentials_storage
+                .store(
+                    UUID.to_owned(),
+                    UdaSession::new(
+                        "https://test.reg.unicycling-software.com".to_owned(),
+                        "test_login".to_owned(),
+                        SessionCookies::default(),
+                    ),
+                )
+                .unwrap();
 
             let rocket = rocket::build()
-                .manage(credentials_storage_mutex)
-                .mount("/", routes![login]);
-            let client = Client::tracked(rocket).await.unwrap();
-            let credentials_as_json = json!(credentials).to_string();
-            let request = client
-                .post("/uda/login")
-                .body(credentials_as_json.as_bytes())
-                .header(Header::new(
-                    CONTENT_TYPE.to_string(),
-                    ContentType::JSON.to_string(),
-                ));
-
-            let response = request.dispatch().await;
-            assert_eq!(Status::BadGateway, response.status());
-            assert!(
-                response
-                    .cookies()
-                    .get_private(AUTHENTICATION_COOKIE)
-                    .is_none()
-            );
+                .manage(Mutex::new(credentials_storage))
+                .mount("/", routes![logout]);
+            Client::tracked(rocket).await.unwrap()
+        }
+
+        fn is_logged_in(client: &Client) -> bool {
+            client
+                .rocket()
+                .state::<Mutex<CredentialsStorage<UdaSession>>>()
+                .unwrap()
+                .lock()
+                .unwrap()
+                .get(UUID)
+                .unwrap()
+                .is_some()
         }
 
         #[async_test]
-        async fn fail_when_unauthorized() {
-            let mock_server = MockServer::start().await;
-            let authenticity_token = "BDv-07yMs8kMDnRn2hVgpSmqn88V_XhCZxImtcXr3u6OOmpnsy0WpFD49rTOuOEfJG_PptBBJag094Vd0uuyZg";
+        async fn success() {
+            let client = build_client().await;
 
-            let body = format!(
-                r#"<html><body><input name="authenticity_token" value="{authenticity_token}"></body></html>"#
-            );
-            Mock::given(method("GET"))
-                .and(path("/en/users/sign_in"))
-                .respond_with(ResponseTemplate::new(200).set_body_string(&body))
-                .mount(&mock_server)
+            let response = client
+                .post("/uda/logout")
+                .cookie(Cookie::new(AUTHENTICATION_COOKIE, UUID))
+                .dispatch()
                 .await;
 
-            let params = format!(
-                "user%5Bemail%5D=wrong_login&user%5Bpassword%5D=password&authenticity_token={authenticity_token}&utf8=%E2%9C%93"
-            );
-            Mock::given(method("POST"))
-                .and(path("/en/users/sign_in"))
-                .and(body_string(&params))
-                .respond_with(ResponseTemplate::new(200).set_body_string("Signed in successfully"))
-                .mount(&mock_server)
-                .await;
+            assert_eq!(Status::Ok, response.status());
+            assert!(!is_logged_in(&client));
+        }
 
-            let credentials =
-                UdaCredentials::new(mock_server.uri(), "login".to_owned(), "password".to_owned());
-            let credentials_storage_mutex =
-                Mutex::new(CredentialsStorage::<UdaCredentials>::default());
+        #[async_test]
+        async fn success_when_not_logged_in() {
+            let client = build_client().await;
 
-            let rocket = rocket::build()
-                .manage(credentials_storage_mutex)
-                .mount("/", routes![login]);
-            let client = Client::tracked(rocket).await.unwrap();
-            let credentials_as_json = json!(credentials).to_string();
-            let request = client
-                .post("/uda/login")
-                .body(credentials_as_json.as_bytes())
-                .header(Header::new(
-                    CONTENT_TYPE.to_string(),
-                    ContentType::JSON.to_string(),
-                ));
-
-            let response = request.dispatch().await;
-            assert_eq!(Status::Unauthorized, response.status());
-            assert!(
-                response
-                    .cookies()
-                    .get_private(AUTHENTICATION_COOKIE)
-                    .is_none()
-            );
+            let response = client.post("/uda/logout").dispatch().await;
+
+            assert_eq!(Status::Ok, response.status());
+            assert!(is_logged_in(&client));
         }
     }
 
+    /// Store the session of a user who has logged in the UDA instance mocked by the server.
+    fn store_session(mock_server: &MockServer) -> Mutex<CredentialsStorage<UdaSession>> {
+        let session = UdaSession::new(
+            mock_server.uri(),
+            "login".to_owned(),
+            SessionCookies::new("_session_id=1234"),
+        );
+        let mut credentials_storage = CredentialsStorage::<UdaSession>::default();
+        credentials_storage.store(UUID.to_owned(), session).unwrap();
+        Mutex::new(credentials_storage)
+    }
+
+    /// Build a server whose user is allowed to check members, and has logged in the UDA instance mocked by the server.
+    fn build_rocket(
+        mock_server: &MockServer,
+        pool: Pool<ConnectionManager<SqliteConnection>>,
+    ) -> Rocket<Build> {
+        rocket::build()
+            .manage(store_session(mock_server))
+            .manage(log_in(&pool, &[Role::Check]))
+            .manage(pool)
+    }
+
+    fn is_logged_in(client: &Client) -> bool {
+        client
+            .rocket()
+            .state::<Mutex<CredentialsStorage<UdaSession>>>()
+            .unwrap()
+            .lock()
+            .unwrap()
+            .get(UUID)
+            .unwrap()
+            .is_some()
+    }
+
     mod retrieve_members_to_check {
+        use crate::database::with_temp_database;
         use crate::uda::authentication::AUTHENTICATION_COOKIE;
-        use crate::uda::credentials::UdaCredentials;
-        use crate::uda::login::tests::setup_authentication;
+        use crate::uda::login::tests::setup_expired_session;
         use crate::uda::retrieve_members::tests::setup_member_retrieval;
+        use crate::uda::session::UdaSession;
+        use crate::user::authentication::AUTHENTICATION_COOKIE as USER_AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::{USER_UUID, log_in};
         use crate::web::api::uda_controller::retrieve_members_to_check;
+        use crate::web::api::uda_controller::tests::{UUID, build_rocket, is_logged_in};
         use crate::web::credentials_storage::CredentialsStorage;
+        use diesel::SqliteConnection;
+        use diesel::r2d2::ConnectionManager;
         use dto::uda_member::UdaMember;
+        use dto::user::Role;
+        use r2d2::Pool;
         use rocket::http::Status;
         use rocket::local::asynchronous::Client;
+        use rocket::tokio::runtime::Runtime;
         use std::sync::Mutex;
         use wiremock::MockServer;
 
-        #[async_test]
-        async fn success() {
-            let mock_server = MockServer::start().await;
-            let credentials = setup_authentication(&mock_server).await;
-            let expected_result = setup_member_retrieval(&mock_server).await;
+        async fn build_client(
+            mock_server: &MockServer,
+            pool: Pool<ConnectionManager<SqliteConnection>>,
+        ) -> Client {
+            let rocket =
+                build_rocket(mock_server, pool).mount("/", routes![retrieve_members_to_check]);
+            Client::tracked(rocket).await.unwrap()
+        }
 
-            let uuid = "e9af5e0f-c441-4bcd-bf22-31cc5b1f2f9e";
-            let mut credentials_storage = CredentialsStorage::<UdaCredentials>::default();
-            credentials_storage.store(uuid.to_string(), credentials);
-            let credentials_storage_mutex = Mutex::new(credentials_storage);
+        #[test]
+        fn success() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let mock_server = MockServer::start().await;
+                let expected_result = setup_member_retrieval(&mock_server).await;
+                let client = build_client(&mock_server, pool).await;
+
+                let request = client
+                    .get("/uda/retrieve")
+                    .cookie((AUTHENTICATION_COOKIE, UUID))
+                    .cookie((USER_AUTHENTICATION_COOKIE, USER_UUID));
+
+                let response = request.dispatch().await;
+                assert_eq!(Status::Ok, response.status());
+                let members: Vec<UdaMember> = response.into_json().await.unwrap();
+                assert_eq!(expected_result, members);
+            }
 
-            let rocket = rocket::build()
-                .manage(credentials_storage_mutex)
-                .mount("/", routes![retrieve_members_to_check]);
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
 
-            let client = Client::tracked(rocket).await.unwrap();
-            let request = client
-                .get("/uda/retrieve")
-                .cookie((AUTHENTICATION_COOKIE, uuid));
+        #[test]
+        fn fail_when_unauthorized() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let credentials_storage_mutex =
+                    Mutex::new(CredentialsStorage::<UdaSession>::default());
 
-            let response = request.dispatch().await;
-            assert_eq!(Status::Ok, response.status());
-            let members: Vec<UdaMember> = response.into_json().await.unwrap();
-            assert_eq!(expected_result, members);
+                let rocket = rocket::build()
+                    .manage(credentials_storage_mutex)
+                    .manage(log_in(&pool, &[Role::Check]))
+                    .manage(pool)
+                    .mount("/", routes![retrieve_members_to_check]);
+
+                let client = Client::tracked(rocket).await.unwrap();
+                let request = client
+                    .get("/uda/retrieve")
+                    .cookie((AUTHENTICATION_COOKIE, UUID))
+                    .cookie((USER_AUTHENTICATION_COOKIE, USER_UUID));
+
+                let response = request.dispatch().await;
+                assert_eq!(Status::Unauthorized, response.status());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
         }
 
-        #[async_test]
-        async fn fail_when_unauthorized() {
-            let uuid = "e9af5e0f-c441-4bcd-bf22-31cc5b1f2f9e";
-            let credentials_storage_mutex =
-                Mutex::new(CredentialsStorage::<UdaCredentials>::default());
+        #[test]
+        fn fail_when_bad_gateway() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let mock_server = MockServer::start().await;
+                let client = build_client(&mock_server, pool).await;
 
-            let rocket = rocket::build()
-                .manage(credentials_storage_mutex)
-                .mount("/", routes![retrieve_members_to_check]);
+                let request = client
+                    .get("/uda/retrieve")
+                    .cookie((AUTHENTICATION_COOKIE, UUID))
+                    .cookie((USER_AUTHENTICATION_COOKIE, USER_UUID));
 
-            let client = Client::tracked(rocket).await.unwrap();
-            let request = client
-                .get("/uda/retrieve")
-                .cookie((AUTHENTICATION_COOKIE, uuid));
+                let response = request.dispatch().await;
+                assert_eq!(Status::BadGateway, response.status());
+                assert!(is_logged_in(&client));
+            }
 
-            let response = request.dispatch().await;
-            assert_eq!(Status::Unauthorized, response.status());
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
         }
 
-        #[async_test]
-        async fn fail_when_bad_gateway() {
-            let mock_server = MockServer::start().await;
-            let credentials = setup_authentication(&mock_server).await;
-            let uuid = "e9af5e0f-c441-4bcd-bf22-31cc5b1f2f9e";
-            let mut credentials_storage = CredentialsStorage::<UdaCredentials>::default();
-            credentials_storage.store(uuid.to_string(), credentials);
-            let credentials_storage_mutex = Mutex::new(credentials_storage);
+        #[test]
+        fn fail_when_session_has_expired() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let mock_server = MockServer::start().await;
+                setup_expired_session(&mock_server, "/en/organization_memberships/export.xls")
+                    .await;
+                let client = build_client(&mock_server, pool).await;
 
-            let rocket = rocket::build()
-                .manage(credentials_storage_mutex)
-                .mount("/", routes![retrieve_members_to_check]);
+                let request = client
+                    .get("/uda/retrieve")
+                    .cookie((AUTHENTICATION_COOKIE, UUID))
+                    .cookie((USER_AUTHENTICATION_COOKIE, USER_UUID));
 
-            let client = Client::tracked(rocket).await.unwrap();
-            let request = client
-                .get("/uda/retrieve")
-                .cookie((AUTHENTICATION_COOKIE, uuid));
+                let response = request.dispatch().await;
+                assert_eq!(Status::Unauthorized, response.status());
+                assert!(!is_logged_in(&client));
+            }
 
-            let response = request.dispatch().await;
-            assert_eq!(Status::BadGateway, response.status());
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
         }
     }
 
     mod confirm_members {
+        use crate::database::{dao, with_temp_database};
+        use crate::uda::authentication::AUTHENTICATION_COOKIE;
         use crate::uda::confirm_member::tests::{setup_confirm_member, setup_csrf_token};
-        use crate::uda::credentials::UdaCredentials;
-        use crate::uda::login::tests::{setup_authentication, setup_authenticity_token};
+        use crate::uda::login::tests::setup_expired_session;
+        use crate::user::authentication::AUTHENTICATION_COOKIE as USER_AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::USER_UUID;
         use crate::web::api::uda_controller::confirm_members;
-        use rocket::http::Status;
-        use rocket::serde::json::Json;
+        use crate::web::api::uda_controller::tests::{UUID, build_rocket, is_logged_in};
+        use diesel::SqliteConnection;
+        use diesel::r2d2::ConnectionManager;
+        use dto::audit_log::{AuditAction, AuditLogFilter};
+        use r2d2::Pool;
+        use rocket::http::{ContentType, Status};
+        use rocket::local::asynchronous::{Client, LocalResponse};
+        use rocket::tokio::runtime::Runtime;
         use std::collections::HashMap;
         use wiremock::MockServer;
 
-        #[async_test]
-        async fn success() {
-            let mock_server = MockServer::start().await;
-            let credentials = setup_authentication(&mock_server).await;
-            let csrf_token = setup_csrf_token(&mock_server).await;
-            setup_confirm_member(&mock_server, &csrf_token, 1).await;
-            setup_confirm_member(&mock_server, &csrf_token, 2).await;
-            setup_confirm_member(&mock_server, &csrf_token, 3).await;
-
-            let (status, value) =
-                confirm_members(Json::from(vec![1_u16, 2_u16, 3_u16]), credentials).await;
-
-            assert_eq!(Status::Ok, status);
-            let result: HashMap<String, Vec<u16>> = rocket::serde::json::from_value(value).unwrap();
-            assert_eq!(&vec![1_u16, 2_u16, 3_u16], result.get("ok").unwrap());
-            assert_eq!(&Vec::<u16>::new(), result.get("nok").unwrap());
+        async fn build_client(
+            mock_server: &MockServer,
+            pool: &Pool<ConnectionManager<SqliteConnection>>,
+        ) -> Client {
+            let rocket =
+                build_rocket(mock_server, pool.clone()).mount("/", routes![confirm_members]);
+            Client::tracked(rocket).await.unwrap()
         }
 
-        #[async_test]
-        async fn fail_to_confirm_some_members() {
-            let mock_server = MockServer::start().await;
-            let credentials = setup_authentication(&mock_server).await;
-            let csrf_token = setup_csrf_token(&mock_server).await;
-            setup_confirm_member(&mock_server, &csrf_token, 1).await;
+        async fn confirm<'c>(client: &'c Client, members_ids: &str) -> LocalResponse<'c> {
+            client
+                .post("/uda/confirm")
+                .header(ContentType::JSON)
+                .cookie((AUTHENTICATION_COOKIE, UUID))
+                .cookie((USER_AUTHENTICATION_COOKIE, USER_UUID))
+                .body(members_ids)
+                .dispatch()
+                .await
+        }
+
+        #[test]
+        fn success() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let mock_server = MockServer::start().await;
+                let csrf_token = setup_csrf_token(&mock_server).await;
+                setup_confirm_member(&mock_server, &csrf_token, 1).await;
+                setup_confirm_member(&mock_server, &csrf_token, 2).await;
+                setup_confirm_member(&mock_server, &csrf_token, 3).await;
+                let client = build_client(&mock_server, &pool).await;
+
+                let response = confirm(&client, "[1, 2, 3]").await;
+
+                assert_eq!(Status::Ok, response.status());
+                let result: HashMap<String, Vec<u16>> = response.into_json().await.unwrap();
+                assert_eq!(&vec![1_u16, 2_u16, 3_u16], result.get("ok").unwrap());
+                assert_eq!(&Vec::<u16>::new(), result.get("nok").unwrap());
 
-            let (status, value) = confirm_members(Json::from(vec![1, 2, 3]), credentials).await;
+                let mut connection = pool.get().unwrap();
+                let audit_log =
+                    dao::audit_log::retrieve(&mut connection, &AuditLogFilter::default()).unwrap();
+                assert_eq!(1, audit_log.len());
+                assert_eq!("test_login", audit_log[0].actor());
+                assert_eq!(&AuditAction::ConfirmUdaMembers, audit_log[0].action());
+                assert_eq!(
+                    &vec!["1".to_owned(), "2".to_owned(), "3".to_owned()],
+                    audit_log[0].targets()
+                );
+            }
 
-            assert_eq!(Status::Unauthorized, status);
-            let result: HashMap<String, Vec<u16>> = rocket::serde::json::from_value(value).unwrap();
-            assert_eq!(&vec![1], result.get("ok").unwrap());
-            assert_eq!(&vec![2, 3], result.get("nok").unwrap());
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
         }
 
-        #[async_test]
-        async fn fail_when_no_authentication() {
-            let mock_server = MockServer::start().await;
-            setup_authenticity_token(&mock_server).await;
+        #[test]
+        fn fail_to_confirm_some_members() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let mock_server = MockServer::start().await;
+                let csrf_token = setup_csrf_token(&mock_server).await;
+                setup_confirm_member(&mock_server, &csrf_token, 1).await;
+                let client = build_client(&mock_server, &pool).await;
 
-            let credentials =
-                UdaCredentials::new(mock_server.uri(), "login".to_owned(), "password".to_owned());
+                let response = confirm(&client, "[1, 2, 3]").await;
 
-            let (status, value) = confirm_members(Json::from(vec![1, 2, 3]), credentials).await;
+                assert_eq!(Status::Unauthorized, response.status());
+                let result: HashMap<String, Vec<u16>> = response.into_json().await.unwrap();
+                assert_eq!(&vec![1], result.get("ok").unwrap());
+                assert_eq!(&vec![2, 3], result.get("nok").unwrap());
+                assert!(is_logged_in(&client));
 
-            assert_eq!(Status::Unauthorized, status);
-            let result: HashMap<String, Vec<u16>> = rocket::serde::json::from_value(value).unwrap();
-            assert_eq!(&Vec::<u16>::new(), result.get("ok").unwrap());
-            assert_eq!(&vec![1, 2, 3], result.get("nok").unwrap());
+                let mut connection = pool.get().unwrap();
+                let audit_log =
+                    dao::audit_log::retrieve(&mut connection, &AuditLogFilter::default()).unwrap();
+                assert_eq!(&vec!["1".to_owned()], audit_log[0].targets());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn fail_when_session_has_expired() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let mock_server = MockServer::start().await;
+                setup_expired_session(&mock_server, "/en/organization_memberships").await;
+                let client = build_client(&mock_server, &pool).await;
+
+                let response = confirm(&client, "[1, 2, 3]").await;
+
+                assert_eq!(Status::Unauthorized, response.status());
+                let result: HashMap<String, Vec<u16>> = response.into_json().await.unwrap();
+                assert_eq!(&Vec::<u16>::new(), result.get("ok").unwrap());
+                assert_eq!(&vec![1, 2, 3], result.get("nok").unwrap());
+                assert!(!is_logged_in(&client));
+
+                let mut connection = pool.get().unwrap();
+                assert!(
+                    dao::audit_log::retrieve(&mut connection, &AuditLogFilter::default())
+                        .unwrap()
+                        .is_empty()
+                );
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
         }
     }
 
@@ -457,11 +802,14 @@ mod tests {
         use crate::database::with_temp_database;
         use crate::uda::configuration::Configuration;
         use crate::uda::instances::tests::{BODY, get_expected_instances};
+        use crate::user::authentication::AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::{USER_UUID, log_in};
         use crate::web::api::uda_controller::list_instances;
         use diesel::SqliteConnection;
         use diesel::r2d2::ConnectionManager;
         use dto::uda_instance::Instance;
         use dto::uda_instance::InstancesList;
+        use dto::user::Role;
         use r2d2::Pool;
         use rocket::http::Status;
         use rocket::local::asynchronous::Client;
@@ -483,12 +831,15 @@ mod tests {
                 let configuration =
                     Configuration::new(format!("{}/tenants?locale=en", mock_server.uri()));
                 let rocket = rocket::build()
+                    .manage(log_in(&pool, &[Role::Check]))
                     .manage(pool)
                     .manage(configuration)
                     .mount("/", routes![list_instances]);
 
                 let client = Client::tracked(rocket).await.unwrap();
-                let request = client.get("/uda/instances");
+                let request = client
+                    .get("/uda/instances")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID));
 
                 let instances_list: InstancesList =
                     request.dispatch().await.into_json().await.unwrap();
@@ -514,12 +865,15 @@ mod tests {
                 let configuration =
                     Configuration::new(format!("{}/tenants?locale=en", mock_server.uri()));
                 let rocket = rocket::build()
+                    .manage(log_in(&pool, &[Role::Check]))
                     .manage(pool)
                     .manage(configuration)
                     .mount("/", routes![list_instances]);
 
                 let client = Client::tracked(rocket).await.unwrap();
-                let request = client.get("/uda/instances");
+                let request = client
+                    .get("/uda/instances")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID));
 
                 let status = request.dispatch().await.status();
                 assert_eq!(Status::BadGateway, status);
@@ -0,0 +1,524 @@
+use crate::database::dao;
+use crate::error::ApplicationError;
+use crate::tools::log_error_and_return;
+use crate::user::account::{authenticate, create_user};
+use crate::user::authentication::{AUTHENTICATION_COOKIE, Authorized, IsAdmin};
+use crate::user::error::UserError;
+use crate::user::session::UserSession;
+use crate::web::audit::record_audit_log;
+use crate::web::authentication;
+use crate::web::credentials_storage::CredentialsStorage;
+use diesel::SqliteConnection;
+use diesel::r2d2::{ConnectionManager, Pool};
+use dto::audit_log::AuditAction;
+use dto::user::{NewUser, Role, UserCredentials};
+use rocket::State;
+use rocket::http::{Cookie, CookieJar, Status};
+use rocket::serde::json::Json;
+use rocket::time::Duration;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// Log a local user in.
+/// If their credentials match, then a new UUID is created and their session is stored with this UUID.
+/// The UUID is returned to the caller through a private cookie, so that it is their new access token.
+#[post("/login", format = "application/json", data = "<credentials>")]
+pub async fn login(
+    pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
+    credentials_storage: &State<Mutex<CredentialsStorage<UserSession>>>,
+    cookie_jar: &CookieJar<'_>,
+    credentials: Json<UserCredentials>,
+) -> Result<Status, Status> {
+    let mut connection = pool
+        .get()
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    let user = authenticate(&mut connection, &credentials)
+        .map_err(log_error_and_return(Status::InternalServerError))?
+        .ok_or(Status::Unauthorized)?;
+
+    let uuid = Uuid::new_v4().to_string();
+    credentials_storage
+        .lock()
+        .map_err(log_error_and_return(Status::InternalServerError))?
+        .store(uuid.clone(), UserSession::new(user.login().to_owned()))
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    let cookie = Cookie::build((AUTHENTICATION_COOKIE.to_owned(), uuid))
+        .max_age(Duration::days(365))
+        .build();
+    cookie_jar.add_private(cookie);
+    Ok(Status::Ok)
+}
+
+/// Log a local user out, by revoking their access token.
+#[post("/logout")]
+pub async fn logout(
+    credentials_storage: &State<Mutex<CredentialsStorage<UserSession>>>,
+    cookie_jar: &CookieJar<'_>,
+) -> Result<Status, Status> {
+    authentication::logout(
+        credentials_storage.inner(),
+        cookie_jar,
+        AUTHENTICATION_COOKIE,
+    )
+}
+
+/// Create a local account.
+/// Logins are restricted to a few characters, since they end up in URLs and in the admin page.
+/// Fail with a Conflict status if the login is already taken.
+#[post("/users", format = "application/json", data = "<new_user>")]
+pub async fn create(
+    pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
+    new_user: Json<NewUser>,
+    admin: Authorized<IsAdmin>,
+) -> Result<Status, Status> {
+    let login = new_user.credentials().login().trim();
+    if login.is_empty() {
+        debug!("Can't create user without login");
+        return Err(Status::BadRequest);
+    }
+    if !login.chars().all(is_allowed_in_login) {
+        debug!("Login contains forbidden characters [login: {login}]");
+        return Err(Status::BadRequest);
+    }
+
+    let mut connection = pool
+        .get()
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    let new_user = NewUser::new(
+        UserCredentials::new(
+            login.to_owned(),
+            new_user.credentials().password().to_owned(),
+        ),
+        new_user.roles().clone(),
+    );
+    let created = create_user(&mut connection, &new_user).map_err(|error| match error {
+        ApplicationError::User(UserError::PasswordTooShort) => {
+            debug!("Password is too short [login: {login}]");
+            Status::BadRequest
+        }
+        _ => log_error_and_return(Status::InternalServerError)(error),
+    })?;
+    if !created {
+        debug!("User already exists [login: {login}]");
+        return Err(Status::Conflict);
+    }
+
+    record_audit_log(
+        pool.inner(),
+        admin.login(),
+        AuditAction::CreateUser,
+        &[login.to_owned()],
+    );
+    Ok(Status::Created)
+}
+
+/// Replace the roles of a user.
+/// Admins can't revoke their own admin role, so that at least one admin is left.
+#[put("/users/<login>/roles", format = "application/json", data = "<roles>")]
+pub async fn update_roles(
+    pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
+    login: &str,
+    roles: Json<Vec<Role>>,
+    admin: Authorized<IsAdmin>,
+) -> Result<Status, Status> {
+    if login == admin.login() && !roles.contains(&Role::Admin) {
+        debug!("Admin can't revoke their own admin role [login: {login}]");
+        return Err(Status::BadRequest);
+    }
+
+    let mut connection = pool
+        .get()
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    let updated = dao::user_account::update_roles(&mut connection, login, &roles)
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    if !updated {
+        debug!("User doesn't exist [login: {login}]");
+        return Err(Status::NotFound);
+    }
+
+    record_audit_log(
+        pool.inner(),
+        admin.login(),
+        AuditAction::UpdateUserRoles,
+        &[login.to_owned()],
+    );
+    Ok(Status::Ok)
+}
+
+/// Delete a local account. Admins can't delete their own account.
+/// Sessions the user has opened are not revoked, but they can no longer be used.
+#[delete("/users/<login>")]
+pub async fn delete(
+    pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
+    login: &str,
+    admin: Authorized<IsAdmin>,
+) -> Result<Status, Status> {
+    if login == admin.login() {
+        debug!("Admin can't delete their own account [login: {login}]");
+        return Err(Status::BadRequest);
+    }
+
+    let mut connection = pool
+        .get()
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    let deleted = dao::user_account::delete(&mut connection, login)
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    if !deleted {
+        debug!("User doesn't exist [login: {login}]");
+        return Err(Status::NotFound);
+    }
+
+    record_audit_log(
+        pool.inner(),
+        admin.login(),
+        AuditAction::DeleteUser,
+        &[login.to_owned()],
+    );
+    Ok(Status::Ok)
+}
+
+fn is_allowed_in_login(character: char) -> bool {
+    character.is_alphanumeric() || matches!(character, '.' | '-' | '_' | '@')
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::web::api::user_controller;
+    use rocket::Route;
+
+    /// The routes mounted by the test client.
+    fn routes() -> Vec<Route> {
+        routes![
+            user_controller::login,
+            user_controller::logout,
+            user_controller::create,
+            user_controller::update_roles,
+            user_controller::delete,
+        ]
+    }
+
+    mod login {
+        use crate::database::with_temp_database;
+        use crate::user::account::create_user;
+        use crate::user::authentication::AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::build_client;
+        use crate::user::session::UserSession;
+        use crate::web::api::user_controller::tests::routes;
+        use crate::web::credentials_storage::CredentialsStorage;
+        use diesel::SqliteConnection;
+        use diesel::r2d2::{ConnectionManager, Pool};
+        use dto::user::{NewUser, Role, UserCredentials};
+        use rocket::http::{ContentType, Status};
+        use rocket::serde::json::json;
+        use rocket::tokio::runtime::Runtime;
+        use std::sync::Mutex;
+
+        fn create_jon(pool: &Pool<ConnectionManager<SqliteConnection>>) {
+            let credentials = UserCredentials::new("jon".to_owned(), "password".to_owned());
+            create_user(
+                &mut pool.get().unwrap(),
+                &NewUser::new(credentials, vec![Role::Check]),
+            )
+            .unwrap();
+        }
+
+        #[test]
+        fn success() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                create_jon(&pool);
+                let client = build_client(pool, &[], routes()).await;
+
+                let response = client
+                    .post("/login")
+                    .header(ContentType::JSON)
+                    .body(json!({"login": "jon", "password": "password"}).to_string())
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Ok, response.status());
+
+                let cookie = response.cookies().get_private(AUTHENTICATION_COOKIE);
+                let uuid = cookie.unwrap().value().to_owned();
+                let session = client
+                    .rocket()
+                    .state::<Mutex<CredentialsStorage<UserSession>>>()
+                    .unwrap()
+                    .lock()
+                    .unwrap()
+                    .get(&uuid)
+                    .unwrap();
+                assert_eq!(Some(UserSession::new("jon".to_owned())), session);
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn fail_when_wrong_password() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                create_jon(&pool);
+                let client = build_client(pool, &[], routes()).await;
+
+                let response = client
+                    .post("/login")
+                    .header(ContentType::JSON)
+                    .body(json!({"login": "jon", "password": "wrong password"}).to_string())
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Unauthorized, response.status());
+                assert!(
+                    response
+                        .cookies()
+                        .get_private(AUTHENTICATION_COOKIE)
+                        .is_none()
+                );
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+    }
+
+    mod create {
+        use crate::database::{dao, with_temp_database};
+        use crate::user::authentication::AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::{USER_UUID, build_client};
+        use crate::web::api::user_controller::tests::routes;
+        use diesel::SqliteConnection;
+        use diesel::r2d2::{ConnectionManager, Pool};
+        use dto::audit_log::{AuditAction, AuditLogFilter};
+        use dto::user::{Role, User};
+        use rocket::http::{ContentType, Status};
+        use rocket::serde::json::json;
+        use rocket::tokio::runtime::Runtime;
+
+        fn new_user(login: &str, password: &str) -> String {
+            json!({
+                "credentials": {"login": login, "password": password},
+                "roles": ["Check", "LookUp"]
+            })
+            .to_string()
+        }
+
+        #[test]
+        fn success() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool.clone(), &[Role::Admin], routes()).await;
+
+                let response = client
+                    .post("/users")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .header(ContentType::JSON)
+                    .body(new_user(" jon ", "password"))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Created, response.status());
+
+                let mut connection = pool.get().unwrap();
+                assert_eq!(
+                    Some(User::new("jon".to_owned(), vec![Role::Check, Role::LookUp])),
+                    dao::user_account::retrieve(&mut connection, "jon").unwrap()
+                );
+                let audit_log =
+                    dao::audit_log::retrieve(&mut connection, &AuditLogFilter::default()).unwrap();
+                assert_eq!(&AuditAction::CreateUser, audit_log[0].action());
+                assert_eq!(&vec!["jon".to_owned()], audit_log[0].targets());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn conflict_when_login_is_taken() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool, &[Role::Admin], routes()).await;
+
+                let response = client
+                    .post("/users")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .header(ContentType::JSON)
+                    .body(new_user("test_login", "password"))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Conflict, response.status());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn bad_request_when_password_is_too_short() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool, &[Role::Admin], routes()).await;
+
+                let response = client
+                    .post("/users")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .header(ContentType::JSON)
+                    .body(new_user("jon", "short"))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::BadRequest, response.status());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn bad_request_when_login_has_forbidden_characters() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool, &[Role::Admin], routes()).await;
+
+                let response = client
+                    .post("/users")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .header(ContentType::JSON)
+                    .body(new_user("jon/snow", "password"))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::BadRequest, response.status());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn forbidden_when_not_admin() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client =
+                    build_client(pool, &[Role::Check, Role::RefreshMemberships], routes()).await;
+
+                let response = client
+                    .post("/users")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .header(ContentType::JSON)
+                    .body(new_user("jon", "password"))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Forbidden, response.status());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+    }
+
+    mod update_roles {
+        use crate::database::{dao, with_temp_database};
+        use crate::user::authentication::AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::{USER_UUID, build_client};
+        use crate::web::api::user_controller::tests::routes;
+        use diesel::SqliteConnection;
+        use diesel::r2d2::{ConnectionManager, Pool};
+        use dto::user::{Role, User};
+        use rocket::http::{ContentType, Status};
+        use rocket::tokio::runtime::Runtime;
+
+        #[test]
+        fn success() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                dao::user_account::insert(&mut pool.get().unwrap(), "jon", "hash", &[Role::Check])
+                    .unwrap();
+                let client = build_client(pool.clone(), &[Role::Admin], routes()).await;
+
+                let response = client
+                    .put("/users/jon/roles")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .header(ContentType::JSON)
+                    .body(r#"["LookUp","Email"]"#)
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Ok, response.status());
+                assert_eq!(
+                    Some(User::new("jon".to_owned(), vec![Role::LookUp, Role::Email])),
+                    dao::user_account::retrieve(&mut pool.get().unwrap(), "jon").unwrap()
+                );
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn not_found() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool, &[Role::Admin], routes()).await;
+
+                let response = client
+                    .put("/users/jon/roles")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .header(ContentType::JSON)
+                    .body(r#"["LookUp"]"#)
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::NotFound, response.status());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn bad_request_when_revoking_own_admin_role() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool, &[Role::Admin], routes()).await;
+
+                let response = client
+                    .put("/users/test_login/roles")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .header(ContentType::JSON)
+                    .body(r#"["Check"]"#)
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::BadRequest, response.status());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+    }
+
+    mod delete {
+        use crate::database::{dao, with_temp_database};
+        use crate::user::authentication::AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::{USER_UUID, build_client};
+        use crate::web::api::user_controller::tests::routes;
+        use diesel::SqliteConnection;
+        use diesel::r2d2::{ConnectionManager, Pool};
+        use dto::user::Role;
+        use rocket::http::Status;
+        use rocket::tokio::runtime::Runtime;
+
+        #[test]
+        fn success() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                dao::user_account::insert(&mut pool.get().unwrap(), "jon", "hash", &[Role::Check])
+                    .unwrap();
+                let client = build_client(pool.clone(), &[Role::Admin], routes()).await;
+
+                let response = client
+                    .delete("/users/jon")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Ok, response.status());
+                assert_eq!(
+                    None,
+                    dao::user_account::retrieve(&mut pool.get().unwrap(), "jon").unwrap()
+                );
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn bad_request_when_deleting_own_account() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool, &[Role::Admin], routes()).await;
+
+                let response = client
+                    .delete("/users/test_login")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::BadRequest, response.status());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+    }
+}
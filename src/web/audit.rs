@@ -0,0 +1,25 @@
+use crate::database::dao;
+use crate::database::error::DatabaseError;
+use diesel::SqliteConnection;
+use diesel::r2d2::{ConnectionManager, Pool};
+use dto::audit_log::AuditAction;
+
+/// Append an entry to the audit log.
+/// A failure is only logged, so that it doesn't prevent the user from getting the result of their action.
+pub(crate) fn record_audit_log(
+    pool: &Pool<ConnectionManager<SqliteConnection>>,
+    actor: &str,
+    action: AuditAction,
+    targets: &[String],
+) {
+    let recorded = pool
+        .get()
+        .map_err(DatabaseError::from)
+        .and_then(|mut connection| dao::audit_log::insert(&mut connection, actor, action, targets));
+    if let Err(error) = recorded {
+        error!(
+            "Can't record audit log entry [actor: {actor}, action: {}, error: {error:?}]",
+            action.value()
+        );
+    }
+}
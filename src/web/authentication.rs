@@ -1,7 +1,7 @@
 use crate::tools::log_error_and_return;
 use crate::web::credentials_storage::CredentialsStorage;
 use rocket::State;
-use rocket::http::{Cookie, Status};
+use rocket::http::{Cookie, CookieJar, Status};
 use rocket::outcome::{Outcome, try_outcome};
 use rocket::request::{self, Request};
 use std::sync::Mutex;
@@ -10,18 +10,24 @@ use std::sync::Mutex;
 /// If no credentials are associated to the cookie, or if no such cookie is present in the request,
 /// then returns a Forawrd outcome containing an Unauthorized status. This lets other routes to take on the request.
 /// Otherwise, return the retrieved credentials as a Success outcome.
-pub async fn from_request<C: Send + Sync + Clone + 'static>(
+pub async fn from_request<C: Send + Sync + 'static>(
     req: &Request<'_>,
     cookie_name: &str,
 ) -> request::Outcome<C, ()> {
-    if let Some(cookie) = get_authentication_cookie(req, cookie_name) {
+    if let Some(cookie) = get_authentication_cookie(req.cookies(), cookie_name) {
         let credentials_storage =
             try_outcome!(req.guard::<&State<Mutex<CredentialsStorage<C>>>>().await);
-        match credentials_storage.lock() {
-            Ok(mut credentials_storage) => match credentials_storage.get(cookie.value()) {
-                None => Outcome::Forward(Status::Unauthorized),
-                Some(credentials) => Outcome::Success(credentials.clone()),
-            },
+        let credentials = credentials_storage
+            .lock()
+            .map_err(|error| error.to_string())
+            .and_then(|mut credentials_storage| {
+                credentials_storage
+                    .get(cookie.value())
+                    .map_err(|error| error.to_string())
+            });
+        match credentials {
+            Ok(None) => Outcome::Forward(Status::Unauthorized),
+            Ok(Some(credentials)) => Outcome::Success(credentials),
             Err(error) => {
                 log_error_and_return(Outcome::Error((Status::InternalServerError, ())))(error)
             }
@@ -31,14 +37,61 @@ pub async fn from_request<C: Send + Sync + Clone + 'static>(
     }
 }
 
+/// Retrieve the token sent through an `Authorization: Bearer <token>` header, if any.
+/// Scripts authenticate this way instead of using cookies.
+pub fn get_bearer_token<'r>(req: &'r Request<'_>) -> Option<&'r str> {
+    req.headers()
+        .get_one("Authorization")?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+}
+
+/// Revoke the credentials associated to the cookie, then remove the cookie itself.
+/// Logging out without being logged in is not an error.
+pub fn logout<C>(
+    credentials_storage: &Mutex<CredentialsStorage<C>>,
+    cookie_jar: &CookieJar<'_>,
+    cookie_name: &'static str,
+) -> Result<Status, Status> {
+    if let Some(cookie) = get_authentication_cookie(cookie_jar, cookie_name) {
+        credentials_storage
+            .lock()
+            .map_err(log_error_and_return(Status::InternalServerError))?
+            .remove(cookie.value())
+            .map_err(log_error_and_return(Status::InternalServerError))?;
+        cookie_jar.remove_private(cookie_name);
+    }
+    Ok(Status::Ok)
+}
+
+/// Log the user out once the session opened for them on the other server has expired,
+/// so that they log in again, then return the status the caller should answer with.
+pub fn revoke_expired_session<C>(
+    credentials_storage: &Mutex<CredentialsStorage<C>>,
+    cookie_jar: &CookieJar<'_>,
+    cookie_name: &'static str,
+) -> Status {
+    match logout(credentials_storage, cookie_jar, cookie_name) {
+        Ok(_) => Status::Unauthorized,
+        Err(status) => status,
+    }
+}
+
 #[cfg(not(test))]
-fn get_authentication_cookie<'a>(req: &'a Request, cookie_name: &str) -> Option<Cookie<'a>> {
-    req.cookies().get_private(cookie_name)
+fn get_authentication_cookie(
+    cookie_jar: &CookieJar<'_>,
+    cookie_name: &str,
+) -> Option<Cookie<'static>> {
+    cookie_jar.get_private(cookie_name)
 }
 
 /// For tests, we have to ensure the cookie is there, pending or not. Otherwise, it doesn't work.
 /// Thus, the need to hijack the normal method.
 #[cfg(test)]
-fn get_authentication_cookie<'a>(req: &'a Request, cookie_name: &str) -> Option<Cookie<'a>> {
-    req.cookies().get_pending(cookie_name)
+fn get_authentication_cookie(
+    cookie_jar: &CookieJar<'_>,
+    cookie_name: &str,
+) -> Option<Cookie<'static>> {
+    cookie_jar.get_pending(cookie_name)
 }
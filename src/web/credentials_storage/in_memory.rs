@@ -0,0 +1,63 @@
+use crate::error::Result;
+use crate::web::credentials_storage::{CREDENTIALS_LIFESPAN_IN_SECONDS, CredentialsBackend};
+use cached::{Cached, TimedSizedCache};
+
+const CACHE_SIZE: usize = 100;
+
+/// Keep credentials in memory. Only 100 credentials can be stored at a time,
+/// and they are all lost when the server restarts.
+pub struct InMemoryCredentialsBackend<C> {
+    credentials: TimedSizedCache<String, C>,
+}
+
+impl<C: Clone + Send + Sync> CredentialsBackend<C> for InMemoryCredentialsBackend<C> {
+    fn store(&mut self, id: &str, credentials: C) -> Result<()> {
+        self.credentials.cache_set(id.to_owned(), credentials);
+        Ok(())
+    }
+
+    fn get(&mut self, id: &str) -> Result<Option<C>> {
+        Ok(self.credentials.cache_get(id).cloned())
+    }
+
+    fn remove(&mut self, id: &str) -> Result<()> {
+        self.credentials.cache_remove(id);
+        Ok(())
+    }
+}
+
+impl<C> Default for InMemoryCredentialsBackend<C> {
+    fn default() -> Self {
+        let credentials =
+            TimedSizedCache::with_size_and_lifespan(CACHE_SIZE, CREDENTIALS_LIFESPAN_IN_SECONDS);
+        Self { credentials }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::web::credentials_storage::CredentialsBackend;
+    use crate::web::credentials_storage::in_memory::InMemoryCredentialsBackend;
+    use cached::Cached;
+
+    #[test]
+    fn should_store_only_100_credentials() {
+        let mut backend: InMemoryCredentialsBackend<()> = InMemoryCredentialsBackend::default();
+        assert_eq!(0, backend.credentials.cache_size());
+        (0..100).for_each(|id| backend.store(&id.to_string(), ()).unwrap());
+        (0..100).for_each(|id| assert_eq!(Some(()), backend.get(&id.to_string()).unwrap()));
+        assert_eq!(100, backend.credentials.cache_size());
+        backend.store("100", ()).unwrap();
+        assert_eq!(100, backend.credentials.cache_size());
+        assert_eq!(None, backend.get("0").unwrap());
+    }
+
+    #[test]
+    fn should_remove_credentials() {
+        let mut backend: InMemoryCredentialsBackend<()> = InMemoryCredentialsBackend::default();
+        backend.store("id", ()).unwrap();
+
+        backend.remove("id").unwrap();
+        assert_eq!(None, backend.get("id").unwrap());
+    }
+}
@@ -0,0 +1,50 @@
+use crate::error::Result;
+use crate::web::credentials_storage::in_memory::InMemoryCredentialsBackend;
+
+mod in_memory;
+pub mod sqlite;
+
+/// How long credentials are kept once the user has logged in.
+const CREDENTIALS_LIFESPAN_IN_SECONDS: u64 = 60 * 60 * 24 * 30;
+
+/// Where credentials are actually kept.
+pub trait CredentialsBackend<C>: Send + Sync {
+    fn store(&mut self, id: &str, credentials: C) -> Result<()>;
+
+    fn get(&mut self, id: &str) -> Result<Option<C>>;
+
+    /// Revoke credentials, e.g. when the user logs out.
+    fn remove(&mut self, id: &str) -> Result<()>;
+}
+
+/// A container for storing credentials, whatever the backend is.
+/// By default, credentials are kept in memory.
+pub struct CredentialsStorage<C> {
+    backend: Box<dyn CredentialsBackend<C>>,
+}
+
+impl<C> CredentialsStorage<C> {
+    pub fn new(backend: impl CredentialsBackend<C> + 'static) -> Self {
+        Self {
+            backend: Box::new(backend),
+        }
+    }
+
+    pub fn store(&mut self, id: String, credentials: C) -> Result<()> {
+        self.backend.store(&id, credentials)
+    }
+
+    pub fn get(&mut self, id: &str) -> Result<Option<C>> {
+        self.backend.get(id)
+    }
+
+    pub fn remove(&mut self, id: &str) -> Result<()> {
+        self.backend.remove(id)
+    }
+}
+
+impl<C: Clone + Send + Sync + 'static> Default for CredentialsStorage<C> {
+    fn default() -> Self {
+        Self::new(InMemoryCredentialsBackend::default())
+    }
+}
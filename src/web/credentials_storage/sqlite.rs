@@ -0,0 +1,212 @@
+use crate::database::dao;
+use crate::database::error::DatabaseError;
+use crate::error::Result;
+use crate::web::credentials_storage::{CREDENTIALS_LIFESPAN_IN_SECONDS, CredentialsBackend};
+use crate::web::error::WebError::{CantEncryptCredentials, WrongSecretKey};
+use chrono::{Duration, Utc};
+use cookie::{Cookie, CookieJar, Key};
+use diesel::SqliteConnection;
+use diesel::r2d2::{ConnectionManager, Pool};
+use rocket::serde::Serialize;
+use rocket::serde::de::DeserializeOwned;
+use rocket::serde::json;
+use std::marker::PhantomData;
+
+/// Keys are derived from secrets at least as long as this.
+const MIN_SECRET_KEY_LENGTH: usize = 32;
+
+/// Credentials that can be stored into the database.
+pub trait StoredCredentials: Serialize + DeserializeOwned + Send + Sync {
+    /// Tell apart credentials of different kinds, in case the same ID is used for both.
+    const KIND: &'static str;
+}
+
+/// Keep credentials into the database, so that they survive restarts.
+/// Credentials are encrypted with a key derived from the secret key of the server,
+/// the same way Rocket encrypts private cookies.
+pub struct SqliteCredentialsBackend<C> {
+    pool: Pool<ConnectionManager<SqliteConnection>>,
+    key: Key,
+    credentials_type: PhantomData<fn() -> C>,
+}
+
+impl<C> SqliteCredentialsBackend<C> {
+    /// The secret key should be at least 32 bytes long, such as the `secret_key` of Rocket configuration.
+    pub fn new(pool: Pool<ConnectionManager<SqliteConnection>>, secret_key: &str) -> Result<Self> {
+        if secret_key.len() < MIN_SECRET_KEY_LENGTH {
+            return Err(WrongSecretKey.into());
+        }
+        Ok(Self {
+            pool,
+            key: Key::derive_from(secret_key.as_bytes()),
+            credentials_type: PhantomData,
+        })
+    }
+
+    fn encrypt(&self, id: &str, value: String) -> Option<String> {
+        let mut jar = CookieJar::new();
+        jar.private_mut(&self.key)
+            .add(Cookie::new(id.to_owned(), value));
+        jar.get(id).map(|cookie| cookie.value().to_owned())
+    }
+
+    /// The ID is used as associated data, so that credentials can't be swapped from one ID to another.
+    fn decrypt(&self, id: &str, encrypted: String) -> Option<String> {
+        let jar = CookieJar::new();
+        jar.private(&self.key)
+            .decrypt(Cookie::new(id.to_owned(), encrypted))
+            .map(|cookie| cookie.value().to_owned())
+    }
+}
+
+impl<C: StoredCredentials> CredentialsBackend<C> for SqliteCredentialsBackend<C> {
+    fn store(&mut self, id: &str, credentials: C) -> Result<()> {
+        let serialized = json::to_string(&credentials)
+            .map_err(|error| CantEncryptCredentials(error.to_string()))?;
+        let encrypted = self
+            .encrypt(id, serialized)
+            .ok_or_else(|| CantEncryptCredentials("Encrypted value is missing".to_owned()))?;
+        let expiration =
+            Utc::now().naive_local() + Duration::seconds(CREDENTIALS_LIFESPAN_IN_SECONDS as i64);
+
+        let mut connection = self.pool.get().map_err(DatabaseError::from)?;
+        dao::credentials_session::upsert(&mut connection, id, C::KIND, &expiration, &encrypted)?;
+        Ok(())
+    }
+
+    /// Credentials that can't be decrypted, e.g. because the secret key has changed, are revoked.
+    fn get(&mut self, id: &str) -> Result<Option<C>> {
+        let mut connection = self.pool.get().map_err(DatabaseError::from)?;
+        let Some(encrypted) = dao::credentials_session::retrieve(&mut connection, id, C::KIND)?
+        else {
+            return Ok(None);
+        };
+
+        let credentials = self
+            .decrypt(id, encrypted)
+            .and_then(|decrypted| json::from_str::<C>(&decrypted).ok());
+        if credentials.is_none() {
+            warn!(
+                "Stored credentials can't be read, revoking them [kind: {}]",
+                C::KIND
+            );
+            dao::credentials_session::delete(&mut connection, id, C::KIND)?;
+        }
+        Ok(credentials)
+    }
+
+    fn remove(&mut self, id: &str) -> Result<()> {
+        let mut connection = self.pool.get().map_err(DatabaseError::from)?;
+        dao::credentials_session::delete(&mut connection, id, C::KIND)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::{dao, with_temp_database};
+    use crate::fileo::session::FileoSession;
+    use crate::tools::web::SessionCookies;
+    use crate::uda::session::UdaSession;
+    use crate::web::credentials_storage::CredentialsBackend;
+    use crate::web::credentials_storage::sqlite::{SqliteCredentialsBackend, StoredCredentials};
+
+    const SECRET_KEY: &str = "hPRYyVRiMyxpw5sBB1XeCMN1kFsDCqKvBi2QJxBVHQk=";
+
+    fn fileo_session() -> FileoSession {
+        FileoSession::new(
+            "test_login".to_owned(),
+            SessionCookies::new("PHPSESSID=1234"),
+        )
+    }
+
+    #[test]
+    fn store_and_get() {
+        with_temp_database(|pool| {
+            let mut backend: SqliteCredentialsBackend<FileoSession> =
+                SqliteCredentialsBackend::new(pool.clone(), SECRET_KEY).unwrap();
+            backend.store("id", fileo_session()).unwrap();
+
+            assert_eq!(Some(fileo_session()), backend.get("id").unwrap());
+            assert_eq!(None, backend.get("other").unwrap());
+
+            let mut uda_backend: SqliteCredentialsBackend<UdaSession> =
+                SqliteCredentialsBackend::new(pool, SECRET_KEY).unwrap();
+            assert_eq!(None, uda_backend.get("id").unwrap());
+        })
+    }
+
+    #[test]
+    fn survive_restart() {
+        with_temp_database(|pool| {
+            let mut backend: SqliteCredentialsBackend<FileoSession> =
+                SqliteCredentialsBackend::new(pool.clone(), SECRET_KEY).unwrap();
+            backend.store("id", fileo_session()).unwrap();
+            drop(backend);
+
+            let mut backend: SqliteCredentialsBackend<FileoSession> =
+                SqliteCredentialsBackend::new(pool, SECRET_KEY).unwrap();
+            assert_eq!(Some(fileo_session()), backend.get("id").unwrap());
+        })
+    }
+
+    #[test]
+    fn encrypted_at_rest() {
+        with_temp_database(|pool| {
+            let mut backend: SqliteCredentialsBackend<FileoSession> =
+                SqliteCredentialsBackend::new(pool.clone(), SECRET_KEY).unwrap();
+            backend.store("id", fileo_session()).unwrap();
+
+            let mut connection = pool.get().unwrap();
+            let stored =
+                dao::credentials_session::retrieve(&mut connection, "id", FileoSession::KIND)
+                    .unwrap()
+                    .unwrap();
+            assert!(!stored.contains("test_login"));
+            assert!(!stored.contains("PHPSESSID"));
+        })
+    }
+
+    #[test]
+    fn revoke_when_secret_key_has_changed() {
+        with_temp_database(|pool| {
+            let mut backend: SqliteCredentialsBackend<FileoSession> =
+                SqliteCredentialsBackend::new(pool.clone(), SECRET_KEY).unwrap();
+            backend.store("id", fileo_session()).unwrap();
+
+            let mut backend: SqliteCredentialsBackend<FileoSession> =
+                SqliteCredentialsBackend::new(
+                    pool.clone(),
+                    "Ah3bWwPpy8mYdV0yWq0l3T8WktPG2yc2Cz1mh5nmLBU=",
+                )
+                .unwrap();
+            assert_eq!(None, backend.get("id").unwrap());
+
+            let mut connection = pool.get().unwrap();
+            assert_eq!(
+                None,
+                dao::credentials_session::retrieve(&mut connection, "id", FileoSession::KIND)
+                    .unwrap()
+            );
+        })
+    }
+
+    #[test]
+    fn remove() {
+        with_temp_database(|pool| {
+            let mut backend: SqliteCredentialsBackend<FileoSession> =
+                SqliteCredentialsBackend::new(pool, SECRET_KEY).unwrap();
+            backend.store("id", fileo_session()).unwrap();
+
+            backend.remove("id").unwrap();
+            assert_eq!(None, backend.get("id").unwrap());
+        })
+    }
+
+    #[test]
+    fn fail_when_secret_key_is_too_short() {
+        with_temp_database(|pool| {
+            assert!(SqliteCredentialsBackend::<FileoSession>::new(pool, "short").is_err());
+        })
+    }
+}
@@ -14,6 +14,12 @@ pub enum WebError {
         "Although the credentials are OK, the user doesn't have permissions to execute the operation."
     )]
     LackOfPermissions,
+    #[error("The session opened on the other server has expired.")]
+    SessionExpired,
     #[error("The requested page or file has not been found.")]
     NotFound,
+    #[error("Credentials can't be encrypted before being stored.")]
+    CantEncryptCredentials(String),
+    #[error("The secret key is too short to encrypt credentials.")]
+    WrongSecretKey,
 }
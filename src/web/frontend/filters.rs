@@ -1,4 +1,4 @@
-use chrono::{NaiveDate, Utc};
+use chrono::{Days, NaiveDate, Utc};
 use rocket::serde::json::Value;
 use std::collections::HashMap;
 
@@ -8,10 +8,23 @@ pub fn is_in_the_past(date: &Value, _: &HashMap<String, Value>) -> tera::Result<
     Ok(Value::Bool(date.cmp(&now).is_le()))
 }
 
+/// Whether the date is yet to come, but within the `threshold` number of days.
+pub fn is_expiring_soon(date: &Value, args: &HashMap<String, Value>) -> tera::Result<Value> {
+    let date: NaiveDate = serde::Deserialize::deserialize(date)?;
+    let threshold: u64 = match args.get("threshold") {
+        Some(threshold) => serde::Deserialize::deserialize(threshold)?,
+        None => return Err(tera::Error::msg("Missing `threshold` argument")),
+    };
+    let now = Utc::now().date_naive();
+    let threshold_date = now
+        .checked_add_days(Days::new(threshold))
+        .ok_or_else(|| tera::Error::msg("Threshold is too far in the future"))?;
+    Ok(Value::Bool(date > now && date <= threshold_date))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::Days;
     use rocket::serde::json::json;
 
     #[test]
@@ -27,4 +40,35 @@ mod tests {
         let result = is_in_the_past(&date, &HashMap::default()).unwrap();
         assert!(result.as_bool().unwrap());
     }
+
+    fn threshold_args(threshold: u64) -> HashMap<String, Value> {
+        HashMap::from([("threshold".to_owned(), json!(threshold))])
+    }
+
+    #[test]
+    fn should_be_expiring_soon() {
+        let date = json!(Utc::now().date_naive().checked_add_days(Days::new(10)));
+        let result = is_expiring_soon(&date, &threshold_args(30)).unwrap();
+        assert!(result.as_bool().unwrap());
+    }
+
+    #[test]
+    fn should_not_be_expiring_soon_when_after_threshold() {
+        let date = json!(Utc::now().date_naive().checked_add_days(Days::new(31)));
+        let result = is_expiring_soon(&date, &threshold_args(30)).unwrap();
+        assert!(!result.as_bool().unwrap());
+    }
+
+    #[test]
+    fn should_not_be_expiring_soon_when_in_the_past() {
+        let date = json!(Utc::now().date_naive().checked_sub_days(Days::new(1)));
+        let result = is_expiring_soon(&date, &threshold_args(30)).unwrap();
+        assert!(!result.as_bool().unwrap());
+    }
+
+    #[test]
+    fn should_fail_when_threshold_is_missing() {
+        let date = json!(Utc::now().date_naive());
+        assert!(is_expiring_soon(&date, &HashMap::default()).is_err());
+    }
 }
@@ -1,10 +1,21 @@
 use crate::database::dao;
 use crate::database::dao::last_update::UpdatableElement;
 use crate::database::dao::last_update::UpdatableElement::UdaInstances;
-use crate::fileo::credentials::FileoCredentials;
+use crate::fileo::session::FileoSession;
+use crate::fileo::sync::retrieve_last_sync;
+use crate::membership::config::retrieve_expiring_soon_threshold;
 use crate::tools::log_error_and_return;
+use crate::user::account::MIN_PASSWORD_LENGTH;
+use crate::user::authentication::{
+    Authorized, CanCheck, CanEmail, CanLookUp, CanRefreshMemberships, IsAdmin,
+};
+use chrono::NaiveDate;
 use diesel::SqliteConnection;
 use diesel::r2d2::{ConnectionManager, Pool};
+use dto::api_token::API_TOKEN_SCOPES;
+use dto::audit_log::{AuditAction, AuditLogFilter};
+use dto::email_template::EmailTemplateUseCase;
+use dto::user::Role;
 use rocket::http::Status;
 use rocket::response::Redirect;
 use rocket::{Request, State};
@@ -20,8 +31,18 @@ pub async fn index() -> Template {
     )
 }
 
+#[get("/login")]
+pub async fn login() -> Template {
+    Template::render(
+        "user/login",
+        context! {
+            title: "Connexion"
+        },
+    )
+}
+
 #[get("/fileo/login")]
-pub async fn fileo_login() -> Template {
+pub async fn fileo_login(_user: Authorized<CanRefreshMemberships>) -> Template {
     Template::render(
         "fileo/fileo-login",
         context! {
@@ -30,30 +51,46 @@ pub async fn fileo_login() -> Template {
     )
 }
 
+#[get("/fileo/login", rank = 2)]
+pub async fn fileo_login_unauthenticated() -> Redirect {
+    Redirect::to(uri!("/login/?page=/fileo/login"))
+}
+
 #[get("/memberships/update")]
 pub async fn update_memberships(
     pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
-    _credentials: FileoCredentials,
+    _user: Authorized<CanRefreshMemberships>,
+    fileo_session: Option<FileoSession>,
 ) -> Result<Template, Status> {
     let last_update = retrieve_last_update(pool)?;
+    let mut connection = pool
+        .get()
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    let last_import = dao::membership_import::retrieve_last(&mut connection)
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    let last_sync = retrieve_last_sync(&mut connection)
+        .map_err(log_error_and_return(Status::InternalServerError))?;
     Ok(Template::render(
         "member/update-memberships",
         context! {
             title: "Mise à jour de la liste des licences",
-            last_update: last_update
+            last_update: last_update,
+            last_import: last_import,
+            last_sync: last_sync,
+            logged_into_fileo: fileo_session.is_some(),
         },
     ))
 }
 
 #[get("/memberships/update", rank = 2)]
 pub async fn update_memberships_unauthenticated() -> Redirect {
-    Redirect::to(uri!("/fileo/login/?page=/memberships/update"))
+    Redirect::to(uri!("/login/?page=/memberships/update"))
 }
 
 #[get("/memberships")]
 pub async fn list_memberships(
     pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
-    _credentials: FileoCredentials,
+    _user: Authorized<CanLookUp>,
 ) -> Result<Template, Status> {
     let mut connection = pool
         .get()
@@ -65,55 +102,58 @@ pub async fn list_memberships(
         "fileo/memberships",
         context! {
             title: "Liste des licences",
-            memberships: memberships
+            memberships: memberships,
+            expiring_soon_threshold: retrieve_expiring_soon_threshold(),
         },
     ))
 }
 
 #[get("/memberships", rank = 2)]
 pub async fn list_memberships_unauthenticated() -> Redirect {
-    Redirect::to(uri!("/fileo/login/?page=/memberships"))
+    Redirect::to(uri!("/login/?page=/memberships"))
 }
 
 #[get("/memberships/lookup")]
-pub async fn look_membership_up(_credentials: FileoCredentials) -> Template {
+pub async fn look_membership_up(_user: Authorized<CanLookUp>) -> Template {
     Template::render(
         "member/lookup-member",
         context! {
             title: "Recherche d'adhésion",
+            expiring_soon_threshold: retrieve_expiring_soon_threshold(),
         },
     )
 }
 
 #[get("/memberships/lookup", rank = 2)]
 pub async fn look_membership_up_unauthenticated() -> Redirect {
-    Redirect::to(uri!("/fileo/login/?page=/memberships/lookup"))
+    Redirect::to(uri!("/login/?page=/memberships/lookup"))
 }
 
 #[get("/csv/check")]
 pub async fn check_members_from_csv(
     pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
-    _credentials: FileoCredentials,
+    _user: Authorized<CanCheck>,
 ) -> Result<Template, Status> {
     let last_update = retrieve_last_update(pool)?;
     Ok(Template::render(
         "fileo/check",
         context! {
             title: "Vérifier les licences depuis un fichier CSV",
-            last_update: last_update
+            last_update: last_update,
+            expiring_soon_threshold: retrieve_expiring_soon_threshold(),
         },
     ))
 }
 
 #[get("/csv/check", rank = 2)]
 pub async fn check_members_from_csv_unauthenticated() -> Redirect {
-    Redirect::to(uri!("/fileo/login/?page=/csv/check"))
+    Redirect::to(uri!("/login/?page=/csv/check"))
 }
 
 #[get("/uda/check")]
 pub async fn check_members_from_uda(
     pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
-    _credentials: FileoCredentials, // Fileo credentials are required for importing from UDA as well
+    _user: Authorized<CanCheck>,
 ) -> Result<Template, Status> {
     let mut connection = pool
         .get()
@@ -133,14 +173,188 @@ pub async fn check_members_from_uda(
         context! {
             title: "Vérifier les licences depuis un import UDA",
             instances: instances,
-            last_update: last_update
+            last_update: last_update,
+            expiring_soon_threshold: retrieve_expiring_soon_threshold(),
         },
     ))
 }
 
 #[get("/uda/check", rank = 2)]
 pub async fn check_members_from_uda_unauthenticated() -> Redirect {
-    Redirect::to(uri!("/fileo/login/?page=/uda/check"))
+    Redirect::to(uri!("/login/?page=/uda/check"))
+}
+
+/// Browse the audit log, filtered by actor, action and period.
+/// Empty criteria, as sent by the filter form, don't filter anything out.
+#[get("/audit-log?<actor>&<action>&<from>&<to>")]
+pub async fn audit_log(
+    pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
+    actor: Option<&str>,
+    action: Option<&str>,
+    from: Option<&str>,
+    to: Option<&str>,
+    _user: Authorized<IsAdmin>,
+) -> Result<Template, Status> {
+    let parse_date = |date: &str| {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|error| {
+            debug!("Can't parse audit log filter date [date: {date}, error: {error:?}]");
+            Status::BadRequest
+        })
+    };
+    let audited_action = non_empty(action)
+        .map(|action| {
+            AuditAction::from_value(action).ok_or_else(|| {
+                debug!("Unknown audited action [action: {action}]");
+                Status::BadRequest
+            })
+        })
+        .transpose()?;
+    let filter = AuditLogFilter::new(
+        non_empty(actor).map(str::to_owned),
+        audited_action,
+        non_empty(from).map(parse_date).transpose()?,
+        non_empty(to).map(parse_date).transpose()?,
+    );
+
+    let mut connection = pool
+        .get()
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    let entries = dao::audit_log::retrieve(&mut connection, &filter)
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    let actions: Vec<_> = AuditAction::ALL
+        .iter()
+        .map(|action| context! { value: action.value(), label: action.label() })
+        .collect();
+
+    Ok(Template::render(
+        "audit/audit-log",
+        context! {
+            title: "Journal d'audit",
+            entries: entries,
+            actions: actions,
+            filter: context! {
+                actor: non_empty(actor).unwrap_or_default(),
+                action: non_empty(action).unwrap_or_default(),
+                from: non_empty(from).unwrap_or_default(),
+                to: non_empty(to).unwrap_or_default(),
+            },
+        },
+    ))
+}
+
+#[get("/audit-log", rank = 2)]
+pub async fn audit_log_unauthenticated() -> Redirect {
+    Redirect::to(uri!("/login/?page=/audit-log"))
+}
+
+/// Empty filter fields are sent as empty strings, which mean no filter.
+fn non_empty(criterion: Option<&str>) -> Option<&str> {
+    criterion.filter(|value| !value.trim().is_empty())
+}
+
+/// Manage local accounts and their roles.
+#[get("/admin/users")]
+pub async fn users(
+    pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
+    admin: Authorized<IsAdmin>,
+) -> Result<Template, Status> {
+    let mut connection = pool
+        .get()
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    let users = dao::user_account::retrieve_all(&mut connection)
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    let roles: Vec<_> = Role::ALL
+        .iter()
+        .map(|role| context! { value: role.value(), label: role.label() })
+        .collect();
+
+    Ok(Template::render(
+        "admin/users",
+        context! {
+            title: "Utilisateurs",
+            users: users,
+            roles: roles,
+            current_login: admin.login(),
+            min_password_length: MIN_PASSWORD_LENGTH,
+        },
+    ))
+}
+
+#[get("/admin/users", rank = 2)]
+pub async fn users_unauthenticated() -> Redirect {
+    Redirect::to(uri!("/login/?page=/admin/users"))
+}
+
+/// Create and revoke API tokens.
+#[get("/admin/api-tokens")]
+pub async fn api_tokens(
+    pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
+    _admin: Authorized<IsAdmin>,
+) -> Result<Template, Status> {
+    let mut connection = pool
+        .get()
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    let tokens = dao::api_token::retrieve_all(&mut connection)
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    let scopes: Vec<_> = API_TOKEN_SCOPES
+        .iter()
+        .map(|scope| context! { value: scope.value(), label: scope.label() })
+        .collect();
+
+    Ok(Template::render(
+        "admin/api-tokens",
+        context! {
+            title: "Jetons d'API",
+            tokens: tokens,
+            scopes: scopes,
+        },
+    ))
+}
+
+#[get("/admin/api-tokens", rank = 2)]
+pub async fn api_tokens_unauthenticated() -> Redirect {
+    Redirect::to(uri!("/login/?page=/admin/api-tokens"))
+}
+
+/// Write the templates notifications start from, and pick the default one for each use case.
+#[get("/email-templates")]
+pub async fn email_templates(
+    pool: &State<Pool<ConnectionManager<SqliteConnection>>>,
+    _user: Authorized<CanEmail>,
+) -> Result<Template, Status> {
+    let mut connection = pool
+        .get()
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    let templates = dao::email_template::retrieve_all(&mut connection)
+        .map_err(log_error_and_return(Status::InternalServerError))?;
+    let use_cases: Vec<_> = EmailTemplateUseCase::ALL
+        .iter()
+        .map(|use_case| context! { value: use_case.value(), label: use_case.label() })
+        .collect();
+
+    Ok(Template::render(
+        "email/templates",
+        context! {
+            title: "Modèles d'email",
+            templates: templates,
+            use_cases: use_cases,
+        },
+    ))
+}
+
+#[get("/email-templates", rank = 2)]
+pub async fn email_templates_unauthenticated() -> Redirect {
+    Redirect::to(uri!("/login/?page=/email-templates"))
+}
+
+#[catch(403)]
+pub async fn forbidden() -> Template {
+    Template::render(
+        "error/403",
+        context! {
+            title: "Accès refusé"
+        },
+    )
 }
 
 #[catch(404)]
@@ -171,37 +385,177 @@ fn retrieve_last_update(
 
 #[cfg(test)]
 mod tests {
-    mod fileo_login {
-        use crate::web::frontend::frontend_controller::fileo_login;
+    mod login {
+        use crate::web::frontend::frontend_controller::login;
         use rocket::http::Status;
         use rocket::local::asynchronous::Client;
         use rocket_dyn_templates::Template;
 
         #[async_test]
-        async fn should_render_fileo_login() {
+        async fn should_render_login() {
             let rocket = rocket::build()
-                .mount("/", routes![fileo_login])
+                .mount("/", routes![login])
                 .attach(Template::fairing());
 
             let client = Client::tracked(rocket).await.unwrap();
-            let request = client.get("/fileo/login");
+            let request = client.get("/login");
 
             let response = request.dispatch().await;
             assert_eq!(Status::Ok, response.status());
         }
     }
 
+    mod fileo_login {
+        use crate::database::with_temp_database;
+        use crate::user::authentication::AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::{USER_UUID, log_in};
+        use crate::web::frontend::frontend_controller::{fileo_login, fileo_login_unauthenticated};
+        use diesel::SqliteConnection;
+        use diesel::r2d2::{ConnectionManager, Pool};
+        use dto::user::Role;
+        use rocket::http::Status;
+        use rocket::local::asynchronous::Client;
+        use rocket::tokio::runtime::Runtime;
+        use rocket_dyn_templates::Template;
+
+        async fn build_client(
+            pool: Pool<ConnectionManager<SqliteConnection>>,
+            roles: &[Role],
+        ) -> Client {
+            let storage = log_in(&pool, roles);
+            let rocket = rocket::build()
+                .mount("/", routes![fileo_login, fileo_login_unauthenticated])
+                .manage(pool)
+                .manage(storage)
+                .attach(Template::fairing());
+            Client::tracked(rocket).await.unwrap()
+        }
+
+        #[test]
+        fn should_render_fileo_login() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool, &[Role::RefreshMemberships]).await;
+
+                let response = client
+                    .get("/fileo/login")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Ok, response.status());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn forbidden_when_lacking_role() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool, &[Role::LookUp]).await;
+
+                let response = client
+                    .get("/fileo/login")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Forbidden, response.status());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+    }
+
+    mod update_memberships {
+        use crate::database::with_temp_database;
+        use crate::fileo::authentication::AUTHENTICATION_COOKIE as FILEO_AUTHENTICATION_COOKIE;
+        use crate::fileo::session::FileoSession;
+        use crate::tools::web::SessionCookies;
+        use crate::user::authentication::AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::{USER_UUID, log_in};
+        use crate::web::credentials_storage::CredentialsStorage;
+        use crate::web::frontend::frontend_controller::{
+            update_memberships, update_memberships_unauthenticated,
+        };
+        use diesel::SqliteConnection;
+        use diesel::r2d2::{ConnectionManager, Pool};
+        use dto::user::Role;
+        use rocket::http::Status;
+        use rocket::local::asynchronous::Client;
+        use rocket::tokio::runtime::Runtime;
+        use rocket_dyn_templates::Template;
+        use std::sync::Mutex;
+
+        const FILEO_UUID: &str = "0ea9a5fb-0f46-4057-902a-2552ed956bde";
+
+        async fn build_client(pool: Pool<ConnectionManager<SqliteConnection>>) -> Client {
+            let storage = log_in(&pool, &[Role::RefreshMemberships]);
+            let session = FileoSession::new("fileo_login".to_owned(), SessionCookies::default());
+            let mut fileo_storage = CredentialsStorage::default();
+            fileo_storage.store(FILEO_UUID.to_owned(), session).unwrap();
+
+            let rocket = rocket::build()
+                .mount(
+                    "/",
+                    routes![update_memberships, update_memberships_unauthenticated],
+                )
+                .manage(pool)
+                .manage(storage)
+                .manage(Mutex::new(fileo_storage))
+                .attach(Template::fairing());
+            Client::tracked(rocket).await.unwrap()
+        }
+
+        #[test]
+        fn should_offer_updating_when_logged_into_fileo() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool).await;
+
+                let response = client
+                    .get("/memberships/update")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .cookie((FILEO_AUTHENTICATION_COOKIE, FILEO_UUID))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Ok, response.status());
+                let body = response.into_string().await.unwrap();
+                assert!(body.contains("app.update_fileo_list()"));
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn should_offer_logging_into_fileo_when_not_logged_into_fileo() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool).await;
+
+                let response = client
+                    .get("/memberships/update")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Ok, response.status());
+                let body = response.into_string().await.unwrap();
+                assert!(!body.contains("app.update_fileo_list()"));
+                assert!(body.contains("/fileo/login?page=/memberships/update"));
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+    }
+
     mod list_memberships {
         use crate::database::with_temp_database;
-        use crate::fileo::authentication::AUTHENTICATION_COOKIE;
-        use crate::fileo::credentials::FileoCredentials;
+        use crate::user::authentication::AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::{USER_UUID, log_in};
+        use crate::user::session::UserSession;
         use crate::web::credentials_storage::CredentialsStorage;
         use crate::web::frontend::frontend_controller::{
             list_memberships, list_memberships_unauthenticated,
         };
         use diesel::SqliteConnection;
         use diesel::r2d2::{ConnectionManager, Pool};
-        use rocket::http::{Cookie, Status};
+        use dto::user::Role;
+        use rocket::http::Status;
         use rocket::local::asynchronous::Client;
         use rocket::tokio::runtime::Runtime;
         use rocket_dyn_templates::Template;
@@ -210,26 +564,20 @@ mod tests {
         #[test]
         fn should_render_membership_list() {
             async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
-                let credentials =
-                    FileoCredentials::new("test_login".to_owned(), "test_password".to_owned());
-                let mut credentials_storage = CredentialsStorage::default();
-                let uuid = "0ea9a5fb-0f46-4057-902a-2552ed956bde".to_owned();
-                credentials_storage.store(uuid.clone(), credentials);
-                let credentials_storage_mutex = Mutex::new(credentials_storage);
-
+                let storage = log_in(&pool, &[Role::LookUp]);
                 let rocket = rocket::build()
                     .mount(
                         "/",
                         routes![list_memberships, list_memberships_unauthenticated],
                     )
                     .manage(pool)
-                    .manage(credentials_storage_mutex)
+                    .manage(storage)
                     .attach(Template::fairing());
 
                 let client = Client::tracked(rocket).await.unwrap();
-                let cookie = Cookie::new(AUTHENTICATION_COOKIE, uuid);
-
-                let request = client.get("/memberships").cookie(cookie.clone());
+                let request = client
+                    .get("/memberships")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID));
 
                 let response = request.dispatch().await;
                 assert_eq!(Status::Ok, response.status());
@@ -246,6 +594,7 @@ mod tests {
                         routes![list_memberships, list_memberships_unauthenticated],
                     )
                     .manage(pool)
+                    .manage(Mutex::new(CredentialsStorage::<UserSession>::default()))
                     .attach(Template::fairing());
 
                 let client = Client::tracked(rocket).await.unwrap();
@@ -254,7 +603,7 @@ mod tests {
                 let response = request.dispatch().await;
                 assert_eq!(Status::SeeOther, response.status());
                 assert_eq!(
-                    "/fileo/login?page=/memberships",
+                    "/login?page=/memberships",
                     response.headers().get_one("location").unwrap()
                 );
             }
@@ -265,33 +614,79 @@ mod tests {
 
     mod check_members_from_csv {
         use crate::database::{dao, with_temp_database};
-        use crate::fileo::authentication::AUTHENTICATION_COOKIE;
-        use crate::fileo::credentials::FileoCredentials;
+        use crate::user::authentication::AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::{USER_UUID, log_in};
+        use crate::user::session::UserSession;
         use crate::web::credentials_storage::CredentialsStorage;
         use crate::web::frontend::frontend_controller::{
             check_members_from_csv, check_members_from_csv_unauthenticated,
         };
         use diesel::SqliteConnection;
         use diesel::r2d2::{ConnectionManager, Pool};
-        use rocket::http::{Cookie, Status};
+        use dto::user::Role;
+        use rocket::http::Status;
         use rocket::local::asynchronous::Client;
         use rocket::tokio::runtime::Runtime;
         use rocket_dyn_templates::Template;
         use std::sync::Mutex;
 
+        async fn build_client(pool: Pool<ConnectionManager<SqliteConnection>>) -> Client {
+            let storage = log_in(&pool, &[Role::Check]);
+            let rocket = rocket::build()
+                .mount(
+                    "/",
+                    routes![
+                        check_members_from_csv,
+                        check_members_from_csv_unauthenticated
+                    ],
+                )
+                .manage(pool)
+                .manage(storage)
+                .attach(Template::fairing());
+            Client::tracked(rocket).await.unwrap()
+        }
+
         #[test]
         fn success() {
             async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
-                let credentials =
-                    FileoCredentials::new("test_login".to_owned(), "test_password".to_owned());
-                let mut credentials_storage = CredentialsStorage::default();
-                let uuid = "0ea9a5fb-0f46-4057-902a-2552ed956bde".to_owned();
-                credentials_storage.store(uuid.clone(), credentials);
-                let credentials_storage_mutex = Mutex::new(credentials_storage);
-
                 let mut connection = pool.get().unwrap();
-                dao::membership::replace_memberships(&mut connection, &[]).unwrap(); // Updating last update date
+                dao::membership::import_memberships(&mut connection, &[]).unwrap(); // Updating last update date
+                let client = build_client(pool).await;
+
+                let request = client
+                    .get("/csv/check")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID));
+
+                let response = request.dispatch().await;
+                assert_eq!(Status::Ok, response.status());
+                let body = response.into_string().await.unwrap();
+                assert!(!body.contains("Jamais"));
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn success_when_never_updated() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool).await;
+
+                let request = client
+                    .get("/csv/check")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID));
+
+                let response = request.dispatch().await;
+                assert_eq!(Status::Ok, response.status());
+                let body = response.into_string().await.unwrap();
+                assert!(body.contains("Jamais"));
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
 
+        #[test]
+        fn fail_when_unauthenticated() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
                 let rocket = rocket::build()
                     .mount(
                         "/",
@@ -301,82 +696,263 @@ mod tests {
                         ],
                     )
                     .manage(pool)
-                    .manage(credentials_storage_mutex)
+                    .manage(Mutex::new(CredentialsStorage::<UserSession>::default()))
                     .attach(Template::fairing());
 
                 let client = Client::tracked(rocket).await.unwrap();
-                let cookie = Cookie::new(AUTHENTICATION_COOKIE, uuid);
-
-                let request = client.get("/csv/check").cookie(cookie.clone());
+                let request = client.get("/csv/check");
 
                 let response = request.dispatch().await;
+                assert_eq!(Status::SeeOther, response.status());
+                assert_eq!(
+                    "/login?page=/csv/check",
+                    response.headers().get_one("location").unwrap()
+                );
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+    }
+
+    mod audit_log {
+        use crate::database::{dao, with_temp_database};
+        use crate::user::authentication::AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::{USER_UUID, log_in};
+        use crate::web::frontend::frontend_controller::{audit_log, audit_log_unauthenticated};
+        use diesel::SqliteConnection;
+        use diesel::r2d2::{ConnectionManager, Pool};
+        use dto::audit_log::AuditAction::{LookMemberUp, NotifyMembers};
+        use dto::user::Role;
+        use rocket::http::Status;
+        use rocket::local::asynchronous::Client;
+        use rocket::tokio::runtime::Runtime;
+        use rocket_dyn_templates::Template;
+
+        async fn build_client(
+            pool: Pool<ConnectionManager<SqliteConnection>>,
+            roles: &[Role],
+        ) -> Client {
+            let storage = log_in(&pool, roles);
+            let rocket = rocket::build()
+                .mount("/", routes![audit_log, audit_log_unauthenticated])
+                .manage(pool)
+                .manage(storage)
+                .attach(Template::fairing());
+            Client::tracked(rocket).await.unwrap()
+        }
+
+        #[test]
+        fn success() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let mut connection = pool.get().unwrap();
+                dao::audit_log::insert(
+                    &mut connection,
+                    "jon",
+                    LookMemberUp,
+                    &["123456".to_owned()],
+                )
+                .unwrap();
+                dao::audit_log::insert(
+                    &mut connection,
+                    "jonette",
+                    NotifyMembers,
+                    &["jon.doe@email.com".to_owned()],
+                )
+                .unwrap();
+                let client = build_client(pool, &[Role::Admin]).await;
+
+                let response = client
+                    .get("/audit-log?actor=&action=LookMemberUp&from=&to=")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .dispatch()
+                    .await;
                 assert_eq!(Status::Ok, response.status());
                 let body = response.into_string().await.unwrap();
-                assert!(!body.contains("Jamais"));
+                assert!(body.contains("123456"));
+                assert!(!body.contains("jon.doe@email.com"));
             }
 
             with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
         }
 
         #[test]
-        fn success_when_never_updated() {
+        fn bad_request_when_wrong_date() {
             async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
-                let credentials =
-                    FileoCredentials::new("test_login".to_owned(), "test_password".to_owned());
-                let mut credentials_storage = CredentialsStorage::default();
-                let uuid = "0ea9a5fb-0f46-4057-902a-2552ed956bde".to_owned();
-                credentials_storage.store(uuid.clone(), credentials);
-                let credentials_storage_mutex = Mutex::new(credentials_storage);
+                let client = build_client(pool, &[Role::Admin]).await;
+
+                let response = client
+                    .get("/audit-log?from=26/04/2025")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::BadRequest, response.status());
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn forbidden_when_not_admin() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool, &[Role::Check, Role::LookUp]).await;
+
+                let response = client
+                    .get("/audit-log")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Forbidden, response.status());
+            }
 
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+
+        #[test]
+        fn fail_when_unauthenticated() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let client = build_client(pool, &[Role::Admin]).await;
+
+                let response = client.get("/audit-log").dispatch().await;
+                assert_eq!(Status::SeeOther, response.status());
+                assert_eq!(
+                    "/login?page=/audit-log",
+                    response.headers().get_one("location").unwrap()
+                );
+            }
+
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+    }
+
+    mod users {
+        use crate::database::{dao, with_temp_database};
+        use crate::user::authentication::AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::{USER_UUID, log_in};
+        use crate::web::frontend::frontend_controller::{users, users_unauthenticated};
+        use diesel::SqliteConnection;
+        use diesel::r2d2::{ConnectionManager, Pool};
+        use dto::user::Role;
+        use rocket::http::Status;
+        use rocket::local::asynchronous::Client;
+        use rocket::tokio::runtime::Runtime;
+        use rocket_dyn_templates::Template;
+
+        #[test]
+        fn success() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let storage = log_in(&pool, &[Role::Admin]);
+                let mut connection = pool.get().unwrap();
+                dao::user_account::insert(&mut connection, "jonette", "hash", &[Role::Email])
+                    .unwrap();
                 let rocket = rocket::build()
-                    .mount(
-                        "/",
-                        routes![
-                            check_members_from_csv,
-                            check_members_from_csv_unauthenticated
-                        ],
-                    )
+                    .mount("/", routes![users, users_unauthenticated])
                     .manage(pool)
-                    .manage(credentials_storage_mutex)
+                    .manage(storage)
                     .attach(Template::fairing());
-
                 let client = Client::tracked(rocket).await.unwrap();
-                let cookie = Cookie::new(AUTHENTICATION_COOKIE, uuid);
 
-                let request = client.get("/csv/check").cookie(cookie.clone());
+                let response = client
+                    .get("/admin/users")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Ok, response.status());
+                let body = response.into_string().await.unwrap();
+                assert!(body.contains("jonette"));
+                assert!(!body.contains("hash"));
+            }
 
-                let response = request.dispatch().await;
+            with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
+        }
+    }
+
+    mod api_tokens {
+        use crate::database::{dao, with_temp_database};
+        use crate::user::authentication::AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::{USER_UUID, log_in};
+        use crate::web::frontend::frontend_controller::{api_tokens, api_tokens_unauthenticated};
+        use diesel::SqliteConnection;
+        use diesel::r2d2::{ConnectionManager, Pool};
+        use dto::user::Role;
+        use rocket::http::Status;
+        use rocket::local::asynchronous::Client;
+        use rocket::tokio::runtime::Runtime;
+        use rocket_dyn_templates::Template;
+
+        #[test]
+        fn success() {
+            async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let storage = log_in(&pool, &[Role::Admin]);
+                let mut connection = pool.get().unwrap();
+                dao::api_token::insert(
+                    &mut connection,
+                    "inscriptions",
+                    "token_hash",
+                    &[Role::Check],
+                    "jon",
+                )
+                .unwrap();
+                let rocket = rocket::build()
+                    .mount("/", routes![api_tokens, api_tokens_unauthenticated])
+                    .manage(pool)
+                    .manage(storage)
+                    .attach(Template::fairing());
+                let client = Client::tracked(rocket).await.unwrap();
+
+                let response = client
+                    .get("/admin/api-tokens")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .dispatch()
+                    .await;
                 assert_eq!(Status::Ok, response.status());
                 let body = response.into_string().await.unwrap();
-                assert!(body.contains("Jamais"));
+                assert!(body.contains("inscriptions"));
+                assert!(!body.contains("token_hash"));
             }
 
             with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
         }
+    }
+
+    mod email_templates {
+        use crate::database::with_temp_database;
+        use crate::user::authentication::AUTHENTICATION_COOKIE;
+        use crate::user::authentication::tests::{USER_UUID, log_in};
+        use crate::web::frontend::frontend_controller::{
+            email_templates, email_templates_unauthenticated,
+        };
+        use diesel::SqliteConnection;
+        use diesel::r2d2::{ConnectionManager, Pool};
+        use dto::user::Role;
+        use rocket::http::Status;
+        use rocket::local::asynchronous::Client;
+        use rocket::tokio::runtime::Runtime;
+        use rocket_dyn_templates::Template;
 
         #[test]
-        fn fail_when_unauthenticated() {
+        fn success() {
             async fn test(pool: Pool<ConnectionManager<SqliteConnection>>) {
+                let storage = log_in(&pool, &[Role::Email]);
                 let rocket = rocket::build()
                     .mount(
                         "/",
-                        routes![
-                            check_members_from_csv,
-                            check_members_from_csv_unauthenticated
-                        ],
+                        routes![email_templates, email_templates_unauthenticated],
                     )
                     .manage(pool)
+                    .manage(storage)
                     .attach(Template::fairing());
-
                 let client = Client::tracked(rocket).await.unwrap();
-                let request = client.get("/csv/check");
 
-                let response = request.dispatch().await;
-                assert_eq!(Status::SeeOther, response.status());
-                assert_eq!(
-                    "/fileo/login?page=/csv/check",
-                    response.headers().get_one("location").unwrap()
-                );
+                let response = client
+                    .get("/email-templates")
+                    .cookie((AUTHENTICATION_COOKIE, USER_UUID))
+                    .dispatch()
+                    .await;
+                assert_eq!(Status::Ok, response.status());
+                let body = response.into_string().await.unwrap();
+                assert!(body.contains("Licence bientôt expirée"));
+                // Variables are shown as is, rather than rendered
+                assert!(body.contains("{{ first_name }}"));
             }
 
             with_temp_database(|pool| Runtime::new().unwrap().block_on(test(pool)));
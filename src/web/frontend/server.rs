@@ -19,7 +19,9 @@ impl Server for FrontendServer {
                 "/",
                 routes![
                     frontend_controller::index,
+                    frontend_controller::login,
                     frontend_controller::fileo_login,
+                    frontend_controller::fileo_login_unauthenticated,
                     frontend_controller::update_memberships,
                     frontend_controller::update_memberships_unauthenticated,
                     frontend_controller::list_memberships,
@@ -30,14 +32,31 @@ impl Server for FrontendServer {
                     frontend_controller::check_members_from_csv_unauthenticated,
                     frontend_controller::check_members_from_uda,
                     frontend_controller::check_members_from_uda_unauthenticated,
+                    frontend_controller::audit_log,
+                    frontend_controller::audit_log_unauthenticated,
+                    frontend_controller::users,
+                    frontend_controller::users_unauthenticated,
+                    frontend_controller::api_tokens,
+                    frontend_controller::api_tokens_unauthenticated,
+                    frontend_controller::email_templates,
+                    frontend_controller::email_templates_unauthenticated,
                 ],
             )
             .mount("/", FileServer::from("./public/static"))
-            .register("/", catchers![frontend_controller::not_found])
+            .register(
+                "/",
+                catchers![
+                    frontend_controller::forbidden,
+                    frontend_controller::not_found
+                ],
+            )
             .attach(Template::custom(|engines| {
                 engines
                     .tera
-                    .register_filter("is_in_the_past", filters::is_in_the_past)
+                    .register_filter("is_in_the_past", filters::is_in_the_past);
+                engines
+                    .tera
+                    .register_filter("is_expiring_soon", filters::is_expiring_soon);
             }))
     }
 }
@@ -1,9 +1,12 @@
+use crate::fileo::sync::fileo_sync_fairing;
+use crate::membership::notification_queue::email_queue_fairing;
 use crate::web::server::build_server;
 use diesel::SqliteConnection;
 use diesel::r2d2::{ConnectionManager, Pool};
 use rocket::{Build, Rocket};
 
 mod api;
+pub(crate) mod audit;
 pub(crate) mod authentication;
 pub mod credentials_storage;
 pub mod error;
@@ -12,4 +15,6 @@ mod server;
 
 pub fn start_servers(pool: Pool<ConnectionManager<SqliteConnection>>) -> Rocket<Build> {
     build_server(pool)
+        .attach(fileo_sync_fairing())
+        .attach(email_queue_fairing())
 }
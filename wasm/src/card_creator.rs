@@ -1,10 +1,12 @@
 use crate::Result;
-use crate::error::Error;
+use crate::error::{DEFAULT_ERROR_MESSAGE, Error};
 use crate::template::get_template;
 use crate::utils::{
-    add_class, append_child, create_element, query_selector_single_element, set_attribute,
+    ElementBuilder, add_class, append_child, create_element, get_element_by_id_dyn,
+    query_selector_single_element, set_attribute,
 };
 use dto::checked_member::{CheckResult, CheckedMember};
+use dto::event_period::EventPeriod;
 use dto::member_to_check::MemberToCheck;
 use dto::membership::Membership;
 use dto::membership_status::{MemberStatus, compute_member_status};
@@ -34,6 +36,10 @@ pub fn create_card_for_member_to_check(
     } else if let Some(identity) = member_to_check.identity() {
         query_selector_single_element(&element, ".identity")?.set_inner_html(identity.as_str());
     }
+    if let Some(birthdate) = member_to_check.birthdate() {
+        query_selector_single_element(&element, ".birthdate")?
+            .set_inner_html(&birthdate.format("%d/%m/%Y").to_string());
+    }
     if let Some(club) = member_to_check.club() {
         let club_element = query_selector_single_element(&element, ".club")?;
         club_element.set_inner_html(&club);
@@ -74,13 +80,18 @@ pub fn create_card_for_checked_member(
     document: &Document,
     checked_member: &CheckedMember<impl MemberToCheck>,
 ) -> Result<Element> {
-    let status = checked_member.compute_member_status();
+    let status = checked_member.status();
     let checked_member_card_template = get_checked_member_template(document)?;
 
     let member_card = create_card_for_member_to_check(document, checked_member.member_to_check())?;
     append_child(&checked_member_card_template, &member_card)?;
 
-    let membership_card = create_membership_card(document, checked_member.membership(), &status)?;
+    let membership_card = create_membership_card(
+        document,
+        checked_member.membership(),
+        status,
+        checked_member.member_to_check().email(),
+    )?;
     append_child(&checked_member_card_template, &membership_card)?;
 
     Ok(checked_member_card_template)
@@ -90,12 +101,22 @@ fn create_membership_card(
     document: &Document,
     check_result: &CheckResult,
     status: &MemberStatus,
+    member_email_address: Option<String>,
 ) -> Result<Element> {
+    if let CheckResult::AmbiguousMatch(memberships) = check_result {
+        return create_membership_candidates_card(document, memberships);
+    }
+
     let card = get_membership_template(document, status)?;
+    if *status == MemberStatus::Unknown {
+        fill_unknown_membership_card(&card, member_email_address)?;
+    }
 
     match &check_result {
-        CheckResult::Match(membership) | CheckResult::PartialMatch(membership) => {
-            if *status == MemberStatus::UpToDate || *status == MemberStatus::Expired {
+        CheckResult::Match(membership)
+        | CheckResult::PartialMatch(membership)
+        | CheckResult::FuzzyMatch(membership, _) => {
+            if *status != MemberStatus::Unknown {
                 query_selector_single_element(&card, ".membership-num")?
                     .set_inner_html(membership.membership_number());
                 query_selector_single_element(&card, ".membership-name")?
@@ -117,17 +138,78 @@ fn create_membership_card(
             if matches!(check_result, CheckResult::PartialMatch(_)) {
                 add_class(&card, "membership-partial-match");
             }
+            if let CheckResult::FuzzyMatch(_, score) = check_result {
+                add_class(&card, "membership-fuzzy-match");
+                query_selector_single_element(&card, ".membership-similarity")?
+                    .set_inner_html(&format!("{score} %"));
+            }
         }
-        CheckResult::NoMatch => {}
+        CheckResult::AmbiguousMatch(_) | CheckResult::NoMatch => {}
     }
     Ok(card)
 }
 
+/// Members without any membership can still be notified, provided they have an email address,
+/// e.g. UDA participants.
+fn fill_unknown_membership_card(card: &Element, email_address: Option<String>) -> Result<()> {
+    match email_address {
+        Some(email_address) => {
+            let email_address_container =
+                query_selector_single_element(card, "a.membership-email-address")?
+                    .dyn_into::<HtmlAnchorElement>()?;
+            email_address_container.set_inner_html(&email_address);
+            email_address_container.set_href(&format!("mailto:{email_address}"));
+        }
+        None => {
+            query_selector_single_element(card, ".membership-email")?.remove();
+            query_selector_single_element(card, ".send-email-checkbox")?.remove();
+        }
+    }
+    Ok(())
+}
+
+/// Create a card listing every membership that may be the member's one,
+/// so that the organizer can pick the right one, see `pick_membership_candidate`.
+fn create_membership_candidates_card(
+    document: &Document,
+    memberships: &[Membership],
+) -> Result<Element> {
+    let card = get_membership_candidates_template(document)?;
+    let candidates_container = query_selector_single_element(&card, ".membership-candidates-list")?;
+
+    for membership in memberships {
+        let candidate_card = create_known_membership_card(document, membership)?;
+        // No candidate is selected by default, as the organizer has to pick the right one.
+        query_selector_single_element(&candidate_card, ".send-email-checkbox input")?
+            .dyn_into::<HtmlInputElement>()?
+            .set_checked(false);
+        let pick_button = ElementBuilder::default()
+            .parent(&candidate_card)
+            .inner_html("C'est cette personne")
+            .build(document, "button")?;
+        add_class(&pick_button, "pick-membership-candidate");
+        set_attribute(&pick_button, "type", "button")?;
+        set_attribute(
+            &pick_button,
+            "onclick",
+            "app.pick_membership_candidate(document, this)",
+        )?;
+        append_child(&candidates_container, &candidate_card)?;
+    }
+
+    Ok(card)
+}
+
 pub fn create_known_membership_card(
     document: &Document,
     membership: &Membership,
 ) -> Result<Element> {
-    let status = compute_member_status(Some(membership));
+    let expiring_soon_threshold = get_expiring_soon_threshold(document)?;
+    let status = compute_member_status(
+        Some(membership),
+        &EventPeriod::today(),
+        expiring_soon_threshold,
+    );
 
     let card = get_membership_template(document, &status)?;
 
@@ -150,6 +232,18 @@ pub fn create_known_membership_card(
     Ok(card)
 }
 
+/// The threshold is set by the server, as it is part of its configuration.
+fn get_expiring_soon_threshold(document: &Document) -> Result<u64> {
+    let value =
+        get_element_by_id_dyn::<HtmlInputElement>(document, "expiring-soon-threshold")?.value();
+    value.parse::<u64>().map_err(|error| {
+        Error::new(
+            DEFAULT_ERROR_MESSAGE,
+            &format!("Can't parse expiring soon threshold [value: {value}, error: {error}]"),
+        )
+    })
+}
+
 fn get_member_to_check_template(document: &Document) -> Result<Element> {
     get_template(document, "member-to-check-template")
 }
@@ -158,10 +252,16 @@ fn get_checked_member_template(document: &Document) -> Result<Element> {
     get_template(document, "checked-member")
 }
 
+fn get_membership_candidates_template(document: &Document) -> Result<Element> {
+    get_template(document, "membership-candidates")
+}
+
 fn get_membership_template(document: &Document, member_status: &MemberStatus) -> Result<Element> {
     match member_status {
         MemberStatus::UpToDate => get_template(document, "membership-up-to-date"),
+        MemberStatus::ExpiringSoon => get_template(document, "membership-expiring-soon"),
         MemberStatus::Expired => get_template(document, "membership-expired"),
+        MemberStatus::StartsAfterEvent => get_template(document, "membership-starts-after-event"),
         MemberStatus::Unknown => get_template(document, "membership-unknown"),
     }
 }
@@ -1,34 +1,75 @@
 use crate::Result;
 use crate::component::alert::{AlertLevel, create_alert, unwrap_or_alert};
 use crate::component::stepper::next_step;
-use crate::error::{DEFAULT_SERVER_ERROR_MESSAGE, Error};
+use crate::email_template::fill_email_templates;
+use crate::error::{DEFAULT_ERROR_MESSAGE, DEFAULT_SERVER_ERROR_MESSAGE, Error};
 use crate::json;
 use crate::user_interface::{get_email_body, get_email_subject, set_loading, with_loading};
-use crate::utils::{get_document, get_element_by_id_dyn, query_selector_single_element};
-use crate::web::fetch;
-use dto::email::Email;
+use crate::utils::{
+    append_child, clear_element, create_element, get_document, get_element_by_id,
+    get_element_by_id_dyn, query_selector_all, query_selector_single_element, set_attribute, sleep,
+};
+use crate::web::{fetch, post_form};
+use chrono::NaiveDate;
+use dto::email::{Email, EmailRecipient, EmailStatus, NotificationStatus, PersonalizedEmail};
+use dto::email_template::EmailTemplateUseCase;
+use dto::event_period::EventPeriod;
+use dto::membership_status::MemberStatus;
 use std::collections::BTreeSet;
 use wasm_bindgen::JsCast;
 use wasm_bindgen::prelude::wasm_bindgen;
-use web_sys::{Document, HtmlButtonElement, HtmlInputElement, HtmlTextAreaElement};
+use web_sys::{
+    Document, Element, FormData, HtmlButtonElement, HtmlInputElement, HtmlSelectElement,
+    HtmlTextAreaElement,
+};
+
+const NOTIFICATION_POLL_INTERVAL_IN_MILLISECONDS: i32 = 3000;
+const INVALID_TEMPLATE_MESSAGE: &str =
+    "Le sujet ou le corps du message contient une variable inconnue ou mal écrite.";
 
 // region Handle steps
 #[wasm_bindgen]
 pub fn toggle_go_to_email_step_button(document: &Document) {
-    let email_addresses_to_notify = unwrap_or_alert(get_email_addresses_to_notify(document));
+    let recipients = unwrap_or_alert(get_email_recipients(document));
 
     let button = unwrap_or_alert(get_element_by_id_dyn::<HtmlButtonElement>(
         document,
         "go-to-send-email-step",
     ));
-    button.set_disabled(email_addresses_to_notify.is_empty());
+    button.set_disabled(recipients.is_empty());
+}
+
+/// Select or unselect every member of a group, e.g. those whose membership is expiring soon,
+/// so that they can be notified all at once.
+#[wasm_bindgen]
+pub fn toggle_recipient_group(document: &Document, group: &str, selected: bool) {
+    unwrap_or_alert(select_recipient_group(document, group, selected));
+    toggle_go_to_email_step_button(document);
+}
+
+fn select_recipient_group(document: &Document, group: &str, selected: bool) -> Result<()> {
+    let checked_members = get_element_by_id(document, "checked-members")?;
+    // Members with several candidate memberships are left aside until the right one is picked.
+    let checkboxes = query_selector_all(
+        &checked_members,
+        &format!(
+            ".checked-member:not(:has(.membership-candidates)) .membership-{group} .send-email-checkbox input"
+        ),
+    )?;
+    for checkbox in checkboxes {
+        checkbox
+            .dyn_into::<HtmlInputElement>()?
+            .set_checked(selected);
+    }
+    Ok(())
 }
 
 #[wasm_bindgen]
-pub fn go_to_notification_step(document: &Document) {
-    let addresses_to_notify = unwrap_or_alert(get_email_addresses_to_notify(document));
-    let text = addresses_to_notify
-        .into_iter()
+pub async fn go_to_notification_step(document: &Document) {
+    let recipients = unwrap_or_alert(get_email_recipients(document));
+    let text = recipients
+        .iter()
+        .map(|recipient| recipient.email_address().as_str())
         .collect::<Vec<_>>()
         .join("\n");
     let element = unwrap_or_alert(get_element_by_id_dyn::<HtmlTextAreaElement>(
@@ -36,77 +77,369 @@ pub fn go_to_notification_step(document: &Document) {
         "email-recipients",
     ));
     element.set_value(&text);
+    unwrap_or_alert(fill_preview_recipients(document, &recipients));
+    let statuses = unwrap_or_alert(get_recipient_statuses(document));
+    let use_case = EmailTemplateUseCase::for_recipients(&statuses);
+    with_loading(async || fill_email_templates(document, use_case).await).await;
 
     next_step(document);
 }
+
+/// Let the user pick whose email to preview, in the order recipients are sent to the server.
+fn fill_preview_recipients(document: &Document, recipients: &[EmailRecipient]) -> Result<()> {
+    let select = get_element_by_id(document, "email-preview-recipient")?;
+    clear_element(&select);
+    for (index, recipient) in recipients.iter().enumerate() {
+        let option = create_element(document, "option")?;
+        set_attribute(&option, "value", &index.to_string())?;
+        option.set_text_content(Some(recipient.email_address()));
+        append_child(&select, &option)?;
+    }
+    Ok(())
+}
+// endregion
+
+// region Handle membership candidates
+/// Keep the membership picked by the organizer among the candidates of an ambiguous match.
+/// The member is then handled as a matched one, i.e. it can be confirmed on UDA and notified.
+#[wasm_bindgen]
+pub fn pick_membership_candidate(document: &Document, button: &Element) {
+    unwrap_or_alert(replace_candidates_with_membership(button));
+    toggle_go_to_email_step_button(document);
+}
+
+fn replace_candidates_with_membership(button: &Element) -> Result<()> {
+    let membership = button.closest(".membership")?.ok_or_else(|| {
+        Error::new(
+            DEFAULT_ERROR_MESSAGE,
+            "No membership for the picked candidate",
+        )
+    })?;
+    let candidates = membership
+        .closest(".membership-candidates")?
+        .ok_or_else(|| {
+            Error::new(
+                DEFAULT_ERROR_MESSAGE,
+                "No candidates for the picked membership",
+            )
+        })?;
+
+    button.remove();
+    // Same default as in the templates: only members who are up-to-date needn't be notified
+    let selected = !membership.matches(".membership-up-to-date")?;
+    query_selector_single_element(&membership, ".send-email-checkbox input")?
+        .dyn_into::<HtmlInputElement>()?
+        .set_checked(selected);
+    candidates.replace_with_with_node_1(&membership)?;
+
+    Ok(())
+}
+// endregion
+
+// region Handle event period
+/// Build the URL to check members against the event period filled in by the user, if any.
+/// Dates are sent as `YYYY-MM-DD`, which is the format of date inputs' values.
+pub fn build_check_url(document: &Document, base_url: &str) -> Result<String> {
+    let start_date =
+        get_element_by_id_dyn::<HtmlInputElement>(document, "event-start-date")?.value();
+    let end_date = get_element_by_id_dyn::<HtmlInputElement>(document, "event-end-date")?.value();
+
+    if start_date.is_empty() && !end_date.is_empty() {
+        return Err(Error::new(
+            "Veuillez renseigner la date de début de l'événement.",
+            "Event end date provided without start date",
+        ));
+    }
+
+    let mut parameters = vec![];
+    if !start_date.is_empty() {
+        parameters.push(format!("start_date={start_date}"));
+    }
+    if !end_date.is_empty() {
+        parameters.push(format!("end_date={end_date}"));
+    }
+
+    if parameters.is_empty() {
+        Ok(base_url.to_owned())
+    } else {
+        Ok(format!("{base_url}?{}", parameters.join("&")))
+    }
+}
 // endregion
 
 // region Handle email sending
 #[wasm_bindgen]
 pub async fn handle_email_sending() {
+    let notification_id = with_loading(async || {
+        let document = &get_document()?;
+        let email = build_email(document)?;
+
+        let form = build_notification_form(document, &email)?;
+        let response = post_form("/api/members/notify", &form)
+            .await
+            .map_err(|error| Error::from_parent(DEFAULT_SERVER_ERROR_MESSAGE, error))?;
+        match response.status() {
+            200..400 => {
+                let body = response
+                    .body()
+                    .clone()
+                    .ok_or_else(|| Error::new(DEFAULT_ERROR_MESSAGE, "No body"))?;
+                let recipients_count = email.recipients().len();
+                create_alert(
+                    &format!(
+                        "L'envoi de l'email à {} adresse{} a été programmé.",
+                        &recipients_count,
+                        if recipients_count > 1 { "s" } else { "" }
+                    ),
+                    AlertLevel::Info,
+                );
+
+                Ok(json::from_str::<i32>(&body))
+            }
+            400 => Err(Error::new(
+                INVALID_TEMPLATE_MESSAGE,
+                "Invalid email template",
+            )),
+            413 => Err(Error::new(
+                "Les pièces jointes sont trop volumineuses.",
+                "Attachments are too large",
+            )),
+            status => Err(Error::from_server_status_error(status)),
+        }
+    })
+    .await;
+
+    unwrap_or_alert(follow_notification(notification_id).await);
+}
+
+/// Emails are sent in the background: show where each of them stands until none is left to send.
+async fn follow_notification(notification_id: i32) -> Result<()> {
+    let document = &get_document()?;
+    loop {
+        let response = fetch(
+            &format!("/api/members/notify/{notification_id}"),
+            "get",
+            None,
+            None,
+        )
+        .await
+        .map_err(|error| Error::from_parent(DEFAULT_SERVER_ERROR_MESSAGE, error))?;
+        if !(200..400).contains(&response.status()) {
+            return Err(Error::from_server_status_error(response.status()));
+        }
+        let body = response
+            .body()
+            .clone()
+            .ok_or_else(|| Error::new(DEFAULT_ERROR_MESSAGE, "No body"))?;
+        let status = json::from_str::<NotificationStatus>(&body);
+        display_notification_status(document, &status)?;
+
+        if status.is_done() {
+            let failed_count = status.count(EmailStatus::Failed);
+            if failed_count == 0 {
+                create_alert("Tous les emails ont été envoyés.", AlertLevel::Info);
+            } else {
+                create_alert(
+                    &format!("{failed_count} email(s) n'ont pas pu être envoyés."),
+                    AlertLevel::Error,
+                );
+            }
+            return Ok(());
+        }
+        sleep(NOTIFICATION_POLL_INTERVAL_IN_MILLISECONDS).await?;
+    }
+}
+
+fn display_notification_status(document: &Document, status: &NotificationStatus) -> Result<()> {
+    let container = get_element_by_id(document, "email-sending-status")?;
+    clear_element(&container);
+    for recipient in status.recipients() {
+        let text = match (recipient.status(), recipient.attempts()) {
+            (EmailStatus::Pending, 1..) => format!(
+                "{} : {} (nouvel essai prévu après {} échec(s))",
+                recipient.recipient(),
+                recipient.status().label(),
+                recipient.attempts()
+            ),
+            (status, _) => format!("{} : {}", recipient.recipient(), status.label()),
+        };
+        let item = create_element(document, "li")?;
+        item.set_text_content(Some(&text));
+        append_child(&container, &item)?;
+    }
+    Ok(())
+}
+
+/// Attachments are sent along with the email, hence a multipart form rather than JSON.
+fn build_notification_form(document: &Document, email: &Email) -> Result<FormData> {
+    let form = FormData::new()?;
+    form.append_with_str("email", &json::to_string(email))?;
+    let files = get_element_by_id_dyn::<HtmlInputElement>(document, "email-attachments")?.files();
+    if let Some(files) = files {
+        for index in 0..files.length() {
+            if let Some(file) = files.get(index) {
+                form.append_with_blob_and_filename("attachments", &file, &file.name())?;
+            }
+        }
+    }
+    Ok(form)
+}
+
+/// Show the email the selected recipient would receive, with the variables replaced.
+#[wasm_bindgen]
+pub async fn preview_email() {
     with_loading(async || {
         let document = &get_document()?;
-        let email_addresses_to_notify = get_email_addresses_to_notify(document)?
-            .into_iter()
-            .collect::<Vec<_>>();
-        let email_subject = get_email_subject(document)?;
-        let email_body = get_email_body(document)?;
-
-        let url = "/api/members/notify";
-        let email = Email::new(
-            email_addresses_to_notify.clone(),
-            email_subject.to_owned(),
-            email_body.to_owned(),
-        );
-        let body = json::to_string(&email);
+        let email = build_email(document)?;
+        let index =
+            get_element_by_id_dyn::<HtmlSelectElement>(document, "email-preview-recipient")?
+                .value();
 
-        let response = fetch(url, "post", Some("application/json"), Some(&body))
+        let url = format!("/api/members/notify/preview?index={index}");
+        let body = json::to_string(&email);
+        let response = fetch(&url, "post", Some("application/json"), Some(&body))
             .await
             .map_err(|error| Error::from_parent(DEFAULT_SERVER_ERROR_MESSAGE, error))?;
-        let status = response.status();
-        if (200..400).contains(&status) {
-            let addresses_count = email_addresses_to_notify.len();
-            create_alert(
-                &format!(
-                    "L'email a bien été envoyé à {} adresse{}.",
-                    &addresses_count,
-                    if addresses_count > 1 { "s" } else { "" }
-                ),
-                AlertLevel::Info,
-            );
-
-            Ok(())
-        } else {
-            Err(Error::from_server_status_error(status))
+        match response.status() {
+            200..400 => {
+                let body = response
+                    .body()
+                    .clone()
+                    .ok_or_else(|| Error::new(DEFAULT_ERROR_MESSAGE, "No body"))?;
+                let personalized_email = json::from_str::<PersonalizedEmail>(&body);
+                get_element_by_id(document, "email-preview-subject")?
+                    .set_text_content(Some(personalized_email.subject()));
+                // The HTML body is built by the server, which escapes what the organizer wrote.
+                get_element_by_id(document, "email-preview-body")?
+                    .set_inner_html(personalized_email.html_body());
+
+                Ok(())
+            }
+            400 => Err(Error::new(
+                INVALID_TEMPLATE_MESSAGE,
+                "Invalid email template",
+            )),
+            status => Err(Error::from_server_status_error(status)),
         }
     })
     .await;
 }
 
-fn get_email_addresses_to_notify(document: &Document) -> Result<BTreeSet<String>> {
-    // let checked_members_container = user_interface::get_checked_members_container(document);
+fn build_email(document: &Document) -> Result<Email> {
+    let recipients = get_email_recipients(document)?;
+    let event_name = get_element_by_id_dyn::<HtmlInputElement>(document, "email-event-name")?
+        .value()
+        .trim()
+        .to_owned();
+
+    Ok(Email::new(
+        recipients,
+        get_email_subject(document)?,
+        get_email_body(document)?,
+        Some(event_name).filter(|event_name| !event_name.is_empty()),
+        get_event_period(document)?,
+    ))
+}
+
+/// Read the event period the members have been checked against, if the page has one.
+fn get_event_period(document: &Document) -> Result<Option<EventPeriod>> {
+    let (Some(start_date), Some(end_date)) = (
+        document.get_element_by_id("event-start-date"),
+        document.get_element_by_id("event-end-date"),
+    ) else {
+        return Ok(None);
+    };
+    let start_date = parse_date(&start_date.dyn_into::<HtmlInputElement>()?.value())?;
+    let end_date = parse_date(&end_date.dyn_into::<HtmlInputElement>()?.value())?;
+
+    match (start_date, end_date) {
+        (Some(start_date), None) => Ok(Some(EventPeriod::single_day(start_date))),
+        (Some(start_date), Some(end_date)) => EventPeriod::new(start_date, end_date)
+            .map(Some)
+            .ok_or_else(|| {
+                Error::new(
+                    "La date de fin de l'événement doit être postérieure à sa date de début.",
+                    "Event ends before starting",
+                )
+            }),
+        _ => Ok(None),
+    }
+}
+
+fn parse_date(value: &str) -> Result<Option<NaiveDate>> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map(Some)
+        .map_err(|error| Error::new(DEFAULT_ERROR_MESSAGE, &error.to_string()))
+}
+
+/// The status of each checked membership, as shown by its card, so that the matching template can be picked.
+fn get_recipient_statuses(document: &Document) -> Result<Vec<MemberStatus>> {
     let memberships = document.get_elements_by_class_name("membership");
-    let mut email_addresses_to_notify = BTreeSet::new();
+    let mut statuses = vec![];
     for index in 0..memberships.length() {
         let membership = memberships.get_with_index(index).unwrap();
-        if membership.class_name().contains("membership-unknown") {
+        let class_name = membership.class_name();
+        let Some(status) = class_name
+            .split_whitespace()
+            .find_map(get_member_status_from_class)
+        else {
+            continue;
+        };
+        // Unknown members without an email address have no checkbox, as they can't be notified.
+        let Some(checkbox) = membership.query_selector("input[type=\"checkbox\"]")? else {
             continue;
+        };
+
+        if checkbox.dyn_into::<HtmlInputElement>()?.checked() {
+            statuses.push(status);
         }
+    }
+    Ok(statuses)
+}
+
+fn get_member_status_from_class(class: &str) -> Option<MemberStatus> {
+    match class {
+        "membership-up-to-date" => Some(MemberStatus::UpToDate),
+        "membership-expiring-soon" => Some(MemberStatus::ExpiringSoon),
+        "membership-expired" => Some(MemberStatus::Expired),
+        "membership-starts-after-event" => Some(MemberStatus::StartsAfterEvent),
+        "membership-unknown" => Some(MemberStatus::Unknown),
+        _ => None,
+    }
+}
+
+/// Recipients are deduplicated and sorted, so that the preview and the notification agree on their order.
+fn get_email_recipients(document: &Document) -> Result<Vec<EmailRecipient>> {
+    let memberships = document.get_elements_by_class_name("membership");
+    let mut recipients = BTreeSet::new();
+    for index in 0..memberships.length() {
+        let membership = memberships.get_with_index(index).unwrap();
+        // Unknown members without an email address have no checkbox, as they can't be notified.
+        let Some(checkbox) = membership.query_selector("input[type=\"checkbox\"]")? else {
+            continue;
+        };
 
-        let checkbox = query_selector_single_element(&membership, "input[type=\"checkbox\"]")?
-            .dyn_into::<HtmlInputElement>()?;
-        let is_checked = checkbox.checked();
+        let is_checked = checkbox.dyn_into::<HtmlInputElement>()?.checked();
         if is_checked {
             let address_container =
                 query_selector_single_element(&membership, ".email-address-container a")?;
             match address_container.text_content() {
                 None => set_loading(false)?,
                 Some(email_address) => {
-                    email_addresses_to_notify.insert(email_address);
+                    // Unknown members have no membership number.
+                    let membership_number = membership
+                        .query_selector(".membership-num")?
+                        .and_then(|membership_number| membership_number.text_content())
+                        .filter(|membership_number| !membership_number.is_empty());
+                    recipients.insert(EmailRecipient::new(email_address, membership_number));
                 }
             };
         }
     }
-    Ok(email_addresses_to_notify)
+    Ok(recipients.into_iter().collect())
 }
 // endregion
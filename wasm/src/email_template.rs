@@ -0,0 +1,186 @@
+use crate::Result;
+use crate::component::alert::unwrap_or_alert;
+use crate::error::{DEFAULT_ERROR_MESSAGE, DEFAULT_SERVER_ERROR_MESSAGE, Error};
+use crate::json;
+use crate::user::admin::reload;
+use crate::user_interface::with_loading;
+use crate::utils::{
+    append_child, clear_element, create_element, get_document, get_element_by_id,
+    get_element_by_id_dyn, query_selector_single_element, set_attribute,
+};
+use crate::web::fetch;
+use dto::email_template::{EmailTemplate, EmailTemplateUseCase, NewEmailTemplate};
+use wasm_bindgen::prelude::wasm_bindgen;
+use web_sys::{Document, HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement};
+
+// region Manage templates
+#[wasm_bindgen(js_name = "createEmailTemplate")]
+pub async fn create_email_template() {
+    with_loading(async || {
+        let document = get_document()?;
+        let Some(template) = read_template_form(&document, "new-email-template")? else {
+            return Ok(());
+        };
+        let response = fetch(
+            "/api/email-templates",
+            "post",
+            Some("application/json"),
+            Some(&json::to_string(&template)),
+        )
+        .await
+        .map_err(|error| Error::from_parent(DEFAULT_SERVER_ERROR_MESSAGE, error))?;
+
+        handle_save_response(response.status())
+    })
+    .await;
+}
+
+#[wasm_bindgen(js_name = "updateEmailTemplate")]
+pub async fn update_email_template(id: i32) {
+    with_loading(async || {
+        let document = get_document()?;
+        let Some(template) = read_template_form(&document, &format!("email-template-{id}"))? else {
+            return Ok(());
+        };
+        let response = fetch(
+            &format!("/api/email-templates/{id}"),
+            "put",
+            Some("application/json"),
+            Some(&json::to_string(&template)),
+        )
+        .await
+        .map_err(|error| Error::from_parent(DEFAULT_SERVER_ERROR_MESSAGE, error))?;
+
+        handle_save_response(response.status())
+    })
+    .await;
+}
+
+#[wasm_bindgen(js_name = "deleteEmailTemplate")]
+pub async fn delete_email_template(id: i32) {
+    with_loading(async || {
+        let url = format!("/api/email-templates/{id}");
+        let response = fetch(&url, "delete", None, None)
+            .await
+            .map_err(|error| Error::from_parent(DEFAULT_SERVER_ERROR_MESSAGE, error))?;
+
+        match response.status() {
+            200..400 => reload(),
+            status => Err(Error::from_server_status_error(status)),
+        }
+    })
+    .await;
+}
+
+/// Fields of a template form have IDs made of a prefix and the field, e.g. `new-email-template-name`.
+/// Return None if a required field is missing, the browser then tells the user which one.
+fn read_template_form(document: &Document, prefix: &str) -> Result<Option<NewEmailTemplate>> {
+    let name_field =
+        get_element_by_id_dyn::<HtmlInputElement>(document, &format!("{prefix}-name"))?;
+    let subject_field =
+        get_element_by_id_dyn::<HtmlInputElement>(document, &format!("{prefix}-subject"))?;
+    if !name_field.report_validity() || !subject_field.report_validity() {
+        return Ok(None);
+    }
+
+    let body =
+        get_element_by_id_dyn::<HtmlTextAreaElement>(document, &format!("{prefix}-body"))?.value();
+    let use_case =
+        get_element_by_id_dyn::<HtmlSelectElement>(document, &format!("{prefix}-use-case"))?
+            .value();
+    Ok(Some(NewEmailTemplate::new(
+        name_field.value(),
+        subject_field.value(),
+        body,
+        EmailTemplateUseCase::from_value(&use_case),
+    )))
+}
+
+fn handle_save_response(status: u16) -> Result<()> {
+    match status {
+        200..400 => reload(),
+        400 => Err(Error::new(
+            "Le modèle doit avoir un nom et un sujet, et ne contenir que des variables connues et bien écrites.",
+            "Invalid email template",
+        )),
+        409 => Err(Error::new(
+            "Un modèle porte déjà ce nom.",
+            "Email template name already taken",
+        )),
+        status => Err(Error::from_server_status_error(status)),
+    }
+}
+// endregion
+
+// region Pick a template
+/// List the templates in the notification step, then apply the default one for the use case, if any.
+/// The subject and the body are left untouched otherwise.
+pub async fn fill_email_templates(
+    document: &Document,
+    use_case: Option<EmailTemplateUseCase>,
+) -> Result<()> {
+    let response = fetch("/api/email-templates", "get", None, None)
+        .await
+        .map_err(|error| Error::from_parent(DEFAULT_SERVER_ERROR_MESSAGE, error))?;
+    let templates = match response.status() {
+        200..400 => {
+            let body = response
+                .body()
+                .clone()
+                .ok_or_else(|| Error::new(DEFAULT_ERROR_MESSAGE, "No body"))?;
+            json::from_str::<Vec<EmailTemplate>>(&body)
+        }
+        status => return Err(Error::from_server_status_error(status)),
+    };
+
+    let default_template = use_case.and_then(|use_case| {
+        templates
+            .iter()
+            .find(|template| *template.use_case() == Some(use_case))
+    });
+
+    let select = get_element_by_id(document, "email-template")?;
+    clear_element(&select);
+    let empty_option = create_element(document, "option")?;
+    set_attribute(&empty_option, "value", "")?;
+    empty_option.set_text_content(Some("Aucun modèle"));
+    append_child(&select, &empty_option)?;
+    for template in &templates {
+        let option = create_element(document, "option")?;
+        set_attribute(&option, "value", &template.id().to_string())?;
+        set_attribute(&option, "data-subject", template.subject())?;
+        set_attribute(&option, "data-body", template.body())?;
+        if default_template.is_some_and(|default_template| default_template.id() == template.id()) {
+            set_attribute(&option, "selected", "")?;
+        }
+        option.set_text_content(Some(template.name()));
+        append_child(&select, &option)?;
+    }
+
+    if default_template.is_some() {
+        write_from_selected_template(document)?;
+    }
+    Ok(())
+}
+
+/// Replace the subject and the body with those of the template the user has picked.
+#[wasm_bindgen]
+pub fn apply_email_template(document: &Document) {
+    unwrap_or_alert(write_from_selected_template(document));
+}
+
+fn write_from_selected_template(document: &Document) -> Result<()> {
+    let select = get_element_by_id(document, "email-template")?;
+    let option = query_selector_single_element(&select, "option:checked")?;
+    let (Some(subject), Some(body)) = (
+        option.get_attribute("data-subject"),
+        option.get_attribute("data-body"),
+    ) else {
+        return Ok(());
+    };
+
+    get_element_by_id_dyn::<HtmlInputElement>(document, "email-subject")?.set_value(&subject);
+    get_element_by_id_dyn::<HtmlTextAreaElement>(document, "email-body")?.set_value(&body);
+    Ok(())
+}
+// endregion
@@ -1,17 +1,44 @@
-use crate::check_memberships::toggle_go_to_email_step_button;
+use crate::check_memberships::{build_check_url, toggle_go_to_email_step_button};
 use crate::component::stepper::next_step;
 use crate::error::{DEFAULT_SERVER_ERROR_MESSAGE, Error};
-use crate::fileo::load_members_from_csv;
+use crate::fileo::load_members_from_spreadsheet::is_spreadsheet;
+use crate::fileo::{csv_mapping, load_members_from_csv};
 use crate::user_interface::with_loading;
 use crate::utils::get_document;
-use crate::web::fetch;
+use crate::web::{download, fetch};
 use crate::{json, user_interface};
 use dto::checked_member::CheckedMember;
 use dto::csv_member::CsvMember;
+use std::collections::BTreeSet;
 use wasm_bindgen::prelude::wasm_bindgen;
 
 #[wasm_bindgen]
 pub async fn handle_members_to_check_file() {
+    with_loading(async || {
+        let document = get_document()?;
+
+        let file = load_members_from_csv::get_members_to_check_file(&document)?;
+        // Columns of spreadsheets are guessed by the server, without any confirmation
+        if load_members_from_csv::is_automatic_format(&document)? && !is_spreadsheet(&file) {
+            // Columns have to be confirmed by the user before members can be loaded
+            let csv_content = load_members_from_csv::read_members_to_check_file(&document).await?;
+            let (layout, first_record) = csv_mapping::guess_layout(&csv_content);
+            user_interface::render_lines(&document, &BTreeSet::new(), &[])?;
+            return csv_mapping::render_mapping(&document, &layout, &first_record);
+        }
+
+        csv_mapping::clear_mapping(&document)?;
+        let (members_to_check, wrong_lines) =
+            load_members_from_csv::load_members_to_check(&document).await?;
+
+        user_interface::render_lines(&document, &members_to_check, &wrong_lines)
+    })
+    .await;
+}
+
+/// Load members to check once the user has confirmed which field each column holds.
+#[wasm_bindgen]
+pub async fn confirm_csv_mapping() {
     with_loading(async || {
         let document = get_document()?;
 
@@ -36,10 +63,10 @@ pub async fn handle_form_submission() {
             ));
         }
 
-        let url = "/api/members/csv/check";
+        let url = build_check_url(document, "/api/members/csv/check")?;
         let body = json::to_string(&members_to_check);
         let response = fetch(
-            url,
+            &url,
             "post",
             Some("application/json"),
             Some(&body),
@@ -62,3 +89,22 @@ pub async fn handle_form_submission() {
     })
         .await;
 }
+
+/// Download the check results as a `csv`, `xlsx` or `pdf` file,
+/// so that they can be filed along with the event paperwork.
+#[wasm_bindgen]
+pub async fn export_csv_check_results(format: String) {
+    with_loading(async || {
+        let document = &get_document()?;
+
+        let (members_to_check, _) = load_members_from_csv::load_members_to_check(document).await?;
+        let url = build_check_url(document, &format!("/api/members/csv/check/export/{format}"))?;
+        download(
+            &url,
+            &json::to_string(&members_to_check),
+            &format!("verification-licences.{format}"),
+        )
+        .await
+    })
+    .await;
+}
@@ -0,0 +1,328 @@
+use crate::Result;
+use crate::error::{DEFAULT_ERROR_MESSAGE, Error};
+use crate::utils::{
+    ElementBuilder, clear_element, get_element_by_id, get_element_by_id_dyn, query_selector_all,
+    set_attribute,
+};
+use csv::StringRecord;
+use dto::member_field::MemberField;
+use encoding::all::WINDOWS_1252;
+use encoding::{DecoderTrap, Encoding};
+use wasm_bindgen::JsCast;
+use web_sys::{Document, HtmlInputElement, HtmlSelectElement};
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+/// Delimiters spreadsheets usually export with, the preferred one last.
+const DELIMITERS: [u8; 3] = [b'\t', b',', b';'];
+
+/// How to read a CSV file of members to check:
+/// which delimiter separates the fields, whether the first line holds headers,
+/// and which field each column holds, if any.
+#[derive(Debug, PartialEq)]
+pub struct CsvLayout {
+    delimiter: u8,
+    has_headers: bool,
+    columns: Vec<Option<MemberField>>,
+}
+
+impl CsvLayout {
+    pub fn new(delimiter: u8, has_headers: bool, columns: Vec<Option<MemberField>>) -> Self {
+        Self {
+            delimiter,
+            has_headers,
+            columns,
+        }
+    }
+
+    pub fn delimiter(&self) -> u8 {
+        self.delimiter
+    }
+
+    pub fn has_headers(&self) -> bool {
+        self.has_headers
+    }
+
+    pub fn columns(&self) -> &[Option<MemberField>] {
+        &self.columns
+    }
+}
+
+/// Files exported from spreadsheets are usually encoded in UTF-8,
+/// but Excel still exports in Windows-1252 from time to time.
+pub fn decode_file(file_content: &[u8]) -> String {
+    let file_content = file_content.strip_prefix(UTF8_BOM).unwrap_or(file_content);
+    match std::str::from_utf8(file_content) {
+        Ok(text) => text.to_owned(),
+        Err(_) => WINDOWS_1252
+            .decode(file_content, DecoderTrap::Replace)
+            .unwrap_or_default(),
+    }
+}
+
+/// Guess the delimiter from the first line of the file.
+/// When no known delimiter appears, the file is considered `;`-separated.
+pub fn detect_delimiter(text: &str) -> u8 {
+    let first_line = text.lines().next().unwrap_or_default();
+    DELIMITERS
+        .into_iter()
+        .max_by_key(|delimiter| first_line.matches(*delimiter as char).count())
+        .unwrap_or(b';')
+}
+
+/// Read the first line of the file, and guess the layout from it.
+/// If no column can be recognized, the first line isn't considered as headers.
+/// Return the guessed layout along with the first line, so that the user can check the mapping.
+pub fn guess_layout(text: &str) -> (CsvLayout, StringRecord) {
+    let delimiter = detect_delimiter(text);
+    let first_record = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text.as_bytes())
+        .records()
+        .next()
+        .and_then(|record| record.ok())
+        .unwrap_or_default();
+
+    let mut columns: Vec<Option<MemberField>> = vec![];
+    for header in first_record.iter() {
+        // A field can't be held by several columns
+        let field = MemberField::guess(header).filter(|field| !columns.contains(&Some(*field)));
+        columns.push(field);
+    }
+    let has_headers = columns.iter().any(Option::is_some);
+
+    (
+        CsvLayout::new(delimiter, has_headers, columns),
+        first_record,
+    )
+}
+
+// region Mapping form
+/// Display the guessed layout, so that the user can fix and confirm it
+/// before members to check are loaded.
+pub fn render_mapping(
+    document: &Document,
+    layout: &CsvLayout,
+    first_record: &StringRecord,
+) -> Result<()> {
+    let container = get_element_by_id(document, "csv-mapping")?;
+    clear_element(&container);
+
+    ElementBuilder::default()
+        .parent(&container)
+        .inner_html("Vérifiez les colonnes détectées dans le fichier :")
+        .build(document, "div")?;
+
+    let has_headers_label = ElementBuilder::default()
+        .parent(&container)
+        .build(document, "label")?;
+    let has_headers_checkbox = ElementBuilder::default()
+        .parent(&has_headers_label)
+        .build(document, "input")?;
+    set_attribute(&has_headers_checkbox, "type", "checkbox")?;
+    set_attribute(&has_headers_checkbox, "id", "csv-has-headers")?;
+    if layout.has_headers() {
+        set_attribute(&has_headers_checkbox, "checked", "true")?;
+    }
+    ElementBuilder::default()
+        .parent(&has_headers_label)
+        .inner_html("La première ligne contient les en-têtes")
+        .build(document, "span")?;
+
+    let columns_container = ElementBuilder::default()
+        .parent(&container)
+        .build(document, "div")?;
+    set_attribute(&columns_container, "class", "csv-mapping-columns")?;
+    for (index, field) in layout.columns().iter().enumerate() {
+        let column_container = ElementBuilder::default()
+            .parent(&columns_container)
+            .build(document, "div")?;
+        let select_id = format!("csv-column-{index}");
+        let label = ElementBuilder::default()
+            .parent(&column_container)
+            .build(document, "label")?;
+        set_attribute(&label, "for", &select_id)?;
+        // Headers come from the file, so they are not trusted as HTML
+        let header = first_record
+            .get(index)
+            .filter(|header| !header.trim().is_empty())
+            .map(str::to_owned)
+            .unwrap_or_else(|| format!("Colonne {}", index + 1));
+        label.set_text_content(Some(&header));
+
+        let select = ElementBuilder::default()
+            .parent(&column_container)
+            .build(document, "select")?;
+        set_attribute(&select, "id", &select_id)?;
+        set_attribute(&select, "class", "csv-column-mapping")?;
+        ElementBuilder::default()
+            .parent(&select)
+            .inner_html("Ignorer")
+            .build(document, "option")?
+            .set_attribute("value", "")?;
+        for member_field in MemberField::ALL {
+            let option = ElementBuilder::default()
+                .parent(&select)
+                .inner_html(member_field.label())
+                .build(document, "option")?;
+            set_attribute(&option, "value", member_field.value())?;
+            if *field == Some(member_field) {
+                set_attribute(&option, "selected", "true")?;
+            }
+        }
+    }
+
+    let confirm_button = ElementBuilder::default()
+        .parent(&container)
+        .inner_html("Confirmer les colonnes")
+        .build(document, "button")?;
+    set_attribute(&confirm_button, "type", "button")?;
+    set_attribute(&confirm_button, "onclick", "app.confirm_csv_mapping()")?;
+
+    Ok(())
+}
+
+pub fn clear_mapping(document: &Document) -> Result<()> {
+    clear_element(&get_element_by_id(document, "csv-mapping")?);
+    Ok(())
+}
+
+/// Read the layout the user has confirmed from the mapping form.
+pub fn read_mapping(document: &Document, text: &str) -> Result<CsvLayout> {
+    let container = get_element_by_id(document, "csv-mapping")?;
+    let selects = query_selector_all(&container, ".csv-column-mapping")?;
+    if selects.is_empty() {
+        return Err(Error::new(
+            "Veuillez sélectionner un fichier CSV.",
+            "The CSV mapping hasn't been rendered",
+        ));
+    }
+
+    let mut columns = vec![];
+    for select in selects {
+        let value = select
+            .dyn_into::<HtmlSelectElement>()
+            .map_err(|_| Error::new(DEFAULT_ERROR_MESSAGE, "Column mapping should be a select"))?
+            .value();
+        if value.is_empty() {
+            columns.push(None);
+        } else {
+            columns.push(Some(MemberField::from_value(&value).ok_or_else(|| {
+                Error::new("Colonne inexistante", "Member field doesn't exist")
+            })?));
+        }
+    }
+    if columns.iter().all(Option::is_none) {
+        return Err(Error::new(
+            "Veuillez indiquer au moins une colonne à utiliser.",
+            "No column has been mapped",
+        ));
+    }
+    let has_headers =
+        get_element_by_id_dyn::<HtmlInputElement>(document, "csv-has-headers")?.checked();
+
+    Ok(CsvLayout::new(detect_delimiter(text), has_headers, columns))
+}
+// endregion
+
+#[cfg(test)]
+mod tests {
+    mod decode_file {
+        use crate::fileo::csv_mapping::decode_file;
+
+        #[test]
+        fn utf8() {
+            assert_eq!("Prénom;Nom", decode_file("Prénom;Nom".as_bytes()));
+        }
+
+        #[test]
+        fn utf8_with_bom() {
+            let file_content = [&[0xEF, 0xBB, 0xBF], "Prénom;Nom".as_bytes()].concat();
+            assert_eq!("Prénom;Nom", decode_file(&file_content));
+        }
+
+        #[test]
+        fn windows_1252() {
+            // `é` is encoded as 0xE9 in Windows-1252, which isn't valid UTF-8
+            let file_content = [b"Pr".as_slice(), &[0xE9], b"nom;Nom"].concat();
+            assert_eq!("Prénom;Nom", decode_file(&file_content));
+        }
+    }
+
+    mod detect_delimiter {
+        use crate::fileo::csv_mapping::detect_delimiter;
+
+        #[test]
+        fn semicolon() {
+            assert_eq!(b';', detect_delimiter("a;b;c\n1;2;3"));
+        }
+
+        #[test]
+        fn comma() {
+            assert_eq!(b',', detect_delimiter("a,b,c\n1,2,3"));
+        }
+
+        #[test]
+        fn tab() {
+            assert_eq!(b'\t', detect_delimiter("a\tb\tc"));
+        }
+
+        #[test]
+        fn most_frequent() {
+            assert_eq!(b';', detect_delimiter("a,b;c;d"));
+        }
+
+        #[test]
+        fn semicolon_when_single_column() {
+            assert_eq!(b';', detect_delimiter("a"));
+        }
+    }
+
+    mod guess_layout {
+        use crate::fileo::csv_mapping::{CsvLayout, guess_layout};
+        use dto::member_field::MemberField::{
+            Club, EmailAddress, FirstName, LastName, MembershipNumber,
+        };
+
+        #[test]
+        fn with_headers() {
+            let text = "Horodateur,Nom,Prénom,Numéro de licence,Email,Club,Taille de t-shirt\n2025-04-01,Doe,Jon,123456,jon@doe.com,My club,M";
+
+            let (layout, first_record) = guess_layout(text);
+            assert_eq!(
+                CsvLayout::new(
+                    b',',
+                    true,
+                    vec![
+                        None,
+                        Some(LastName),
+                        Some(FirstName),
+                        Some(MembershipNumber),
+                        Some(EmailAddress),
+                        Some(Club),
+                        None
+                    ]
+                ),
+                layout
+            );
+            assert_eq!(Some("Horodateur"), first_record.get(0));
+        }
+
+        #[test]
+        fn should_not_map_a_field_twice() {
+            let text = "Nom;Prénom;Nom d'usage";
+
+            let (layout, _) = guess_layout(text);
+            assert_eq!(&[Some(LastName), Some(FirstName), None], layout.columns());
+        }
+
+        #[test]
+        fn without_headers() {
+            let text = "123456;Doe;Jon";
+
+            let (layout, _) = guess_layout(text);
+            assert_eq!(CsvLayout::new(b';', false, vec![None, None, None]), layout);
+        }
+    }
+}
@@ -1,19 +1,35 @@
 use crate::error::Error;
+use crate::fileo::csv_mapping::{CsvLayout, decode_file, detect_delimiter};
+use crate::fileo::load_members_from_spreadsheet::{
+    is_spreadsheet, load_members_to_check_from_spreadsheet,
+};
 use crate::utils::get_element_by_id_dyn;
+use chrono::NaiveDate;
 use csv::{Reader, StringRecord};
 use dto::csv_member::CsvMember;
+use dto::member_field::MemberField;
+use dto::member_field::MemberField::{
+    Birthdate, Club, EmailAddress, FirstName, Identity, LastName, MembershipNumber,
+};
 use log::warn;
 use std::collections::BTreeSet;
-use web_sys::{Document, HtmlInputElement, HtmlSelectElement};
+use web_sys::js_sys::Uint8Array;
+use web_sys::{Document, File, HtmlInputElement, HtmlSelectElement};
 
 enum MembersToCheckFileFormat {
     MembershipNumberLastNameFirstName,
     MembershipNumberIdentity,
     MembershipNumber,
     LastNameFirstName,
+    LastNameFirstNameBirthdate,
     Identity,
 }
 
+/// Birthdates are expected to be formatted the French way, such as `31/12/1990`.
+const BIRTHDATE_FORMAT: &str = "%d/%m/%Y";
+/// The format selector value for files whose columns are detected from their headers.
+const AUTOMATIC_FORMAT: &str = "Automatic";
+
 impl TryFrom<String> for MembersToCheckFileFormat {
     type Error = Error;
 
@@ -25,57 +41,101 @@ impl TryFrom<String> for MembersToCheckFileFormat {
             "MembershipNumberIdentity" => Ok(MembersToCheckFileFormat::MembershipNumberIdentity),
             "MembershipNumber" => Ok(MembersToCheckFileFormat::MembershipNumber),
             "LastNameFirstName" => Ok(MembersToCheckFileFormat::LastNameFirstName),
+            "LastNameFirstNameBirthdate" => {
+                Ok(MembersToCheckFileFormat::LastNameFirstNameBirthdate)
+            }
             "Identity" => Ok(MembersToCheckFileFormat::Identity),
             _ => Err(Error::new("Format inexistant", "Format doesn't exist")),
         }
     }
 }
 
-pub async fn load_members_to_check(
-    document: &Document,
-) -> crate::Result<(BTreeSet<CsvMember>, Vec<String>)> {
-    let members_to_check_picker =
-        get_element_by_id_dyn::<HtmlInputElement>(document, "members-to-check-picker")?;
+impl MembersToCheckFileFormat {
+    /// Which field each column of a headerless file in this format holds.
+    fn columns(&self) -> Vec<Option<MemberField>> {
+        let columns = match self {
+            MembersToCheckFileFormat::MembershipNumberLastNameFirstName => {
+                vec![MembershipNumber, LastName, FirstName]
+            }
+            MembersToCheckFileFormat::MembershipNumberIdentity => vec![MembershipNumber, Identity],
+            MembersToCheckFileFormat::MembershipNumber => vec![MembershipNumber],
+            MembersToCheckFileFormat::LastNameFirstName => vec![LastName, FirstName],
+            MembersToCheckFileFormat::LastNameFirstNameBirthdate => {
+                vec![LastName, FirstName, Birthdate]
+            }
+            MembersToCheckFileFormat::Identity => vec![Identity],
+        };
+        columns.into_iter().map(Some).collect()
+    }
+}
+
+/// Whether the user has asked for columns to be detected from the headers of the file,
+/// rather than picking one of the fixed formats.
+pub fn is_automatic_format(document: &Document) -> crate::Result<bool> {
     let members_to_check_format_selector =
         get_element_by_id_dyn::<HtmlSelectElement>(document, "members-to-check-format-selector")?;
-    let format = members_to_check_format_selector.value();
-    let format = MembersToCheckFileFormat::try_from(format)?;
+    Ok(members_to_check_format_selector.value() == AUTOMATIC_FORMAT)
+}
 
-    let csv_file = members_to_check_picker
+pub fn get_members_to_check_file(document: &Document) -> crate::Result<File> {
+    get_element_by_id_dyn::<HtmlInputElement>(document, "members-to-check-picker")?
         .files()
-        .expect("no files")
-        .get(0)
-        .expect("file should be accessible");
+        .and_then(|files| files.get(0))
+        .ok_or_else(|| Error::new("Veuillez sélectionner un fichier.", "No file picked"))
+}
 
-    let promise = csv_file.text();
-    let text_jsvalue = wasm_bindgen_futures::JsFuture::from(promise).await?;
-    let csv_content = text_jsvalue.as_string().ok_or_else(|| Error::new("Le fichier CSV contient des caractères incorrects. Vérifiez l'encodage UTF-8 du fichier.", "csv file should contain only valid UTF-8 characters"))?;
+/// Read the picked file, whatever its encoding.
+pub async fn read_members_to_check_file(document: &Document) -> crate::Result<String> {
+    let csv_file = get_members_to_check_file(document)?;
 
-    let (members_to_check, wrong_lines) =
-        load_members_to_check_from_csv_string(&csv_content, &format);
-    Ok((members_to_check, wrong_lines))
+    let buffer = wasm_bindgen_futures::JsFuture::from(csv_file.array_buffer()).await?;
+    Ok(decode_file(&Uint8Array::new(&buffer).to_vec()))
 }
 
-/// Load members to check from a CSV-formatted String, such as:
-/// `membership_num;name;firstname`
-fn load_members_to_check_from_csv_string(
+/// Load members to check from the picked file.
+/// Spreadsheets are read by the server, while CSV files are read according to the selected format.
+pub async fn load_members_to_check(
+    document: &Document,
+) -> crate::Result<(BTreeSet<CsvMember>, Vec<String>)> {
+    let file = get_members_to_check_file(document)?;
+    if is_spreadsheet(&file) {
+        return load_members_to_check_from_spreadsheet(&file).await;
+    }
+
+    let csv_content = read_members_to_check_file(document).await?;
+    let layout = if is_automatic_format(document)? {
+        crate::fileo::csv_mapping::read_mapping(document, &csv_content)?
+    } else {
+        let members_to_check_format_selector = get_element_by_id_dyn::<HtmlSelectElement>(
+            document,
+            "members-to-check-format-selector",
+        )?;
+        let format = MembersToCheckFileFormat::try_from(members_to_check_format_selector.value())?;
+        CsvLayout::new(detect_delimiter(&csv_content), false, format.columns())
+    };
+
+    Ok(load_members_to_check_with_layout(&csv_content, &layout))
+}
+
+/// Load members to check from a CSV-formatted String, whose columns are described by `layout`.
+fn load_members_to_check_with_layout(
     members_to_check: &str,
-    format: &MembersToCheckFileFormat,
+    layout: &CsvLayout,
 ) -> (BTreeSet<CsvMember>, Vec<String>) {
     let mut reader = csv::ReaderBuilder::new()
-        .delimiter(b';')
-        .has_headers(false)
+        .delimiter(layout.delimiter())
+        .has_headers(layout.has_headers())
         .flexible(true)
         .from_reader(members_to_check.as_bytes());
 
-    load_members_to_check_from_csv(&mut reader, format)
+    load_members_to_check_from_csv(&mut reader, layout)
 }
 
-/// Load members to check rom a CSV-formatted Reader, such as:
+/// Load members to check from a CSV-formatted Reader, such as:
 /// `membership_num;name;firstname`
 fn load_members_to_check_from_csv<T>(
     reader: &mut Reader<T>,
-    format: &MembersToCheckFileFormat,
+    layout: &CsvLayout,
 ) -> (BTreeSet<CsvMember>, Vec<String>)
 where
     T: std::io::Read,
@@ -84,7 +144,7 @@ where
     let mut wrong_lines = vec![];
 
     reader.records().for_each(|record| {
-        match deserialize_member_to_check(record, format) {
+        match deserialize_member_to_check(record, layout) {
             Ok(member) => {
                 members_to_check.insert(member);
             }
@@ -99,92 +159,92 @@ where
     (members_to_check, wrong_lines)
 }
 
+/// Build a member to check from a record, reading each field from the column the layout maps it to.
+/// A record is rejected if it doesn't have as many fields as the layout has columns,
+/// or if its birthdate can't be read.
+/// Records whose mapped fields are all empty, such as trailing lines of spreadsheets, are skipped.
 fn deserialize_member_to_check(
     record: Result<StringRecord, csv::Error>,
-    format: &MembersToCheckFileFormat,
+    layout: &CsvLayout,
 ) -> Result<CsvMember, Option<String>> {
-    if let Ok(record) = record {
-        let fields_count = record.len();
-
-        match format {
-            MembersToCheckFileFormat::MembershipNumberLastNameFirstName => {
-                if fields_count != 3 {
-                    Err(Some(record.iter().collect::<Vec<_>>().join(";")))
-                } else {
-                    Ok(CsvMember::new(
-                        Some(record.get(0).unwrap().to_owned()),
-                        None,
-                        Some(record.get(1).unwrap().to_owned()),
-                        Some(record.get(2).unwrap().to_owned()),
-                    ))
-                }
-            }
-            MembersToCheckFileFormat::MembershipNumberIdentity => {
-                if fields_count != 2 {
-                    Err(Some(record.iter().collect::<Vec<_>>().join(";")))
-                } else {
-                    Ok(CsvMember::new(
-                        Some(record.get(0).unwrap().to_owned()),
-                        Some(record.get(1).unwrap().to_owned()),
-                        None,
-                        None,
-                    ))
-                }
-            }
-            MembersToCheckFileFormat::MembershipNumber => {
-                if fields_count != 1 {
-                    Err(Some(record.iter().collect::<Vec<_>>().join(";")))
-                } else {
-                    Ok(CsvMember::new(
-                        Some(record.get(0).unwrap().to_owned()),
-                        None,
-                        None,
-                        None,
-                    ))
-                }
-            }
-            MembersToCheckFileFormat::LastNameFirstName => {
-                if fields_count != 2 {
-                    Err(Some(record.iter().collect::<Vec<_>>().join(";")))
-                } else {
-                    Ok(CsvMember::new(
-                        None,
-                        None,
-                        Some(record.get(0).unwrap().to_owned()),
-                        Some(record.get(1).unwrap().to_owned()),
-                    ))
-                }
-            }
-            MembersToCheckFileFormat::Identity => {
-                if fields_count != 1 {
-                    Err(Some(record.iter().collect::<Vec<_>>().join(";")))
-                } else {
-                    Ok(CsvMember::new(
-                        None,
-                        Some(record.get(0).unwrap().to_owned()),
-                        None,
-                        None,
-                    ))
-                }
-            }
+    let record = match record {
+        Ok(record) => record,
+        Err(error) => {
+            warn!(
+                "Error while deserializing member to check [error: {:?}]",
+                error
+            );
+            return Err(None);
         }
-    } else if let Err(error) = record {
-        warn!(
-            "Error while deserializing member to check [error: {:?}]",
-            error
-        );
+    };
+    let wrong_line = || {
+        Some(
+            record
+                .iter()
+                .collect::<Vec<_>>()
+                .join(&(layout.delimiter() as char).to_string()),
+        )
+    };
+    if record.len() != layout.columns().len() {
+        return Err(wrong_line());
+    }
+
+    let get = |field: MemberField| {
+        layout
+            .columns()
+            .iter()
+            .position(|column| *column == Some(field))
+            .and_then(|index| record.get(index))
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_owned)
+    };
+    let birthdate = match get(Birthdate) {
+        None => None,
+        Some(birthdate) => match NaiveDate::parse_from_str(&birthdate, BIRTHDATE_FORMAT) {
+            Ok(birthdate) => Some(birthdate),
+            Err(_) => return Err(wrong_line()),
+        },
+    };
+    let member = CsvMember::new(
+        get(MembershipNumber),
+        get(Identity),
+        get(LastName),
+        get(FirstName),
+        birthdate,
+        get(EmailAddress),
+        get(Club),
+    );
+
+    if member == CsvMember::new(None, None, None, None, None, None, None) {
         Err(None)
     } else {
-        Err(None)
+        Ok(member)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    mod load_members_to_check_from_csv_string {
+    use crate::fileo::csv_mapping::{CsvLayout, detect_delimiter};
+    use crate::fileo::load_members_from_csv::{
+        MembersToCheckFileFormat, load_members_to_check_with_layout,
+    };
+    use dto::csv_member::CsvMember;
+    use std::collections::BTreeSet;
+
+    /// Load members to check the same way as when a format has been selected.
+    fn load_members_to_check_with_format(
+        members_to_check: &str,
+        format: &MembersToCheckFileFormat,
+    ) -> (BTreeSet<CsvMember>, Vec<String>) {
+        let layout = CsvLayout::new(detect_delimiter(members_to_check), false, format.columns());
+        load_members_to_check_with_layout(members_to_check, &layout)
+    }
+
+    mod load_members_to_check_with_format {
         mod membership_num_last_name_first_name {
             use crate::fileo::load_members_from_csv::{
-                MembersToCheckFileFormat, load_members_to_check_from_csv_string,
+                MembersToCheckFileFormat, tests::load_members_to_check_with_format,
             };
             use MembersToCheckFileFormat::MembershipNumberLastNameFirstName;
             use dto::csv_member::CsvMember;
@@ -197,7 +257,7 @@ mod tests {
                 let first_name = "John".to_owned();
                 let csv = format!("{membership_num};{name};{first_name}");
                 let result =
-                    load_members_to_check_from_csv_string(&csv, &MembershipNumberLastNameFirstName);
+                    load_members_to_check_with_format(&csv, &MembershipNumberLastNameFirstName);
                 assert_eq!(
                     (
                         BTreeSet::from_iter(vec![CsvMember::new(
@@ -205,6 +265,34 @@ mod tests {
                             None,
                             Some(name),
                             Some(first_name),
+                            None,
+                            None,
+                            None,
+                        )]),
+                        vec![]
+                    ),
+                    result
+                )
+            }
+
+            #[test]
+            fn success_when_comma_separated() {
+                let membership_num = "123".to_owned();
+                let name = "Doe".to_owned();
+                let first_name = "John".to_owned();
+                let csv = format!("{membership_num},{name},{first_name}");
+                let result =
+                    load_members_to_check_with_format(&csv, &MembershipNumberLastNameFirstName);
+                assert_eq!(
+                    (
+                        BTreeSet::from_iter(vec![CsvMember::new(
+                            Some(membership_num),
+                            None,
+                            Some(name),
+                            Some(first_name),
+                            None,
+                            None,
+                            None,
                         )]),
                         vec![]
                     ),
@@ -217,7 +305,7 @@ mod tests {
                 let membership_num = "123".to_owned();
                 let csv = membership_num.to_string();
                 let result =
-                    load_members_to_check_from_csv_string(&csv, &MembershipNumberLastNameFirstName);
+                    load_members_to_check_with_format(&csv, &MembershipNumberLastNameFirstName);
                 let expected_result = (BTreeSet::new(), vec![csv]);
                 assert_eq!(expected_result, result)
             }
@@ -228,7 +316,7 @@ mod tests {
                 let identity = "Doe John".to_owned();
                 let csv = format!("{membership_num};{identity}");
                 let result =
-                    load_members_to_check_from_csv_string(&csv, &MembershipNumberLastNameFirstName);
+                    load_members_to_check_with_format(&csv, &MembershipNumberLastNameFirstName);
                 let expected_result = (BTreeSet::new(), vec![csv]);
                 assert_eq!(expected_result, result)
             }
@@ -236,7 +324,7 @@ mod tests {
 
         mod membership_num_identity {
             use crate::fileo::load_members_from_csv::{
-                MembersToCheckFileFormat, load_members_to_check_from_csv_string,
+                MembersToCheckFileFormat, tests::load_members_to_check_with_format,
             };
             use MembersToCheckFileFormat::MembershipNumberIdentity;
             use dto::csv_member::CsvMember;
@@ -247,7 +335,7 @@ mod tests {
                 let membership_num = "123".to_owned();
                 let identity = "Doe John".to_owned();
                 let csv = format!("{membership_num};{identity}");
-                let result = load_members_to_check_from_csv_string(&csv, &MembershipNumberIdentity);
+                let result = load_members_to_check_with_format(&csv, &MembershipNumberIdentity);
                 assert_eq!(
                     (
                         BTreeSet::from_iter(vec![CsvMember::new(
@@ -255,6 +343,9 @@ mod tests {
                             Some(identity),
                             None,
                             None,
+                            None,
+                            None,
+                            None,
                         )]),
                         vec![]
                     ),
@@ -266,7 +357,7 @@ mod tests {
             fn fail_when_one_field() {
                 let membership_num = "123".to_owned();
                 let csv = membership_num.to_string();
-                let result = load_members_to_check_from_csv_string(&csv, &MembershipNumberIdentity);
+                let result = load_members_to_check_with_format(&csv, &MembershipNumberIdentity);
                 let expected_result = (BTreeSet::new(), vec![csv]);
                 assert_eq!(expected_result, result)
             }
@@ -277,7 +368,7 @@ mod tests {
                 let name = "Doe".to_owned();
                 let first_name = "John".to_owned();
                 let csv = format!("{membership_num};{name};{first_name}");
-                let result = load_members_to_check_from_csv_string(&csv, &MembershipNumberIdentity);
+                let result = load_members_to_check_with_format(&csv, &MembershipNumberIdentity);
                 let expected_result = (BTreeSet::new(), vec![csv]);
                 assert_eq!(expected_result, result)
             }
@@ -285,7 +376,7 @@ mod tests {
 
         mod membership_num {
             use crate::fileo::load_members_from_csv::MembersToCheckFileFormat::MembershipNumber;
-            use crate::fileo::load_members_from_csv::load_members_to_check_from_csv_string;
+            use crate::fileo::load_members_from_csv::tests::load_members_to_check_with_format;
             use dto::csv_member::CsvMember;
             use std::collections::BTreeSet;
 
@@ -293,7 +384,7 @@ mod tests {
             fn success() {
                 let membership_num = "123".to_owned();
                 let csv = membership_num.to_string();
-                let result = load_members_to_check_from_csv_string(&csv, &MembershipNumber);
+                let result = load_members_to_check_with_format(&csv, &MembershipNumber);
                 assert_eq!(
                     (
                         BTreeSet::from_iter(vec![CsvMember::new(
@@ -301,6 +392,9 @@ mod tests {
                             None,
                             None,
                             None,
+                            None,
+                            None,
+                            None,
                         )]),
                         vec![]
                     ),
@@ -313,7 +407,7 @@ mod tests {
                 let membership_num = "123".to_owned();
                 let identity = "Doe John".to_owned();
                 let csv = format!("{membership_num};{identity}");
-                let result = load_members_to_check_from_csv_string(&csv, &MembershipNumber);
+                let result = load_members_to_check_with_format(&csv, &MembershipNumber);
                 let expected_result = (BTreeSet::new(), vec![csv]);
                 assert_eq!(expected_result, result)
             }
@@ -324,7 +418,7 @@ mod tests {
                 let name = "Doe".to_owned();
                 let first_name = "John".to_owned();
                 let csv = format!("{membership_num};{name};{first_name}");
-                let result = load_members_to_check_from_csv_string(&csv, &MembershipNumber);
+                let result = load_members_to_check_with_format(&csv, &MembershipNumber);
                 let expected_result = (BTreeSet::new(), vec![csv]);
                 assert_eq!(expected_result, result)
             }
@@ -332,7 +426,7 @@ mod tests {
 
         mod last_name_first_name {
             use crate::fileo::load_members_from_csv::{
-                MembersToCheckFileFormat, load_members_to_check_from_csv_string,
+                MembersToCheckFileFormat, tests::load_members_to_check_with_format,
             };
             use MembersToCheckFileFormat::LastNameFirstName;
             use dto::csv_member::CsvMember;
@@ -343,7 +437,7 @@ mod tests {
                 let name = "Doe".to_owned();
                 let first_name = "John".to_owned();
                 let csv = format!("{name};{first_name}");
-                let result = load_members_to_check_from_csv_string(&csv, &LastNameFirstName);
+                let result = load_members_to_check_with_format(&csv, &LastNameFirstName);
                 assert_eq!(
                     (
                         BTreeSet::from_iter(vec![CsvMember::new(
@@ -351,6 +445,9 @@ mod tests {
                             None,
                             Some(name),
                             Some(first_name),
+                            None,
+                            None,
+                            None,
                         )]),
                         vec![]
                     ),
@@ -362,7 +459,7 @@ mod tests {
             fn fail_when_one_field() {
                 let membership_num = "123".to_owned();
                 let csv = membership_num.to_string();
-                let result = load_members_to_check_from_csv_string(&csv, &LastNameFirstName);
+                let result = load_members_to_check_with_format(&csv, &LastNameFirstName);
                 let expected_result = (BTreeSet::new(), vec![csv]);
                 assert_eq!(expected_result, result)
             }
@@ -373,7 +470,60 @@ mod tests {
                 let name = "Doe".to_owned();
                 let first_name = "John".to_owned();
                 let csv = format!("{membership_num};{name};{first_name}");
-                let result = load_members_to_check_from_csv_string(&csv, &LastNameFirstName);
+                let result = load_members_to_check_with_format(&csv, &LastNameFirstName);
+                let expected_result = (BTreeSet::new(), vec![csv]);
+                assert_eq!(expected_result, result)
+            }
+        }
+
+        mod last_name_first_name_birthdate {
+            use crate::fileo::load_members_from_csv::{
+                MembersToCheckFileFormat, tests::load_members_to_check_with_format,
+            };
+            use MembersToCheckFileFormat::LastNameFirstNameBirthdate;
+            use chrono::NaiveDate;
+            use dto::csv_member::CsvMember;
+            use std::collections::BTreeSet;
+
+            #[test]
+            fn success() {
+                let name = "Doe".to_owned();
+                let first_name = "John".to_owned();
+                let csv = format!("{name};{first_name};01/02/1980");
+                let result = load_members_to_check_with_format(&csv, &LastNameFirstNameBirthdate);
+                assert_eq!(
+                    (
+                        BTreeSet::from_iter(vec![CsvMember::new(
+                            None,
+                            None,
+                            Some(name),
+                            Some(first_name),
+                            NaiveDate::from_ymd_opt(1980, 2, 1),
+                            None,
+                            None,
+                        )]),
+                        vec![]
+                    ),
+                    result
+                )
+            }
+
+            #[test]
+            fn fail_when_invalid_birthdate() {
+                let name = "Doe".to_owned();
+                let first_name = "John".to_owned();
+                let csv = format!("{name};{first_name};1980-02-01");
+                let result = load_members_to_check_with_format(&csv, &LastNameFirstNameBirthdate);
+                let expected_result = (BTreeSet::new(), vec![csv]);
+                assert_eq!(expected_result, result)
+            }
+
+            #[test]
+            fn fail_when_two_fields() {
+                let name = "Doe".to_owned();
+                let first_name = "John".to_owned();
+                let csv = format!("{name};{first_name}");
+                let result = load_members_to_check_with_format(&csv, &LastNameFirstNameBirthdate);
                 let expected_result = (BTreeSet::new(), vec![csv]);
                 assert_eq!(expected_result, result)
             }
@@ -381,7 +531,7 @@ mod tests {
 
         mod identity {
             use crate::fileo::load_members_from_csv::{
-                MembersToCheckFileFormat, load_members_to_check_from_csv_string,
+                MembersToCheckFileFormat, tests::load_members_to_check_with_format,
             };
             use MembersToCheckFileFormat::Identity;
             use dto::csv_member::CsvMember;
@@ -391,12 +541,18 @@ mod tests {
             fn success() {
                 let identity = "Doe John".to_owned();
                 let csv = identity.to_owned();
-                let result = load_members_to_check_from_csv_string(&csv, &Identity);
+                let result = load_members_to_check_with_format(&csv, &Identity);
                 assert_eq!(
                     (
-                        BTreeSet::from_iter(vec![
-                            CsvMember::new(None, Some(identity), None, None,)
-                        ]),
+                        BTreeSet::from_iter(vec![CsvMember::new(
+                            None,
+                            Some(identity),
+                            None,
+                            None,
+                            None,
+                            None,
+                            None,
+                        )]),
                         vec![]
                     ),
                     result
@@ -408,7 +564,7 @@ mod tests {
                 let membership_num = "123".to_owned();
                 let identity = "Doe John".to_owned();
                 let csv = format!("{membership_num};{identity}");
-                let result = load_members_to_check_from_csv_string(&csv, &Identity);
+                let result = load_members_to_check_with_format(&csv, &Identity);
                 let expected_result = (BTreeSet::new(), vec![csv]);
                 assert_eq!(expected_result, result)
             }
@@ -419,10 +575,64 @@ mod tests {
                 let name = "Doe".to_owned();
                 let first_name = "John".to_owned();
                 let csv = format!("{membership_num};{name};{first_name}");
-                let result = load_members_to_check_from_csv_string(&csv, &Identity);
+                let result = load_members_to_check_with_format(&csv, &Identity);
                 let expected_result = (BTreeSet::new(), vec![csv]);
                 assert_eq!(expected_result, result)
             }
         }
     }
+
+    mod load_members_to_check_with_layout {
+        use crate::fileo::csv_mapping::CsvLayout;
+        use crate::fileo::load_members_from_csv::load_members_to_check_with_layout;
+        use dto::csv_member::CsvMember;
+        use dto::member_field::MemberField::{
+            Club, EmailAddress, FirstName, LastName, MembershipNumber,
+        };
+        use std::collections::BTreeSet;
+
+        fn get_layout() -> CsvLayout {
+            CsvLayout::new(
+                b',',
+                true,
+                vec![
+                    None,
+                    Some(LastName),
+                    Some(FirstName),
+                    Some(MembershipNumber),
+                    Some(EmailAddress),
+                    Some(Club),
+                ],
+            )
+        }
+
+        #[test]
+        fn success() {
+            let csv = "Horodateur,Nom,Prénom,Numéro de licence,Email,Club\n2025-04-01, Doe ,Jon,123456,jon@doe.com,\n,,,,,";
+            let result = load_members_to_check_with_layout(csv, &get_layout());
+            assert_eq!(
+                (
+                    BTreeSet::from_iter(vec![CsvMember::new(
+                        Some("123456".to_owned()),
+                        None,
+                        Some("Doe".to_owned()),
+                        Some("Jon".to_owned()),
+                        None,
+                        Some("jon@doe.com".to_owned()),
+                        None,
+                    )]),
+                    vec![]
+                ),
+                result
+            )
+        }
+
+        #[test]
+        fn fail_when_missing_fields() {
+            let csv = "Horodateur,Nom,Prénom,Numéro de licence,Email,Club\n2025-04-01,Doe,Jon";
+            let result = load_members_to_check_with_layout(csv, &get_layout());
+            let expected_result = (BTreeSet::new(), vec!["2025-04-01,Doe,Jon".to_owned()]);
+            assert_eq!(expected_result, result)
+        }
+    }
 }
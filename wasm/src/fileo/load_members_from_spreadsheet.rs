@@ -0,0 +1,53 @@
+use crate::error::{DEFAULT_SERVER_ERROR_MESSAGE, Error};
+use crate::fileo::update_list::describe_rejected_row;
+use crate::json;
+use crate::web::upload;
+use dto::csv_member::{CsvMember, SpreadsheetMembers};
+use std::collections::BTreeSet;
+use web_sys::File;
+
+/// Extensions of the spreadsheets the server can read members to check from.
+const SPREADSHEET_EXTENSIONS: [&str; 3] = [".xlsx", ".xls", ".ods"];
+
+/// Whether the picked file is a spreadsheet, rather than a CSV file.
+pub fn is_spreadsheet(file: &File) -> bool {
+    let name = file.name().to_lowercase();
+    SPREADSHEET_EXTENSIONS
+        .iter()
+        .any(|extension| name.ends_with(extension))
+}
+
+/// Have the server read members to check from a spreadsheet,
+/// whose columns are guessed from the headers of its first sheet.
+pub async fn load_members_to_check_from_spreadsheet(
+    file: &File,
+) -> crate::Result<(BTreeSet<CsvMember>, Vec<String>)> {
+    let response = upload("/api/members/spreadsheet/load", file)
+        .await
+        .map_err(|error| Error::from_parent(DEFAULT_SERVER_ERROR_MESSAGE, error))?;
+
+    let status = response.status();
+    if (200..400).contains(&status) {
+        let text = response.body().clone().unwrap_or(String::new());
+        let spreadsheet_members: SpreadsheetMembers = json::from_str(&text);
+        let wrong_lines = spreadsheet_members
+            .rejected_rows()
+            .iter()
+            .map(describe_rejected_row)
+            .collect();
+        Ok((
+            spreadsheet_members.members().iter().cloned().collect(),
+            wrong_lines,
+        ))
+    } else if status == 400 {
+        Err(Error::new(
+            "Le tableur ne peut pas être lu, ou aucune de ses colonnes n'a été reconnue. Vérifiez que la première ligne contient les en-têtes.",
+            "Malformed spreadsheet",
+        ))
+    } else {
+        Err(Error::new(
+            DEFAULT_SERVER_ERROR_MESSAGE,
+            &format!("Server error: {}", status),
+        ))
+    }
+}
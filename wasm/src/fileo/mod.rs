@@ -5,7 +5,9 @@ use web_sys::Document;
 
 mod check;
 mod credentials;
+mod csv_mapping;
 pub mod load_members_from_csv;
+mod load_members_from_spreadsheet;
 pub mod login;
 mod update_list;
 
@@ -1,10 +1,17 @@
+use crate::Result;
 use crate::component::alert::{AlertLevel, create_alert};
 use crate::error::Error;
+use crate::json;
 use crate::user_interface::with_loading;
-use crate::utils::{get_document, get_element_by_id};
-use crate::web::fetch;
+use crate::utils::{
+    ElementBuilder, clear_element, get_document, get_element_by_id, get_element_by_id_dyn,
+};
+use crate::web::{Response, fetch, upload};
+use dto::membership::Membership;
+use dto::membership_import::{MembershipImportDiff, MembershipImportPreview, RejectedRow};
 use wasm_bindgen::prelude::wasm_bindgen;
 use web_sys::js_sys::Date;
+use web_sys::{Document, File, HtmlInputElement};
 
 /// Validate the field, then try to log into Fileo app.
 /// If it succeeds, then redirect to the verification page.
@@ -18,32 +25,212 @@ pub async fn update_fileo_list() {
                 error,
             )
         })?;
+        handle_import_response(&response)
+    })
+    .await;
+}
+
+/// Parse the Fileo export picked by the user, without importing it,
+/// and show how many memberships it contains and which rows are rejected.
+#[wasm_bindgen]
+pub async fn preview_uploaded_memberships() {
+    with_loading(async || {
+        let document = get_document()?;
+        let file = get_picked_memberships_file(&document)?;
+        let url = "/api/fileo/memberships/upload/preview";
+        let response = upload(url, &file).await.map_err(|error| {
+            Error::from_parent(
+                "Le serveur a rencontré une erreur lors du traitement. Veuillez réessayer.",
+                error,
+            )
+        })?;
         let status = response.status();
         if (200..400).contains(&status) {
-            create_alert(
-                "Mise à jour effectuée. Vous pouvez désormais vérifier les licences.",
-                AlertLevel::Info,
-            );
-            let document = get_document()?;
-            let last_update_field = get_element_by_id(&document, "last-update")?;
-            let now = Date::new_0();
-            let day = now.get_date();
-            let month = now.get_month() + 1;
-            let year = now.get_full_year();
-            last_update_field.set_text_content(Some(&format!("{:02}/{:02}/{}", day, month, year)));
-
-            Ok(())
-        } else if status == 401 {
+            let text = response.body().clone().unwrap_or_default();
+            let preview: MembershipImportPreview = json::from_str(&text);
+            display_import_preview(&document, &preview)
+        } else if status == 400 {
             Err(Error::new(
-                "Vos identifiants sont incorrects. Veuillez réessayer.",
-                "Wrong credentials",
+                "Le fichier n'est pas un export Fileo valide.",
+                "Malformed memberships file",
             ))
         } else {
             Err(Error::new(
-                "Impossible de mettre à jour la liste. Veuillez réessayer.",
+                "Impossible de lire le fichier. Veuillez réessayer.",
                 &format!("Server error: {}", status),
             ))
         }
     })
     .await;
 }
+
+/// Import the Fileo export picked by the user, e.g. when Fileo can't be reached.
+#[wasm_bindgen]
+pub async fn upload_memberships() {
+    with_loading(async || {
+        let document = get_document()?;
+        let file = get_picked_memberships_file(&document)?;
+        let url = "/api/fileo/memberships/upload";
+        let response = upload(url, &file).await.map_err(|error| {
+            Error::from_parent(
+                "Le serveur a rencontré une erreur lors du traitement. Veuillez réessayer.",
+                error,
+            )
+        })?;
+        if response.status() == 400 {
+            return Err(Error::new(
+                "Le fichier n'est pas un export Fileo valide.",
+                "Malformed memberships file",
+            ));
+        }
+        handle_import_response(&response)
+    })
+    .await;
+}
+
+fn get_picked_memberships_file(document: &Document) -> Result<File> {
+    get_element_by_id_dyn::<HtmlInputElement>(document, "memberships-file-picker")?
+        .files()
+        .and_then(|files| files.get(0))
+        .ok_or_else(|| Error::new("Veuillez choisir un fichier.", "No file picked"))
+}
+
+fn handle_import_response(response: &Response) -> Result<()> {
+    let status = response.status();
+    if (200..400).contains(&status) {
+        let text = response.body().clone().unwrap_or_default();
+        let diff: MembershipImportDiff = json::from_str(&text);
+        create_alert(
+            &format!(
+                "Mise à jour effectuée : {} adhésion(s) ajoutée(s), {} modifiée(s), {} supprimée(s). Vous pouvez désormais vérifier les licences.",
+                diff.added().len(),
+                diff.updated().len(),
+                diff.removed().len()
+            ),
+            AlertLevel::Info,
+        );
+        let document = get_document()?;
+        let last_update_field = get_element_by_id(&document, "last-update")?;
+        let now = Date::new_0();
+        let day = now.get_date();
+        let month = now.get_month() + 1;
+        let year = now.get_full_year();
+        last_update_field.set_text_content(Some(&format!("{:02}/{:02}/{}", day, month, year)));
+        display_import_diff(&document, &diff)?;
+
+        Ok(())
+    } else if status == 401 {
+        Err(Error::new(
+            "Votre session Fileo a expiré. Veuillez vous reconnecter à Fileo.",
+            "Not logged into Fileo",
+        ))
+    } else if status == 403 {
+        Err(Error::new(
+            "Vous n'avez pas les droits nécessaires à la mise à jour de la liste des licences.",
+            "Lack permissions to refresh memberships",
+        ))
+    } else if status == 422 {
+        Err(Error::new(
+            "Trop de lignes du fichier n'ont pas pu être lues : la liste n'a pas été mise à jour. Le format de l'export Fileo a peut-être changé.",
+            "Too many rejected rows",
+        ))
+    } else {
+        Err(Error::new(
+            "Impossible de mettre à jour la liste. Veuillez réessayer.",
+            &format!("Server error: {}", status),
+        ))
+    }
+}
+
+/// Show what importing the picked file would do, so that the user can decide to import it or not.
+fn display_import_preview(document: &Document, preview: &MembershipImportPreview) -> Result<()> {
+    let container = get_element_by_id(document, "membership-import-preview")?;
+    clear_element(&container);
+
+    ElementBuilder::default()
+        .parent(&container)
+        .inner_html(&format!(
+            "{} adhésion(s) lue(s), {} ligne(s) rejetée(s)",
+            preview.memberships_count(),
+            preview.rejected_rows().len()
+        ))
+        .build(document, "div")?;
+    if *preview.too_many_rejected_rows() {
+        ElementBuilder::default()
+            .parent(&container)
+            .inner_html("Trop de lignes ont été rejetées : l'import sera refusé. Le format de l'export Fileo a peut-être changé.")
+            .build(document, "div")?;
+    }
+    if !preview.rejected_rows().is_empty() {
+        let list = ElementBuilder::default()
+            .parent(&container)
+            .build(document, "ul")?;
+        for rejected_row in preview.rejected_rows() {
+            // Rejected rows may contain raw content from the file, so it's not trusted as HTML
+            ElementBuilder::default()
+                .parent(&list)
+                .build(document, "li")?
+                .set_text_content(Some(&describe_rejected_row(rejected_row)));
+        }
+    }
+
+    Ok(())
+}
+
+/// List memberships that have been added, updated or removed by the import.
+fn display_import_diff(document: &Document, diff: &MembershipImportDiff) -> Result<()> {
+    let container = get_element_by_id(document, "membership-import-diff")?;
+    clear_element(&container);
+
+    let groups = [
+        ("Adhésions ajoutées", diff.added()),
+        ("Adhésions modifiées", diff.updated()),
+        ("Adhésions supprimées", diff.removed()),
+    ];
+    for (title, memberships) in groups {
+        if memberships.is_empty() {
+            continue;
+        }
+
+        let group = ElementBuilder::default()
+            .parent(&container)
+            .build(document, "div")?;
+        ElementBuilder::default()
+            .parent(&group)
+            .inner_html(&format!("{title} ({})", memberships.len()))
+            .build(document, "div")?;
+        let list = ElementBuilder::default()
+            .parent(&group)
+            .build(document, "ul")?;
+        for membership in memberships {
+            ElementBuilder::default()
+                .parent(&list)
+                .inner_html(&describe_membership(membership))
+                .build(document, "li")?;
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) fn describe_rejected_row(rejected_row: &RejectedRow) -> String {
+    match rejected_row.column() {
+        Some(column) => format!(
+            "Ligne {}, colonne « {column} » : {}",
+            rejected_row.line(),
+            rejected_row.reason()
+        ),
+        None => format!("Ligne {} : {}", rejected_row.line(), rejected_row.reason()),
+    }
+}
+
+fn describe_membership(membership: &Membership) -> String {
+    format!(
+        "{} {} ({}) : du {} au {}",
+        membership.first_name(),
+        membership.name(),
+        membership.membership_number(),
+        membership.start_date().format("%d/%m/%Y"),
+        membership.end_date().format("%d/%m/%Y")
+    )
+}
@@ -1,12 +1,14 @@
 mod card_creator;
 mod check_memberships;
 mod component;
+mod email_template;
 mod error;
 mod fileo;
 mod json;
 mod lookup;
 mod template;
 mod uda;
+mod user;
 mod user_interface;
 mod utils;
 mod web;
@@ -17,6 +19,7 @@ use crate::error::Error;
 use crate::fileo::init_fileo_page;
 use crate::lookup::init_lookup_page;
 use crate::uda::init_uda_page;
+use crate::user::init_login_page;
 use crate::utils::{get_document, get_element_by_id};
 use wasm_bindgen::prelude::*;
 
@@ -34,6 +37,8 @@ fn run() {
         init_uda_page(document);
     } else if get_element_by_id(document, "lookup-container").is_ok() {
         init_lookup_page(document);
+    } else if get_element_by_id(document, "login-container").is_ok() {
+        init_login_page(document);
     }
 }
 
@@ -1,9 +1,9 @@
-use crate::check_memberships::toggle_go_to_email_step_button;
+use crate::check_memberships::{build_check_url, toggle_go_to_email_step_button};
 use crate::component::stepper::next_step;
 use crate::error::{DEFAULT_ERROR_MESSAGE, Error};
 use crate::user_interface::{handle_checked_members, with_loading};
 use crate::utils::get_element_by_id;
-use crate::web::fetch;
+use crate::web::{download, fetch};
 use crate::{Result, json};
 use dto::checked_member::CheckedMember;
 use dto::uda_member::UdaMember;
@@ -22,18 +22,34 @@ pub async fn check_members(document: &Document) {
     .await;
 }
 
-async fn check(document: &Document) -> Result<Vec<CheckedMember<UdaMember>>> {
+/// Download the check results of UDA participants as a `csv`, `xlsx` or `pdf` file.
+#[wasm_bindgen]
+pub async fn export_uda_check_results(document: &Document, format: String) {
+    with_loading(async || {
+        let members = get_members_as_json(document)?;
+        let url = build_check_url(document, &format!("/api/members/uda/check/export/{format}"))?;
+        download(&url, &members, &format!("verification-licences.{format}")).await
+    })
+    .await;
+}
+
+fn get_members_as_json(document: &Document) -> Result<String> {
     let element_id = "members-as-json";
-    let members = get_element_by_id(document, element_id)?
+    get_element_by_id(document, element_id)?
         .text_content()
         .ok_or_else(|| {
             Error::new(
                 "Liste de membres à vérifier introuvable. Veuillez réessayer.",
                 &format!("No members to check [id: {element_id}]."),
             )
-        })?;
+        })
+}
+
+async fn check(document: &Document) -> Result<Vec<CheckedMember<UdaMember>>> {
+    let members = get_members_as_json(document)?;
+    let url = build_check_url(document, "/api/members/uda/check")?;
     let response = fetch(
-        "/api/members/uda/check",
+        &url,
         "post",
         Some("application/json"),
         Some(members.as_str()),
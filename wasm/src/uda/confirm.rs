@@ -11,8 +11,11 @@ use wasm_bindgen::prelude::wasm_bindgen;
 pub async fn confirm_members() {
     with_loading(async || {
         let body = get_body()?;
-        let id_inputs =
-            query_selector_all(&body, ".checked-member:has(.membership-up-to-date) .uda-id")?;
+        // Members with several candidate memberships are left aside until the right one is picked.
+        let id_inputs = query_selector_all(
+            &body,
+            ".checked-member:has(.membership-up-to-date, .membership-expiring-soon):not(:has(.membership-candidates)) .uda-id",
+        )?;
 
         let mut ids = vec![];
         for id_input in id_inputs {
@@ -0,0 +1,124 @@
+use crate::Result;
+use crate::error::{DEFAULT_SERVER_ERROR_MESSAGE, Error};
+use crate::json;
+use crate::user_interface::with_loading;
+use crate::utils::{get_document, get_element_by_id_dyn, get_location, get_value_from_element};
+use crate::web::fetch;
+use dto::user::{NewUser, Role, UserCredentials};
+use wasm_bindgen::prelude::wasm_bindgen;
+use web_sys::{Document, HtmlInputElement};
+
+/// Create a user from the creation form, then reload the page to list them.
+#[wasm_bindgen(js_name = "createUser")]
+pub async fn create_user() {
+    with_loading(async || {
+        let document = get_document()?;
+        let login_field = get_element_by_id_dyn::<HtmlInputElement>(&document, "new-user-login")?;
+        let password_field =
+            get_element_by_id_dyn::<HtmlInputElement>(&document, "new-user-password")?;
+
+        if !login_field.report_validity() || !password_field.report_validity() {
+            return Ok(());
+        }
+
+        let new_user = NewUser::new(
+            UserCredentials::new(
+                get_value_from_element(&login_field),
+                get_value_from_element(&password_field),
+            ),
+            get_checked_roles(&document, "new-user", &Role::ALL)?,
+        );
+        let body = json::to_string(&new_user);
+        let response = fetch("/api/users", "post", Some("application/json"), Some(&body))
+            .await
+            .map_err(|error| Error::from_parent(DEFAULT_SERVER_ERROR_MESSAGE, error))?;
+
+        match response.status() {
+            200..400 => reload(),
+            400 => Err(Error::new(
+                "L'identifiant ne peut contenir que des lettres, des chiffres, et les caractères « . », « - », « _ » et « @ ». Le mot de passe doit contenir au moins 8 caractères.",
+                "Invalid login or password",
+            )),
+            409 => Err(Error::new(
+                "Cet identifiant est déjà utilisé.",
+                "Login already taken",
+            )),
+            status => Err(Error::from_server_status_error(status)),
+        }
+    })
+    .await;
+}
+
+/// Replace the roles of a user with those checked on their row.
+#[wasm_bindgen(js_name = "updateUserRoles")]
+pub async fn update_user_roles(login: String) {
+    with_loading(async || {
+        let document = get_document()?;
+        let roles = get_checked_roles(&document, &login, &Role::ALL)?;
+        let url = format!("/api/users/{login}/roles");
+        let response = fetch(
+            &url,
+            "put",
+            Some("application/json"),
+            Some(&json::to_string(&roles)),
+        )
+        .await
+        .map_err(|error| Error::from_parent(DEFAULT_SERVER_ERROR_MESSAGE, error))?;
+
+        match response.status() {
+            200..400 => reload(),
+            400 => Err(Error::new(
+                "Vous ne pouvez pas vous retirer le rôle d'administration.",
+                "Can't revoke own admin role",
+            )),
+            status => Err(Error::from_server_status_error(status)),
+        }
+    })
+    .await;
+}
+
+#[wasm_bindgen(js_name = "deleteUser")]
+pub async fn delete_user(login: String) {
+    with_loading(async || {
+        let url = format!("/api/users/{login}");
+        let response = fetch(&url, "delete", None, None)
+            .await
+            .map_err(|error| Error::from_parent(DEFAULT_SERVER_ERROR_MESSAGE, error))?;
+
+        match response.status() {
+            200..400 => reload(),
+            400 => Err(Error::new(
+                "Vous ne pouvez pas supprimer votre propre compte.",
+                "Can't delete own account",
+            )),
+            status => Err(Error::from_server_status_error(status)),
+        }
+    })
+    .await;
+}
+
+/// Roles are checkboxes whose IDs are made of a prefix and the role, e.g. `new-user-Check`.
+pub(super) fn get_checked_roles(
+    document: &Document,
+    prefix: &str,
+    candidates: &[Role],
+) -> Result<Vec<Role>> {
+    let mut roles = vec![];
+    for &role in candidates {
+        let id = format!("{prefix}-{}", role.value());
+        if get_element_by_id_dyn::<HtmlInputElement>(document, &id)?.checked() {
+            roles.push(role);
+        }
+    }
+
+    Ok(roles)
+}
+
+pub(crate) fn reload() -> Result<()> {
+    get_location()?.reload().map_err(|error| {
+        Error::from_parent(
+            &format!("Impossible de recharger la page : {error:?}"),
+            Error::from(error),
+        )
+    })
+}
@@ -0,0 +1,77 @@
+use crate::error::{DEFAULT_ERROR_MESSAGE, DEFAULT_SERVER_ERROR_MESSAGE, Error};
+use crate::json;
+use crate::user::admin::{get_checked_roles, reload};
+use crate::user_interface::with_loading;
+use crate::utils::{
+    get_document, get_element_by_id, get_element_by_id_dyn, get_value_from_element, remove_class,
+};
+use crate::web::fetch;
+use dto::api_token::{API_TOKEN_SCOPES, CreatedApiToken, NewApiToken};
+use wasm_bindgen::prelude::wasm_bindgen;
+use web_sys::HtmlInputElement;
+
+/// Create an API token, then display it, since it can't be retrieved afterward.
+#[wasm_bindgen(js_name = "createApiToken")]
+pub async fn create_api_token() {
+    with_loading(async || {
+        let document = get_document()?;
+        let name_field =
+            get_element_by_id_dyn::<HtmlInputElement>(&document, "new-api-token-name")?;
+        if !name_field.report_validity() {
+            return Ok(());
+        }
+
+        let new_token = NewApiToken::new(
+            get_value_from_element(&name_field),
+            get_checked_roles(&document, "new-api-token", &API_TOKEN_SCOPES)?,
+        );
+        let body = json::to_string(&new_token);
+        let response = fetch(
+            "/api/api-tokens",
+            "post",
+            Some("application/json"),
+            Some(&body),
+        )
+        .await
+        .map_err(|error| Error::from_parent(DEFAULT_SERVER_ERROR_MESSAGE, error))?;
+
+        match response.status() {
+            200..400 => {
+                let body = response
+                    .body()
+                    .clone()
+                    .ok_or_else(|| Error::new(DEFAULT_ERROR_MESSAGE, "No body"))?;
+                let created = json::from_str::<CreatedApiToken>(&body);
+                get_element_by_id(&document, "created-api-token-value")?
+                    .set_text_content(Some(created.token()));
+                remove_class(
+                    &get_element_by_id(&document, "created-api-token")?,
+                    "hidden",
+                );
+                Ok(())
+            }
+            400 => Err(Error::new(
+                "Le jeton doit avoir un nom et au moins un droit.",
+                "Missing name or scope",
+            )),
+            status => Err(Error::from_server_status_error(status)),
+        }
+    })
+    .await;
+}
+
+#[wasm_bindgen(js_name = "revokeApiToken")]
+pub async fn revoke_api_token(id: i32) {
+    with_loading(async || {
+        let url = format!("/api/api-tokens/{id}");
+        let response = fetch(&url, "delete", None, None)
+            .await
+            .map_err(|error| Error::from_parent(DEFAULT_SERVER_ERROR_MESSAGE, error))?;
+
+        match response.status() {
+            200..400 => reload(),
+            status => Err(Error::from_server_status_error(status)),
+        }
+    })
+    .await;
+}
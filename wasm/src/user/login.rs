@@ -0,0 +1,77 @@
+use crate::error::{DEFAULT_SERVER_ERROR_MESSAGE, Error};
+use crate::json;
+use crate::user_interface::with_loading;
+use crate::utils::{get_document, get_element_by_id_dyn, get_location, get_value_from_element};
+use crate::web::fetch;
+use dto::user::UserCredentials;
+use wasm_bindgen::prelude::wasm_bindgen;
+use web_sys::{HtmlInputElement, UrlSearchParams};
+
+/// Validate the fields, then try to log the user in.
+/// If it succeeds, then redirect to the page they were trying to reach.
+#[wasm_bindgen(js_name = "logIn")]
+pub async fn login() {
+    with_loading(async || {
+        let document = get_document()?;
+        let login_field = get_element_by_id_dyn::<HtmlInputElement>(&document, "login")?;
+        let password_field = get_element_by_id_dyn::<HtmlInputElement>(&document, "password")?;
+
+        if !login_field.report_validity() || !password_field.report_validity() {
+            return Ok(());
+        }
+
+        let credentials = UserCredentials::new(
+            get_value_from_element(&login_field),
+            get_value_from_element(&password_field),
+        );
+        let body = json::to_string(&credentials);
+        let response = fetch("/api/login", "post", Some("application/json"), Some(&body))
+            .await
+            .map_err(|error| Error::from_parent(DEFAULT_SERVER_ERROR_MESSAGE, error))?;
+
+        let status = response.status();
+        if (200..400).contains(&status) {
+            let location = get_location()?;
+            let query_params = location.search().map_err(Error::from)?;
+            let query_params = UrlSearchParams::new_with_str(&query_params).map_err(Error::from)?;
+            let url_to_redirect = query_params.get("page").unwrap_or_else(|| "/".to_owned());
+            location.set_href(&url_to_redirect).map_err(|error| {
+                Error::from_parent(
+                    "Erreur lors de la redirection. Veuillez actualiser la page.",
+                    Error::from(error),
+                )
+            })
+        } else if status == 401 {
+            Err(Error::new(
+                "Vos identifiants sont incorrects. Veuillez réessayer.",
+                "Wrong credentials, can't login",
+            ))
+        } else {
+            Err(Error::from_server_status_error(status))
+        }
+    })
+    .await;
+}
+
+/// Log the user out, then send them back to the login page.
+#[wasm_bindgen(js_name = "logOut")]
+pub async fn logout() {
+    with_loading(async || {
+        let response = fetch("/api/logout", "post", None, None)
+            .await
+            .map_err(|error| Error::from_parent(DEFAULT_SERVER_ERROR_MESSAGE, error))?;
+
+        let status = response.status();
+        if (200..400).contains(&status) {
+            get_location()?.set_href("/login").map_err(|error| {
+                Error::from_parent(
+                    "Erreur lors de la redirection. Veuillez actualiser la page.",
+                    Error::from(error),
+                )
+            })
+        } else {
+            Err(Error::from_server_status_error(status))
+        }
+    })
+    .await;
+}
@@ -0,0 +1,10 @@
+use crate::component::login_form::add_enter_listener_on_form;
+use web_sys::Document;
+
+pub(crate) mod admin;
+mod api_token;
+mod login;
+
+pub fn init_login_page(document: &Document) {
+    add_enter_listener_on_form(document, "login-form-user");
+}
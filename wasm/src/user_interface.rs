@@ -11,7 +11,7 @@ use dto::csv_member::CsvMember;
 use dto::member_to_check::MemberToCheck;
 use dto::membership_status::MemberStatus;
 use std::collections::BTreeSet;
-use web_sys::{Document, Element, HtmlElement, HtmlInputElement};
+use web_sys::{Document, Element, HtmlElement, HtmlInputElement, HtmlTextAreaElement};
 
 // region Handle "members to check" file
 pub fn render_lines(
@@ -67,10 +67,11 @@ fn create_wrong_lines(document: &Document, wrong_lines: &[String]) -> Result<Ele
         .build(document, "div")?;
 
     for wrong_line in wrong_lines {
+        // Wrong lines come from the file, so they are not trusted as HTML
         ElementBuilder::default()
             .parent(&parent)
-            .inner_html(wrong_line)
-            .build(document, "p")?;
+            .build(document, "p")?
+            .set_text_content(Some(wrong_line));
     }
 
     Ok(parent)
@@ -86,14 +87,18 @@ pub fn handle_checked_members(
     clear_element(&parent);
 
     let mut up_to_date_member_cards = vec![];
+    let mut expiring_soon_member_cards = vec![];
     let mut expired_member_cards = vec![];
+    let mut starts_after_event_member_cards = vec![];
     let mut unknown_member_cards = vec![];
 
     for checked_member in checked_members {
         let card = create_card_for_checked_member(document, checked_member)?;
-        match checked_member.compute_member_status() {
+        match checked_member.status() {
             MemberStatus::UpToDate => up_to_date_member_cards.push(card),
+            MemberStatus::ExpiringSoon => expiring_soon_member_cards.push(card),
             MemberStatus::Expired => expired_member_cards.push(card),
+            MemberStatus::StartsAfterEvent => starts_after_event_member_cards.push(card),
             MemberStatus::Unknown => unknown_member_cards.push(card),
         }
     }
@@ -101,7 +106,9 @@ pub fn handle_checked_members(
     let accordion = create_accordion_for_checked_members(
         document,
         &up_to_date_member_cards,
+        &expiring_soon_member_cards,
         &expired_member_cards,
+        &starts_after_event_member_cards,
         &unknown_member_cards,
     )?;
     append_child(&parent, &accordion)?;
@@ -112,7 +119,9 @@ pub fn handle_checked_members(
 pub fn create_accordion_for_checked_members(
     document: &Document,
     up_to_date_member_cards: &[Element],
+    expiring_soon_member_cards: &[Element],
     expired_member_cards: &[Element],
+    starts_after_event_member_cards: &[Element],
     unknown_member_cards: &[Element],
 ) -> Result<HtmlElement> {
     let up_to_date_element = create_accordion_line_for_checked_members(
@@ -121,12 +130,24 @@ pub fn create_accordion_for_checked_members(
         "Membres à jour",
         up_to_date_member_cards,
     )?;
+    let expiring_soon_element = create_accordion_line_for_checked_members(
+        document,
+        "expiring-soon",
+        "Membres dont l'adhésion expire bientôt",
+        expiring_soon_member_cards,
+    )?;
     let expired_element = create_accordion_line_for_checked_members(
         document,
         "expired",
         "Membres expirés",
         expired_member_cards,
     )?;
+    let starts_after_event_element = create_accordion_line_for_checked_members(
+        document,
+        "starts-after-event",
+        "Membres dont l'adhésion débute après l'événement",
+        starts_after_event_member_cards,
+    )?;
     let unknown_element = create_accordion_line_for_checked_members(
         document,
         "unknown",
@@ -134,10 +155,16 @@ pub fn create_accordion_for_checked_members(
         unknown_member_cards,
     )?;
 
-    let elements = [up_to_date_element, expired_element, unknown_element]
-        .into_iter()
-        .flatten()
-        .collect::<Vec<_>>();
+    let elements = [
+        up_to_date_element,
+        expiring_soon_element,
+        expired_element,
+        starts_after_event_element,
+        unknown_element,
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>();
     create_accordion(document, &elements, true)
 }
 
@@ -193,7 +220,8 @@ pub fn get_email_subject(document: &Document) -> Result<String> {
 }
 
 pub fn get_email_body(document: &Document) -> Result<String> {
-    get_element_by_id(document, "email-body").map(|element| element.inner_html())
+    get_element_by_id_dyn::<HtmlTextAreaElement>(document, "email-body")
+        .map(|element| element.value())
 }
 // endregion
 
@@ -1,6 +1,8 @@
 use crate::Result;
 use crate::error::{DEFAULT_ERROR_MESSAGE, Error};
 use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::js_sys::Promise;
 use web_sys::{Document, Element, HtmlElement, HtmlInputElement, Location, Node, Window};
 
 pub fn set_panic_hook() {
@@ -14,6 +16,20 @@ pub fn set_panic_hook() {
     console_error_panic_hook::set_once();
 }
 
+/// Wait without blocking the page, e.g. before polling the server again.
+pub async fn sleep(milliseconds: i32) -> Result<()> {
+    let window = get_window()?;
+    let promise = Promise::new(&mut |resolve, _reject| {
+        if let Err(error) =
+            window.set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, milliseconds)
+        {
+            log::error!("Can't set timeout: {error:?}");
+        }
+    });
+    JsFuture::from(promise).await?;
+    Ok(())
+}
+
 // region Get elements
 pub fn get_window() -> Result<Window> {
     web_sys::window().ok_or_else(|| Error::new(DEFAULT_ERROR_MESSAGE, "no global `window` exists"))
@@ -1,9 +1,9 @@
 use crate::Result;
 use crate::component::alert::unwrap_or_alert;
 use crate::error::Error;
-use crate::utils::get_window;
+use crate::utils::{get_document, get_window};
 use wasm_bindgen::{JsCast, JsValue};
-use web_sys::{Headers, Request, RequestInit};
+use web_sys::{Blob, File, FormData, Headers, HtmlAnchorElement, Request, RequestInit, Url};
 
 #[derive(Debug)]
 pub struct Response {
@@ -28,10 +28,77 @@ pub async fn fetch(
     content_type: Option<&str>,
     body: Option<&str>,
 ) -> Result<Response> {
+    send(url, method, content_type, body.map(JsValue::from_str)).await
+}
+
+/// Send a file picked by the user as the raw body of a POST request.
+pub async fn upload(url: &str, file: &File) -> Result<Response> {
+    send(url, "post", None, Some(JsValue::from(file))).await
+}
+
+/// Send a multipart form, e.g. to upload files along with other fields.
+pub async fn post_form(url: &str, form: &FormData) -> Result<Response> {
+    send(url, "post", None, Some(JsValue::from(form))).await
+}
+
+/// Post a JSON body, and save the file the server answers with under `file_name`.
+pub async fn download(url: &str, body: &str, file_name: &str) -> Result<()> {
+    let response = send_request(
+        url,
+        "post",
+        Some("application/json"),
+        Some(JsValue::from_str(body)),
+    )
+    .await?;
+    let status = response.status();
+    if !(200..400).contains(&status) {
+        return Err(Error::new(
+            "Impossible de générer le fichier. Veuillez réessayer.",
+            &format!("Server error: {status}"),
+        ));
+    }
+
+    let blob = wasm_bindgen_futures::JsFuture::from(response.blob()?)
+        .await?
+        .dyn_into::<Blob>()?;
+    let object_url = Url::create_object_url_with_blob(&blob)?;
+    let anchor = get_document()?
+        .create_element("a")?
+        .dyn_into::<HtmlAnchorElement>()?;
+    anchor.set_href(&object_url);
+    anchor.set_download(file_name);
+    anchor.click();
+    Url::revoke_object_url(&object_url)?;
+
+    Ok(())
+}
+
+async fn send(
+    url: &str,
+    method: &str,
+    content_type: Option<&str>,
+    body: Option<JsValue>,
+) -> Result<Response> {
+    let response = send_request(url, method, content_type, body).await?;
+    let status = response.status();
+    Ok(Response {
+        status,
+        body: wasm_bindgen_futures::JsFuture::from(response.text()?)
+            .await?
+            .as_string(),
+    })
+}
+
+async fn send_request(
+    url: &str,
+    method: &str,
+    content_type: Option<&str>,
+    body: Option<JsValue>,
+) -> Result<web_sys::Response> {
     let window = get_window()?;
     let request_init = RequestInit::new();
     if let Some(body) = body {
-        request_init.set_body(&JsValue::from_str(body));
+        request_init.set_body(&body);
     }
     request_init.set_method(method);
     let headers = Headers::new()?;
@@ -45,11 +112,5 @@ pub async fn fetch(
     let response = wasm_bindgen_futures::JsFuture::from(promise)
         .await?
         .dyn_into::<web_sys::Response>()?;
-    let status = response.status();
-    Ok(Response {
-        status,
-        body: wasm_bindgen_futures::JsFuture::from(response.text()?)
-            .await?
-            .as_string(),
-    })
+    Ok(response)
 }